@@ -1,5 +1,7 @@
+mod audit;
 mod connection;
 mod diff;
+mod file_conflicts;
 mod mention;
 mod terminal;
 
@@ -30,9 +32,11 @@ pub fn subagent_session_id_from_meta(meta: &Option<acp::Meta>) -> Option<acp::Se
 pub fn meta_with_tool_name(tool_name: &str) -> acp::Meta {
     acp::Meta::from_iter([(TOOL_NAME_META_KEY.into(), tool_name.into())])
 }
+pub use audit::*;
 use collections::HashSet;
 pub use connection::*;
 pub use diff::*;
+pub use file_conflicts::*;
 use language::language_settings::FormatOnSave;
 pub use mention::*;
 use project::lsp_store::{FormatTrigger, LspFormatTarget};
@@ -51,6 +55,7 @@ use itertools::Itertools;
 use language::{Anchor, Buffer, BufferSnapshot, LanguageRegistry, Point, ToPoint, text_diff};
 use markdown::Markdown;
 use project::{AgentLocation, Project, git_store::GitStoreCheckpoint};
+use sandbox::SandboxPolicy;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Formatter, Write};
@@ -79,6 +84,24 @@ pub struct Checkpoint {
     pub show: bool,
 }
 
+impl Checkpoint {
+    /// The project as it was right before the message was sent.
+    pub fn git_checkpoint(&self) -> &GitStoreCheckpoint {
+        &self.git_checkpoint
+    }
+}
+
+/// The state of a thread right before one of its user messages was sent.
+#[derive(Debug)]
+pub struct ForkPoint {
+    pub message_id: Option<UserMessageId>,
+    /// The conversation before the message.
+    pub transcript: String,
+    pub message: Vec<acp::ContentBlock>,
+    /// The project as it was when the message was sent.
+    pub checkpoint: Option<GitStoreCheckpoint>,
+}
+
 impl UserMessage {
     fn to_markdown(&self, cx: &App) -> String {
         let mut markdown = String::new();
@@ -885,6 +908,11 @@ pub struct TokenUsage {
     pub used_tokens: u64,
     pub input_tokens: u64,
     pub output_tokens: u64,
+    /// The completion request that just reported this usage. Requests report their usage
+    /// cumulatively, several times each, and usage that is only reported again, like after
+    /// switching models, has none.
+    #[serde(default)]
+    pub request_id: Option<u64>,
 }
 
 impl TokenUsage {
@@ -927,6 +955,16 @@ pub struct RetryStatus {
     pub duration: Duration,
 }
 
+/// A command run by a thread's agent in a persistent terminal session, which keeps running when
+/// Zed quits.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentTerminalSession {
+    pub terminal_id: acp::TerminalId,
+    pub session_id: String,
+    pub command: String,
+    pub cwd: Option<PathBuf>,
+}
+
 pub struct AcpThread {
     parent_session_id: Option<acp::SessionId>,
     title: SharedString,
@@ -947,6 +985,8 @@ pub struct AcpThread {
     // subagent cancellation fields
     user_stopped: Arc<std::sync::atomic::AtomicBool>,
     user_stop_tx: watch::Sender<bool>,
+    file_registry: Entity<AgentFileRegistry>,
+    file_conflict_resolutions: HashMap<FileConflictId, oneshot::Sender<FileConflictResolution>>,
 }
 
 impl From<&AcpThread> for ActionLogTelemetry {
@@ -976,6 +1016,7 @@ pub enum AcpThreadEvent {
     AvailableCommandsUpdated(Vec<acp::AvailableCommand>),
     ModeUpdated(acp::SessionModeId),
     ConfigOptionsUpdated(Vec<acp::SessionConfigOption>),
+    FileConflict(FileConflictId),
 }
 
 impl EventEmitter<AcpThreadEvent> for AcpThread {}
@@ -1038,6 +1079,7 @@ impl AcpThread {
                     label,
                     cwd,
                     output_byte_limit,
+                    None,
                     terminal,
                     cx,
                 );
@@ -1166,6 +1208,18 @@ impl AcpThread {
 
         let (user_stop_tx, _user_stop_rx) = watch::channel(false);
 
+        let file_registry = AgentFileRegistry::for_project(&project, cx);
+        let this = cx.weak_entity();
+        file_registry.update(cx, |registry, _| {
+            registry.register_session(session_id.clone(), this);
+        });
+        cx.on_release(|this, cx| {
+            this.file_registry.update(cx, |registry, cx| {
+                registry.unregister_session(&this.session_id, cx);
+            });
+        })
+        .detach();
+
         Self {
             parent_session_id,
             action_log,
@@ -1185,7 +1239,62 @@ impl AcpThread {
             pending_terminal_exit: HashMap::default(),
             user_stopped: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             user_stop_tx,
+            file_registry,
+            file_conflict_resolutions: HashMap::default(),
+        }
+    }
+
+    pub fn file_registry(&self) -> &Entity<AgentFileRegistry> {
+        &self.file_registry
+    }
+
+    /// Writes from this thread that are on hold until the user resolves a conflict.
+    pub fn pending_file_conflicts(&self, cx: &App) -> Vec<FileConflict> {
+        self.file_registry
+            .read(cx)
+            .conflicts_for_session(&self.session_id)
+            .filter(|conflict| conflict.writer == self.session_id)
+            .cloned()
+            .collect()
+    }
+
+    pub fn resolve_file_conflict(
+        &mut self,
+        id: FileConflictId,
+        resolution: FileConflictResolution,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(tx) = self.file_conflict_resolutions.remove(&id) {
+            tx.send(resolution).ok();
+        }
+        self.file_registry
+            .update(cx, |registry, cx| registry.remove_conflict(id, cx));
+        cx.notify();
+    }
+
+    /// Holds a write to `buffer` for a user decision if another session has edited it since
+    /// this session last saw it.
+    fn check_file_conflict(
+        &mut self,
+        path: PathBuf,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<oneshot::Receiver<FileConflictResolution>> {
+        let other_sessions =
+            self.file_registry
+                .read(cx)
+                .conflicting_sessions(&self.session_id, buffer, cx);
+        if other_sessions.is_empty() {
+            return None;
         }
+
+        let id = self.file_registry.update(cx, |registry, cx| {
+            registry.add_conflict(path, buffer, self.session_id.clone(), other_sessions, cx)
+        });
+        let (tx, rx) = oneshot::channel();
+        self.file_conflict_resolutions.insert(id, tx);
+        cx.emit(AcpThreadEvent::FileConflict(id));
+        Some(rx)
     }
 
     pub fn parent_session_id(&self) -> Option<&acp::SessionId> {
@@ -1519,6 +1628,10 @@ impl AcpThread {
         let AgentThreadEntry::ToolCall(call) = &mut self.entries[ix] else {
             unreachable!()
         };
+        let was_finished = matches!(
+            call.status,
+            ToolCallStatus::Completed | ToolCallStatus::Failed
+        );
 
         match update {
             ToolCallUpdate::UpdateFields(update) => {
@@ -1546,6 +1659,7 @@ impl AcpThread {
             }
         }
 
+        self.audit_finished_tool_call(ix, was_finished, cx);
         cx.emit(AcpThreadEvent::EntryUpdated(ix));
 
         Ok(())
@@ -1592,6 +1706,10 @@ impl AcpThread {
             let AgentThreadEntry::ToolCall(call) = &mut self.entries[ix] else {
                 unreachable!()
             };
+            let was_finished = matches!(
+                call.status,
+                ToolCallStatus::Completed | ToolCallStatus::Failed
+            );
 
             call.update_fields(
                 update.fields,
@@ -1603,6 +1721,7 @@ impl AcpThread {
             )?;
             call.status = status;
 
+            self.audit_finished_tool_call(ix, was_finished, cx);
             cx.emit(AcpThreadEvent::EntryUpdated(ix));
         } else {
             let call = ToolCall::from_acp(
@@ -1614,6 +1733,7 @@ impl AcpThread {
                 cx,
             )?;
             self.push_entry(AgentThreadEntry::ToolCall(call), cx);
+            self.audit_finished_tool_call(self.entries.len() - 1, false, cx);
         };
 
         self.resolve_locations(id, cx);
@@ -1778,6 +1898,7 @@ impl AcpThread {
         };
 
         let curr_status = mem::replace(&mut call.status, new_status);
+        let title = call.label.read(cx).source().to_string();
 
         if let ToolCallStatus::WaitingForConfirmation { respond_tx, .. } = curr_status {
             respond_tx.send(option_id).log_err();
@@ -1785,6 +1906,20 @@ impl AcpThread {
             panic!("tried to authorize an already authorized tool call");
         }
 
+        let outcome = match option_kind {
+            acp::PermissionOptionKind::AllowOnce => "allowed once",
+            acp::PermissionOptionKind::AllowAlways => "always allowed",
+            acp::PermissionOptionKind::RejectOnce => "rejected once",
+            acp::PermissionOptionKind::RejectAlways => "always rejected",
+            _ => "answered",
+        };
+        AuditLog::record(
+            self.audit_entry(AuditAction::PermissionDecision, title)
+                .rule("user")
+                .outcome(outcome),
+            cx,
+        );
+
         cx.emit(AcpThreadEvent::EntryUpdated(ix));
     }
 
@@ -1811,6 +1946,24 @@ impl AcpThread {
         first_tool_call
     }
 
+    /// Number of tool calls in the current turn that are waiting for the user's permission.
+    pub fn tool_calls_awaiting_confirmation(&self) -> usize {
+        self.entries
+            .iter()
+            .rev()
+            .take_while(|entry| matches!(entry, AgentThreadEntry::ToolCall(_)))
+            .filter(|entry| {
+                matches!(
+                    entry,
+                    AgentThreadEntry::ToolCall(ToolCall {
+                        status: ToolCallStatus::WaitingForConfirmation { .. },
+                        ..
+                    })
+                )
+            })
+            .count()
+    }
+
     pub fn plan(&self) -> &Plan {
         &self.plan
     }
@@ -2194,6 +2347,23 @@ impl AcpThread {
         })
     }
 
+    /// Returns what a fork of this thread before the user message at `entry_ix` starts from.
+    pub fn fork_point(&self, entry_ix: usize, cx: &App) -> Option<ForkPoint> {
+        let message = self.entries.get(entry_ix)?.user_message()?;
+        Some(ForkPoint {
+            message_id: message.id.clone(),
+            transcript: self.entries[..entry_ix]
+                .iter()
+                .map(|entry| entry.to_markdown(cx))
+                .collect(),
+            message: message.chunks.clone(),
+            checkpoint: message
+                .checkpoint
+                .as_ref()
+                .map(|checkpoint| checkpoint.git_checkpoint.clone()),
+        })
+    }
+
     pub fn read_text_file(
         &self,
         path: PathBuf,
@@ -2207,6 +2377,9 @@ impl AcpThread {
         let limit = limit.unwrap_or(u32::MAX);
         let project = self.project.clone();
         let action_log = self.action_log.clone();
+        let audit_entry = self
+            .audit_entry(AuditAction::FileWrite, "Wrote file")
+            .path(path.clone());
         cx.spawn(async move |this, cx| {
             let load = project.update(cx, |project, cx| {
                 let path = project
@@ -2281,34 +2454,84 @@ impl AcpThread {
         let action_log = self.action_log.clone();
         cx.spawn(async move |this, cx| {
             let load = project.update(cx, |project, cx| {
-                let path = project
+                let project_path = project
                     .project_path_for_absolute_path(&path, cx)
                     .context("invalid path")?;
-                anyhow::Ok(project.open_buffer(path, cx))
+                anyhow::Ok(project.open_buffer(project_path, cx))
             });
             let buffer = load?.await?;
-            let snapshot = this.update(cx, |this, cx| {
-                this.shared_buffers
+            let conflict = this.update(cx, |this, cx| {
+                this.check_file_conflict(path.clone(), &buffer, cx)
+            })?;
+            let resolution = match conflict {
+                Some(resolution) => Some(
+                    resolution
+                        .await
+                        .context("file conflict was dismissed without a resolution")?,
+                ),
+                None => None,
+            };
+            if resolution == Some(FileConflictResolution::KeepTheirs) {
+                anyhow::bail!(
+                    "{} was modified by another agent since you last read it, and the user chose to keep those changes. \
+                    Read the file again before editing it.",
+                    path.display()
+                );
+            }
+            let (snapshot, their_edits) = this.update(cx, |this, cx| {
+                // Diffing against the snapshot this session last read replays its changes on
+                // top of any edits made since; diffing against the current contents replaces them.
+                if resolution == Some(FileConflictResolution::KeepMine) {
+                    return (buffer.read(cx).snapshot(), Vec::new());
+                }
+                let snapshot = this
+                    .shared_buffers
                     .get(&buffer)
                     .cloned()
-                    .unwrap_or_else(|| buffer.read(cx).snapshot())
+                    .unwrap_or_else(|| buffer.read(cx).snapshot());
+                let their_edits = if resolution == Some(FileConflictResolution::Merge) {
+                    buffer
+                        .read(cx)
+                        .edits_since::<usize>(&snapshot.version)
+                        .map(|edit| edit.old)
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                (snapshot, their_edits)
             })?;
             let edits = cx
                 .background_executor()
                 .spawn(async move {
                     let old_text = snapshot.text();
-                    text_diff(old_text.as_str(), &content)
-                        .into_iter()
-                        .map(|(range, replacement)| {
-                            (
-                                snapshot.anchor_after(range.start)
-                                    ..snapshot.anchor_before(range.end),
-                                replacement,
-                            )
+                    let edits = text_diff(old_text.as_str(), &content);
+                    // Changes that touch the same part of the file can't be merged.
+                    let conflicts = edits.iter().any(|(range, _)| {
+                        their_edits.iter().any(|their_range| {
+                            range.start <= their_range.end && their_range.start <= range.end
                         })
-                        .collect::<Vec<_>>()
+                    });
+                    anyhow::ensure!(
+                        !conflicts,
+                        "{} was modified by another agent since you last read it, and your changes \
+                        overlap with theirs, so they couldn't be merged. \
+                        Read the file again before editing it.",
+                        path.display()
+                    );
+                    anyhow::Ok(
+                        edits
+                            .into_iter()
+                            .map(|(range, replacement)| {
+                                (
+                                    snapshot.anchor_after(range.start)
+                                        ..snapshot.anchor_before(range.end),
+                                    replacement,
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    )
                 })
-                .await;
+                .await?;
 
             project.update(cx, |project, cx| {
                 project.set_agent_location(
@@ -2364,7 +2587,9 @@ impl AcpThread {
 
             project
                 .update(cx, |project, cx| project.save_buffer(buffer, cx))
-                .await
+                .await?;
+            cx.update(|cx| AuditLog::record(audit_entry, cx));
+            Ok(())
         })
     }
 
@@ -2375,6 +2600,7 @@ impl AcpThread {
         extra_env: Vec<acp::EnvVariable>,
         cwd: Option<PathBuf>,
         output_byte_limit: Option<u64>,
+        sandbox: Option<SandboxPolicy>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
         let env = match &cwd {
@@ -2398,6 +2624,13 @@ impl AcpThread {
         let project = self.project.clone();
         let language_registry = project.read(cx).languages().clone();
         let is_windows = project.read(cx).path_style(cx).is_windows();
+        let sandbox = project_sandbox_policy(sandbox, project.read(cx), cx);
+        self.audit_terminal_command(
+            &format!("{} {}", command, args.join(" ")),
+            cwd.clone(),
+            sandbox.is_some(),
+            cx,
+        );
 
         let terminal_id = acp::TerminalId::new(Uuid::new_v4().to_string());
         let terminal_task = cx.spawn({
@@ -2415,9 +2648,13 @@ impl AcpThread {
                     ShellBuilder::new(&Shell::Program(shell), is_windows)
                         .redirect_stdin_to_dev_null()
                         .build(Some(command.clone()), &args);
+                let (task_command, task_args) = match &sandbox {
+                    Some(sandbox) => sandbox.wrap_command(task_command, task_args)?,
+                    None => (task_command, task_args),
+                };
                 let terminal = project
                     .update(cx, |project, cx| {
-                        project.create_terminal_task(
+                        project.create_agent_terminal_task(
                             task::SpawnInTerminal {
                                 command: Some(task_command),
                                 args: task_args,
@@ -2436,6 +2673,7 @@ impl AcpThread {
                         &format!("{} {}", command, args.join(" ")),
                         cwd,
                         output_byte_limit.map(|l| l as usize),
+                        sandbox,
                         terminal,
                         language_registry,
                         cx,
@@ -2504,10 +2742,12 @@ impl AcpThread {
         command_label: String,
         working_dir: Option<PathBuf>,
         output_byte_limit: Option<u64>,
+        sandbox: Option<SandboxPolicy>,
         terminal: Entity<::terminal::Terminal>,
         cx: &mut Context<Self>,
     ) -> Entity<Terminal> {
         let language_registry = self.project.read(cx).languages().clone();
+        self.audit_terminal_command(&command_label, working_dir.clone(), sandbox.is_some(), cx);
 
         let entity = cx.new(|cx| {
             Terminal::new(
@@ -2515,6 +2755,7 @@ impl AcpThread {
                 &command_label,
                 working_dir.clone(),
                 output_byte_limit.map(|l| l as usize),
+                sandbox,
                 terminal,
                 language_registry,
                 cx,
@@ -2523,6 +2764,190 @@ impl AcpThread {
         self.terminals.insert(terminal_id.clone(), entity.clone());
         entity
     }
+
+    /// The commands of this thread's agent that are still running in persistent terminal
+    /// sessions, which [`Self::reattach_terminals`] can reattach to after Zed restarts.
+    pub fn running_terminal_sessions(&self, cx: &App) -> Vec<AgentTerminalSession> {
+        let mut sessions = self
+            .terminals
+            .values()
+            .filter_map(|terminal| {
+                let terminal = terminal.read(cx);
+                Some(AgentTerminalSession {
+                    terminal_id: terminal.id().clone(),
+                    session_id: terminal.session_id(cx)?,
+                    command: terminal.command_label().to_string(),
+                    cwd: terminal.working_dir().clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        sessions
+    }
+
+    /// Reattaches to the commands of this thread's agent that kept running in persistent
+    /// terminal sessions while Zed was closed, showing each in a tool call at the end of the
+    /// thread. Commands that finished in the meantime are skipped.
+    pub fn reattach_terminals(
+        &mut self,
+        sessions: Vec<AgentTerminalSession>,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let project = self.project.clone();
+        cx.spawn(async move |this, cx| {
+            for session in sessions {
+                let terminal = project
+                    .update(cx, |project, cx| {
+                        project.reattach_agent_terminal_task(
+                            task::SpawnInTerminal {
+                                label: session.command.clone(),
+                                full_label: session.command.clone(),
+                                command_label: session.command.clone(),
+                                cwd: session.cwd.clone(),
+                                ..Default::default()
+                            },
+                            session.session_id.clone(),
+                            cx,
+                        )
+                    })
+                    .await;
+                let Some(terminal) = terminal.log_err() else {
+                    continue;
+                };
+                if this
+                    .update(cx, |this, cx| {
+                        this.show_reattached_terminal(session, terminal, cx)
+                    })
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        })
+    }
+
+    fn show_reattached_terminal(
+        &mut self,
+        session: AgentTerminalSession,
+        terminal: Entity<::terminal::Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        let language_registry = self.project.read(cx).languages().clone();
+        let terminal_id = session.terminal_id;
+        let terminal = cx.new(|cx| {
+            Terminal::new(
+                terminal_id.clone(),
+                &session.command,
+                session.cwd,
+                None,
+                None,
+                terminal,
+                language_registry,
+                cx,
+            )
+        });
+        let exit = terminal.read(cx).wait_for_exit();
+        self.terminals.insert(terminal_id.clone(), terminal);
+
+        let tool_call_id = acp::ToolCallId::new(terminal_id.0.clone());
+        self.upsert_tool_call(
+            acp::ToolCall::new(tool_call_id.clone(), format!("`{}`", session.command))
+                .kind(acp::ToolKind::Execute)
+                .status(acp::ToolCallStatus::InProgress)
+                .content(vec![acp::ToolCallContent::Terminal(acp::Terminal::new(
+                    terminal_id,
+                ))]),
+            cx,
+        )
+        .log_err();
+
+        cx.spawn(async move |this, cx| {
+            let exit_status = exit.await;
+            let status = if exit_status.exit_code == Some(0) {
+                acp::ToolCallStatus::Completed
+            } else {
+                acp::ToolCallStatus::Failed
+            };
+            this.update(cx, |this, cx| {
+                this.update_tool_call(
+                    acp::ToolCallUpdate::new(
+                        tool_call_id,
+                        acp::ToolCallUpdateFields::new().status(status),
+                    ),
+                    cx,
+                )
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Starts an audit log entry for an action of this thread's agent.
+    pub fn audit_entry(&self, action: AuditAction, description: impl Into<String>) -> AuditEntry {
+        AuditEntry::new(
+            self.session_id.clone(),
+            self.connection.telemetry_id(),
+            action,
+            description,
+        )
+    }
+
+    fn audit_terminal_command(
+        &self,
+        command: &str,
+        cwd: Option<PathBuf>,
+        sandboxed: bool,
+        cx: &App,
+    ) {
+        let mut entry = self.audit_entry(AuditAction::TerminalCommand, command.trim());
+        if let Some(cwd) = cwd {
+            entry = entry.path(cwd);
+        }
+        if sandboxed {
+            entry = entry.rule("sandbox");
+        }
+        AuditLog::record(entry, cx);
+    }
+
+    fn audit_finished_tool_call(&self, ix: usize, was_finished: bool, cx: &App) {
+        let Some(AgentThreadEntry::ToolCall(call)) = self.entries.get(ix) else {
+            return;
+        };
+        let outcome = match call.status {
+            ToolCallStatus::Completed => "completed",
+            ToolCallStatus::Failed => "failed",
+            _ => return,
+        };
+        // Tool calls also finish when a thread's history is loaded, but those were already
+        // recorded when the agent made them.
+        if was_finished || !matches!(self.status(), ThreadStatus::Generating) {
+            return;
+        }
+
+        let title = call.label.read(cx).source().to_string();
+        let mut entry = self.audit_entry(AuditAction::ToolCall, title.clone());
+        if let Some(location) = call.locations.first() {
+            entry = entry.path(location.path.clone());
+        }
+        if let Some(tool_name) = &call.tool_name {
+            entry = entry.rule(tool_name.to_string());
+        }
+        AuditLog::record(entry.outcome(outcome), cx);
+
+        if matches!(call.status, ToolCallStatus::Completed) {
+            for content in &call.content {
+                if let ToolCallContent::Diff(diff) = content
+                    && let Some(path) = diff.read(cx).path(cx)
+                {
+                    AuditLog::record(
+                        self.audit_entry(AuditAction::FileWrite, title.clone())
+                            .path(path),
+                        cx,
+                    );
+                }
+            }
+        }
+    }
 }
 
 fn markdown_for_raw_output(
@@ -2795,6 +3220,7 @@ mod tests {
                     cx,
                     vec![],
                     PathStyle::local(),
+                    None,
                 )
             })
             .await
@@ -3068,6 +3494,121 @@ mod tests {
         request.await.unwrap();
     }
 
+    /// Has two sessions read `/tmp/foo`, the first one write `ONE` over `one` in it, and the
+    /// second one then write `second_content`, which conflicts with the first session's write.
+    ///
+    /// Returns the result of the second write after resolving the conflict, and the file's
+    /// final contents.
+    async fn write_after_other_session(
+        second_content: &str,
+        resolution: FileConflictResolution,
+        cx: &mut TestAppContext,
+    ) -> (Result<()>, String) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/tmp"), json!({"foo": "one\ntwo\nthree\n"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/tmp").as_ref()], cx).await;
+        let connection = Rc::new(FakeAgentConnection::new());
+        let first = cx
+            .update(|cx| {
+                connection
+                    .clone()
+                    .new_session(project.clone(), Path::new(path!("/tmp")), cx)
+            })
+            .await
+            .unwrap();
+        let second = cx
+            .update(|cx| connection.new_session(project.clone(), Path::new(path!("/tmp")), cx))
+            .await
+            .unwrap();
+
+        for thread in [&first, &second] {
+            thread
+                .update(cx, |thread, cx| {
+                    thread.read_text_file(path!("/tmp/foo").into(), None, None, false, cx)
+                })
+                .await
+                .unwrap();
+        }
+        first
+            .update(cx, |thread, cx| {
+                thread.write_text_file(
+                    path!("/tmp/foo").into(),
+                    "ONE\ntwo\nthree\n".to_string(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert!(first.read_with(cx, |thread, cx| {
+            thread.pending_file_conflicts(cx).is_empty()
+        }));
+
+        let write = second.update(cx, |thread, cx| {
+            thread.write_text_file(path!("/tmp/foo").into(), second_content.to_string(), cx)
+        });
+        cx.run_until_parked();
+        let conflicts = second.read_with(cx, |thread, cx| thread.pending_file_conflicts(cx));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, PathBuf::from(path!("/tmp/foo")));
+        let first_session_id = first.read_with(cx, |thread, _| thread.session_id().clone());
+        assert_eq!(conflicts[0].other_sessions, [first_session_id]);
+        // The write is held until the user resolves the conflict.
+        assert_eq!(
+            String::from_utf8(fs.read_file_sync(path!("/tmp/foo")).unwrap()).unwrap(),
+            "ONE\ntwo\nthree\n"
+        );
+
+        second.update(cx, |thread, cx| {
+            thread.resolve_file_conflict(conflicts[0].id, resolution, cx)
+        });
+        let result = write.await;
+        cx.run_until_parked();
+        assert!(second.read_with(cx, |thread, cx| {
+            thread.pending_file_conflicts(cx).is_empty()
+        }));
+        let content = String::from_utf8(fs.read_file_sync(path!("/tmp/foo")).unwrap()).unwrap();
+        (result, content)
+    }
+
+    #[gpui::test]
+    async fn test_conflicting_write_keep_mine(cx: &mut TestAppContext) {
+        let (result, content) =
+            write_after_other_session("one\ntwo\nTHREE\n", FileConflictResolution::KeepMine, cx)
+                .await;
+        result.unwrap();
+        assert_eq!(content, "one\ntwo\nTHREE\n");
+    }
+
+    #[gpui::test]
+    async fn test_conflicting_write_keep_theirs(cx: &mut TestAppContext) {
+        let (result, content) =
+            write_after_other_session("one\ntwo\nTHREE\n", FileConflictResolution::KeepTheirs, cx)
+                .await;
+        assert!(result.is_err());
+        assert_eq!(content, "ONE\ntwo\nthree\n");
+    }
+
+    #[gpui::test]
+    async fn test_conflicting_write_merge(cx: &mut TestAppContext) {
+        let (result, content) =
+            write_after_other_session("one\ntwo\nTHREE\n", FileConflictResolution::Merge, cx).await;
+        result.unwrap();
+        assert_eq!(content, "ONE\ntwo\nTHREE\n");
+    }
+
+    #[gpui::test]
+    async fn test_conflicting_write_merge_overlapping(cx: &mut TestAppContext) {
+        // Both sessions changed the first line, so the second write fails instead of
+        // discarding the first session's change.
+        let (result, content) =
+            write_after_other_session("uno\ntwo\nTHREE\n", FileConflictResolution::Merge, cx).await;
+        assert!(result.is_err());
+        assert_eq!(content, "ONE\ntwo\nthree\n");
+    }
+
     #[gpui::test]
     async fn test_reading_from_line(cx: &mut TestAppContext) {
         init_test(cx);
@@ -0,0 +1,164 @@
+use agent_client_protocol as acp;
+use chrono::{DateTime, Utc};
+use gpui::{App, Global, SharedString};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
+
+/// What an agent did, as recorded in the audit log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    PermissionDecision,
+    ToolCall,
+    FileWrite,
+    TerminalCommand,
+}
+
+impl AuditAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PermissionDecision => "permission_decision",
+            Self::ToolCall => "tool_call",
+            Self::FileWrite => "file_write",
+            Self::TerminalCommand => "terminal_command",
+        }
+    }
+
+    pub fn from_name(action: &str) -> Option<Self> {
+        match action {
+            "permission_decision" => Some(Self::PermissionDecision),
+            "tool_call" => Some(Self::ToolCall),
+            "file_write" => Some(Self::FileWrite),
+            "terminal_command" => Some(Self::TerminalCommand),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PermissionDecision => "Permission",
+            Self::ToolCall => "Tool Call",
+            Self::FileWrite => "File Write",
+            Self::TerminalCommand => "Terminal",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub session_id: acp::SessionId,
+    /// The telemetry id of the agent, e.g. `zed` for the native agent.
+    pub agent: SharedString,
+    pub timestamp: DateTime<Utc>,
+    pub action: AuditAction,
+    pub description: String,
+    pub path: Option<PathBuf>,
+    /// The permission rule that decided the action, if it wasn't the user.
+    pub rule: Option<String>,
+    pub outcome: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        session_id: acp::SessionId,
+        agent: SharedString,
+        action: AuditAction,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            session_id,
+            agent,
+            timestamp: Utc::now(),
+            action,
+            description: description.into(),
+            path: None,
+            rule: None,
+            outcome: None,
+        }
+    }
+
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn rule(mut self, rule: impl Into<String>) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
+
+    pub fn outcome(mut self, outcome: impl Into<String>) -> Self {
+        self.outcome = Some(outcome.into());
+        self
+    }
+}
+
+type AuditSink = Arc<dyn Fn(AuditEntry) + Send + Sync>;
+
+/// Where the actions of every agent are recorded.
+///
+/// The log is stored by the `agent` crate, which installs it at startup with
+/// [`AuditLog::set_sink`]. Until then, entries are dropped.
+#[derive(Default)]
+pub struct AuditLog {
+    sink: Option<AuditSink>,
+}
+
+impl Global for AuditLog {}
+
+impl AuditLog {
+    pub fn set_sink(sink: impl Fn(AuditEntry) + Send + Sync + 'static, cx: &mut App) {
+        cx.default_global::<Self>().sink = Some(Arc::new(sink));
+    }
+
+    pub fn record(entry: AuditEntry, cx: &App) {
+        if let Some(sink) = cx.try_global::<Self>().and_then(|log| log.sink.as_ref()) {
+            sink(entry);
+        }
+    }
+
+    /// Returns a handle that records entries for one session from any thread.
+    pub fn recorder(
+        session_id: acp::SessionId,
+        agent: SharedString,
+        cx: &App,
+    ) -> Option<AuditRecorder> {
+        let sink = cx.try_global::<Self>()?.sink.clone()?;
+        Some(AuditRecorder {
+            sink,
+            session_id,
+            agent,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct AuditRecorder {
+    sink: AuditSink,
+    session_id: acp::SessionId,
+    agent: SharedString,
+}
+
+impl AuditRecorder {
+    pub fn entry(&self, action: AuditAction, description: impl Into<String>) -> AuditEntry {
+        AuditEntry::new(
+            self.session_id.clone(),
+            self.agent.clone(),
+            action,
+            description,
+        )
+    }
+
+    pub fn record(&self, entry: AuditEntry) {
+        (self.sink)(entry);
+    }
+}
+
+impl std::fmt::Debug for AuditRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditRecorder")
+            .field("session_id", &self.session_id)
+            .field("agent", &self.agent)
+            .finish()
+    }
+}
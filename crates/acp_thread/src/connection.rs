@@ -111,6 +111,11 @@ pub trait AgentConnection {
         None
     }
 
+    /// Returns a handle for copying the session into a new one, if the agent supports it.
+    fn fork(&self, _session_id: &acp::SessionId, _cx: &App) -> Option<Rc<dyn AgentSessionFork>> {
+        None
+    }
+
     fn set_title(
         &self,
         _session_id: &acp::SessionId,
@@ -164,6 +169,12 @@ pub trait AgentSessionTruncate {
     fn run(&self, message_id: UserMessageId, cx: &mut App) -> Task<Result<()>>;
 }
 
+pub trait AgentSessionFork {
+    /// Saves a new session with the history before the given message, which can then be
+    /// opened with [`AgentConnection::load_session`].
+    fn run(&self, message_id: UserMessageId, cx: &mut App) -> Task<Result<AgentSessionInfo>>;
+}
+
 pub trait AgentSessionRetry {
     fn run(&self, cx: &mut App) -> Task<Result<acp::PromptResponse>>;
 }
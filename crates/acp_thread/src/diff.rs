@@ -6,7 +6,12 @@ use language::{
     Anchor, Buffer, Capability, LanguageRegistry, OffsetRangeExt as _, Point, TextBuffer,
 };
 use multi_buffer::{MultiBuffer, PathKey, excerpt_context_lines};
-use std::{cmp::Reverse, ops::Range, path::Path, sync::Arc};
+use std::{
+    cmp::Reverse,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use util::ResultExt;
 
 pub enum Diff {
@@ -148,6 +153,20 @@ impl Diff {
         }
     }
 
+    /// Returns the path of the edited file, if it's known.
+    pub fn path(&self, cx: &App) -> Option<PathBuf> {
+        match self {
+            Self::Pending(PendingDiff { new_buffer, .. }) => {
+                let file = new_buffer.read(cx).file()?;
+                Some(match file.as_local() {
+                    Some(file) => file.abs_path(cx),
+                    None => file.full_path(cx),
+                })
+            }
+            Self::Finalized(FinalizedDiff { path, .. }) => Some(PathBuf::from(path)),
+        }
+    }
+
     pub fn multibuffer(&self) -> &Entity<MultiBuffer> {
         match self {
             Self::Pending(PendingDiff { multibuffer, .. }) => multibuffer,
@@ -0,0 +1,196 @@
+use crate::AcpThread;
+use agent_client_protocol as acp;
+use collections::HashMap;
+use gpui::{App, AppContext as _, Context, Entity, EntityId, EventEmitter, Global, WeakEntity};
+use language::Buffer;
+use project::Project;
+use std::path::PathBuf;
+
+/// How the user chose to settle a write that would have clobbered another agent's edits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileConflictResolution {
+    /// Overwrite the file with the writing agent's content.
+    KeepMine,
+    /// Discard the write and keep the file as the other agents left it.
+    KeepTheirs,
+    /// Apply the writing agent's changes on top of the other agents' edits, failing the write
+    /// if they touch the same part of the file.
+    Merge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FileConflictId(usize);
+
+/// A write that is being held until the user decides how to resolve it.
+#[derive(Clone, Debug)]
+pub struct FileConflict {
+    pub id: FileConflictId,
+    pub path: PathBuf,
+    pub buffer: WeakEntity<Buffer>,
+    /// The session whose write is on hold.
+    pub writer: acp::SessionId,
+    /// Sessions that edited the buffer since the writer last read it.
+    pub other_sessions: Vec<acp::SessionId>,
+}
+
+impl FileConflict {
+    pub fn involves(&self, session_id: &acp::SessionId) -> bool {
+        &self.writer == session_id || self.other_sessions.contains(session_id)
+    }
+}
+
+pub enum AgentFileRegistryEvent {
+    ConflictDetected(FileConflictId),
+    ConflictResolved(FileConflictId),
+}
+
+/// Tracks which agent sessions in a project have read or edited each buffer, using each
+/// thread's [`action_log::ActionLog`], so that concurrent agents don't silently overwrite
+/// each other's changes.
+pub struct AgentFileRegistry {
+    sessions: HashMap<acp::SessionId, WeakEntity<AcpThread>>,
+    conflicts: Vec<FileConflict>,
+    next_conflict_id: usize,
+}
+
+#[derive(Default)]
+struct GlobalAgentFileRegistries(HashMap<EntityId, WeakEntity<AgentFileRegistry>>);
+
+impl Global for GlobalAgentFileRegistries {}
+
+impl EventEmitter<AgentFileRegistryEvent> for AgentFileRegistry {}
+
+impl AgentFileRegistry {
+    /// Returns the registry shared by every agent thread in the given project.
+    pub fn for_project(project: &Entity<Project>, cx: &mut App) -> Entity<Self> {
+        let registries = cx.default_global::<GlobalAgentFileRegistries>();
+        if let Some(registry) = registries
+            .0
+            .get(&project.entity_id())
+            .and_then(|registry| registry.upgrade())
+        {
+            return registry;
+        }
+
+        let registry = cx.new(|_| Self {
+            sessions: HashMap::default(),
+            conflicts: Vec::new(),
+            next_conflict_id: 0,
+        });
+        let registries = cx.default_global::<GlobalAgentFileRegistries>();
+        registries
+            .0
+            .retain(|_, registry| registry.upgrade().is_some());
+        registries
+            .0
+            .insert(project.entity_id(), registry.downgrade());
+        registry
+    }
+
+    pub fn register_session(&mut self, session_id: acp::SessionId, thread: WeakEntity<AcpThread>) {
+        self.sessions.insert(session_id, thread);
+    }
+
+    pub fn unregister_session(&mut self, session_id: &acp::SessionId, cx: &mut Context<Self>) {
+        self.sessions.remove(session_id);
+        let conflict_count = self.conflicts.len();
+        self.conflicts
+            .retain(|conflict| &conflict.writer != session_id);
+        for conflict in &mut self.conflicts {
+            conflict.other_sessions.retain(|other| other != session_id);
+        }
+        if self.conflicts.len() != conflict_count {
+            cx.notify();
+        }
+    }
+
+    /// Returns the sessions that edited `buffer` since `session_id` last read or wrote it.
+    pub fn conflicting_sessions(
+        &self,
+        session_id: &acp::SessionId,
+        buffer: &Entity<Buffer>,
+        cx: &App,
+    ) -> Vec<acp::SessionId> {
+        let Some(action_log) = self
+            .sessions
+            .get(session_id)
+            .and_then(|thread| thread.upgrade())
+            .map(|thread| thread.read(cx).action_log().clone())
+        else {
+            return Vec::new();
+        };
+        let last_seen = action_log
+            .read(cx)
+            .last_seen_version(buffer)
+            .cloned()
+            .unwrap_or_default();
+
+        self.sessions
+            .iter()
+            .filter(|(other_id, _)| *other_id != session_id)
+            .filter_map(|(other_id, other_thread)| {
+                let other_action_log = other_thread.upgrade()?.read(cx).action_log().clone();
+                // Subagents share their parent's action log.
+                if other_action_log == action_log {
+                    return None;
+                }
+                let last_edit = other_action_log.read(cx).last_edit_version(buffer)?;
+                (!last_seen.observed_all(last_edit)).then(|| other_id.clone())
+            })
+            .collect()
+    }
+
+    pub fn add_conflict(
+        &mut self,
+        path: PathBuf,
+        buffer: &Entity<Buffer>,
+        writer: acp::SessionId,
+        other_sessions: Vec<acp::SessionId>,
+        cx: &mut Context<Self>,
+    ) -> FileConflictId {
+        let id = FileConflictId(self.next_conflict_id);
+        self.next_conflict_id += 1;
+        self.conflicts.push(FileConflict {
+            id,
+            path,
+            buffer: buffer.downgrade(),
+            writer,
+            other_sessions,
+        });
+        cx.emit(AgentFileRegistryEvent::ConflictDetected(id));
+        cx.notify();
+        id
+    }
+
+    pub fn remove_conflict(&mut self, id: FileConflictId, cx: &mut Context<Self>) {
+        let conflict_count = self.conflicts.len();
+        self.conflicts.retain(|conflict| conflict.id != id);
+        if self.conflicts.len() != conflict_count {
+            cx.emit(AgentFileRegistryEvent::ConflictResolved(id));
+            cx.notify();
+        }
+    }
+
+    pub fn conflict(&self, id: FileConflictId) -> Option<&FileConflict> {
+        self.conflicts.iter().find(|conflict| conflict.id == id)
+    }
+
+    /// Unresolved conflicts that the given session is either writing or being overwritten in.
+    pub fn conflicts_for_session<'a>(
+        &'a self,
+        session_id: &'a acp::SessionId,
+    ) -> impl Iterator<Item = &'a FileConflict> {
+        self.conflicts
+            .iter()
+            .filter(move |conflict| conflict.involves(session_id))
+    }
+
+    pub fn session_title(
+        &self,
+        session_id: &acp::SessionId,
+        cx: &App,
+    ) -> Option<gpui::SharedString> {
+        let thread = self.sessions.get(session_id)?.upgrade()?;
+        Some(thread.read(cx).title())
+    }
+}
@@ -5,6 +5,7 @@ use gpui::{App, AppContext, AsyncApp, Context, Entity, Task};
 use language::LanguageRegistry;
 use markdown::Markdown;
 use project::Project;
+use sandbox::{SandboxDenial, SandboxPolicy};
 use std::{
     path::PathBuf,
     process::ExitStatus,
@@ -19,12 +20,14 @@ use util::get_default_system_shell_preferring_bash;
 
 pub struct Terminal {
     id: acp::TerminalId,
+    command_label: String,
     command: Entity<Markdown>,
     working_dir: Option<PathBuf>,
     terminal: Entity<terminal::Terminal>,
     started_at: Instant,
     output: Option<TerminalOutput>,
     output_byte_limit: Option<usize>,
+    sandbox: Option<SandboxPolicy>,
     _output_task: Shared<Task<acp::TerminalExitStatus>>,
     /// Flag indicating whether this terminal was stopped by explicit user action
     /// (e.g., clicking the Stop button). This is set before kill() is called
@@ -38,6 +41,8 @@ pub struct TerminalOutput {
     pub content: String,
     pub original_content_len: usize,
     pub content_line_count: usize,
+    /// What the sandbox may have blocked, if the command failed.
+    pub sandbox_denial: Option<SandboxDenial>,
 }
 
 impl Terminal {
@@ -46,6 +51,7 @@ impl Terminal {
         command_label: &str,
         working_dir: Option<PathBuf>,
         output_byte_limit: Option<usize>,
+        sandbox: Option<SandboxPolicy>,
         terminal: Entity<terminal::Terminal>,
         language_registry: Arc<LanguageRegistry>,
         cx: &mut Context<Self>,
@@ -53,6 +59,7 @@ impl Terminal {
         let command_task = terminal.read(cx).wait_for_completed_task(cx);
         Self {
             id,
+            command_label: command_label.to_string(),
             command: cx.new(|cx| {
                 Markdown::new(
                     format!("```\n{}\n```", command_label).into(),
@@ -66,6 +73,7 @@ impl Terminal {
             started_at: Instant::now(),
             output: None,
             output_byte_limit,
+            sandbox,
             user_stopped: Arc::new(AtomicBool::new(false)),
             _output_task: cx
                 .spawn(async move |this, cx| {
@@ -74,6 +82,11 @@ impl Terminal {
                     this.update(cx, |this, cx| {
                         let (content, original_content_len) = this.truncated_output(cx);
                         let content_line_count = this.terminal.read(cx).total_lines();
+                        let sandbox_denial = this
+                            .sandbox
+                            .as_ref()
+                            .filter(|_| !exit_status.is_some_and(|status| status.success()))
+                            .and_then(|sandbox| sandbox.denial(&content));
 
                         this.output = Some(TerminalOutput {
                             ended_at: Instant::now(),
@@ -81,6 +94,7 @@ impl Terminal {
                             content,
                             original_content_len,
                             content_line_count,
+                            sandbox_denial,
                         });
                         cx.notify();
                     })
@@ -167,10 +181,26 @@ impl Terminal {
         &self.command
     }
 
+    pub fn command_label(&self) -> &str {
+        &self.command_label
+    }
+
+    /// The persistent session the command runs in, while it's running in one.
+    pub fn session_id(&self, cx: &App) -> Option<String> {
+        if self.output.is_some() {
+            return None;
+        }
+        self.terminal.read(cx).session_id().map(ToOwned::to_owned)
+    }
+
     pub fn working_dir(&self) -> &Option<PathBuf> {
         &self.working_dir
     }
 
+    pub fn sandbox(&self) -> Option<&SandboxPolicy> {
+        self.sandbox.as_ref()
+    }
+
     pub fn started_at(&self) -> Instant {
         self.started_at
     }
@@ -191,11 +221,33 @@ impl Terminal {
     }
 }
 
+/// Returns the sandbox for a command run in the project, which can also write to the project's
+/// folders. Commands in remote projects run on another machine, so they aren't sandboxed.
+pub fn project_sandbox_policy(
+    sandbox: Option<SandboxPolicy>,
+    project: &Project,
+    cx: &App,
+) -> Option<SandboxPolicy> {
+    let mut sandbox = sandbox?;
+    if !project.is_local() {
+        return None;
+    }
+    sandbox.writable_paths.extend(
+        project
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf()),
+    );
+    Some(sandbox)
+}
+
+/// Spawns the command in a terminal, in the given sandbox if any, which should come from
+/// [`project_sandbox_policy`].
 pub async fn create_terminal_entity(
     command: String,
     args: &[String],
     env_vars: Vec<(String, String)>,
     cwd: Option<PathBuf>,
+    sandbox: Option<&SandboxPolicy>,
     project: &Entity<Project>,
     cx: &mut AsyncApp,
 ) -> Result<Entity<terminal::Terminal>> {
@@ -231,10 +283,14 @@ pub async fn create_terminal_entity(
     let (task_command, task_args) = task::ShellBuilder::new(&shell, is_windows)
         .redirect_stdin_to_dev_null()
         .build(Some(command.clone()), &args);
+    let (task_command, task_args) = match sandbox {
+        Some(sandbox) => sandbox.wrap_command(task_command, task_args)?,
+        None => (task_command, task_args),
+    };
 
     project
         .update(cx, |project, cx| {
-            project.create_terminal_task(
+            project.create_agent_terminal_task(
                 task::SpawnInTerminal {
                     command: Some(task_command),
                     args: task_args,
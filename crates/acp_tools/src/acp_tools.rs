@@ -1,9 +1,13 @@
+mod recording;
+
 use std::{
     cell::RefCell,
     collections::HashSet,
     fmt::Display,
+    path::PathBuf,
     rc::{Rc, Weak},
     sync::Arc,
+    time::Instant,
 };
 
 use agent_client_protocol as acp;
@@ -17,12 +21,16 @@ use markdown::{CodeBlockRenderer, Markdown, MarkdownElement, MarkdownStyle};
 use project::Project;
 use settings::Settings;
 use theme::ThemeSettings;
-use ui::{CopyButton, Tooltip, WithScrollbar, prelude::*};
+use ui::{
+    ContextMenu, CopyButton, DropdownMenu, DropdownStyle, Tooltip, WithScrollbar, prelude::*,
+};
 use util::ResultExt as _;
 use workspace::{
     Item, ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
 };
 
+pub use recording::*;
+
 actions!(dev, [OpenAcpLogs]);
 
 pub fn init(cx: &mut App) {
@@ -44,12 +52,20 @@ impl Global for GlobalAcpConnectionRegistry {}
 
 #[derive(Default)]
 pub struct AcpConnectionRegistry {
-    active_connection: RefCell<Option<ActiveConnection>>,
+    /// The open connections, from the oldest to the most recent.
+    connections: RefCell<Vec<ActiveConnection>>,
 }
 
 struct ActiveConnection {
+    connection: RegisteredConnection,
+    _recording_task: Task<()>,
+}
+
+#[derive(Clone)]
+struct RegisteredConnection {
     server_name: SharedString,
     connection: Weak<acp::ClientSideConnection>,
+    recording: Rc<RefCell<AcpRecording>>,
 }
 
 impl AcpConnectionRegistry {
@@ -63,18 +79,52 @@ impl AcpConnectionRegistry {
         }
     }
 
-    pub fn set_active_connection(
+    /// Starts recording a new connection, next to the other open ones.
+    pub fn add_connection(
         &self,
         server_name: impl Into<SharedString>,
         connection: &Rc<acp::ClientSideConnection>,
         cx: &mut Context<Self>,
     ) {
-        self.active_connection.replace(Some(ActiveConnection {
-            server_name: server_name.into(),
-            connection: Rc::downgrade(connection),
-        }));
+        // Subscribe before the caller sends `initialize`, so the recording can be replayed even
+        // if the log view is opened later.
+        let recording = Rc::new(RefCell::new(AcpRecording::default()));
+        let mut receiver = connection.subscribe();
+        let recording_task = cx.spawn({
+            let recording = recording.clone();
+            async move |_, _| {
+                let started_at = Instant::now();
+                while let Ok(message) = receiver.recv().await {
+                    let mut recording = recording.borrow_mut();
+                    recording.push(RecordedMessage::new(&message, started_at.elapsed()));
+                    if recording.is_full() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut connections = self.connections.borrow_mut();
+        connections.retain(|active| active.connection.connection.strong_count() > 0);
+        connections.push(ActiveConnection {
+            connection: RegisteredConnection {
+                server_name: server_name.into(),
+                connection: Rc::downgrade(connection),
+                recording,
+            },
+            _recording_task: recording_task,
+        });
         cx.notify();
     }
+
+    fn open_connections(&self) -> Vec<RegisteredConnection> {
+        self.connections
+            .borrow()
+            .iter()
+            .filter(|active| active.connection.connection.strong_count() > 0)
+            .map(|active| active.connection.clone())
+            .collect()
+    }
 }
 
 struct AcpTools {
@@ -88,11 +138,14 @@ struct AcpTools {
 
 struct WatchedConnection {
     server_name: SharedString,
+    /// Whether the connection was picked in the toolbar, rather than being the most recent one.
+    picked: bool,
     messages: Vec<WatchedConnectionMessage>,
     list_state: ListState,
     connection: Weak<acp::ClientSideConnection>,
     incoming_request_methods: HashMap<acp::RequestId, Arc<str>>,
     outgoing_request_methods: HashMap<acp::RequestId, Arc<str>>,
+    recording: Rc<RefCell<AcpRecording>>,
     _task: Task<()>,
 }
 
@@ -118,21 +171,34 @@ impl AcpTools {
     }
 
     fn update_connection(&mut self, cx: &mut Context<Self>) {
-        let active_connection = self.connection_registry.read(cx).active_connection.borrow();
-        let Some(active_connection) = active_connection.as_ref() else {
+        // Follow the most recent connection, unless one was picked and it's still open.
+        if self
+            .watched_connection
+            .as_ref()
+            .is_some_and(|watched| watched.picked && watched.connection.strong_count() > 0)
+        {
+            return;
+        }
+        let Some(latest) = self.connection_registry.read(cx).open_connections().pop() else {
             return;
         };
+        self.watch_connection(latest, false, cx);
+    }
 
-        if let Some(watched_connection) = self.watched_connection.as_ref() {
-            if Weak::ptr_eq(
-                &watched_connection.connection,
-                &active_connection.connection,
-            ) {
-                return;
-            }
+    fn watch_connection(
+        &mut self,
+        registered: RegisteredConnection,
+        picked: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(watched_connection) = self.watched_connection.as_mut()
+            && Weak::ptr_eq(&watched_connection.connection, &registered.connection)
+        {
+            watched_connection.picked = picked;
+            return;
         }
 
-        if let Some(connection) = active_connection.connection.upgrade() {
+        if let Some(connection) = registered.connection.upgrade() {
             let mut receiver = connection.subscribe();
             let task = cx.spawn(async move |this, cx| {
                 while let Ok(message) = receiver.recv().await {
@@ -143,15 +209,19 @@ impl AcpTools {
                 }
             });
 
+            self.expanded.clear();
             self.watched_connection = Some(WatchedConnection {
-                server_name: active_connection.server_name.clone(),
+                server_name: registered.server_name,
+                picked,
                 messages: vec![],
                 list_state: ListState::new(0, ListAlignment::Bottom, px(2048.)),
-                connection: active_connection.connection.clone(),
+                connection: registered.connection,
                 incoming_request_methods: HashMap::default(),
                 outgoing_request_methods: HashMap::default(),
+                recording: registered.recording,
                 _task: task,
             });
+            cx.notify();
         }
     }
 
@@ -257,6 +327,36 @@ impl AcpTools {
         serde_json::to_string_pretty(&messages).ok()
     }
 
+    fn save_recording(&self, cx: &mut Context<Self>) {
+        let Some(connection) = self.watched_connection.as_ref() else {
+            return;
+        };
+        let recording = connection.recording.borrow().to_jsonl();
+        let suggested_name = format!("{}.acp.jsonl", connection.server_name);
+        let directory = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from(util::paths::home_dir()));
+        let fs = self.project.read(cx).fs().clone();
+        let path = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.background_spawn(async move {
+            let Some(path) = path
+                .await
+                .log_err()
+                .and_then(|path| path.log_err())
+                .flatten()
+            else {
+                return;
+            };
+            fs.atomic_write(path, recording).await.log_err();
+        })
+        .detach();
+    }
+
     fn clear_messages(&mut self, cx: &mut Context<Self>) {
         if let Some(connection) = self.watched_connection.as_mut() {
             connection.messages.clear();
@@ -551,20 +651,76 @@ impl AcpToolsToolbarItemView {
 }
 
 impl Render for AcpToolsToolbarItemView {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let Some(acp_tools) = self.acp_tools.as_ref() else {
             return Empty.into_any_element();
         };
 
         let acp_tools = acp_tools.clone();
+        let connections = acp_tools
+            .read(cx)
+            .connection_registry
+            .read(cx)
+            .open_connections();
+        let watched_connection = acp_tools
+            .read(cx)
+            .watched_connection
+            .as_ref()
+            .map(|connection| connection.connection.clone());
+        let connection_picker = (connections.len() > 1).then(|| {
+            let current_name = acp_tools
+                .read(cx)
+                .watched_connection
+                .as_ref()
+                .map_or(SharedString::from("Disconnected"), |connection| {
+                    connection.server_name.clone()
+                });
+            let menu = ContextMenu::build(window, cx, {
+                let acp_tools = acp_tools.clone();
+                move |mut menu, _, _| {
+                    for (ix, connection) in connections.into_iter().enumerate() {
+                        let is_watched = watched_connection
+                            .as_ref()
+                            .is_some_and(|watched| Weak::ptr_eq(watched, &connection.connection));
+                        let label = format!("{} ({})", connection.server_name, ix + 1);
+                        menu =
+                            menu.toggleable_entry(label, is_watched, IconPosition::Start, None, {
+                                let acp_tools = acp_tools.clone();
+                                move |_, cx| {
+                                    acp_tools.update(cx, |acp_tools, cx| {
+                                        acp_tools.watch_connection(connection.clone(), true, cx)
+                                    });
+                                }
+                            });
+                    }
+                    menu
+                }
+            });
+            DropdownMenu::new("acp-connection", current_name, menu).style(DropdownStyle::Outlined)
+        });
         let has_messages = acp_tools
             .read(cx)
             .watched_connection
             .as_ref()
             .is_some_and(|connection| !connection.messages.is_empty());
+        let (recorded_messages, recording_is_full) = acp_tools
+            .read(cx)
+            .watched_connection
+            .as_ref()
+            .map_or((0, false), |connection| {
+                let recording = connection.recording.borrow();
+                (recording.messages.len(), recording.is_full())
+            });
+        let has_recording = recorded_messages > 0;
+        let save_tooltip = if recording_is_full {
+            format!("Save Recording (First {recorded_messages} Messages)")
+        } else {
+            "Save Recording".to_string()
+        };
 
         h_flex()
             .gap_2()
+            .children(connection_picker)
             .child({
                 let message = acp_tools
                     .read(cx)
@@ -575,6 +731,18 @@ impl Render for AcpToolsToolbarItemView {
                     .tooltip_label("Copy All Messages")
                     .disabled(!has_messages)
             })
+            .child(
+                IconButton::new("save_recording", IconName::Download)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text(save_tooltip))
+                    .disabled(!has_recording)
+                    .on_click({
+                        let acp_tools = acp_tools.clone();
+                        move |_, _window, cx| {
+                            acp_tools.update(cx, |acp_tools, cx| acp_tools.save_recording(cx));
+                        }
+                    }),
+            )
             .child(
                 IconButton::new("clear_messages", IconName::Trash)
                     .icon_size(IconSize::Small)
@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use agent_client_protocol as acp;
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+/// Which way a recorded message travelled, from Zed's point of view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedDirection {
+    /// Sent by the agent to Zed.
+    Incoming,
+    /// Sent by Zed to the agent.
+    Outgoing,
+}
+
+impl From<acp::StreamMessageDirection> for RecordedDirection {
+    fn from(direction: acp::StreamMessageDirection) -> Self {
+        match direction {
+            acp::StreamMessageDirection::Incoming => Self::Incoming,
+            acp::StreamMessageDirection::Outgoing => Self::Outgoing,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedContent {
+    Request {
+        id: acp::RequestId,
+        method: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        params: Option<serde_json::Value>,
+    },
+    Response {
+        id: acp::RequestId,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<serde_json::Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<acp::Error>,
+    },
+    Notification {
+        method: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        params: Option<serde_json::Value>,
+    },
+}
+
+/// A single line of an [`AcpRecording`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    /// Milliseconds since the recording started.
+    pub timestamp_ms: u64,
+    pub direction: RecordedDirection,
+    #[serde(flatten)]
+    pub content: RecordedContent,
+}
+
+impl RecordedMessage {
+    pub fn new(message: &acp::StreamMessage, timestamp: Duration) -> Self {
+        let content = match &message.message {
+            acp::StreamMessageContent::Request { id, method, params } => RecordedContent::Request {
+                id: id.clone(),
+                method: method.to_string(),
+                params: params.clone(),
+            },
+            acp::StreamMessageContent::Response { id, result } => {
+                let (result, error) = match result {
+                    Ok(result) => (result.clone(), None),
+                    Err(error) => (None, Some(error.clone())),
+                };
+                RecordedContent::Response {
+                    id: id.clone(),
+                    result,
+                    error,
+                }
+            }
+            acp::StreamMessageContent::Notification { method, params } => {
+                RecordedContent::Notification {
+                    method: method.to_string(),
+                    params: params.clone(),
+                }
+            }
+        };
+        Self {
+            timestamp_ms: timestamp.as_millis() as u64,
+            direction: message.direction.into(),
+            content,
+        }
+    }
+
+    pub fn method(&self) -> Option<&str> {
+        match &self.content {
+            RecordedContent::Request { method, .. }
+            | RecordedContent::Notification { method, .. } => Some(method),
+            RecordedContent::Response { .. } => None,
+        }
+    }
+}
+
+/// Recordings stop once they have this many messages, or once their messages take up
+/// [`MAX_RECORDED_BYTES`]. They keep the first ones rather than the latest, since replaying starts
+/// from `initialize`.
+pub const MAX_RECORDED_MESSAGES: usize = 10_000;
+/// The most JSON a recording keeps, so that large file contents and tool outputs can't grow it
+/// without bound while the connection stays open.
+pub const MAX_RECORDED_BYTES: usize = 16 * 1024 * 1024;
+
+/// The traffic of one ACP connection, stored as JSON Lines with one [`RecordedMessage`] per
+/// line, so that it can be replayed without running the agent.
+#[derive(Clone, Debug, Default)]
+pub struct AcpRecording {
+    pub messages: Vec<RecordedMessage>,
+    bytes: usize,
+    is_full: bool,
+}
+
+impl AcpRecording {
+    pub fn from_jsonl(text: &str) -> Result<Self> {
+        let messages = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(ix, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("invalid ACP recording message on line {}", ix + 1))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            messages,
+            bytes: text.len(),
+            is_full: false,
+        })
+    }
+
+    /// Adds a message, unless the recording is full. The first message that doesn't fit fills
+    /// it, so that later, smaller messages can't leave gaps in the recording.
+    pub fn push(&mut self, message: RecordedMessage) {
+        if self.is_full {
+            return;
+        }
+        let size = serde_json::to_vec(&message).map_or(0, |line| line.len() + 1);
+        if self.messages.len() >= MAX_RECORDED_MESSAGES || self.bytes + size > MAX_RECORDED_BYTES {
+            self.is_full = true;
+            return;
+        }
+        self.messages.push(message);
+        self.bytes += size;
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.is_full
+    }
+
+    pub fn to_jsonl(&self) -> String {
+        let mut text = String::new();
+        for message in &self.messages {
+            if let Ok(line) = serde_json::to_string(message) {
+                text.push_str(&line);
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_round_trip() {
+        let jsonl = concat!(
+            r#"{"timestamp_ms":0,"direction":"outgoing","type":"request","id":0,"method":"initialize","params":{"protocolVersion":1}}"#,
+            "\n",
+            r#"{"timestamp_ms":12,"direction":"incoming","type":"response","id":0,"result":{"protocolVersion":1}}"#,
+            "\n",
+            r#"{"timestamp_ms":30,"direction":"incoming","type":"notification","method":"session/update","params":{"sessionId":"a"}}"#,
+            "\n",
+        );
+        let recording = AcpRecording::from_jsonl(jsonl).unwrap();
+        assert_eq!(recording.messages.len(), 3);
+        assert_eq!(recording.messages[0].method(), Some("initialize"));
+        assert_eq!(recording.messages[1].direction, RecordedDirection::Incoming);
+        assert!(matches!(
+            recording.messages[2].content,
+            RecordedContent::Notification { .. }
+        ));
+        assert_eq!(recording.to_jsonl(), jsonl);
+    }
+
+    #[test]
+    fn test_recording_stops_at_byte_limit() {
+        let notification = |text: &str| RecordedMessage {
+            timestamp_ms: 0,
+            direction: RecordedDirection::Incoming,
+            content: RecordedContent::Notification {
+                method: "session/update".into(),
+                params: Some(serde_json::json!({ "text": text })),
+            },
+        };
+
+        let mut recording = AcpRecording::default();
+        recording.push(notification("first"));
+        recording.push(notification(&"a".repeat(MAX_RECORDED_BYTES)));
+        recording.push(notification("last"));
+        assert!(recording.is_full());
+        assert_eq!(recording.messages.len(), 1);
+        assert_eq!(recording.to_jsonl().lines().count(), 1);
+    }
+}
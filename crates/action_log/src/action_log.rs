@@ -106,6 +106,7 @@ impl ActionLog {
                     snapshot: text_snapshot,
                     status,
                     version: buffer.read(cx).version(),
+                    last_agent_edit: None,
                     diff,
                     diff_update: diff_update_tx,
                     _open_lsp_handle: open_lsp_handle,
@@ -479,6 +480,7 @@ impl ActionLog {
             tracked_buffer.status = TrackedBufferStatus::Modified;
         }
 
+        tracked_buffer.last_agent_edit = Some(new_version.clone());
         tracked_buffer.version = new_version;
         tracked_buffer.schedule_diff_update(ChangeAuthor::Agent, cx);
     }
@@ -774,6 +776,20 @@ impl ActionLog {
             .collect()
     }
 
+    /// Returns the buffer's version as of the agent's last read or edit of it.
+    pub fn last_seen_version(&self, buffer: &Entity<Buffer>) -> Option<&clock::Global> {
+        self.tracked_buffers
+            .get(buffer)
+            .map(|tracked_buffer| &tracked_buffer.version)
+    }
+
+    /// Returns the buffer's version right after the agent last edited it.
+    pub fn last_edit_version(&self, buffer: &Entity<Buffer>) -> Option<&clock::Global> {
+        self.tracked_buffers
+            .get(buffer)
+            .and_then(|tracked_buffer| tracked_buffer.last_agent_edit.as_ref())
+    }
+
     /// Returns all tracked buffers for debugging purposes
     #[cfg(any(test, feature = "test-support"))]
     pub fn tracked_buffers_for_debug(
@@ -988,6 +1004,7 @@ pub struct TrackedBuffer {
     unreviewed_edits: Patch<u32>,
     status: TrackedBufferStatus,
     version: clock::Global,
+    last_agent_edit: Option<clock::Global>,
     diff: Entity<BufferDiff>,
     snapshot: text::BufferSnapshot,
     diff_update: mpsc::UnboundedSender<(ChangeAuthor, text::BufferSnapshot)>,
@@ -1130,6 +1147,70 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test]
+    async fn test_edit_versions_across_logs(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file": "abc\ndef\nghi"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let first_log = cx.new(|_| ActionLog::new(project.clone()));
+        let second_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file", cx))
+            .unwrap();
+        let buffer = project
+            .update(cx, |project, cx| project.open_buffer(file_path, cx))
+            .await
+            .unwrap();
+
+        cx.update(|cx| {
+            first_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+            second_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+        });
+        cx.run_until_parked();
+        first_log.read_with(cx, |log, _| {
+            assert!(log.last_seen_version(&buffer).is_some());
+            assert_eq!(log.last_edit_version(&buffer), None);
+        });
+
+        cx.update(|cx| {
+            buffer.update(cx, |buffer, cx| {
+                buffer
+                    .edit([(Point::new(1, 0)..Point::new(1, 3), "DEF")], None, cx)
+                    .unwrap()
+            });
+            second_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+        });
+        cx.run_until_parked();
+
+        let second_edit = second_log
+            .read_with(cx, |log, _| log.last_edit_version(&buffer).cloned())
+            .unwrap();
+        assert_eq!(
+            second_edit,
+            buffer.read_with(cx, |buffer, _| buffer.version())
+        );
+        first_log.read_with(cx, |log, _| {
+            assert!(
+                !log.last_seen_version(&buffer)
+                    .unwrap()
+                    .observed_all(&second_edit)
+            );
+        });
+
+        cx.update(|cx| first_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx)));
+        cx.run_until_parked();
+        first_log.read_with(cx, |log, _| {
+            assert!(
+                log.last_seen_version(&buffer)
+                    .unwrap()
+                    .observed_all(&second_edit)
+            );
+        });
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_deletions(cx: &mut TestAppContext) {
         init_test(cx);
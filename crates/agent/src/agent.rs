@@ -1,3 +1,4 @@
+mod audit_log;
 mod db;
 mod edit_agent;
 mod legacy_thread;
@@ -8,10 +9,12 @@ mod templates;
 #[cfg(test)]
 mod tests;
 mod thread;
+mod thread_search;
 mod thread_store;
 mod tool_permissions;
 mod tools;
 
+pub use audit_log::*;
 use context_server::ContextServerId;
 pub use db::*;
 pub use native_agent_server::NativeAgentServer;
@@ -19,6 +22,7 @@ pub use pattern_extraction::*;
 pub use shell_command_parser::extract_commands;
 pub use templates::*;
 pub use thread::*;
+pub use thread_search::*;
 pub use thread_store::*;
 pub use tool_permissions::*;
 pub use tools::*;
@@ -28,6 +32,7 @@ use acp_thread::{
     AgentSessionListResponse, UserMessageId,
 };
 use agent_client_protocol as acp;
+use agent_settings::AgentSettings;
 use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
 use collections::{HashMap, HashSet, IndexMap};
@@ -45,7 +50,7 @@ use prompt_store::{
     WorktreeContext,
 };
 use serde::{Deserialize, Serialize};
-use settings::{LanguageModelSelection, update_settings_file};
+use settings::{LanguageModelSelection, Settings as _, update_settings_file};
 use std::any::Any;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
@@ -376,6 +381,7 @@ impl NativeAgent {
                 allowed_tool_names,
                 Rc::new(NativeThreadEnvironment {
                     acp_thread: acp_thread.downgrade(),
+                    thread: cx.weak_entity(),
                     agent: weak,
                 }) as _,
                 cx,
@@ -1392,6 +1398,21 @@ impl acp_thread::AgentConnection for NativeAgentConnection {
         })
     }
 
+    fn fork(
+        &self,
+        session_id: &acp::SessionId,
+        cx: &App,
+    ) -> Option<Rc<dyn acp_thread::AgentSessionFork>> {
+        self.0.read_with(cx, |agent, _cx| {
+            agent.sessions.get(session_id).map(|session| {
+                Rc::new(NativeAgentSessionFork {
+                    thread: session.thread.clone(),
+                    thread_store: agent.thread_store.clone(),
+                }) as _
+            })
+        })
+    }
+
     fn set_title(
         &self,
         session_id: &acp::SessionId,
@@ -1544,6 +1565,37 @@ impl acp_thread::AgentSessionTruncate for NativeAgentSessionTruncate {
     }
 }
 
+struct NativeAgentSessionFork {
+    thread: Entity<Thread>,
+    thread_store: Entity<ThreadStore>,
+}
+
+impl acp_thread::AgentSessionFork for NativeAgentSessionFork {
+    fn run(
+        &self,
+        message_id: acp_thread::UserMessageId,
+        cx: &mut App,
+    ) -> Task<Result<AgentSessionInfo>> {
+        let db_thread = self.thread.read(cx).to_db(cx);
+        let thread_store = self.thread_store.clone();
+        cx.spawn(async move |cx| {
+            let db_thread = db_thread.await.fork(&message_id)?;
+            let id = acp::SessionId::new(uuid::Uuid::new_v4().to_string());
+            let info = AgentSessionInfo {
+                session_id: id.clone(),
+                cwd: None,
+                title: Some(db_thread.title.clone()),
+                updated_at: Some(db_thread.updated_at),
+                meta: None,
+            };
+            thread_store
+                .update(cx, |store, cx| store.save_thread(id, db_thread, cx))
+                .await?;
+            Ok(info)
+        })
+    }
+}
+
 struct NativeAgentSessionRetry {
     connection: NativeAgentConnection,
     session_id: acp::SessionId,
@@ -1577,6 +1629,7 @@ impl acp_thread::AgentSessionSetTitle for NativeAgentSessionSetTitle {
 pub struct NativeThreadEnvironment {
     agent: WeakEntity<NativeAgent>,
     acp_thread: WeakEntity<AcpThread>,
+    thread: WeakEntity<Thread>,
 }
 
 impl NativeThreadEnvironment {
@@ -1695,8 +1748,15 @@ impl ThreadEnvironment for NativeThreadEnvironment {
         output_byte_limit: Option<u64>,
         cx: &mut AsyncApp,
     ) -> Task<Result<Rc<dyn TerminalHandle>>> {
+        let sandbox = self
+            .thread
+            .read_with(cx, |thread, cx| {
+                AgentSettings::get_global(cx).terminal_sandbox(thread.profile())
+            })
+            .ok()
+            .flatten();
         let task = self.acp_thread.update(cx, |thread, cx| {
-            thread.create_terminal(command, vec![], vec![], cwd, output_byte_limit, cx)
+            thread.create_terminal(command, vec![], vec![], cwd, output_byte_limit, sandbox, cx)
         });
 
         let acp_thread = self.acp_thread.clone();
@@ -1858,7 +1918,7 @@ mod internal_tests {
     use acp_thread::{AgentConnection, AgentModelGroupName, AgentModelInfo, MentionUri};
     use fs::FakeFs;
     use gpui::TestAppContext;
-    use indoc::formatdoc;
+    use indoc::{formatdoc, indoc};
     use language_model::fake_provider::{FakeLanguageModel, FakeLanguageModelProvider};
     use language_model::{LanguageModelProviderId, LanguageModelProviderName};
     use serde_json::json;
@@ -2559,6 +2619,125 @@ mod internal_tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_fork_session(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/", json!({ "a": {} })).await;
+        let project = Project::test(fs.clone(), [path!("/a").as_ref()], cx).await;
+        let thread_store = cx.new(|cx| ThreadStore::new(cx));
+        let agent = NativeAgent::new(
+            project.clone(),
+            thread_store.clone(),
+            Templates::new(),
+            None,
+            fs.clone(),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let connection = Rc::new(NativeAgentConnection(agent.clone()));
+
+        let acp_thread = cx
+            .update(|cx| {
+                connection
+                    .clone()
+                    .new_session(project.clone(), Path::new(""), cx)
+            })
+            .await
+            .unwrap();
+        let session_id = acp_thread.read_with(cx, |thread, _| thread.session_id().clone());
+        let thread = agent.read_with(cx, |agent, _| {
+            agent.sessions.get(&session_id).unwrap().thread.clone()
+        });
+        let model = Arc::new(FakeLanguageModel::default());
+        let summary_model = Arc::new(FakeLanguageModel::default());
+        thread.update(cx, |thread, cx| {
+            thread.set_model(model.clone(), cx);
+            thread.set_summarization_model(Some(summary_model.clone()), cx);
+        });
+
+        for (message, response) in [("First", "One."), ("Second", "Two.")] {
+            let send = acp_thread.update(cx, |thread, cx| thread.send(vec![message.into()], cx));
+            let send = cx.foreground_executor().spawn(send);
+            cx.run_until_parked();
+            model.send_last_completion_stream_text_chunk(response);
+            model.end_last_completion_stream();
+            cx.run_until_parked();
+            send.await.unwrap();
+        }
+        summary_model.send_last_completion_stream_text_chunk("Counting");
+        summary_model.end_last_completion_stream();
+        cx.run_until_parked();
+
+        // Fork before the second message.
+        let second_message_id = acp_thread.read_with(cx, |thread, _| {
+            thread
+                .entries()
+                .iter()
+                .filter_map(|entry| entry.user_message())
+                .nth(1)
+                .and_then(|message| message.id.clone())
+                .unwrap()
+        });
+        let fork = cx
+            .update(|cx| connection.fork(&session_id, cx))
+            .expect("the Zed agent should fork its sessions");
+        let fork_info = cx
+            .update(|cx| fork.run(second_message_id, cx))
+            .await
+            .unwrap();
+        assert_ne!(fork_info.session_id, session_id);
+        assert_eq!(fork_info.title, Some("Counting (fork)".into()));
+
+        let fork_thread = agent
+            .update(cx, |agent, cx| {
+                agent.open_thread(fork_info.session_id.clone(), cx)
+            })
+            .await
+            .unwrap();
+        fork_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                indoc! {"
+                    ## User
+
+                    First
+
+                    ## Assistant
+
+                    One.
+
+                "}
+            )
+        });
+
+        // The original thread keeps both messages.
+        acp_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                indoc! {"
+                    ## User
+
+                    First
+
+                    ## Assistant
+
+                    One.
+
+                    ## User
+
+                    Second
+
+                    ## Assistant
+
+                    Two.
+
+                "}
+            )
+        });
+    }
+
     fn thread_entries(
         thread_store: &Entity<ThreadStore>,
         cx: &mut TestAppContext,
@@ -0,0 +1,64 @@
+use crate::ThreadsDatabase;
+use acp_thread::{AuditEntry, AuditLog};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{App, Task};
+use util::ResultExt as _;
+
+/// Which audit log entries to return from [`query_audit_log`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditFilter {
+    pub agent: Option<String>,
+    /// Only returns entries whose path contains this.
+    pub path: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only returns this many of the latest entries, if set.
+    pub limit: Option<usize>,
+}
+
+impl Default for AuditFilter {
+    fn default() -> Self {
+        Self {
+            agent: None,
+            path: None,
+            since: None,
+            until: None,
+            limit: Some(1000),
+        }
+    }
+}
+
+/// Stores the [`AuditLog`] of every agent in the threads database.
+pub fn init_audit_log(cx: &mut App) {
+    let (entries_tx, mut entries_rx) = mpsc::unbounded::<AuditEntry>();
+    AuditLog::set_sink(
+        move |entry| {
+            entries_tx.unbounded_send(entry).ok();
+        },
+        cx,
+    );
+
+    let database_future = ThreadsDatabase::connect(cx);
+    cx.background_spawn(async move {
+        let database = database_future.await.map_err(|err| anyhow!(err))?;
+        while let Some(entry) = entries_rx.next().await {
+            let mut entries = vec![entry];
+            while let Ok(Some(entry)) = entries_rx.try_next() {
+                entries.push(entry);
+            }
+            database.save_audit_entries(entries).await.log_err();
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+pub fn query_audit_log(filter: AuditFilter, cx: &mut App) -> Task<Result<Vec<AuditEntry>>> {
+    let database_future = ThreadsDatabase::connect(cx);
+    cx.background_spawn(async move {
+        let database = database_future.await.map_err(|err| anyhow!(err))?;
+        database.audit_entries(filter).await
+    })
+}
@@ -1,9 +1,15 @@
-use crate::{AgentMessage, AgentMessageContent, UserMessage, UserMessageContent};
-use acp_thread::UserMessageId;
+use crate::thread_search::{
+    SNIPPET_MATCH_END, SNIPPET_MATCH_START, native_search_source, parse_snippet,
+};
+use crate::{
+    AgentMessage, AgentMessageContent, AuditFilter, ThreadSearchEntry, ThreadSearchMatch,
+    UserMessage, UserMessageContent,
+};
+use acp_thread::{AuditAction, AuditEntry, UserMessageId};
 use agent_client_protocol as acp;
 use agent_settings::AgentProfileId;
 use anyhow::{Result, anyhow};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use collections::{HashMap, IndexMap};
 use futures::{FutureExt, future::Shared};
 use gpui::{BackgroundExecutor, Global, Task};
@@ -15,8 +21,9 @@ use sqlez::{
     connection::Connection,
     statement::Statement,
 };
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 use ui::{App, SharedString};
+use util::ResultExt as _;
 use zed_env_vars::ZED_STATELESS;
 
 pub type DbMessage = crate::Message;
@@ -125,6 +132,38 @@ impl DbThread {
         }
     }
 
+    /// Returns a copy of the thread with only the messages before the given user message.
+    pub fn fork(mut self, message_id: &UserMessageId) -> Result<Self> {
+        let position = self
+            .messages
+            .iter()
+            .position(|message| {
+                matches!(message, DbMessage::User(UserMessage { id, .. }) if id == message_id)
+            })
+            .ok_or_else(|| anyhow!("Message not found"))?;
+        self.messages.truncate(position);
+
+        let remaining_message_ids = self
+            .messages
+            .iter()
+            .filter_map(|message| match message {
+                DbMessage::User(message) => Some(message.id.clone()),
+                DbMessage::Agent(_) | DbMessage::Resume => None,
+            })
+            .collect::<Vec<_>>();
+        self.request_token_usage
+            .retain(|id, _| remaining_message_ids.contains(id));
+
+        Ok(Self {
+            title: format!("{} (fork)", self.title).into(),
+            updated_at: Utc::now(),
+            detailed_summary: None,
+            cumulative_token_usage: Default::default(),
+            subagent_context: None,
+            ..self
+        })
+    }
+
     fn upgrade_from_agent_1(thread: crate::legacy_thread::SerializedThread) -> Result<Self> {
         let mut messages = Vec::new();
         let mut request_token_usage = HashMap::default();
@@ -318,8 +357,14 @@ impl ThreadsDatabase {
             .spawn({
                 let executor = executor.clone();
                 async move {
-                    match ThreadsDatabase::new(executor) {
-                        Ok(db) => Ok(Arc::new(db)),
+                    match ThreadsDatabase::new(executor.clone()) {
+                        Ok(db) => {
+                            let index_threads = db.index_unindexed_threads();
+                            executor
+                                .spawn(async move { index_threads.await.log_err() })
+                                .detach();
+                            Ok(Arc::new(db))
+                        }
                         Err(err) => Err(Arc::new(err)),
                     }
                 }
@@ -369,6 +414,49 @@ impl ThreadsDatabase {
             s().ok();
         }
 
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                agent TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                action TEXT NOT NULL,
+                description TEXT NOT NULL,
+                path TEXT,
+                rule TEXT,
+                outcome TEXT
+            )
+        "})?()
+        .map_err(|e| anyhow!("Failed to create audit_log table: {}", e))?;
+
+        connection.exec(indoc! {"
+            CREATE INDEX IF NOT EXISTS audit_log_timestamp ON audit_log (timestamp)
+        "})?()
+        .map_err(|e| anyhow!("Failed to create audit_log index: {}", e))?;
+
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS thread_search_index (
+                source TEXT NOT NULL,
+                thread_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (source, thread_id)
+            )
+        "})?()
+        .map_err(|e| anyhow!("Failed to create thread_search_index table: {}", e))?;
+
+        connection.exec(indoc! {"
+            CREATE VIRTUAL TABLE IF NOT EXISTS thread_search USING fts5 (
+                source UNINDEXED,
+                thread_id UNINDEXED,
+                turn UNINDEXED,
+                body,
+                paths,
+                tokenize = 'porter unicode61'
+            )
+        "})?()
+        .map_err(|e| anyhow!("Failed to create thread_search table: {}", e))?;
+
         let db = Self {
             executor,
             connection: Arc::new(Mutex::new(connection)),
@@ -391,6 +479,13 @@ impl ThreadsDatabase {
             version: &'static str,
         }
 
+        let search_entry = ThreadSearchEntry {
+            source: native_search_source(),
+            thread_id: id.0.clone(),
+            title: thread.title.clone(),
+            updated_at: thread.updated_at,
+            documents: thread.search_documents(),
+        };
         let title = thread.title.to_string();
         let updated_at = thread.updated_at.to_rfc3339();
         let parent_id = thread
@@ -414,9 +509,215 @@ impl ThreadsDatabase {
 
         insert((id.0, parent_id, title, updated_at, data_type, data))?;
 
+        Self::index_thread_sync(&connection, search_entry)
+    }
+
+    fn index_thread_sync(connection: &Connection, entry: ThreadSearchEntry) -> Result<()> {
+        connection.with_savepoint("index_thread", || {
+            let source = entry.source.to_string();
+            Self::remove_from_search_index_sync(
+                connection,
+                source.clone(),
+                Some(entry.thread_id.clone()),
+            )?;
+
+            let mut insert_thread =
+                connection.exec_bound::<(String, Arc<str>, String, String)>(indoc! {"
+                    INSERT INTO thread_search_index (source, thread_id, title, updated_at)
+                    VALUES (?, ?, ?, ?)
+                "})?;
+            insert_thread((
+                source.clone(),
+                entry.thread_id.clone(),
+                entry.title.to_string(),
+                entry.updated_at.to_rfc3339(),
+            ))?;
+
+            let mut insert_document = connection
+                .exec_bound::<(String, Arc<str>, usize, String, String)>(indoc! {"
+                    INSERT INTO thread_search (source, thread_id, turn, body, paths)
+                    VALUES (?, ?, ?, ?, ?)
+                "})?;
+            for document in entry.documents {
+                insert_document((
+                    source.clone(),
+                    entry.thread_id.clone(),
+                    document.turn,
+                    document.body,
+                    document.paths.join("\n"),
+                ))?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn remove_from_search_index_sync(
+        connection: &Connection,
+        source: String,
+        thread_id: Option<Arc<str>>,
+    ) -> Result<()> {
+        let mut delete_documents = connection
+            .exec_bound::<(String, Option<Arc<str>>, Option<Arc<str>>)>(indoc! {"
+                DELETE FROM thread_search WHERE source = ? AND (? IS NULL OR thread_id = ?)
+            "})?;
+        delete_documents((source.clone(), thread_id.clone(), thread_id.clone()))?;
+
+        let mut delete_thread = connection
+            .exec_bound::<(String, Option<Arc<str>>, Option<Arc<str>>)>(indoc! {"
+                DELETE FROM thread_search_index WHERE source = ? AND (? IS NULL OR thread_id = ?)
+            "})?;
+        delete_thread((source, thread_id.clone(), thread_id))?;
+
         Ok(())
     }
 
+    /// Indexes the threads that were saved before search was supported.
+    pub fn index_unindexed_threads(&self) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let ids = {
+                let connection = connection.lock();
+                let mut select = connection.select_bound::<String, Arc<str>>(indoc! {"
+                    SELECT id FROM threads
+                    WHERE id NOT IN (SELECT thread_id FROM thread_search_index WHERE source = ?)
+                "})?;
+                select(native_search_source().to_string())?
+            };
+
+            for id in ids {
+                let connection = connection.lock();
+                let Some(thread) = Self::load_thread_sync(&connection, id.clone())? else {
+                    continue;
+                };
+                Self::index_thread_sync(
+                    &connection,
+                    ThreadSearchEntry {
+                        source: native_search_source(),
+                        thread_id: id,
+                        title: thread.title.clone(),
+                        updated_at: thread.updated_at,
+                        documents: thread.search_documents(),
+                    },
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    pub fn index_thread(&self, entry: ThreadSearchEntry) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor
+            .spawn(async move { Self::index_thread_sync(&connection.lock(), entry) })
+    }
+
+    pub fn remove_from_search_index(
+        &self,
+        source: SharedString,
+        thread_id: Option<Arc<str>>,
+    ) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            connection.with_savepoint("remove_from_search_index", || {
+                Self::remove_from_search_index_sync(&connection, source.to_string(), thread_id)
+            })
+        })
+    }
+
+    pub fn indexed_threads(
+        &self,
+        source: SharedString,
+    ) -> Task<Result<HashMap<Arc<str>, DateTime<Utc>>>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select = connection.select_bound::<String, (Arc<str>, String)>(indoc! {"
+                SELECT thread_id, updated_at FROM thread_search_index WHERE source = ?
+            "})?;
+
+            let mut threads = HashMap::default();
+            for (thread_id, updated_at) in select(source.to_string())? {
+                threads.insert(
+                    thread_id,
+                    DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+                );
+            }
+
+            Ok(threads)
+        })
+    }
+
+    /// Returns the turns matching an FTS5 query, best matches first.
+    pub fn search_threads(
+        &self,
+        query: String,
+        source: Option<SharedString>,
+        limit: usize,
+    ) -> Task<Result<Vec<ThreadSearchMatch>>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let source = source.map(|source| source.to_string());
+            let mut select = connection.select_bound::<(
+                String,
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                usize,
+            ), (String, Arc<str>, usize, String, String, String)>(
+                indoc! {"
+                SELECT
+                    thread_search.source,
+                    thread_search.thread_id,
+                    thread_search.turn,
+                    thread_search_index.title,
+                    thread_search_index.updated_at,
+                    snippet(thread_search, -1, ?, ?, '…', 16)
+                FROM thread_search
+                JOIN thread_search_index
+                    ON thread_search_index.source = thread_search.source
+                    AND thread_search_index.thread_id = thread_search.thread_id
+                WHERE thread_search MATCH ? AND (? IS NULL OR thread_search.source = ?)
+                ORDER BY bm25(thread_search, 0.0, 0.0, 0.0, 1.0, 2.0)
+                LIMIT ?
+            "}
+            )?;
+
+            let rows = select((
+                SNIPPET_MATCH_START.to_string(),
+                SNIPPET_MATCH_END.to_string(),
+                query,
+                source.clone(),
+                source,
+                limit,
+            ))?;
+
+            let mut matches = Vec::with_capacity(rows.len());
+            for (source, thread_id, turn, title, updated_at, snippet) in rows {
+                let (snippet, highlights) = parse_snippet(&snippet);
+                matches.push(ThreadSearchMatch {
+                    source: source.into(),
+                    thread_id,
+                    title: title.into(),
+                    updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+                    turn,
+                    snippet: snippet.into(),
+                    highlights,
+                });
+            }
+
+            Ok(matches)
+        })
+    }
+
     pub fn list_threads(&self) -> Task<Result<Vec<DbThreadMetadata>>> {
         let connection = self.connection.clone();
 
@@ -447,27 +748,29 @@ impl ThreadsDatabase {
     pub fn load_thread(&self, id: acp::SessionId) -> Task<Result<Option<DbThread>>> {
         let connection = self.connection.clone();
 
-        self.executor.spawn(async move {
-            let connection = connection.lock();
-            let mut select = connection.select_bound::<Arc<str>, (DataType, Vec<u8>)>(indoc! {"
-                SELECT data_type, data FROM threads WHERE id = ? LIMIT 1
-            "})?;
+        self.executor
+            .spawn(async move { Self::load_thread_sync(&connection.lock(), id.0) })
+    }
 
-            let rows = select(id.0)?;
-            if let Some((data_type, data)) = rows.into_iter().next() {
-                let json_data = match data_type {
-                    DataType::Zstd => {
-                        let decompressed = zstd::decode_all(&data[..])?;
-                        String::from_utf8(decompressed)?
-                    }
-                    DataType::Json => String::from_utf8(data)?,
-                };
-                let thread = DbThread::from_json(json_data.as_bytes())?;
-                Ok(Some(thread))
-            } else {
-                Ok(None)
-            }
-        })
+    fn load_thread_sync(connection: &Connection, id: Arc<str>) -> Result<Option<DbThread>> {
+        let mut select = connection.select_bound::<Arc<str>, (DataType, Vec<u8>)>(indoc! {"
+            SELECT data_type, data FROM threads WHERE id = ? LIMIT 1
+        "})?;
+
+        let rows = select(id)?;
+        if let Some((data_type, data)) = rows.into_iter().next() {
+            let json_data = match data_type {
+                DataType::Zstd => {
+                    let decompressed = zstd::decode_all(&data[..])?;
+                    String::from_utf8(decompressed)?
+                }
+                DataType::Json => String::from_utf8(data)?,
+            };
+            let thread = DbThread::from_json(json_data.as_bytes())?;
+            Ok(Some(thread))
+        } else {
+            Ok(None)
+        }
     }
 
     pub fn save_thread(&self, id: acp::SessionId, thread: DbThread) -> Task<Result<()>> {
@@ -487,12 +790,127 @@ impl ThreadsDatabase {
                 DELETE FROM threads WHERE id = ?
             "})?;
 
-            delete(id.0)?;
+            delete(id.0.clone())?;
+
+            Self::remove_from_search_index_sync(
+                &connection,
+                native_search_source().to_string(),
+                Some(id.0),
+            )
+        })
+    }
+
+    /// Appends entries to the audit log. Unlike threads, they're kept when history is cleared.
+    pub fn save_audit_entries(&self, entries: Vec<AuditEntry>) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+
+            let mut insert = connection.exec_bound::<(
+                Arc<str>,
+                String,
+                String,
+                &'static str,
+                String,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            )>(indoc! {"
+                INSERT INTO audit_log (session_id, agent, timestamp, action, description, path, rule, outcome)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "})?;
+
+            for entry in entries {
+                insert((
+                    entry.session_id.0,
+                    entry.agent.to_string(),
+                    audit_timestamp(entry.timestamp),
+                    entry.action.name(),
+                    entry.description,
+                    entry.path.map(|path| path.to_string_lossy().into_owned()),
+                    entry.rule,
+                    entry.outcome,
+                ))?;
+            }
 
             Ok(())
         })
     }
 
+    /// Returns the newest audit log entries matching the filter, newest first.
+    pub fn audit_entries(&self, filter: AuditFilter) -> Task<Result<Vec<AuditEntry>>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+
+            let agent = filter.agent;
+            let path = filter.path;
+            let since = filter.since.map(audit_timestamp);
+            let until = filter.until.map(audit_timestamp);
+            let mut select = connection.select_bound::<(
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                i64,
+            ), (
+                Arc<str>,
+                String,
+                String,
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            )>(indoc! {"
+                SELECT session_id, agent, timestamp, action, description, path, rule, outcome
+                FROM audit_log
+                WHERE (? IS NULL OR agent = ?)
+                    AND (? IS NULL OR instr(path, ?) > 0)
+                    AND (? IS NULL OR timestamp >= ?)
+                    AND (? IS NULL OR timestamp < ?)
+                ORDER BY timestamp DESC, id DESC
+                LIMIT ?
+            "})?;
+
+            let rows = select((
+                agent.clone(),
+                agent,
+                path.clone(),
+                path,
+                since.clone(),
+                since,
+                until.clone(),
+                until,
+                // SQLite returns every row for a negative limit.
+                filter.limit.map_or(-1, |limit| limit as i64),
+            ))?;
+
+            let mut entries = Vec::with_capacity(rows.len());
+            for (session_id, agent, timestamp, action, description, path, rule, outcome) in rows {
+                entries.push(AuditEntry {
+                    session_id: acp::SessionId::new(session_id),
+                    agent: agent.into(),
+                    timestamp: DateTime::parse_from_rfc3339(&timestamp)?.with_timezone(&Utc),
+                    action: AuditAction::from_name(&action)
+                        .ok_or_else(|| anyhow!("Unknown audit action: {}", action))?,
+                    description,
+                    path: path.map(PathBuf::from),
+                    rule,
+                    outcome,
+                });
+            }
+
+            Ok(entries)
+        })
+    }
+
     pub fn delete_threads(&self) -> Task<Result<()>> {
         let connection = self.connection.clone();
 
@@ -505,11 +923,20 @@ impl ThreadsDatabase {
 
             delete(())?;
 
-            Ok(())
+            Self::remove_from_search_index_sync(
+                &connection,
+                native_search_source().to_string(),
+                None,
+            )
         })
     }
 }
 
+/// Formats timestamps so that they sort in chronological order.
+fn audit_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -573,6 +1000,124 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fork_keeps_messages_before_the_fork_point() {
+        let user_message = |id: &UserMessageId, text: &str| {
+            DbMessage::User(UserMessage {
+                id: id.clone(),
+                content: vec![UserMessageContent::Text(text.into())],
+            })
+        };
+        let first_id = UserMessageId::new();
+        let second_id = UserMessageId::new();
+        let mut thread = make_thread("Thread", Utc::now());
+        thread.messages = vec![
+            user_message(&first_id, "first"),
+            DbMessage::Resume,
+            user_message(&second_id, "second"),
+            DbMessage::Resume,
+        ];
+        thread
+            .request_token_usage
+            .insert(first_id.clone(), Default::default());
+        thread
+            .request_token_usage
+            .insert(second_id.clone(), Default::default());
+
+        let fork = thread.fork(&second_id).unwrap();
+        assert_eq!(fork.title, "Thread (fork)");
+        assert_eq!(fork.messages.len(), 2);
+        assert!(matches!(&fork.messages[0], DbMessage::User(message) if message.id == first_id));
+        assert_eq!(
+            fork.request_token_usage.keys().collect::<Vec<_>>(),
+            vec![&first_id]
+        );
+
+        let thread = make_thread("Thread", Utc::now());
+        assert!(thread.fork(&first_id).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_search_threads_matches_messages_and_tool_calls(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+        let tool_use_id = language_model::LanguageModelToolUseId::from("tool-1");
+        let mut thread = make_thread("Parser work", Utc::now());
+        thread.messages = vec![
+            DbMessage::User(UserMessage {
+                id: UserMessageId::new(),
+                content: vec![UserMessageContent::Text("Hello".into())],
+            }),
+            DbMessage::User(UserMessage {
+                id: UserMessageId::new(),
+                content: vec![UserMessageContent::Text(
+                    "Why does the parser panic?".into(),
+                )],
+            }),
+            DbMessage::Agent(AgentMessage {
+                content: vec![AgentMessageContent::ToolUse(
+                    language_model::LanguageModelToolUse {
+                        id: tool_use_id.clone(),
+                        name: "read_file".into(),
+                        raw_input: r#"{"path":"src/lexer.rs"}"#.into(),
+                        input: serde_json::json!({ "path": "src/lexer.rs" }),
+                        is_input_complete: true,
+                        thought_signature: None,
+                    },
+                )],
+                tool_results: IndexMap::from_iter([(
+                    tool_use_id.clone(),
+                    language_model::LanguageModelToolResult {
+                        tool_use_id,
+                        tool_name: "read_file".into(),
+                        is_error: false,
+                        content: language_model::LanguageModelToolResultContent::Text(
+                            "fn tokenize_identifiers() {}".into(),
+                        ),
+                        output: None,
+                    },
+                )]),
+                reasoning_details: None,
+            }),
+        ];
+        database
+            .save_thread(session_id("thread-a"), thread)
+            .await
+            .unwrap();
+
+        let matches = database
+            .search_threads("\"tokenize\"*".into(), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].thread_id.as_ref(), "thread-a");
+        assert_eq!(matches[0].title, "Parser work");
+        assert_eq!(matches[0].turn, 1);
+        let highlight = matches[0].highlights[0].clone();
+        assert_eq!(&matches[0].snippet[highlight], "tokenize");
+
+        let matches = database
+            .search_threads("\"lexer\"".into(), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let matches = database
+            .search_threads("\"parser\"".into(), Some("Other Agent".into()), 10)
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+
+        database
+            .delete_thread(session_id("thread-a"))
+            .await
+            .unwrap();
+        let matches = database
+            .search_threads("\"parser\"".into(), None, 10)
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+    }
+
     #[gpui::test]
     async fn test_list_threads_orders_by_updated_at(cx: &mut TestAppContext) {
         let database = ThreadsDatabase::new(cx.executor()).unwrap();
@@ -713,4 +1258,110 @@ mod tests {
             "Regular threads should have no subagent_context"
         );
     }
+
+    #[gpui::test]
+    async fn test_audit_entries_are_filtered(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+
+        let entry = |agent: &str, hour, action, path: Option<&str>| {
+            let mut entry = AuditEntry::new(
+                session_id("thread-a"),
+                agent.to_string().into(),
+                action,
+                format!("{agent} at {hour}"),
+            );
+            entry.timestamp = Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+            if let Some(path) = path {
+                entry = entry.path(path);
+            }
+            entry
+        };
+        database
+            .save_audit_entries(vec![
+                entry(
+                    "zed",
+                    1,
+                    AuditAction::FileWrite,
+                    Some("/project/src/main.rs"),
+                ),
+                entry(
+                    "claude-code",
+                    2,
+                    AuditAction::TerminalCommand,
+                    Some("/project"),
+                ),
+                entry("zed", 3, AuditAction::ToolCall, None).rule("edit_file.default: Allow"),
+            ])
+            .await
+            .unwrap();
+
+        let descriptions = |filter| {
+            let entries = database.audit_entries(filter);
+            async move {
+                entries
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|entry| entry.description)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            descriptions(AuditFilter::default()).await,
+            vec!["zed at 3", "claude-code at 2", "zed at 1"]
+        );
+        assert_eq!(
+            descriptions(AuditFilter {
+                agent: Some("zed".into()),
+                ..Default::default()
+            })
+            .await,
+            vec!["zed at 3", "zed at 1"]
+        );
+        assert_eq!(
+            descriptions(AuditFilter {
+                path: Some("src/".into()),
+                ..Default::default()
+            })
+            .await,
+            vec!["zed at 1"]
+        );
+        assert_eq!(
+            descriptions(AuditFilter {
+                since: Some(Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap()),
+                until: Some(Utc.with_ymd_and_hms(2024, 1, 1, 3, 0, 0).unwrap()),
+                ..Default::default()
+            })
+            .await,
+            vec!["claude-code at 2"]
+        );
+        assert_eq!(
+            descriptions(AuditFilter {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await,
+            vec!["zed at 3"]
+        );
+        assert_eq!(
+            descriptions(AuditFilter {
+                limit: None,
+                ..Default::default()
+            })
+            .await,
+            vec!["zed at 3", "claude-code at 2", "zed at 1"]
+        );
+
+        let entries = database
+            .audit_entries(AuditFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(entries[0].action, AuditAction::ToolCall);
+        assert_eq!(entries[0].rule.as_deref(), Some("edit_file.default: Allow"));
+        assert_eq!(
+            entries[2].path.as_deref(),
+            Some(std::path::Path::new("/project/src/main.rs"))
+        );
+    }
 }
@@ -19,6 +19,8 @@ pub struct NativeAgentServer {
 }
 
 impl NativeAgentServer {
+    pub const NAME: &str = "Zed Agent";
+
     pub fn new(fs: Arc<dyn Fs>, thread_store: Entity<ThreadStore>) -> Self {
         Self { fs, thread_store }
     }
@@ -26,7 +28,7 @@ impl NativeAgentServer {
 
 impl AgentServer for NativeAgentServer {
     fn name(&self) -> SharedString {
-        "Zed Agent".into()
+        Self::NAME.into()
     }
 
     fn logo(&self) -> ui::IconName {
@@ -2492,6 +2492,7 @@ async fn test_truncate_first_message(cx: &mut TestAppContext) {
         language_model::TokenUsage {
             input_tokens: 32_000,
             output_tokens: 16_000,
+            request_id: None,
             cache_creation_input_tokens: 0,
             cache_read_input_tokens: 0,
         },
@@ -2517,6 +2518,7 @@ async fn test_truncate_first_message(cx: &mut TestAppContext) {
                 max_tokens: 1_000_000,
                 input_tokens: 32_000,
                 output_tokens: 16_000,
+                request_id: None,
             })
         );
     });
@@ -2552,6 +2554,7 @@ async fn test_truncate_first_message(cx: &mut TestAppContext) {
         language_model::TokenUsage {
             input_tokens: 40_000,
             output_tokens: 20_000,
+            request_id: None,
             cache_creation_input_tokens: 0,
             cache_read_input_tokens: 0,
         },
@@ -2578,6 +2581,7 @@ async fn test_truncate_first_message(cx: &mut TestAppContext) {
                 max_tokens: 1_000_000,
                 input_tokens: 40_000,
                 output_tokens: 20_000,
+                request_id: None,
             })
         );
     });
@@ -2599,6 +2603,7 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
         language_model::TokenUsage {
             input_tokens: 32_000,
             output_tokens: 16_000,
+            request_id: None,
             cache_creation_input_tokens: 0,
             cache_read_input_tokens: 0,
         },
@@ -2628,6 +2633,7 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
                     max_tokens: 1_000_000,
                     input_tokens: 32_000,
                     output_tokens: 16_000,
+                    request_id: None,
                 })
             );
         });
@@ -2648,6 +2654,7 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
         language_model::TokenUsage {
             input_tokens: 40_000,
             output_tokens: 20_000,
+            request_id: None,
             cache_creation_input_tokens: 0,
             cache_read_input_tokens: 0,
         },
@@ -2684,6 +2691,7 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
                 max_tokens: 1_000_000,
                 input_tokens: 40_000,
                 output_tokens: 20_000,
+                request_id: None,
             })
         );
     });
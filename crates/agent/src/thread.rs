@@ -4,9 +4,9 @@ use crate::{
     ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
     RestoreFileFromDiskTool, SaveFileTool, StreamingEditFileTool, SubagentTool,
     SystemPromptTemplate, Template, Templates, TerminalTool, ToolPermissionDecision, WebSearchTool,
-    decide_permission_from_settings,
+    decide_permission_from_settings, decision_rule,
 };
-use acp_thread::{MentionUri, UserMessageId};
+use acp_thread::{AuditAction, AuditLog, AuditRecorder, MentionUri, UserMessageId};
 use action_log::ActionLog;
 use feature_flags::{FeatureFlagAppExt as _, SubagentsFeatureFlag};
 
@@ -815,6 +815,8 @@ pub struct Thread {
     pending_message: Option<AgentMessage>,
     pub(crate) tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
     request_token_usage: HashMap<UserMessageId, language_model::TokenUsage>,
+    /// Identifies the completion request that is streaming in the usage it reports.
+    completion_request_id: u64,
     #[allow(unused)]
     cumulative_token_usage: TokenUsage,
     #[allow(unused)]
@@ -906,6 +908,7 @@ impl Thread {
             pending_message: None,
             tools: BTreeMap::default(),
             request_token_usage: HashMap::default(),
+            completion_request_id: 0,
             cumulative_token_usage: TokenUsage::default(),
             initial_project_snapshot: {
                 let project_snapshot = Self::project_snapshot(project.clone(), cx);
@@ -1028,6 +1031,7 @@ impl Thread {
                 stream.clone(),
                 Some(self.project.read(cx).fs().clone()),
                 cancellation_rx,
+                None,
             );
             tool.replay(tool_use.input.clone(), output, tool_event_stream, cx)
                 .log_err();
@@ -1122,6 +1126,7 @@ impl Thread {
             pending_message: None,
             tools: BTreeMap::default(),
             request_token_usage: db_thread.request_token_usage.clone(),
+            completion_request_id: 0,
             cumulative_token_usage: db_thread.cumulative_token_usage,
             initial_project_snapshot: Task::ready(db_thread.initial_project_snapshot).shared(),
             context_server_registry,
@@ -1425,7 +1430,13 @@ impl Thread {
 
         self.request_token_usage
             .insert(last_user_message.id.clone(), update);
-        cx.emit(TokenUsageUpdated(self.latest_token_usage()));
+        let usage = self
+            .latest_token_usage()
+            .map(|usage| acp_thread::TokenUsage {
+                request_id: Some(self.completion_request_id),
+                ..usage
+            });
+        cx.emit(TokenUsageUpdated(usage));
         cx.notify();
     }
 
@@ -1467,6 +1478,7 @@ impl Thread {
             used_tokens: usage.total_tokens(),
             input_tokens: usage.input_tokens,
             output_tokens: usage.output_tokens,
+            request_id: None,
         })
     }
 
@@ -1696,8 +1708,10 @@ impl Thread {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
         loop {
-            let request =
-                this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
+            let request = this.update(cx, |this, cx| {
+                this.completion_request_id += 1;
+                this.build_completion_request(intent, cx)
+            })??;
 
             telemetry::event!(
                 "Agent Thread Completion",
@@ -2095,6 +2109,7 @@ impl Thread {
             event_stream.clone(),
             Some(fs),
             cancellation_rx,
+            AuditLog::recorder(self.id.clone(), "zed".into(), cx),
         );
         tool_event_stream.update_fields(
             acp::ToolCallUpdateFields::new().status(acp::ToolCallStatus::InProgress),
@@ -2991,6 +3006,7 @@ pub struct ToolCallEventStream {
     stream: ThreadEventStream,
     fs: Option<Arc<dyn Fs>>,
     cancellation_rx: watch::Receiver<bool>,
+    audit: Option<AuditRecorder>,
 }
 
 impl ToolCallEventStream {
@@ -3010,6 +3026,7 @@ impl ToolCallEventStream {
             ThreadEventStream(events_tx),
             None,
             cancellation_rx,
+            None,
         );
 
         (
@@ -3030,15 +3047,47 @@ impl ToolCallEventStream {
         stream: ThreadEventStream,
         fs: Option<Arc<dyn Fs>>,
         cancellation_rx: watch::Receiver<bool>,
+        audit: Option<AuditRecorder>,
     ) -> Self {
         Self {
             tool_use_id,
             stream,
             fs,
             cancellation_rx,
+            audit,
         }
     }
 
+    /// Records a permission decision that was made without asking the user in the audit log.
+    /// Decisions the user makes are recorded by the `AcpThread` when they answer.
+    pub fn audit_permission_decision(
+        &self,
+        tool_name: &str,
+        inputs: &[String],
+        decision: &ToolPermissionDecision,
+        cx: &App,
+    ) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let outcome = match decision {
+            ToolPermissionDecision::Allow => "allowed",
+            ToolPermissionDecision::Deny(_) => "denied",
+            ToolPermissionDecision::Confirm => return,
+        };
+        let settings = AgentSettings::get_global(cx);
+        let rule = decision_rule(tool_name, inputs, decision, &settings.tool_permissions);
+        audit.record(
+            audit
+                .entry(
+                    AuditAction::PermissionDecision,
+                    format!("{tool_name}: {}", inputs.join(", ")),
+                )
+                .rule(rule)
+                .outcome(outcome),
+        );
+    }
+
     /// Returns a future that resolves when the user cancels the tool call.
     /// Tools should select on this alongside their main work to detect user cancellation.
     pub fn cancelled_by_user(&self) -> impl std::future::Future<Output = ()> + '_ {
@@ -3119,6 +3168,7 @@ impl ToolCallEventStream {
         let settings = agent_settings::AgentSettings::get_global(cx);
 
         let decision = decide_permission_from_settings(&tool_id, &[String::new()], &settings);
+        self.audit_permission_decision(&tool_id, &[String::new()], &decision, cx);
 
         match decision {
             ToolPermissionDecision::Allow => return Task::ready(Ok(())),
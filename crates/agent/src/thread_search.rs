@@ -0,0 +1,324 @@
+use crate::{
+    AgentMessageContent, DbMessage, DbThread, NativeAgentServer, ThreadsDatabase,
+    UserMessageContent,
+};
+use acp_thread::{AcpThread, AgentThreadEntry, AssistantMessageChunk, MentionUri};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use collections::HashMap;
+use gpui::{App, SharedString, Task};
+use language_model::LanguageModelToolResultContent;
+use std::{ops::Range, sync::Arc};
+
+/// Marks the start of a highlighted match in snippets returned by the database.
+pub(crate) const SNIPPET_MATCH_START: char = '\u{1}';
+/// Marks the end of a highlighted match in snippets returned by the database.
+pub(crate) const SNIPPET_MATCH_END: char = '\u{2}';
+
+/// The searchable text of one turn: a user message and everything the agent did in response.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThreadSearchDocument {
+    /// How many user messages come before this one in the thread.
+    pub turn: usize,
+    pub body: String,
+    /// Files the turn mentioned or that its tool calls touched.
+    pub paths: Vec<String>,
+}
+
+impl ThreadSearchDocument {
+    pub fn new(turn: usize) -> Self {
+        Self {
+            turn,
+            ..Default::default()
+        }
+    }
+
+    /// Appends text to the body, on its own line.
+    pub fn push_text(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if !self.body.is_empty() {
+            self.body.push('\n');
+        }
+        self.body.push_str(text);
+    }
+
+    fn push_path(&mut self, path: String) {
+        if !self.paths.contains(&path) {
+            self.paths.push(path);
+        }
+    }
+
+    fn push_tool_input(&mut self, input: &serde_json::Value) {
+        let Some(input) = input.as_object() else {
+            return;
+        };
+        for (key, value) in input {
+            if key.ends_with("path")
+                && let Some(path) = value.as_str()
+            {
+                self.push_path(path.to_string());
+            }
+        }
+    }
+
+    fn push_mention(&mut self, uri: &MentionUri) {
+        self.push_text(&uri.name());
+        match uri {
+            MentionUri::File { abs_path }
+            | MentionUri::Directory { abs_path }
+            | MentionUri::Symbol { abs_path, .. }
+            | MentionUri::Selection {
+                abs_path: Some(abs_path),
+                ..
+            } => self.push_path(abs_path.to_string_lossy().into_owned()),
+            _ => {}
+        }
+    }
+}
+
+/// A thread to add to the search index, replacing what was indexed for it before.
+#[derive(Clone, Debug)]
+pub struct ThreadSearchEntry {
+    /// The name of the agent the thread belongs to.
+    pub source: SharedString,
+    pub thread_id: Arc<str>,
+    pub title: SharedString,
+    pub updated_at: DateTime<Utc>,
+    pub documents: Vec<ThreadSearchDocument>,
+}
+
+/// A turn of a thread that matches a search query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThreadSearchMatch {
+    pub source: SharedString,
+    pub thread_id: Arc<str>,
+    pub title: SharedString,
+    pub updated_at: DateTime<Utc>,
+    pub turn: usize,
+    /// An excerpt of the turn around the match.
+    pub snippet: SharedString,
+    /// Byte ranges of the matched terms in the snippet.
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl DbThread {
+    /// Splits the thread into turns for the search index.
+    pub fn search_documents(&self) -> Vec<ThreadSearchDocument> {
+        let mut documents: Vec<ThreadSearchDocument> = Vec::new();
+        for message in &self.messages {
+            match message {
+                DbMessage::User(message) => {
+                    let mut document = ThreadSearchDocument::new(documents.len());
+                    for content in &message.content {
+                        match content {
+                            UserMessageContent::Text(text) => document.push_text(text),
+                            UserMessageContent::Mention { uri, .. } => document.push_mention(uri),
+                            UserMessageContent::Image(_) => {}
+                        }
+                    }
+                    documents.push(document);
+                }
+                DbMessage::Agent(message) => {
+                    let Some(document) = documents.last_mut() else {
+                        continue;
+                    };
+                    for content in &message.content {
+                        match content {
+                            AgentMessageContent::Text(text)
+                            | AgentMessageContent::Thinking { text, .. } => {
+                                document.push_text(text)
+                            }
+                            AgentMessageContent::RedactedThinking(_) => {}
+                            AgentMessageContent::ToolUse(tool_use) => {
+                                document.push_text(&tool_use.name);
+                                document.push_text(&tool_use.raw_input);
+                                document.push_tool_input(&tool_use.input);
+                            }
+                        }
+                    }
+                    for result in message.tool_results.values() {
+                        if let LanguageModelToolResultContent::Text(text) = &result.content {
+                            document.push_text(text);
+                        }
+                    }
+                }
+                DbMessage::Resume => {}
+            }
+        }
+        documents
+    }
+}
+
+/// Splits a thread of any agent into turns for the search index.
+pub fn acp_thread_search_documents(thread: &AcpThread, cx: &App) -> Vec<ThreadSearchDocument> {
+    let mut documents: Vec<ThreadSearchDocument> = Vec::new();
+    for entry in thread.entries() {
+        match entry {
+            AgentThreadEntry::UserMessage(message) => {
+                let mut document = ThreadSearchDocument::new(documents.len());
+                for chunk in &message.chunks {
+                    match chunk {
+                        acp::ContentBlock::Text(text) => document.push_text(&text.text),
+                        acp::ContentBlock::ResourceLink(acp::ResourceLink { uri, .. })
+                        | acp::ContentBlock::Resource(acp::EmbeddedResource {
+                            resource:
+                                acp::EmbeddedResourceResource::TextResourceContents(
+                                    acp::TextResourceContents { uri, .. },
+                                ),
+                            ..
+                        }) => {
+                            match MentionUri::parse(uri, thread.project().read(cx).path_style(cx)) {
+                                Ok(uri) => document.push_mention(&uri),
+                                Err(_) => document.push_text(uri),
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                documents.push(document);
+            }
+            AgentThreadEntry::AssistantMessage(message) => {
+                let Some(document) = documents.last_mut() else {
+                    continue;
+                };
+                for chunk in &message.chunks {
+                    let (AssistantMessageChunk::Message { block }
+                    | AssistantMessageChunk::Thought { block }) = chunk;
+                    document.push_text(block.to_markdown(cx));
+                }
+            }
+            AgentThreadEntry::ToolCall(tool_call) => {
+                let Some(document) = documents.last_mut() else {
+                    continue;
+                };
+                document.push_text(tool_call.label.read(cx).source());
+                if let Some(raw_input) = &tool_call.raw_input {
+                    document.push_text(&raw_input.to_string());
+                    document.push_tool_input(raw_input);
+                }
+                for content in &tool_call.content {
+                    document.push_text(&content.to_markdown(cx));
+                }
+                for location in &tool_call.locations {
+                    document.push_path(location.path.to_string_lossy().into_owned());
+                }
+            }
+        }
+    }
+    documents
+}
+
+/// Adds a thread to the search index.
+pub fn index_thread(entry: ThreadSearchEntry, cx: &mut App) -> Task<Result<()>> {
+    let database_future = ThreadsDatabase::connect(cx);
+    cx.background_spawn(async move {
+        let database = database_future.await.map_err(|err| anyhow!(err))?;
+        database.index_thread(entry).await
+    })
+}
+
+/// Removes a thread from the search index, or every thread of the source if `thread_id` is `None`.
+pub fn remove_from_search_index(
+    source: SharedString,
+    thread_id: Option<Arc<str>>,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    let database_future = ThreadsDatabase::connect(cx);
+    cx.background_spawn(async move {
+        let database = database_future.await.map_err(|err| anyhow!(err))?;
+        database.remove_from_search_index(source, thread_id).await
+    })
+}
+
+/// Returns when each of the source's threads was last indexed, to find the ones that are stale.
+pub fn indexed_threads(
+    source: SharedString,
+    cx: &mut App,
+) -> Task<Result<HashMap<Arc<str>, DateTime<Utc>>>> {
+    let database_future = ThreadsDatabase::connect(cx);
+    cx.background_spawn(async move {
+        let database = database_future.await.map_err(|err| anyhow!(err))?;
+        database.indexed_threads(source).await
+    })
+}
+
+/// Searches the messages, tool calls, and paths of past threads, best matches first.
+pub fn search_threads(
+    query: &str,
+    source: Option<SharedString>,
+    limit: usize,
+    cx: &mut App,
+) -> Task<Result<Vec<ThreadSearchMatch>>> {
+    let Some(query) = fts_query(query) else {
+        return Task::ready(Ok(Vec::new()));
+    };
+    let database_future = ThreadsDatabase::connect(cx);
+    cx.background_spawn(async move {
+        let database = database_future.await.map_err(|err| anyhow!(err))?;
+        database.search_threads(query, source, limit).await
+    })
+}
+
+pub(crate) fn native_search_source() -> SharedString {
+    NativeAgentServer::NAME.into()
+}
+
+/// Turns what the user typed into an FTS5 query that matches all of its words, treating the last
+/// one as a prefix since the user may still be typing it.
+fn fts_query(query: &str) -> Option<String> {
+    let terms = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", terms.join(" ")))
+}
+
+/// Removes the match markers from a snippet, returning where they were.
+pub(crate) fn parse_snippet(snippet: &str) -> (String, Vec<Range<usize>>) {
+    let mut text = String::with_capacity(snippet.len());
+    let mut highlights = Vec::new();
+    let mut start = None;
+    for char in snippet.chars() {
+        match char {
+            SNIPPET_MATCH_START => start = Some(text.len()),
+            SNIPPET_MATCH_END => {
+                if let Some(start) = start.take() {
+                    highlights.push(start..text.len());
+                }
+            }
+            '\n' => text.push(' '),
+            _ => text.push(char),
+        }
+    }
+    (text, highlights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(fts_query("parse"), Some("\"parse\"*".into()));
+        assert_eq!(
+            fts_query("fix \"json\" pars"),
+            Some("\"fix\" \"\"\"json\"\"\" \"pars\"*".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_snippet() {
+        let (text, highlights) = parse_snippet("…the \u{1}parser\u{2}\nfailed on \u{1}json\u{2}");
+        assert_eq!(text, "…the parser failed on json");
+        assert_eq!(&text[highlights[0].clone()], "parser");
+        assert_eq!(&text[highlights[1].clone()], "json");
+    }
+}
@@ -1,5 +1,8 @@
 use crate::AgentTool;
-use crate::tools::TerminalTool;
+use crate::tools::{
+    DeletePathTool, EditFileTool, FetchTool, GrepTool, MovePathTool, ReadFileTool, TerminalTool,
+};
+use agent_client_protocol as acp;
 use agent_settings::{AgentSettings, CompiledRegex, ToolPermissions, ToolRules};
 use settings::ToolPermissionMode;
 use shell_command_parser::extract_commands;
@@ -498,6 +501,42 @@ pub fn decide_permission_for_paths(
     most_restrictive(raw_decision, normalized_decision)
 }
 
+/// Describes the setting that made [`ToolPermissionDecision::from_input`] reach a decision
+/// without asking the user, for the audit log. Denials describe themselves in their reason.
+pub fn decision_rule(
+    tool_name: &str,
+    inputs: &[String],
+    decision: &ToolPermissionDecision,
+    permissions: &ToolPermissions,
+) -> String {
+    if let ToolPermissionDecision::Deny(reason) = decision {
+        return reason.clone();
+    }
+
+    let Some(rules) = permissions.tools.get(tool_name) else {
+        return format!("tool_permissions.default: {}", permissions.default);
+    };
+    let patterns = match decision {
+        ToolPermissionDecision::Confirm => &rules.always_confirm,
+        _ => &rules.always_allow,
+    };
+    let kind = match decision {
+        ToolPermissionDecision::Confirm => "always_confirm",
+        _ => "always_allow",
+    };
+    if let Some(regex) = patterns
+        .iter()
+        .find(|regex| inputs.iter().any(|input| regex.is_match(input)))
+    {
+        return format!("{tool_name}.{kind}: {}", regex.pattern);
+    }
+
+    match rules.default {
+        Some(mode) => format!("{tool_name}.default: {mode}"),
+        None => format!("tool_permissions.default: {}", permissions.default),
+    }
+}
+
 pub fn decide_permission_for_path(
     tool_name: &str,
     raw_path: &str,
@@ -520,6 +559,155 @@ pub fn most_restrictive(
     }
 }
 
+/// Decides an external agent's permission request using the rules of the native tool that does
+/// the same kind of work, e.g. the `terminal` rules for `acp::ToolKind::Execute`. This subjects
+/// every agent to the same `tool_permissions`, including the hardcoded security rules.
+///
+/// Requests that can't be mapped to a tool, or whose input can't be found in the tool call, are
+/// always confirmed with the user.
+pub fn decide_permission_for_acp_tool_call(
+    agent_name: &str,
+    tool_call: &acp::ToolCallUpdateFields,
+    settings: &AgentSettings,
+) -> ToolPermissionDecision {
+    let Some((tool_name, inputs)) = acp_tool_inputs(tool_call) else {
+        return ToolPermissionDecision::Confirm;
+    };
+
+    let permissions = settings.tool_permissions_for_agent(agent_name);
+    let decision =
+        ToolPermissionDecision::from_input(tool_name, &inputs, permissions, ShellKind::system());
+    if tool_name == TerminalTool::NAME
+        || tool_name == FetchTool::NAME
+        || tool_name == GrepTool::NAME
+    {
+        return decision;
+    }
+
+    // Check normalized paths too, like `decide_permission_for_paths` does for native tools.
+    let normalized: Vec<String> = inputs.iter().map(|path| normalize_path(path)).collect();
+    if normalized == inputs {
+        return decision;
+    }
+    let normalized_decision = ToolPermissionDecision::from_input(
+        tool_name,
+        &normalized,
+        permissions,
+        ShellKind::system(),
+    );
+    most_restrictive(decision, normalized_decision)
+}
+
+/// Describes the setting behind a decision of [`decide_permission_for_acp_tool_call`].
+pub fn acp_tool_call_decision_rule(
+    agent_name: &str,
+    tool_call: &acp::ToolCallUpdateFields,
+    decision: &ToolPermissionDecision,
+    settings: &AgentSettings,
+) -> String {
+    match acp_tool_inputs(tool_call) {
+        Some((tool_name, inputs)) => decision_rule(
+            tool_name,
+            &inputs,
+            decision,
+            settings.tool_permissions_for_agent(agent_name),
+        ),
+        // Tool calls that can't be mapped to a native tool are always confirmed.
+        None => "unmapped tool call: confirm".to_string(),
+    }
+}
+
+/// Returns the native tool whose rules apply to an external agent's tool call, and the inputs to
+/// match them against.
+fn acp_tool_inputs(tool_call: &acp::ToolCallUpdateFields) -> Option<(&'static str, Vec<String>)> {
+    let raw_input = tool_call.raw_input.as_ref();
+    let (tool_name, inputs) = match tool_call.kind {
+        Some(acp::ToolKind::Execute) => (TerminalTool::NAME, acp_command_inputs(raw_input)),
+        Some(acp::ToolKind::Edit) => (EditFileTool::NAME, acp_path_inputs(tool_call)),
+        Some(acp::ToolKind::Delete) => (DeletePathTool::NAME, acp_path_inputs(tool_call)),
+        Some(acp::ToolKind::Move) => (MovePathTool::NAME, acp_path_inputs(tool_call)),
+        Some(acp::ToolKind::Read) => (ReadFileTool::NAME, acp_path_inputs(tool_call)),
+        Some(acp::ToolKind::Fetch) => (FetchTool::NAME, acp_string_inputs(raw_input, &["url"])),
+        Some(acp::ToolKind::Search) => (
+            GrepTool::NAME,
+            acp_string_inputs(raw_input, &["pattern", "query", "regex"]),
+        ),
+        _ => return None,
+    };
+    if inputs.is_empty() {
+        return None;
+    }
+    Some((tool_name, inputs))
+}
+
+/// Extracts the command of an external agent's terminal tool call. Agents either send it as a
+/// string, or as an argv array, which may run the actual command through `sh -c`.
+fn acp_command_inputs(raw_input: Option<&serde_json::Value>) -> Vec<String> {
+    let Some(command) = raw_input.and_then(|input| input.get("command").or(input.get("cmd")))
+    else {
+        return Vec::new();
+    };
+    match command {
+        serde_json::Value::String(command) => vec![command.clone()],
+        serde_json::Value::Array(args) => {
+            let Some(args) = args
+                .iter()
+                .map(|arg| arg.as_str())
+                .collect::<Option<Vec<_>>>()
+            else {
+                return Vec::new();
+            };
+            match args.as_slice() {
+                [shell, flag, command]
+                    if ["sh", "bash", "zsh"].contains(
+                        &Path::new(shell)
+                            .file_name()
+                            .and_then(|name| name.to_str())
+                            .unwrap_or_default(),
+                    ) && (*flag == "-c" || *flag == "-lc") =>
+                {
+                    vec![command.to_string()]
+                }
+                [] => Vec::new(),
+                args => vec![args.join(" ")],
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Extracts the paths an external agent's file tool call touches, from its locations and the
+/// path-like fields of its raw input.
+fn acp_path_inputs(tool_call: &acp::ToolCallUpdateFields) -> Vec<String> {
+    const PATH_KEYS: &[&str] = &[
+        "path",
+        "file_path",
+        "abs_path",
+        "notebook_path",
+        "source_path",
+        "destination_path",
+    ];
+
+    let mut paths = acp_string_inputs(tool_call.raw_input.as_ref(), PATH_KEYS);
+    for location in tool_call.locations.iter().flatten() {
+        let path = location.path.to_string_lossy().into_owned();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+fn acp_string_inputs(raw_input: Option<&serde_json::Value>, keys: &[&str]) -> Vec<String> {
+    let Some(raw_input) = raw_input else {
+        return Vec::new();
+    };
+    keys.iter()
+        .filter_map(|key| raw_input.get(key)?.as_str())
+        .map(|value| value.to_string())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -560,7 +748,11 @@ mod tests {
             use_modifier_to_send: true,
             message_editor_min_lines: 1,
             tool_permissions,
+            agent_tool_permissions: Default::default(),
             show_turn_stats: false,
+            enable_agent_sessions_server: false,
+            session_budgets: Default::default(),
+            jobs: Vec::new(),
         }
     }
 
@@ -2200,4 +2392,198 @@ mod tests {
         let decision = path_perm(EditFileTool::NAME, "src/../.env", &["^\\.env"], &[], &[]);
         assert!(matches!(decision, ToolPermissionDecision::Deny(_)));
     }
+
+    fn acp_settings(global_default: ToolPermissionMode) -> AgentSettings {
+        let mut tools = collections::HashMap::default();
+        tools.insert(
+            Arc::from(TerminalTool::NAME),
+            ToolRules {
+                always_allow: vec![CompiledRegex::new(r"^cargo\s", false).unwrap()],
+                always_deny: vec![CompiledRegex::new(r"\bsudo\b", false).unwrap()],
+                ..Default::default()
+            },
+        );
+        tools.insert(
+            Arc::from(EditFileTool::NAME),
+            ToolRules {
+                always_deny: vec![CompiledRegex::new(r"\.env$", false).unwrap()],
+                ..Default::default()
+            },
+        );
+        let mut settings = test_agent_settings(ToolPermissions {
+            default: global_default,
+            tools: tools.clone(),
+        });
+        tools.insert(
+            Arc::from(EditFileTool::NAME),
+            ToolRules {
+                default: Some(ToolPermissionMode::Allow),
+                always_deny: vec![CompiledRegex::new(r"\.env$", false).unwrap()],
+                ..Default::default()
+            },
+        );
+        settings.agent_tool_permissions.insert(
+            Arc::from("Claude Code"),
+            ToolPermissions {
+                default: global_default,
+                tools,
+            },
+        );
+        settings
+    }
+
+    fn acp_tool_call(
+        kind: acp::ToolKind,
+        raw_input: serde_json::Value,
+    ) -> acp::ToolCallUpdateFields {
+        acp::ToolCallUpdateFields::new()
+            .kind(kind)
+            .raw_input(raw_input)
+    }
+
+    #[test]
+    fn acp_execute_uses_terminal_rules() {
+        let settings = acp_settings(ToolPermissionMode::Confirm);
+        let decide = |raw_input| {
+            decide_permission_for_acp_tool_call(
+                "Codex",
+                &acp_tool_call(acp::ToolKind::Execute, raw_input),
+                &settings,
+            )
+        };
+
+        assert_eq!(
+            decide(serde_json::json!({ "command": "cargo test" })),
+            ToolPermissionDecision::Allow
+        );
+        assert_eq!(
+            decide(serde_json::json!({ "command": ["bash", "-lc", "cargo build"] })),
+            ToolPermissionDecision::Allow
+        );
+        // Sub-commands are checked separately.
+        assert!(matches!(
+            decide(serde_json::json!({ "command": "cargo test && sudo reboot" })),
+            ToolPermissionDecision::Deny(_)
+        ));
+        assert_eq!(
+            decide(serde_json::json!({ "command": "ls" })),
+            ToolPermissionDecision::Confirm
+        );
+        // Without a command there is nothing to check, so the user decides.
+        assert_eq!(
+            decide(serde_json::json!({ "description": "cargo test" })),
+            ToolPermissionDecision::Confirm
+        );
+    }
+
+    #[test]
+    fn acp_execute_respects_hardcoded_security_rules() {
+        let settings = acp_settings(ToolPermissionMode::Allow);
+        let decision = decide_permission_for_acp_tool_call(
+            "Claude Code",
+            &acp_tool_call(
+                acp::ToolKind::Execute,
+                serde_json::json!({ "command": "echo hi; rm -rf /" }),
+            ),
+            &settings,
+        );
+        assert_eq!(
+            decision,
+            ToolPermissionDecision::Deny(HARDCODED_SECURITY_DENIAL_MESSAGE.into())
+        );
+    }
+
+    #[test]
+    fn acp_edit_uses_per_agent_rules() {
+        let settings = acp_settings(ToolPermissionMode::Confirm);
+        let edit = |agent_name, path: &str| {
+            decide_permission_for_acp_tool_call(
+                agent_name,
+                &acp_tool_call(
+                    acp::ToolKind::Edit,
+                    serde_json::json!({ "file_path": path }),
+                ),
+                &settings,
+            )
+        };
+
+        assert_eq!(
+            edit("Claude Code", "src/main.rs"),
+            ToolPermissionDecision::Allow
+        );
+        assert_eq!(
+            edit("Codex", "src/main.rs"),
+            ToolPermissionDecision::Confirm
+        );
+        assert!(matches!(
+            edit("Claude Code", "config/../.env"),
+            ToolPermissionDecision::Deny(_)
+        ));
+
+        let from_locations = acp::ToolCallUpdateFields::new()
+            .kind(acp::ToolKind::Edit)
+            .locations(vec![acp::ToolCallLocation::new("/project/.env")]);
+        assert!(matches!(
+            decide_permission_for_acp_tool_call("Claude Code", &from_locations, &settings),
+            ToolPermissionDecision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn acp_unmapped_kinds_are_confirmed() {
+        let settings = acp_settings(ToolPermissionMode::Allow);
+        let decision = decide_permission_for_acp_tool_call(
+            "Claude Code",
+            &acp_tool_call(acp::ToolKind::Other, serde_json::json!({})),
+            &settings,
+        );
+        assert_eq!(decision, ToolPermissionDecision::Confirm);
+    }
+
+    #[test]
+    fn decision_rule_names_the_matching_setting() {
+        let settings = acp_settings(ToolPermissionMode::Confirm);
+        let rule = |agent_name, tool_call: acp::ToolCallUpdateFields| {
+            let decision = decide_permission_for_acp_tool_call(agent_name, &tool_call, &settings);
+            acp_tool_call_decision_rule(agent_name, &tool_call, &decision, &settings)
+        };
+
+        assert_eq!(
+            rule(
+                "Codex",
+                acp_tool_call(
+                    acp::ToolKind::Execute,
+                    serde_json::json!({ "command": "cargo test" })
+                )
+            ),
+            r"terminal.always_allow: ^cargo\s"
+        );
+        assert_eq!(
+            rule(
+                "Claude Code",
+                acp_tool_call(
+                    acp::ToolKind::Edit,
+                    serde_json::json!({ "file_path": "src/main.rs" })
+                )
+            ),
+            "edit_file.default: Allow"
+        );
+        assert_eq!(
+            rule(
+                "Codex",
+                acp_tool_call(acp::ToolKind::Read, serde_json::json!({ "path": "a.rs" }))
+            ),
+            "tool_permissions.default: Confirm"
+        );
+        assert_eq!(
+            rule(
+                "Codex",
+                acp_tool_call(
+                    acp::ToolKind::Execute,
+                    serde_json::json!({ "command": "sudo reboot" })
+                )
+            ),
+            "Command blocked by security rule for terminal tool"
+        );
+    }
 }
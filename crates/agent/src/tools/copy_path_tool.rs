@@ -86,15 +86,19 @@ impl AgentTool for CopyPathTool {
         let settings = AgentSettings::get_global(cx);
 
         let paths = vec![input.source_path.clone(), input.destination_path.clone()];
-        let decision = decide_permission_for_paths(Self::NAME, &paths, settings);
+        let mut decision = decide_permission_for_paths(Self::NAME, &paths, settings);
+        if matches!(decision, ToolPermissionDecision::Allow)
+            && (is_sensitive_settings_path(Path::new(&input.source_path))
+                || is_sensitive_settings_path(Path::new(&input.destination_path)))
+        {
+            decision = ToolPermissionDecision::Confirm;
+        }
+        event_stream.audit_permission_decision(Self::NAME, &paths, &decision, cx);
         if let ToolPermissionDecision::Deny(reason) = decision {
             return Task::ready(Err(anyhow!("{}", reason)));
         }
 
-        let needs_confirmation = matches!(decision, ToolPermissionDecision::Confirm)
-            || (matches!(decision, ToolPermissionDecision::Allow)
-                && (is_sensitive_settings_path(Path::new(&input.source_path))
-                    || is_sensitive_settings_path(Path::new(&input.destination_path))));
+        let needs_confirmation = matches!(decision, ToolPermissionDecision::Confirm);
 
         let authorize = if needs_confirmation {
             let src = MarkdownInlineCode(&input.source_path);
@@ -77,6 +77,12 @@ impl AgentTool for CreateDirectoryTool {
         if matches!(decision, ToolPermissionDecision::Allow) && sensitive_kind.is_some() {
             decision = ToolPermissionDecision::Confirm;
         }
+        event_stream.audit_permission_decision(
+            Self::NAME,
+            std::slice::from_ref(&input.path),
+            &decision,
+            cx,
+        );
 
         let authorize = match decision {
             ToolPermissionDecision::Allow => None,
@@ -85,6 +85,12 @@ impl AgentTool for DeletePathTool {
         {
             decision = ToolPermissionDecision::Confirm;
         }
+        event_stream.audit_permission_decision(
+            Self::NAME,
+            std::slice::from_ref(&path),
+            &decision,
+            cx,
+        );
 
         let authorize = match decision {
             ToolPermissionDecision::Allow => None,
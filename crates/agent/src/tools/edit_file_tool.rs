@@ -248,13 +248,15 @@ pub fn authorize_file_edit(
     let settings = agent_settings::AgentSettings::get_global(cx);
     let decision = decide_permission_for_path(tool_name, &path_str, settings);
 
-    if let ToolPermissionDecision::Deny(reason) = decision {
+    if let ToolPermissionDecision::Deny(reason) = &decision {
+        event_stream.audit_permission_decision(tool_name, &[path_str.to_string()], &decision, cx);
         return Task::ready(Err(anyhow!("{}", reason)));
     }
 
     let explicitly_allowed = matches!(decision, ToolPermissionDecision::Allow);
 
     if explicitly_allowed && !is_sensitive_settings_path(path) {
+        event_stream.audit_permission_decision(tool_name, &[path_str.to_string()], &decision, cx);
         return Task::ready(Ok(()));
     }
 
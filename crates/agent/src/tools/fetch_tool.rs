@@ -148,6 +148,12 @@ impl AgentTool for FetchTool {
         let settings = AgentSettings::get_global(cx);
         let decision =
             decide_permission_from_settings(Self::NAME, std::slice::from_ref(&input.url), settings);
+        event_stream.audit_permission_decision(
+            Self::NAME,
+            std::slice::from_ref(&input.url),
+            &decision,
+            cx,
+        );
 
         let authorize = match decision {
             ToolPermissionDecision::Allow => None,
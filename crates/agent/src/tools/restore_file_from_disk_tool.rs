@@ -79,9 +79,22 @@ impl AgentTool for RestoreFileFromDiskTool {
                 ToolPermissionDecision::Allow => {
                     if is_sensitive_settings_path(Path::new(&*path_str)) {
                         confirmation_paths.push(path_str.to_string());
+                    } else {
+                        event_stream.audit_permission_decision(
+                            Self::NAME,
+                            &[path_str.to_string()],
+                            &decision,
+                            cx,
+                        );
                     }
                 }
-                ToolPermissionDecision::Deny(reason) => {
+                ToolPermissionDecision::Deny(ref reason) => {
+                    event_stream.audit_permission_decision(
+                        Self::NAME,
+                        &[path_str.to_string()],
+                        &decision,
+                        cx,
+                    );
                     return Task::ready(Err(anyhow::anyhow!("{}", reason)));
                 }
                 ToolPermissionDecision::Confirm => {
@@ -100,6 +100,12 @@ impl AgentTool for TerminalTool {
             std::slice::from_ref(&input.command),
             settings,
         );
+        event_stream.audit_permission_decision(
+            Self::NAME,
+            std::slice::from_ref(&input.command),
+            &decision,
+            cx,
+        );
 
         let authorize = match decision {
             ToolPermissionDecision::Allow => None,
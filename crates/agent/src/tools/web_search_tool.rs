@@ -77,6 +77,12 @@ impl AgentTool for WebSearchTool {
             std::slice::from_ref(&input.query),
             settings,
         );
+        event_stream.audit_permission_decision(
+            Self::NAME,
+            std::slice::from_ref(&input.query),
+            &decision,
+            cx,
+        );
 
         let authorize = match decision {
             ToolPermissionDecision::Allow => None,
@@ -5,11 +5,12 @@ use acp_thread::{
 use acp_tools::AcpConnectionRegistry;
 use action_log::ActionLog;
 use agent_client_protocol::{self as acp, Agent as _, ErrorCode};
+use agent_settings::AgentSettings;
 use anyhow::anyhow;
 use collections::HashMap;
 use feature_flags::{AcpBetaFeatureFlag, FeatureFlagAppExt as _};
-use futures::AsyncBufReadExt as _;
 use futures::io::BufReader;
+use futures::{AsyncBufReadExt as _, AsyncRead, AsyncWrite};
 use project::Project;
 use project::agent_server_store::AgentServerCommand;
 use serde::Deserialize;
@@ -18,8 +19,10 @@ use task::ShellBuilder;
 use util::ResultExt as _;
 use util::process::Child;
 
+use serde_json::value::RawValue;
 use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::Arc;
 use std::{any::Any, cell::RefCell};
 use std::{path::Path, rc::Rc};
 use thiserror::Error;
@@ -27,7 +30,10 @@ use thiserror::Error;
 use anyhow::{Context as _, Result};
 use gpui::{App, AppContext as _, AsyncApp, Entity, SharedString, Task, WeakEntity};
 
-use acp_thread::{AcpThread, AuthRequired, LoadError, TerminalProviderEvent};
+use crate::{AcpPermissionRules, AgentExtMethodRegistry, AutoPermission, ExtMethodCall};
+use acp_thread::{
+    AcpThread, AuditAction, AuditLog, AuthRequired, LoadError, TerminalProviderEvent,
+};
 use terminal::TerminalBuilder;
 use terminal::terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 
@@ -46,7 +52,7 @@ pub struct AcpConnection {
     default_model: Option<acp::ModelId>,
     default_config_options: HashMap<String, String>,
     root_dir: PathBuf,
-    child: Child,
+    child: Option<Child>,
     session_list: Option<Rc<AcpSessionList>>,
     _io_task: Task<Result<(), acp::Error>>,
     _wait_task: Task<Result<()>>,
@@ -216,31 +222,6 @@ impl AcpConnection {
 
         let sessions = Rc::new(RefCell::new(HashMap::default()));
 
-        let (release_channel, version): (Option<&str>, String) = cx.update(|cx| {
-            (
-                release_channel::ReleaseChannel::try_global(cx)
-                    .map(|release_channel| release_channel.display_name()),
-                release_channel::AppVersion::global(cx).to_string(),
-            )
-        });
-
-        let client_session_list: Rc<RefCell<Option<Rc<AcpSessionList>>>> =
-            Rc::new(RefCell::new(None));
-
-        let client = ClientDelegate {
-            sessions: sessions.clone(),
-            session_list: client_session_list.clone(),
-            cx: cx.clone(),
-        };
-        let (connection, io_task) = acp::ClientSideConnection::new(client, stdin, stdout, {
-            let foreground_executor = cx.foreground_executor().clone();
-            move |fut| {
-                foreground_executor.spawn(fut).detach();
-            }
-        });
-
-        let io_task = cx.background_spawn(io_task);
-
         let stderr_task = cx.background_spawn(async move {
             let mut stderr = BufReader::new(stderr);
             let mut line = String::new();
@@ -272,11 +253,95 @@ impl AcpConnection {
             }
         });
 
+        Self::from_streams(
+            server_name,
+            stdin,
+            stdout,
+            Some(child),
+            sessions,
+            root_dir,
+            default_mode,
+            default_model,
+            default_config_options,
+            wait_task,
+            stderr_task,
+            cx,
+        )
+        .await
+    }
+
+    /// Connects to an agent that runs inside Zed, such as the scripted mock agent, instead
+    /// of spawning one.
+    #[cfg(any(test, feature = "test-support"))]
+    pub async fn in_process(
+        server_name: SharedString,
+        outgoing: impl AsyncWrite + Unpin + Send + 'static,
+        incoming: impl AsyncRead + Unpin + Send + 'static,
+        root_dir: &Path,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        Self::from_streams(
+            server_name,
+            outgoing,
+            incoming,
+            None,
+            Rc::new(RefCell::new(HashMap::default())),
+            root_dir,
+            None,
+            None,
+            HashMap::default(),
+            Task::ready(Ok(())),
+            Task::ready(Ok(())),
+            cx,
+        )
+        .await
+    }
+
+    async fn from_streams(
+        server_name: SharedString,
+        outgoing: impl AsyncWrite + Unpin + Send + 'static,
+        incoming: impl AsyncRead + Unpin + Send + 'static,
+        child: Option<Child>,
+        sessions: Rc<RefCell<HashMap<acp::SessionId, AcpSession>>>,
+        root_dir: &Path,
+        default_mode: Option<acp::SessionModeId>,
+        default_model: Option<acp::ModelId>,
+        default_config_options: HashMap<String, String>,
+        wait_task: Task<Result<()>>,
+        stderr_task: Task<Result<()>>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let (release_channel, version): (Option<&str>, String) = cx.update(|cx| {
+            (
+                release_channel::ReleaseChannel::try_global(cx)
+                    .map(|release_channel| release_channel.display_name()),
+                release_channel::AppVersion::global(cx).to_string(),
+            )
+        });
+
+        let client_session_list: Rc<RefCell<Option<Rc<AcpSessionList>>>> =
+            Rc::new(RefCell::new(None));
+
+        let client = ClientDelegate {
+            server_name: server_name.clone(),
+            sessions: sessions.clone(),
+            session_list: client_session_list.clone(),
+            cx: cx.clone(),
+        };
+        let (connection, io_task) = acp::ClientSideConnection::new(client, outgoing, incoming, {
+            let foreground_executor = cx.foreground_executor().clone();
+            move |fut| {
+                foreground_executor.spawn(fut).detach();
+            }
+        });
+
+        let io_task = cx.background_spawn(io_task);
+
         let connection = Rc::new(connection);
 
         cx.update(|cx| {
             AcpConnectionRegistry::default_global(cx).update(cx, |registry, cx| {
-                registry.set_active_connection(server_name.clone(), &connection, cx)
+                registry.add_connection(server_name.clone(), &connection, cx)
             });
         });
 
@@ -356,7 +421,9 @@ impl AcpConnection {
 
 impl Drop for AcpConnection {
     fn drop(&mut self) {
-        self.child.kill().log_err();
+        if let Some(child) = self.child.as_mut() {
+            child.kill().log_err();
+        }
     }
 }
 
@@ -958,6 +1025,9 @@ fn mcp_servers_for_project(project: &Entity<Project>, cx: &App) -> Vec<acp::McpS
                 _ => None,
             }
         })
+        .chain(crate::agent_sessions_server::agent_sessions_mcp_server(
+            project, cx,
+        ))
         .collect()
 }
 
@@ -1136,6 +1206,7 @@ impl acp_thread::AgentSessionConfigOptions for AcpSessionConfigOptions {
 }
 
 struct ClientDelegate {
+    server_name: SharedString,
     sessions: Rc<RefCell<HashMap<acp::SessionId, AcpSession>>>,
     session_list: Rc<RefCell<Option<Rc<AcpSessionList>>>>,
     cx: AsyncApp,
@@ -1158,6 +1229,74 @@ impl acp::Client for ClientDelegate {
 
         let cx = &mut self.cx.clone();
 
+        let auto_permission = thread.update(cx, |thread, cx| {
+            // Permission requests may only carry the fields that changed since the tool call
+            // was announced.
+            let mut fields = arguments.tool_call.fields.clone();
+            if let Some((_, tool_call)) = thread.tool_call(&arguments.tool_call.tool_call_id) {
+                fields.kind.get_or_insert(tool_call.kind);
+                if fields.raw_input.is_none() {
+                    fields.raw_input = tool_call.raw_input.clone();
+                }
+                if fields.locations.is_none() {
+                    fields.locations = Some(tool_call.locations.clone());
+                }
+            }
+            AcpPermissionRules::decide(&self.server_name, &fields, cx)
+        })?;
+
+        if let Some(auto_permission) = auto_permission {
+            // Only answer for this call: an "always" option would let the agent skip the rules
+            // for all its later calls of the tool, so the user gets asked instead.
+            let (option_kind, status) = match &auto_permission {
+                AutoPermission::Allow(_) => (
+                    acp::PermissionOptionKind::AllowOnce,
+                    acp_thread::ToolCallStatus::InProgress,
+                ),
+                AutoPermission::Deny(_) => (
+                    acp::PermissionOptionKind::RejectOnce,
+                    acp_thread::ToolCallStatus::Rejected,
+                ),
+            };
+            let option = arguments
+                .options
+                .iter()
+                .find(|option| option.kind == option_kind);
+            if let Some(option) = option {
+                let mut tool_call = arguments.tool_call.clone();
+                let (rule, outcome) = match auto_permission {
+                    AutoPermission::Allow(rule) => (rule, "allowed"),
+                    AutoPermission::Deny(reason) => {
+                        tool_call.fields.content = Some(vec![acp::ToolCallContent::Content(
+                            acp::Content::new(reason.clone()),
+                        )]);
+                        (reason, "denied")
+                    }
+                };
+                thread.update(cx, |thread, cx| {
+                    let tool_call_id = tool_call.tool_call_id.clone();
+                    thread.upsert_tool_call_inner(tool_call, status, cx)?;
+                    let title = thread
+                        .tool_call(&tool_call_id)
+                        .map(|(_, tool_call)| tool_call.label.read(cx).source().to_string())
+                        .unwrap_or_default();
+                    AuditLog::record(
+                        thread
+                            .audit_entry(AuditAction::PermissionDecision, title)
+                            .rule(rule)
+                            .outcome(outcome),
+                        cx,
+                    );
+                    Ok::<_, acp::Error>(())
+                })??;
+                return Ok(acp::RequestPermissionResponse::new(
+                    acp::RequestPermissionOutcome::Selected(acp::SelectedPermissionOutcome::new(
+                        option.option_id.clone(),
+                    )),
+                ));
+            }
+        }
+
         let task = thread.update(cx, |thread, cx| {
             thread.request_tool_call_authorization(
                 arguments.tool_call,
@@ -1352,6 +1491,15 @@ impl acp::Client for ClientDelegate {
     ) -> Result<acp::CreateTerminalResponse, acp::Error> {
         let thread = self.session_thread(&args.session_id)?;
         let project = thread.read_with(&self.cx, |thread, _cx| thread.project().clone())?;
+        // External agents don't have profiles, so they use the default profile's sandbox.
+        let sandbox = project.read_with(&self.cx, |project, cx| {
+            let settings = AgentSettings::get_global(cx);
+            acp_thread::project_sandbox_policy(
+                settings.terminal_sandbox(&settings.default_profile),
+                project,
+                cx,
+            )
+        });
 
         let terminal_entity = acp_thread::create_terminal_entity(
             args.command.clone(),
@@ -1361,6 +1509,7 @@ impl acp::Client for ClientDelegate {
                 .map(|env| (env.name, env.value))
                 .collect(),
             args.cwd.clone(),
+            sandbox.as_ref(),
             &project,
             &mut self.cx.clone(),
         )
@@ -1373,6 +1522,7 @@ impl acp::Client for ClientDelegate {
                 format!("{} {}", args.command, args.args.join(" ")),
                 args.cwd.clone(),
                 args.output_byte_limit,
+                sandbox,
                 terminal_entity,
                 cx,
             )
@@ -1393,12 +1543,24 @@ impl acp::Client for ClientDelegate {
         Ok(Default::default())
     }
 
-    async fn ext_method(&self, _args: acp::ExtRequest) -> Result<acp::ExtResponse, acp::Error> {
-        Err(acp::Error::method_not_found())
+    async fn ext_method(&self, args: acp::ExtRequest) -> Result<acp::ExtResponse, acp::Error> {
+        let task = self.cx.update(|cx| {
+            let call = self.ext_method_call(args.method, args.params, cx);
+            AgentExtMethodRegistry::handle(call, cx)
+        });
+        let result = task.await?;
+        let result = serde_json::value::to_raw_value(&result)
+            .map_err(|error| acp::Error::internal_error().data(error.to_string()))?;
+        Ok(acp::ExtResponse::new(result.into()))
     }
 
-    async fn ext_notification(&self, _args: acp::ExtNotification) -> Result<(), acp::Error> {
-        Err(acp::Error::method_not_found())
+    async fn ext_notification(&self, args: acp::ExtNotification) -> Result<(), acp::Error> {
+        let task = self.cx.update(|cx| {
+            let call = self.ext_method_call(args.method, args.params, cx);
+            AgentExtMethodRegistry::handle(call, cx)
+        });
+        task.await?;
+        Ok(())
     }
 
     async fn release_terminal(
@@ -1444,6 +1606,40 @@ impl acp::Client for ClientDelegate {
 }
 
 impl ClientDelegate {
+    fn ext_method_call(&self, method: Arc<str>, params: Arc<RawValue>, cx: &App) -> ExtMethodCall {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SessionParams {
+            session_id: Option<acp::SessionId>,
+        }
+
+        let thread = serde_json::from_str::<SessionParams>(params.get())
+            .ok()
+            .and_then(|params| params.session_id)
+            .and_then(|session_id| self.session_thread(&session_id).ok());
+        // Calls that aren't about a particular session still run in the agent's project,
+        // which all of its sessions share.
+        let project = thread
+            .clone()
+            .or_else(|| {
+                self.sessions
+                    .borrow()
+                    .values()
+                    .next()
+                    .map(|session| session.thread.clone())
+            })
+            .and_then(|thread| thread.upgrade())
+            .map(|thread| thread.read(cx).project().clone());
+
+        ExtMethodCall {
+            agent_name: self.server_name.clone(),
+            method: SharedString::new(method),
+            params,
+            thread,
+            project,
+        }
+    }
+
     fn session_thread(&self, session_id: &acp::SessionId) -> Result<WeakEntity<AcpThread>> {
         let sessions = self.sessions.borrow();
         sessions
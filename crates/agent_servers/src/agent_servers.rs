@@ -1,20 +1,31 @@
 mod acp;
+mod agent_sessions_server;
 mod claude;
 mod codex;
 mod custom;
+mod ext_methods;
 mod gemini;
+mod permission_rules;
+mod replay;
 
 #[cfg(any(test, feature = "test-support"))]
 pub mod e2e_tests;
+#[cfg(any(test, feature = "test-support"))]
+pub mod mock_agent;
 
+pub use agent_sessions_server::{
+    AGENT_SESSIONS_SERVER_NAME, register_agent_sessions_server, unregister_agent_sessions_server,
+};
 pub use claude::*;
 use client::ProxySettings;
 pub use codex::*;
 use collections::{HashMap, HashSet};
 pub use custom::*;
+pub use ext_methods::*;
 use fs::Fs;
 pub use gemini::*;
 use http_client::read_no_proxy_from_env;
+pub use permission_rules::*;
 use project::agent_server_store::AgentServerStore;
 
 use acp_thread::AgentConnection;
@@ -25,6 +36,7 @@ use settings::SettingsStore;
 use std::{any::Any, path::Path, rc::Rc, sync::Arc};
 
 pub use acp::AcpConnection;
+pub use replay::ReplayAgentConnection;
 
 pub struct AgentServerDelegate {
     store: Entity<AgentServerStore>,
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use agent_client_protocol as acp;
+use collections::HashMap;
+use gpui::{App, Entity, EntityId, Global};
+use project::Project;
+use util::ResultExt as _;
+
+/// The name of the MCP server through which the agents of a project see each other's sessions.
+pub const AGENT_SESSIONS_SERVER_NAME: &str = "zed-agent-sessions";
+
+/// The sockets of the agent sessions servers, by project.
+#[derive(Default)]
+struct AgentSessionsServers(HashMap<EntityId, PathBuf>);
+
+impl Global for AgentSessionsServers {}
+
+/// Offers the MCP server listening on the socket to every agent that starts in the project.
+pub fn register_agent_sessions_server(project_id: EntityId, socket_path: PathBuf, cx: &mut App) {
+    cx.default_global::<AgentSessionsServers>()
+        .0
+        .insert(project_id, socket_path);
+}
+
+pub fn unregister_agent_sessions_server(project_id: EntityId, cx: &mut App) {
+    cx.default_global::<AgentSessionsServers>()
+        .0
+        .remove(&project_id);
+}
+
+/// Returns the agent sessions server of the project, which agents reach by running Zed as a
+/// bridge between their stdio and the server's socket.
+pub(crate) fn agent_sessions_mcp_server(
+    project: &Entity<Project>,
+    cx: &App,
+) -> Option<acp::McpServer> {
+    // Agents of remote projects run on another machine, which can't reach the socket.
+    if !project.read(cx).is_local() {
+        return None;
+    }
+    let socket_path = cx
+        .try_global::<AgentSessionsServers>()?
+        .0
+        .get(&project.entity_id())?;
+    let zed_path = std::env::current_exe().log_err()?;
+    Some(acp::McpServer::Stdio(
+        acp::McpServerStdio::new(AGENT_SESSIONS_SERVER_NAME, &zed_path).args(vec![
+            "--nc".to_string(),
+            socket_path.to_string_lossy().into_owned(),
+        ]),
+    ))
+}
@@ -0,0 +1,42 @@
+//! Runs the scripted mock ACP agent over stdio, so that it can be configured as a custom
+//! agent server:
+//!
+//! ```sh
+//! mock_acp_agent path/to/script.json
+//! ```
+
+use std::rc::Rc;
+
+use agent_client_protocol as acp;
+use agent_servers::mock_agent::{MockAgent, MockAgentScript};
+use anyhow::{Context as _, Result};
+
+fn main() -> Result<()> {
+    let script_path = std::env::args()
+        .nth(1)
+        .context("usage: mock_acp_agent <script.json>")?;
+    let script = std::fs::read_to_string(&script_path)
+        .with_context(|| format!("failed to read {script_path}"))?;
+    let script = MockAgentScript::from_json(&script)?;
+
+    let executor = Rc::new(smol::LocalExecutor::new());
+    smol::block_on(executor.run(async {
+        let (agent, client) = MockAgent::new(script, |duration| {
+            Box::pin(async move {
+                smol::Timer::after(duration).await;
+            })
+        });
+        let (connection, io_task) = acp::AgentSideConnection::new(
+            agent,
+            smol::Unblock::new(std::io::stdout()),
+            smol::Unblock::new(std::io::stdin()),
+            {
+                let executor = executor.clone();
+                move |fut| executor.spawn(fut).detach()
+            },
+        );
+        client.set(connection).ok();
+        io_task.await?;
+        Ok(())
+    }))
+}
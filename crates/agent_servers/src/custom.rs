@@ -95,6 +95,7 @@ impl AgentServer for CustomAgentServer {
                     favorite_models: Vec::new(),
                     default_config_options: Default::default(),
                     favorite_config_option_values: Default::default(),
+                    allowed_extension_methods: Vec::new(),
                 });
 
             match settings {
@@ -144,6 +145,7 @@ impl AgentServer for CustomAgentServer {
                     favorite_models: Vec::new(),
                     default_config_options: Default::default(),
                     favorite_config_option_values: Default::default(),
+                    allowed_extension_methods: Vec::new(),
                 });
 
             match settings {
@@ -185,6 +187,7 @@ impl AgentServer for CustomAgentServer {
                     favorite_models: Vec::new(),
                     default_config_options: Default::default(),
                     favorite_config_option_values: Default::default(),
+                    allowed_extension_methods: Vec::new(),
                 });
 
             match settings {
@@ -238,6 +241,7 @@ impl AgentServer for CustomAgentServer {
                     favorite_models: Vec::new(),
                     default_config_options: Default::default(),
                     favorite_config_option_values: Default::default(),
+                    allowed_extension_methods: Vec::new(),
                 });
 
             let favorite_models = match settings {
@@ -300,6 +304,7 @@ impl AgentServer for CustomAgentServer {
                     favorite_models: Vec::new(),
                     default_config_options: Default::default(),
                     favorite_config_option_values: Default::default(),
+                    allowed_extension_methods: Vec::new(),
                 });
 
             match settings {
@@ -0,0 +1,225 @@
+use acp_thread::AcpThread;
+use agent_client_protocol as acp;
+use collections::HashMap;
+use gpui::{App, Entity, Global, SharedString, Task, WeakEntity};
+use project::{Project, agent_server_store::AllAgentServersSettings};
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use settings::Settings as _;
+use std::{rc::Rc, sync::Arc};
+
+/// An extension method or notification sent by an agent, as passed to its handler.
+pub struct ExtMethodCall {
+    /// The name of the agent server that sent the call.
+    pub agent_name: SharedString,
+    pub method: SharedString,
+    pub params: Arc<RawValue>,
+    /// The session named by the call's `sessionId` parameter, if it has one and it's known.
+    pub thread: Option<WeakEntity<AcpThread>>,
+    /// The project the agent is running in.
+    pub project: Option<Entity<Project>>,
+}
+
+impl ExtMethodCall {
+    /// Parses the call's parameters, failing with an `invalid_params` error.
+    pub fn params<T: DeserializeOwned>(&self) -> Result<T, acp::Error> {
+        serde_json::from_str(self.params.get())
+            .map_err(|error| acp::Error::invalid_params().data(error.to_string()))
+    }
+}
+
+type ExtMethodHandler =
+    Rc<dyn Fn(ExtMethodCall, &mut App) -> Task<Result<serde_json::Value, acp::Error>>>;
+
+/// Handlers for the `_`-prefixed extension methods that agents can call on top of the
+/// core protocol.
+///
+/// Agents can only call the methods they've been allowed to in their
+/// `allowed_extension_methods` setting.
+#[derive(Default)]
+pub struct AgentExtMethodRegistry {
+    handlers: HashMap<SharedString, ExtMethodHandler>,
+}
+
+impl Global for AgentExtMethodRegistry {}
+
+impl AgentExtMethodRegistry {
+    /// Registers the handler for an extension method, replacing any previous handler of the
+    /// same name. The leading `_` of the method name is optional.
+    ///
+    /// The same handler answers notifications for the method, whose result is discarded.
+    pub fn register(
+        method: impl Into<SharedString>,
+        handler: impl Fn(ExtMethodCall, &mut App) -> Task<Result<serde_json::Value, acp::Error>>
+        + 'static,
+        cx: &mut App,
+    ) {
+        let method = normalize_method_name(&method.into());
+        cx.default_global::<Self>()
+            .handlers
+            .insert(method, Rc::new(handler));
+    }
+
+    pub fn unregister(method: &str, cx: &mut App) {
+        let method = normalize_method_name(method);
+        cx.default_global::<Self>().handlers.remove(&method);
+    }
+
+    /// Returns the registered extension methods, without their leading `_`.
+    pub fn methods(cx: &App) -> Vec<SharedString> {
+        cx.try_global::<Self>()
+            .map(|registry| registry.handlers.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn handle(
+        call: ExtMethodCall,
+        cx: &mut App,
+    ) -> Task<Result<serde_json::Value, acp::Error>> {
+        let method = normalize_method_name(&call.method);
+        if !is_method_allowed(&call.agent_name, &method, cx) {
+            log::warn!(
+                "agent {} called extension method {} without being allowed to",
+                call.agent_name,
+                call.method
+            );
+            return Task::ready(Err(acp::Error::method_not_found()));
+        }
+        let Some(handler) = cx
+            .try_global::<Self>()
+            .and_then(|registry| registry.handlers.get(&method).cloned())
+        else {
+            return Task::ready(Err(acp::Error::method_not_found()));
+        };
+        handler(call, cx)
+    }
+}
+
+fn normalize_method_name(method: &str) -> SharedString {
+    SharedString::new(method.strip_prefix('_').unwrap_or(method))
+}
+
+fn is_method_allowed(agent_name: &str, method: &str, cx: &App) -> bool {
+    AllAgentServersSettings::get_global(cx)
+        .custom
+        .get(agent_name)
+        .is_some_and(|settings| {
+            settings
+                .allowed_extension_methods()
+                .iter()
+                .any(|allowed| normalize_method_name(allowed).as_ref() == method)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    fn init_test(cx: &mut App) {
+        let mut settings_store = SettingsStore::test(cx);
+        settings_store
+            .set_user_settings(
+                &json!({
+                    "agent_servers": {
+                        "My Agent": {
+                            "type": "custom",
+                            "command": "my-agent",
+                            "allowed_extension_methods": ["_test/echo", "test/unregistered"]
+                        }
+                    }
+                })
+                .to_string(),
+                cx,
+            )
+            .unwrap();
+        cx.set_global(settings_store);
+    }
+
+    fn call(agent_name: &str, method: &str, params: serde_json::Value) -> ExtMethodCall {
+        ExtMethodCall {
+            agent_name: SharedString::new(agent_name),
+            method: SharedString::new(method),
+            params: serde_json::value::to_raw_value(&params).unwrap().into(),
+            thread: None,
+            project: None,
+        }
+    }
+
+    #[gpui::test]
+    fn test_is_method_allowed(cx: &mut App) {
+        init_test(cx);
+
+        assert!(is_method_allowed("My Agent", "test/echo", cx));
+        assert!(is_method_allowed("My Agent", "test/unregistered", cx));
+        assert!(!is_method_allowed("My Agent", "test/other", cx));
+        assert!(!is_method_allowed("Other Agent", "test/echo", cx));
+    }
+
+    #[gpui::test]
+    fn test_register(cx: &mut App) {
+        assert_eq!(&*normalize_method_name("_test/echo"), "test/echo");
+        assert_eq!(&*normalize_method_name("test/echo"), "test/echo");
+
+        let handler = |_: ExtMethodCall, _: &mut App| Task::ready(Ok(serde_json::Value::Null));
+        AgentExtMethodRegistry::register("_test/echo", handler, cx);
+        AgentExtMethodRegistry::register("test/other", handler, cx);
+        let mut methods = AgentExtMethodRegistry::methods(cx);
+        methods.sort();
+        assert_eq!(
+            methods,
+            [SharedString::from("test/echo"), "test/other".into()]
+        );
+
+        AgentExtMethodRegistry::unregister("_test/other", cx);
+        assert_eq!(
+            AgentExtMethodRegistry::methods(cx),
+            [SharedString::from("test/echo")]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_handle(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            init_test(cx);
+            AgentExtMethodRegistry::register(
+                "test/echo",
+                |call, _| Task::ready(call.params::<serde_json::Value>()),
+                cx,
+            );
+            AgentExtMethodRegistry::register(
+                "test/other",
+                |_, _| Task::ready(Ok(serde_json::Value::Null)),
+                cx,
+            );
+        });
+
+        // Agents may call the method with or without the leading `_`.
+        for method in ["_test/echo", "test/echo"] {
+            let result = cx
+                .update(|cx| {
+                    AgentExtMethodRegistry::handle(call("My Agent", method, json!({ "a": 1 })), cx)
+                })
+                .await;
+            assert_eq!(result.unwrap(), json!({ "a": 1 }));
+        }
+
+        // Methods that are registered but not allowed, allowed but not registered, or called
+        // by agents that aren't allowed any aren't found.
+        for (agent_name, method) in [
+            ("My Agent", "_test/other"),
+            ("My Agent", "_test/unregistered"),
+            ("Other Agent", "_test/echo"),
+        ] {
+            let error = cx
+                .update(|cx| {
+                    AgentExtMethodRegistry::handle(call(agent_name, method, json!({})), cx)
+                })
+                .await
+                .unwrap_err();
+            assert_eq!(error.code, acp::ErrorCode::MethodNotFound);
+        }
+    }
+}
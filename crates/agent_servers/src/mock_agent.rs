@@ -0,0 +1,796 @@
+//! A stand-in ACP agent that follows a script instead of calling a model, so that agent
+//! behavior can be tested without a network or a real agent CLI.
+//!
+//! [`MockAgentServer`] runs the agent inside Zed for tests, and the `mock_acp_agent` binary
+//! runs it over stdio, so that it can also be configured as a custom agent.
+
+use std::{
+    any::Any,
+    cell::{Cell, OnceCell, RefCell},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+use acp_thread::AgentConnection;
+use agent_client_protocol::{self as acp, Client as _};
+use anyhow::Result;
+use collections::HashMap;
+use futures::future::LocalBoxFuture;
+use gpui::{App, AsyncApp, SharedString, Task};
+use serde::Deserialize;
+
+use crate::{AcpConnection, AgentServer, AgentServerDelegate};
+
+/// What the mock agent does in response to each prompt.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct MockAgentScript {
+    pub turns: Vec<MockTurn>,
+}
+
+impl MockAgentScript {
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// The agent's response to a single prompt.
+///
+/// Every session works through the turns in order, skipping those whose `prompt_contains`
+/// doesn't match the prompt.
+#[derive(Clone, Debug, Deserialize)]
+pub struct MockTurn {
+    #[serde(default)]
+    pub prompt_contains: Option<String>,
+    #[serde(default)]
+    pub events: Vec<MockEvent>,
+    #[serde(default = "default_stop_reason")]
+    pub stop_reason: acp::StopReason,
+}
+
+fn default_stop_reason() -> acp::StopReason {
+    acp::StopReason::EndTurn
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MockEvent {
+    /// Streams a chunk of the agent's reply.
+    Message {
+        text: String,
+    },
+    /// Streams a chunk of the agent's reasoning.
+    Thought {
+        text: String,
+    },
+    /// Sends any session update as is.
+    Update {
+        update: acp::SessionUpdate,
+    },
+    ToolCall {
+        tool_call: acp::ToolCall,
+    },
+    /// Asks for permission and waits for the answer. Rejecting ends the turn.
+    RequestPermission {
+        tool_call: acp::ToolCallUpdate,
+        options: Vec<acp::PermissionOption>,
+    },
+    /// Reads a file through the client. Relative paths are resolved against the session's
+    /// working directory.
+    ReadTextFile {
+        path: PathBuf,
+        /// Whether to send the file's content back as a message.
+        #[serde(default)]
+        echo: bool,
+    },
+    WriteTextFile {
+        path: PathBuf,
+        content: String,
+    },
+    /// Runs a command in a client terminal and waits for it to exit.
+    CreateTerminal {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        cwd: Option<PathBuf>,
+        /// The tool call to show the terminal in.
+        #[serde(default)]
+        tool_call_id: Option<acp::ToolCallId>,
+    },
+    /// Calls an extension method on the client.
+    ExtMethod {
+        method: String,
+        #[serde(default)]
+        params: Option<serde_json::Value>,
+        /// Whether to send the result, or the error, back as a message instead of failing
+        /// the turn on errors.
+        #[serde(default)]
+        echo: bool,
+    },
+    Sleep {
+        ms: u64,
+    },
+}
+
+struct MockSession {
+    cwd: PathBuf,
+    used_turns: Vec<bool>,
+    cancelled: Rc<Cell<bool>>,
+}
+
+/// Implements the agent side of ACP by playing back a [`MockAgentScript`].
+pub struct MockAgent {
+    script: MockAgentScript,
+    client: Rc<OnceCell<acp::AgentSideConnection>>,
+    sessions: RefCell<HashMap<acp::SessionId, MockSession>>,
+    next_session_id: Cell<usize>,
+    sleep: Box<dyn Fn(Duration) -> LocalBoxFuture<'static, ()>>,
+}
+
+impl MockAgent {
+    /// Creates the agent and returns the cell to put its connection to the client in.
+    pub fn new(
+        script: MockAgentScript,
+        sleep: impl Fn(Duration) -> LocalBoxFuture<'static, ()> + 'static,
+    ) -> (Self, Rc<OnceCell<acp::AgentSideConnection>>) {
+        let client = Rc::new(OnceCell::new());
+        let agent = Self {
+            script,
+            client: client.clone(),
+            sessions: RefCell::new(HashMap::default()),
+            next_session_id: Cell::new(0),
+            sleep: Box::new(sleep),
+        };
+        (agent, client)
+    }
+
+    fn client(&self) -> Result<&acp::AgentSideConnection, acp::Error> {
+        self.client
+            .get()
+            .ok_or_else(|| acp::Error::internal_error().data("the mock agent isn't connected"))
+    }
+
+    async fn notify(
+        &self,
+        session_id: &acp::SessionId,
+        update: acp::SessionUpdate,
+    ) -> Result<(), acp::Error> {
+        self.client()?
+            .session_notification(acp::SessionNotification::new(session_id.clone(), update))
+            .await
+    }
+
+    /// Runs the events of a turn, returning early with a stop reason if the turn is
+    /// cancelled or a permission request is rejected.
+    async fn run_events(
+        &self,
+        session_id: &acp::SessionId,
+        cwd: &Path,
+        events: &[MockEvent],
+        cancelled: &Cell<bool>,
+    ) -> Result<Option<acp::StopReason>, acp::Error> {
+        for event in events {
+            if cancelled.get() {
+                return Ok(Some(acp::StopReason::Cancelled));
+            }
+            match event {
+                MockEvent::Message { text } => {
+                    self.notify(
+                        session_id,
+                        acp::SessionUpdate::AgentMessageChunk(acp::ContentChunk::new(
+                            text.clone().into(),
+                        )),
+                    )
+                    .await?;
+                }
+                MockEvent::Thought { text } => {
+                    self.notify(
+                        session_id,
+                        acp::SessionUpdate::AgentThoughtChunk(acp::ContentChunk::new(
+                            text.clone().into(),
+                        )),
+                    )
+                    .await?;
+                }
+                MockEvent::Update { update } => {
+                    self.notify(session_id, update.clone()).await?;
+                }
+                MockEvent::ToolCall { tool_call } => {
+                    self.notify(session_id, acp::SessionUpdate::ToolCall(tool_call.clone()))
+                        .await?;
+                }
+                MockEvent::RequestPermission { tool_call, options } => {
+                    let response = self
+                        .client()?
+                        .request_permission(acp::RequestPermissionRequest::new(
+                            session_id.clone(),
+                            tool_call.clone(),
+                            options.clone(),
+                        ))
+                        .await?;
+                    let acp::RequestPermissionOutcome::Selected(selected) = response.outcome else {
+                        return Ok(Some(acp::StopReason::Cancelled));
+                    };
+                    let rejected = options.iter().any(|option| {
+                        option.option_id == selected.option_id
+                            && matches!(
+                                option.kind,
+                                acp::PermissionOptionKind::RejectOnce
+                                    | acp::PermissionOptionKind::RejectAlways
+                            )
+                    });
+                    if rejected {
+                        return Ok(Some(acp::StopReason::EndTurn));
+                    }
+                }
+                MockEvent::ReadTextFile { path, echo } => {
+                    let response = self
+                        .client()?
+                        .read_text_file(acp::ReadTextFileRequest::new(
+                            session_id.clone(),
+                            cwd.join(path),
+                        ))
+                        .await?;
+                    if *echo {
+                        self.notify(
+                            session_id,
+                            acp::SessionUpdate::AgentMessageChunk(acp::ContentChunk::new(
+                                response.content.into(),
+                            )),
+                        )
+                        .await?;
+                    }
+                }
+                MockEvent::WriteTextFile { path, content } => {
+                    self.client()?
+                        .write_text_file(acp::WriteTextFileRequest::new(
+                            session_id.clone(),
+                            cwd.join(path),
+                            content.clone(),
+                        ))
+                        .await?;
+                }
+                MockEvent::CreateTerminal {
+                    command,
+                    args,
+                    cwd: terminal_cwd,
+                    tool_call_id,
+                } => {
+                    let client = self.client()?;
+                    let terminal = client
+                        .create_terminal(
+                            acp::CreateTerminalRequest::new(session_id.clone(), command.clone())
+                                .args(args.clone())
+                                .cwd(terminal_cwd.as_ref().map(|path| cwd.join(path))),
+                        )
+                        .await?;
+                    if let Some(tool_call_id) = tool_call_id {
+                        self.notify(
+                            session_id,
+                            acp::SessionUpdate::ToolCallUpdate(acp::ToolCallUpdate::new(
+                                tool_call_id.clone(),
+                                acp::ToolCallUpdateFields::new().content(vec![
+                                    acp::ToolCallContent::Terminal(acp::Terminal::new(
+                                        terminal.terminal_id.clone(),
+                                    )),
+                                ]),
+                            )),
+                        )
+                        .await?;
+                    }
+                    client
+                        .wait_for_terminal_exit(acp::WaitForTerminalExitRequest::new(
+                            session_id.clone(),
+                            terminal.terminal_id.clone(),
+                        ))
+                        .await?;
+                    client
+                        .release_terminal(acp::ReleaseTerminalRequest::new(
+                            session_id.clone(),
+                            terminal.terminal_id,
+                        ))
+                        .await?;
+                }
+                MockEvent::ExtMethod {
+                    method,
+                    params,
+                    echo,
+                } => {
+                    let params = params.clone().unwrap_or_else(|| serde_json::json!({}));
+                    let params = serde_json::value::to_raw_value(&params)
+                        .map_err(|error| acp::Error::invalid_params().data(error.to_string()))?;
+                    let response = self
+                        .client()?
+                        .ext_method(acp::ExtRequest::new(method.as_str(), params.into()))
+                        .await;
+                    if *echo {
+                        let text = match response {
+                            Ok(response) => serde_json::to_string(&response).map_err(|error| {
+                                acp::Error::internal_error().data(error.to_string())
+                            })?,
+                            Err(error) => format!("error: {}", error.message),
+                        };
+                        self.notify(
+                            session_id,
+                            acp::SessionUpdate::AgentMessageChunk(acp::ContentChunk::new(
+                                text.into(),
+                            )),
+                        )
+                        .await?;
+                    } else {
+                        response?;
+                    }
+                }
+                MockEvent::Sleep { ms } => {
+                    (self.sleep)(Duration::from_millis(*ms)).await;
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl acp::Agent for MockAgent {
+    async fn initialize(
+        &self,
+        _args: acp::InitializeRequest,
+    ) -> Result<acp::InitializeResponse, acp::Error> {
+        Ok(acp::InitializeResponse::new(acp::ProtocolVersion::V1)
+            .agent_info(acp::Implementation::new("mock-agent", "0.1.0")))
+    }
+
+    async fn authenticate(
+        &self,
+        _args: acp::AuthenticateRequest,
+    ) -> Result<acp::AuthenticateResponse, acp::Error> {
+        Ok(Default::default())
+    }
+
+    async fn new_session(
+        &self,
+        args: acp::NewSessionRequest,
+    ) -> Result<acp::NewSessionResponse, acp::Error> {
+        let id = self.next_session_id.get();
+        self.next_session_id.set(id + 1);
+        let session_id = acp::SessionId::new(format!("mock-{id}"));
+        self.sessions.borrow_mut().insert(
+            session_id.clone(),
+            MockSession {
+                cwd: args.cwd,
+                used_turns: vec![false; self.script.turns.len()],
+                cancelled: Rc::new(Cell::new(false)),
+            },
+        );
+        Ok(acp::NewSessionResponse::new(session_id))
+    }
+
+    async fn prompt(&self, args: acp::PromptRequest) -> Result<acp::PromptResponse, acp::Error> {
+        let prompt_text = args
+            .prompt
+            .iter()
+            .filter_map(|block| match block {
+                acp::ContentBlock::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("");
+
+        let (turn, cwd, cancelled) = {
+            let mut sessions = self.sessions.borrow_mut();
+            let session = sessions
+                .get_mut(&args.session_id)
+                .ok_or_else(|| acp::Error::invalid_params().data("unknown session"))?;
+            let turn_ix = self
+                .script
+                .turns
+                .iter()
+                .enumerate()
+                .position(|(ix, turn)| {
+                    !session.used_turns[ix]
+                        && turn
+                            .prompt_contains
+                            .as_ref()
+                            .is_none_or(|text| prompt_text.contains(text.as_str()))
+                })
+                .ok_or_else(|| {
+                    acp::Error::internal_error()
+                        .data(format!("no turn in the script matches {prompt_text:?}"))
+                })?;
+            session.used_turns[turn_ix] = true;
+            session.cancelled.set(false);
+            (
+                self.script.turns[turn_ix].clone(),
+                session.cwd.clone(),
+                session.cancelled.clone(),
+            )
+        };
+
+        let stop_reason = self
+            .run_events(&args.session_id, &cwd, &turn.events, &cancelled)
+            .await?
+            .unwrap_or(turn.stop_reason);
+        Ok(acp::PromptResponse::new(stop_reason))
+    }
+
+    async fn cancel(&self, args: acp::CancelNotification) -> Result<(), acp::Error> {
+        if let Some(session) = self.sessions.borrow().get(&args.session_id) {
+            session.cancelled.set(true);
+        }
+        Ok(())
+    }
+}
+
+/// Runs a [`MockAgent`] inside Zed and connects to it like any other ACP agent.
+pub struct MockAgentServer {
+    script: MockAgentScript,
+}
+
+impl MockAgentServer {
+    pub fn new(script: MockAgentScript) -> Self {
+        Self { script }
+    }
+}
+
+impl AgentServer for MockAgentServer {
+    fn logo(&self) -> ui::IconName {
+        ui::IconName::Ai
+    }
+
+    fn name(&self) -> SharedString {
+        "Mock Agent".into()
+    }
+
+    fn connect(
+        &self,
+        root_dir: Option<&Path>,
+        _delegate: AgentServerDelegate,
+        cx: &mut App,
+    ) -> Task<Result<(Rc<dyn AgentConnection>, Option<task::SpawnInTerminal>)>> {
+        let script = self.script.clone();
+        let root_dir = root_dir.map(Path::to_path_buf).unwrap_or_default();
+        let name = self.name();
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let (client_writer, agent_reader) = async_pipe::pipe();
+            let (agent_writer, client_reader) = async_pipe::pipe();
+
+            let executor = cx.background_executor().clone();
+            let (agent, client_cell) = MockAgent::new(script, move |duration| {
+                let timer = executor.timer(duration);
+                Box::pin(async move {
+                    timer.await;
+                })
+            });
+            let foreground_executor = cx.foreground_executor().clone();
+            let (agent_connection, io_task) =
+                acp::AgentSideConnection::new(agent, agent_writer, agent_reader, {
+                    let foreground_executor = foreground_executor.clone();
+                    move |fut| {
+                        foreground_executor.spawn(fut).detach();
+                    }
+                });
+            client_cell.set(agent_connection).ok();
+            foreground_executor.spawn(io_task).detach();
+
+            let connection =
+                AcpConnection::in_process(name, client_writer, client_reader, &root_dir, cx)
+                    .await?;
+            Ok((Rc::new(connection) as Rc<dyn AgentConnection>, None))
+        })
+    }
+
+    fn into_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AcpPermissionRules, AutoPermission};
+    use acp_thread::{AcpThread, AgentThreadEntry, ToolCall, ToolCallStatus};
+    use fs::{FakeFs, Fs as _};
+    use gpui::{Entity, TestAppContext};
+    use indoc::indoc;
+    use project::Project;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) -> std::sync::Arc<FakeFs> {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+        FakeFs::new(cx.executor())
+    }
+
+    async fn new_thread(
+        script: &str,
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> Entity<AcpThread> {
+        let server = MockAgentServer::new(MockAgentScript::from_json(script).unwrap());
+        let store = project.read_with(cx, |project, _| project.agent_server_store().clone());
+        let delegate = AgentServerDelegate::new(store, project.clone(), None, None);
+        let (connection, _) = cx
+            .update(|cx| server.connect(Some(Path::new(path!("/project"))), delegate, cx))
+            .await
+            .unwrap();
+        cx.update(|cx| connection.new_session(project.clone(), Path::new(path!("/project")), cx))
+            .await
+            .unwrap()
+    }
+
+    #[gpui::test]
+    async fn test_scripted_turns(cx: &mut TestAppContext) {
+        let fs = init_test(cx);
+        fs.insert_tree(path!("/project"), json!({ "a.txt": "Lorem ipsum" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let thread = new_thread(
+            indoc! {r#"
+                {
+                    "turns": [
+                        {
+                            "prompt_contains": "read",
+                            "events": [
+                                { "type": "message", "text": "a.txt says: " },
+                                { "type": "read_text_file", "path": "a.txt", "echo": true }
+                            ]
+                        },
+                        {
+                            "events": [
+                                { "type": "write_text_file", "path": "b.txt", "content": "Hello" },
+                                { "type": "message", "text": "Done" }
+                            ]
+                        }
+                    ]
+                }
+            "#},
+            &project,
+            cx,
+        )
+        .await;
+
+        // The first turn without a filter answers prompts that don't match the others.
+        thread
+            .update(cx, |thread, cx| thread.send_raw("Write b.txt", cx))
+            .await
+            .unwrap();
+        thread
+            .update(cx, |thread, cx| thread.send_raw("Now read a.txt", cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                indoc! {"
+                    ## User
+
+                    Write b.txt
+
+                    ## Assistant
+
+                    Done
+
+                    ## User
+
+                    Now read a.txt
+
+                    ## Assistant
+
+                    a.txt says: Lorem ipsum
+
+                "}
+            );
+        });
+        assert_eq!(
+            fs.load(Path::new(path!("/project/b.txt"))).await.unwrap(),
+            "Hello"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_permission_requests(cx: &mut TestAppContext) {
+        let fs = init_test(cx);
+        let project = Project::test(fs, [], cx).await;
+        let thread = new_thread(
+            indoc! {r#"
+                {
+                    "turns": [
+                        {
+                            "events": [
+                                { "type": "tool_call", "tool_call": { "toolCallId": "1", "title": "Delete everything" } },
+                                {
+                                    "type": "request_permission",
+                                    "tool_call": { "toolCallId": "1" },
+                                    "options": [
+                                        { "optionId": "allow", "name": "Allow", "kind": "allow_once" },
+                                        { "optionId": "reject", "name": "Reject", "kind": "reject_once" }
+                                    ]
+                                },
+                                { "type": "message", "text": "Deleted" }
+                            ]
+                        }
+                    ]
+                }
+            "#},
+            &project,
+            cx,
+        )
+        .await;
+
+        let turn = thread.update(cx, |thread, cx| thread.send_raw("Clean up", cx));
+        cx.run_until_parked();
+        thread.update(cx, |thread, cx| {
+            let Some(AgentThreadEntry::ToolCall(ToolCall {
+                id,
+                status: ToolCallStatus::WaitingForConfirmation { .. },
+                ..
+            })) = thread.entries().last()
+            else {
+                panic!("expected a permission request");
+            };
+            thread.authorize_tool_call(
+                id.clone(),
+                acp::PermissionOptionId::new("reject"),
+                acp::PermissionOptionKind::RejectOnce,
+                cx,
+            );
+        });
+        turn.await.unwrap();
+
+        // Rejecting ends the turn before the rest of the events.
+        thread.read_with(cx, |thread, _| {
+            assert!(matches!(
+                thread.entries().last(),
+                Some(AgentThreadEntry::ToolCall(_))
+            ));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_permission_rules(cx: &mut TestAppContext) {
+        let fs = init_test(cx);
+        let project = Project::test(fs, [], cx).await;
+        cx.update(|cx| {
+            AcpPermissionRules::set(
+                |_, tool_call, _| match tool_call.kind {
+                    Some(acp::ToolKind::Read) => Some(AutoPermission::Allow("read".into())),
+                    _ => None,
+                },
+                cx,
+            )
+        });
+        let thread = new_thread(
+            indoc! {r#"
+                {
+                    "turns": [
+                        {
+                            "events": [
+                                { "type": "tool_call", "tool_call": { "toolCallId": "1", "title": "Read a.txt", "kind": "read" } },
+                                {
+                                    "type": "request_permission",
+                                    "tool_call": { "toolCallId": "1" },
+                                    "options": [
+                                        { "optionId": "allow", "name": "Allow", "kind": "allow_once" },
+                                        { "optionId": "reject", "name": "Reject", "kind": "reject_once" }
+                                    ]
+                                },
+                                { "type": "message", "text": "Read it" }
+                            ]
+                        },
+                        {
+                            "events": [
+                                { "type": "tool_call", "tool_call": { "toolCallId": "2", "title": "Read b.txt", "kind": "read" } },
+                                {
+                                    "type": "request_permission",
+                                    "tool_call": { "toolCallId": "2" },
+                                    "options": [
+                                        { "optionId": "allow", "name": "Always Allow", "kind": "allow_always" },
+                                        { "optionId": "reject", "name": "Reject", "kind": "reject_once" }
+                                    ]
+                                },
+                                { "type": "message", "text": "Read it" }
+                            ]
+                        }
+                    ]
+                }
+            "#},
+            &project,
+            cx,
+        )
+        .await;
+
+        // The kind comes from the earlier tool call, so the rule allows it without asking.
+        thread
+            .update(cx, |thread, cx| thread.send_raw("Read a.txt", cx))
+            .await
+            .unwrap();
+        thread.read_with(cx, |thread, _| {
+            assert!(matches!(
+                thread.entries().last(),
+                Some(AgentThreadEntry::AssistantMessage(_))
+            ));
+        });
+
+        // Choosing "always" would allow the tool's later calls without the rules, so the user
+        // is asked instead.
+        let turn = thread.update(cx, |thread, cx| thread.send_raw("Read b.txt", cx));
+        cx.run_until_parked();
+        thread.update(cx, |thread, cx| {
+            let Some(AgentThreadEntry::ToolCall(ToolCall {
+                id,
+                status: ToolCallStatus::WaitingForConfirmation { .. },
+                ..
+            })) = thread.entries().last()
+            else {
+                panic!("expected a permission request");
+            };
+            thread.authorize_tool_call(
+                id.clone(),
+                acp::PermissionOptionId::new("reject"),
+                acp::PermissionOptionKind::RejectOnce,
+                cx,
+            );
+        });
+        turn.await.unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_concurrent_sessions(cx: &mut TestAppContext) {
+        let fs = init_test(cx);
+        let project = Project::test(fs, [], cx).await;
+        let script = indoc! {r#"
+            {
+                "turns": [
+                    {
+                        "prompt_contains": "slow",
+                        "events": [
+                            { "type": "message", "text": "Thinking" },
+                            { "type": "sleep", "ms": 1000 },
+                            { "type": "message", "text": " slowly" }
+                        ]
+                    },
+                    {
+                        "prompt_contains": "fast",
+                        "events": [{ "type": "message", "text": "Quick" }]
+                    }
+                ]
+            }
+        "#};
+        let slow_thread = new_thread(script, &project, cx).await;
+        let fast_thread = new_thread(script, &project, cx).await;
+
+        let slow_turn = slow_thread.update(cx, |thread, cx| thread.send_raw("Be slow", cx));
+        let fast_turn = fast_thread.update(cx, |thread, cx| thread.send_raw("Be fast", cx));
+        fast_turn.await.unwrap();
+        cx.run_until_parked();
+
+        slow_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                "## User\n\nBe slow\n\n## Assistant\n\nThinking\n\n"
+            );
+        });
+        fast_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                "## User\n\nBe fast\n\n## Assistant\n\nQuick\n\n"
+            );
+        });
+
+        cx.executor().advance_clock(Duration::from_secs(1));
+        slow_turn.await.unwrap();
+        slow_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                "## User\n\nBe slow\n\n## Assistant\n\nThinking slowly\n\n"
+            );
+        });
+    }
+}
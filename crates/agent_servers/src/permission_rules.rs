@@ -0,0 +1,45 @@
+use agent_client_protocol as acp;
+use gpui::{App, Global};
+use std::rc::Rc;
+
+/// An answer to an external agent's permission request that doesn't need the user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AutoPermission {
+    /// Allows the request, giving the rule that allowed it for the audit log.
+    Allow(String),
+    /// Rejects the request, giving the reason shown in the tool call.
+    Deny(String),
+}
+
+type PermissionRule = Rc<dyn Fn(&str, &acp::ToolCallUpdateFields, &App) -> Option<AutoPermission>>;
+
+/// Answers the permission requests of external agents on the user's behalf.
+///
+/// The rules themselves live with the native agent's `tool_permissions`, which this crate can't
+/// depend on, so they're installed at startup with [`AcpPermissionRules::set`].
+#[derive(Default)]
+pub struct AcpPermissionRules {
+    rule: Option<PermissionRule>,
+}
+
+impl Global for AcpPermissionRules {}
+
+impl AcpPermissionRules {
+    /// Sets the function deciding permission requests. It's given the agent's name and the tool
+    /// call the agent wants to run, and returns `None` to ask the user.
+    pub fn set(
+        rule: impl Fn(&str, &acp::ToolCallUpdateFields, &App) -> Option<AutoPermission> + 'static,
+        cx: &mut App,
+    ) {
+        cx.default_global::<Self>().rule = Some(Rc::new(rule));
+    }
+
+    pub(crate) fn decide(
+        agent_name: &str,
+        tool_call: &acp::ToolCallUpdateFields,
+        cx: &App,
+    ) -> Option<AutoPermission> {
+        let rule = cx.try_global::<Self>()?.rule.clone()?;
+        rule(agent_name, tool_call, cx)
+    }
+}
@@ -0,0 +1,401 @@
+use std::{any::Any, cell::RefCell, path::Path, rc::Rc};
+
+use acp_thread::{AcpThread, AgentConnection, PermissionOptions, UserMessageId};
+use acp_tools::{AcpRecording, RecordedContent, RecordedDirection, RecordedMessage};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use gpui::{App, AppContext as _, AsyncApp, Entity, SharedString, Task, WeakEntity};
+use project::Project;
+use serde::de::DeserializeOwned;
+
+const INITIALIZE_METHOD: &str = "initialize";
+const NEW_SESSION_METHOD: &str = "session/new";
+const PROMPT_METHOD: &str = "session/prompt";
+const SESSION_UPDATE_METHOD: &str = "session/update";
+const REQUEST_PERMISSION_METHOD: &str = "session/request_permission";
+
+/// An [`AgentConnection`] that plays an [`AcpRecording`] back instead of talking to an agent.
+///
+/// Every new session and prompt consumes the next `session/new` or `session/prompt` request
+/// in the recording, and the agent's messages up to the recorded response are applied to
+/// the thread in order, without waiting for the recorded delays. Permission requests are
+/// shown as usual, but what's replayed after them doesn't depend on the user's choice.
+pub struct ReplayAgentConnection {
+    server_name: SharedString,
+    auth_methods: Vec<acp::AuthMethod>,
+    prompt_capabilities: acp::PromptCapabilities,
+    state: Rc<RefCell<ReplayState>>,
+}
+
+struct ReplayState {
+    messages: Vec<RecordedMessage>,
+    /// The index of the next message to replay.
+    cursor: usize,
+    sessions: HashMap<acp::SessionId, WeakEntity<AcpThread>>,
+    /// The sessions whose current turn was cancelled.
+    cancelled: HashSet<acp::SessionId>,
+}
+
+impl ReplayAgentConnection {
+    pub fn new(server_name: impl Into<SharedString>, recording: AcpRecording) -> Result<Self> {
+        let state = ReplayState {
+            messages: recording.messages,
+            cursor: 0,
+            sessions: HashMap::default(),
+            cancelled: HashSet::default(),
+        };
+        let initialize_response = state
+            .next_request(INITIALIZE_METHOD)
+            .ok()
+            .and_then(|request_id| state.response(&request_id))
+            .map(parse_params::<acp::InitializeResponse>)
+            .transpose()?;
+        let (auth_methods, prompt_capabilities) = match initialize_response {
+            Some(response) => (
+                response.auth_methods,
+                response.agent_capabilities.prompt_capabilities,
+            ),
+            None => (Vec::new(), acp::PromptCapabilities::new()),
+        };
+
+        Ok(Self {
+            server_name: server_name.into(),
+            auth_methods,
+            prompt_capabilities,
+            state: Rc::new(RefCell::new(state)),
+        })
+    }
+
+    pub fn from_jsonl(server_name: impl Into<SharedString>, jsonl: &str) -> Result<Self> {
+        Self::new(server_name, AcpRecording::from_jsonl(jsonl)?)
+    }
+}
+
+impl ReplayState {
+    /// Finds the next request Zed sent with the given method, without consuming anything.
+    fn next_request(&self, method: &str) -> Result<acp::RequestId> {
+        self.messages[self.cursor..]
+            .iter()
+            .find_map(|message| match &message.content {
+                RecordedContent::Request {
+                    id,
+                    method: request_method,
+                    ..
+                } if message.direction == RecordedDirection::Outgoing
+                    && request_method == method =>
+                {
+                    Some(id.clone())
+                }
+                _ => None,
+            })
+            .with_context(|| format!("the recording has no more {method} requests"))
+    }
+
+    /// Returns the result the agent recorded for one of Zed's requests.
+    fn response(&self, request_id: &acp::RequestId) -> Option<Option<serde_json::Value>> {
+        self.messages
+            .iter()
+            .find_map(|message| match &message.content {
+                RecordedContent::Response { id, result, .. }
+                    if message.direction == RecordedDirection::Incoming && id == request_id =>
+                {
+                    Some(result.clone())
+                }
+                _ => None,
+            })
+    }
+
+    fn next_message(&mut self) -> Option<RecordedMessage> {
+        let message = self.messages.get(self.cursor).cloned()?;
+        self.cursor += 1;
+        Some(message)
+    }
+
+    fn thread(&self, session_id: &acp::SessionId) -> Result<WeakEntity<AcpThread>> {
+        self.sessions
+            .get(session_id)
+            .cloned()
+            .with_context(|| format!("the recording refers to unknown session {session_id}"))
+    }
+
+    /// Skips past the agent's response to the given request.
+    fn skip_to_response(&mut self, request_id: &acp::RequestId) {
+        while let Some(message) = self.next_message() {
+            if message.direction == RecordedDirection::Incoming
+                && let RecordedContent::Response { id, .. } = &message.content
+                && id == request_id
+            {
+                break;
+            }
+        }
+    }
+}
+
+/// Applies the agent's messages to their threads until it responds to `request_id`, and
+/// returns its response. Returns `None` if the turn of `session_id` was cancelled in the
+/// meantime.
+async fn replay_until_response(
+    state: Rc<RefCell<ReplayState>>,
+    request_id: acp::RequestId,
+    session_id: Option<&acp::SessionId>,
+    cx: &mut AsyncApp,
+) -> Result<Option<Option<serde_json::Value>>> {
+    loop {
+        let message = {
+            let mut state = state.borrow_mut();
+            if session_id.is_some_and(|session_id| state.cancelled.remove(session_id)) {
+                state.skip_to_response(&request_id);
+                return Ok(None);
+            }
+            state
+                .next_message()
+                .context("the recording ended before the agent responded")?
+        };
+        if message.direction == RecordedDirection::Outgoing {
+            continue;
+        }
+        if let RecordedContent::Response { id, result, error } = &message.content
+            && *id == request_id
+        {
+            if let Some(error) = error.clone() {
+                return Err(error.into());
+            }
+            return Ok(Some(result.clone()));
+        }
+        replay_message(&state, message, cx).await?;
+    }
+}
+
+/// Applies the agent's messages that were sent after its last response, up to the next
+/// request Zed made.
+async fn replay_pending_messages(state: Rc<RefCell<ReplayState>>, cx: &mut AsyncApp) -> Result<()> {
+    loop {
+        let message = {
+            let mut state = state.borrow_mut();
+            let is_pending = state.messages.get(state.cursor).is_some_and(|message| {
+                message.direction == RecordedDirection::Incoming
+                    && !matches!(message.content, RecordedContent::Response { .. })
+            });
+            if is_pending {
+                state.next_message()
+            } else {
+                None
+            }
+        };
+        let Some(message) = message else {
+            return Ok(());
+        };
+        replay_message(&state, message, cx).await?;
+    }
+}
+
+async fn replay_message(
+    state: &Rc<RefCell<ReplayState>>,
+    message: RecordedMessage,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    match message.content {
+        RecordedContent::Notification { method, params } if method == SESSION_UPDATE_METHOD => {
+            let notification = parse_params::<acp::SessionNotification>(params)?;
+            let thread = state.borrow().thread(&notification.session_id)?;
+            thread.update(cx, |thread, cx| {
+                thread.handle_session_update(notification.update, cx)
+            })??;
+        }
+        RecordedContent::Request { method, params, .. } if method == REQUEST_PERMISSION_METHOD => {
+            let request = parse_params::<acp::RequestPermissionRequest>(params)?;
+            let thread = state.borrow().thread(&request.session_id)?;
+            let task = thread.update(cx, |thread, cx| {
+                thread.request_tool_call_authorization(
+                    request.tool_call,
+                    PermissionOptions::Flat(request.options),
+                    cx,
+                )
+            })??;
+            task.await;
+        }
+        // Everything else the agent asked for, like reading files or running terminals,
+        // only matters to the agent, which isn't running.
+        RecordedContent::Request { method, .. } | RecordedContent::Notification { method, .. } => {
+            log::debug!("not replaying {method} from the ACP recording");
+        }
+        RecordedContent::Response { .. } => {}
+    }
+    Ok(())
+}
+
+fn parse_params<T: DeserializeOwned>(params: Option<serde_json::Value>) -> Result<T> {
+    Ok(serde_json::from_value(
+        params.unwrap_or(serde_json::Value::Null),
+    )?)
+}
+
+impl AgentConnection for ReplayAgentConnection {
+    fn telemetry_id(&self) -> SharedString {
+        "replay".into()
+    }
+
+    fn new_session(
+        self: Rc<Self>,
+        project: Entity<Project>,
+        _cwd: &Path,
+        cx: &mut App,
+    ) -> Task<Result<Entity<AcpThread>>> {
+        let request_id = match self.state.borrow().next_request(NEW_SESSION_METHOD) {
+            Ok(request_id) => request_id,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.spawn(async move |cx| {
+            let response = replay_until_response(self.state.clone(), request_id, None, cx)
+                .await?
+                .context("session creation was cancelled")?;
+            let response = parse_params::<acp::NewSessionResponse>(response)?;
+
+            let action_log = cx.new(|_| ActionLog::new(project.clone()));
+            let thread: Entity<AcpThread> = cx.new(|cx| {
+                AcpThread::new(
+                    None,
+                    self.server_name.clone(),
+                    self.clone(),
+                    project,
+                    action_log,
+                    response.session_id.clone(),
+                    watch::Receiver::constant(self.prompt_capabilities.clone()),
+                    cx,
+                )
+            });
+            self.state
+                .borrow_mut()
+                .sessions
+                .insert(response.session_id, thread.downgrade());
+
+            replay_pending_messages(self.state.clone(), cx).await?;
+            Ok(thread)
+        })
+    }
+
+    fn auth_methods(&self) -> &[acp::AuthMethod] {
+        &self.auth_methods
+    }
+
+    fn authenticate(&self, _method: acp::AuthMethodId, _cx: &mut App) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+
+    fn prompt(
+        &self,
+        _user_message_id: Option<UserMessageId>,
+        params: acp::PromptRequest,
+        cx: &mut App,
+    ) -> Task<Result<acp::PromptResponse>> {
+        let request_id = match self.state.borrow().next_request(PROMPT_METHOD) {
+            Ok(request_id) => request_id,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let session_id = params.session_id;
+        self.state.borrow_mut().cancelled.remove(&session_id);
+
+        let state = self.state.clone();
+        cx.spawn(async move |cx| {
+            let Some(response) =
+                replay_until_response(state.clone(), request_id, Some(&session_id), cx).await?
+            else {
+                return Ok(acp::PromptResponse::new(acp::StopReason::Cancelled));
+            };
+            let response = parse_params::<acp::PromptResponse>(response)?;
+            replay_pending_messages(state, cx).await?;
+            Ok(response)
+        })
+    }
+
+    fn cancel(&self, session_id: &acp::SessionId, _cx: &mut App) {
+        self.state.borrow_mut().cancelled.insert(session_id.clone());
+    }
+
+    fn into_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use settings::SettingsStore;
+
+    const RECORDING: &str = indoc! {r#"
+        {"timestamp_ms":0,"direction":"outgoing","type":"request","id":0,"method":"initialize","params":{"protocolVersion":1}}
+        {"timestamp_ms":40,"direction":"incoming","type":"response","id":0,"result":{"protocolVersion":1,"agentCapabilities":{},"authMethods":[]}}
+        {"timestamp_ms":41,"direction":"outgoing","type":"request","id":1,"method":"session/new","params":{"cwd":"/test","mcpServers":[]}}
+        {"timestamp_ms":90,"direction":"incoming","type":"response","id":1,"result":{"sessionId":"recorded"}}
+        {"timestamp_ms":1000,"direction":"outgoing","type":"request","id":2,"method":"session/prompt","params":{"sessionId":"recorded","prompt":[{"type":"text","text":"Hi"}]}}
+        {"timestamp_ms":1200,"direction":"incoming","type":"notification","method":"session/update","params":{"sessionId":"recorded","update":{"sessionUpdate":"agent_message_chunk","content":{"type":"text","text":"Hello"}}}}
+        {"timestamp_ms":1250,"direction":"incoming","type":"notification","method":"session/update","params":{"sessionId":"recorded","update":{"sessionUpdate":"agent_message_chunk","content":{"type":"text","text":" there"}}}}
+        {"timestamp_ms":1300,"direction":"incoming","type":"response","id":2,"result":{"stopReason":"end_turn"}}
+    "#};
+
+    #[gpui::test]
+    async fn test_replay_recording(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let connection = Rc::new(ReplayAgentConnection::from_jsonl("replay", RECORDING).unwrap());
+
+        let thread = cx
+            .update(|cx| connection.new_session(project, Path::new("/test"), cx))
+            .await
+            .unwrap();
+        thread
+            .update(cx, |thread, cx| thread.send_raw("Hi", cx))
+            .await
+            .unwrap();
+
+        thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                "## User\n\nHi\n\n## Assistant\n\nHello there\n\n"
+            );
+        });
+
+        // The recording only has one prompt in it.
+        let result = thread
+            .update(cx, |thread, cx| thread.send_raw("Again", cx))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_cancelling_another_session(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let connection = Rc::new(ReplayAgentConnection::from_jsonl("replay", RECORDING).unwrap());
+
+        let thread = cx
+            .update(|cx| connection.new_session(project, Path::new("/test"), cx))
+            .await
+            .unwrap();
+        let send = thread.update(cx, |thread, cx| thread.send_raw("Hi", cx));
+        // Only the turn of the cancelled session stops.
+        cx.update(|cx| connection.cancel(&acp::SessionId::new("other"), cx));
+        send.await.unwrap();
+
+        thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                "## User\n\nHi\n\n## Assistant\n\nHello there\n\n"
+            );
+        });
+    }
+}
@@ -0,0 +1,191 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use util::paths::{PathMatcher, PathStyle};
+
+use crate::SessionBudget;
+
+/// An agent run that starts on its own, configured in the `jobs` setting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AgentJob {
+    pub name: Arc<str>,
+    /// The name of the agent that runs the job.
+    pub agent: Arc<str>,
+    /// The prompt, before its placeholders are replaced.
+    pub prompt: String,
+    pub trigger: AgentJobTrigger,
+    /// The limits for each run, with unset ones filled in from the agent's session budget.
+    pub budget: SessionBudget,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AgentJobTrigger {
+    Schedule(CronSchedule),
+    FileSaved(PathMatcher),
+    TaskFailed { label: Option<String> },
+    DiagnosticError(Option<PathMatcher>),
+}
+
+impl AgentJob {
+    /// Replaces the placeholders in the job's prompt.
+    pub fn render_prompt(&self, file: Option<&str>, task: Option<&str>, date: &str) -> String {
+        self.prompt
+            .replace("{file}", file.unwrap_or_default())
+            .replace("{task}", task.unwrap_or_default())
+            .replace("{date}", date)
+    }
+}
+
+/// Compiles a job whose runs have the given budget.
+pub(crate) fn compile_agent_job(
+    content: &settings::AgentJobContent,
+    budget: SessionBudget,
+) -> Result<AgentJob> {
+    let glob = |glob: &str| {
+        PathMatcher::new([glob], PathStyle::local())
+            .with_context(|| format!("invalid glob {glob:?}"))
+    };
+    let trigger = match &content.trigger {
+        settings::AgentJobTriggerContent::Schedule { cron } => {
+            AgentJobTrigger::Schedule(CronSchedule::parse(cron)?)
+        }
+        settings::AgentJobTriggerContent::FileSaved { glob: pattern } => {
+            AgentJobTrigger::FileSaved(glob(pattern)?)
+        }
+        settings::AgentJobTriggerContent::TaskFailed { label } => AgentJobTrigger::TaskFailed {
+            label: label.clone(),
+        },
+        settings::AgentJobTriggerContent::DiagnosticError { glob: pattern } => {
+            AgentJobTrigger::DiagnosticError(pattern.as_deref().map(glob).transpose()?)
+        }
+    };
+
+    Ok(AgentJob {
+        name: content.name.as_str().into(),
+        agent: job_agent_name(content).into(),
+        prompt: content.prompt.clone(),
+        trigger,
+        budget,
+    })
+}
+
+pub(crate) fn job_agent_name(content: &settings::AgentJobContent) -> &str {
+    content.agent.as_deref().unwrap_or("Zed Agent")
+}
+
+/// A cron expression with five fields: minute, hour, day of month, month, and day of week.
+///
+/// Each field is `*`, a number, a range like `1-5`, a step like `*/15` or `0-30/10`, or a
+/// comma-separated list of those.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day of month and day of week fields were both restricted, in which case
+    /// matching either of them is enough, as in cron.
+    either_day: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            bail!("expected 5 fields in cron expression {expression:?}");
+        };
+        let parse = |field: &str, range: RangeInclusive<u32>| {
+            parse_cron_field(field, range)
+                .with_context(|| format!("invalid cron expression {expression:?}"))
+        };
+
+        let mut weekdays_mask = parse(weekdays, 0..=7)?;
+        // Both 0 and 7 mean Sunday.
+        if weekdays_mask & (1 << 7) != 0 {
+            weekdays_mask |= 1;
+        }
+        Ok(Self {
+            minutes: parse(minutes, 0..=59)?,
+            hours: parse(hours, 0..=23)?,
+            days: parse(days, 1..=31)?,
+            months: parse(months, 1..=12)?,
+            weekdays: weekdays_mask,
+            either_day: *days != "*" && *weekdays != "*",
+        })
+    }
+
+    /// Returns whether the schedule fires at the given local time, where `weekday` counts
+    /// from Sunday as 0.
+    pub fn matches(&self, minute: u32, hour: u32, day: u32, month: u32, weekday: u32) -> bool {
+        let has = |mask: u64, value: u32| value < 64 && mask & (1 << value) != 0;
+        let day_matches = if self.either_day {
+            has(self.days, day) || has(self.weekdays, weekday)
+        } else {
+            has(self.days, day) && has(self.weekdays, weekday)
+        };
+        has(self.minutes, minute) && has(self.hours, hour) && has(self.months, month) && day_matches
+    }
+}
+
+fn parse_cron_field(field: &str, range: RangeInclusive<u32>) -> Result<u64> {
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (values, step) = match part.split_once('/') {
+            Some((values, step)) => (values, step.parse::<u32>()?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            bail!("step can't be 0");
+        }
+        let (start, end) = if values == "*" {
+            (*range.start(), *range.end())
+        } else if let Some((start, end)) = values.split_once('-') {
+            (start.parse()?, end.parse()?)
+        } else {
+            let value = values.parse()?;
+            // `5/10` means every 10 starting at 5.
+            (value, if step > 1 { *range.end() } else { value })
+        };
+        if !range.contains(&start) || !range.contains(&end) || start > end {
+            return Err(anyhow!(
+                "{part:?} is outside of {}-{}",
+                range.start(),
+                range.end()
+            ));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cron_schedule() {
+        let weekdays_at_six = CronSchedule::parse("0 18 * * 1-5").unwrap();
+        // October 16th, 2026 is a Friday.
+        assert!(weekdays_at_six.matches(0, 18, 16, 10, 5));
+        assert!(!weekdays_at_six.matches(1, 18, 16, 10, 5));
+        assert!(!weekdays_at_six.matches(0, 18, 18, 10, 0));
+
+        let every_quarter_hour = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert!(every_quarter_hour.matches(45, 3, 1, 1, 4));
+        assert!(!every_quarter_hour.matches(50, 3, 1, 1, 4));
+
+        // Restricting both days matches either of them.
+        let first_or_sunday = CronSchedule::parse("30 9 1 * 7").unwrap();
+        assert!(first_or_sunday.matches(30, 9, 1, 3, 2));
+        assert!(first_or_sunday.matches(30, 9, 18, 10, 0));
+        assert!(!first_or_sunday.matches(30, 9, 17, 10, 6));
+
+        assert!(CronSchedule::parse("0 18 * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+}
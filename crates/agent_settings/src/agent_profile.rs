@@ -1,13 +1,14 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::{Result, bail};
 use collections::IndexMap;
 use convert_case::{Case, Casing as _};
 use fs::Fs;
 use gpui::{App, SharedString};
+use sandbox::SandboxPolicy;
 use settings::{
     AgentProfileContent, ContextServerPresetContent, LanguageModelSelection, Settings as _,
-    SettingsContent, update_settings_file,
+    SettingsContent, TerminalSandboxContent, update_settings_file,
 };
 use util::ResultExt as _;
 
@@ -70,6 +71,10 @@ impl AgentProfile {
         let default_model = base_profile
             .as_ref()
             .and_then(|profile| profile.default_model.clone());
+        let sandbox = base_profile
+            .as_ref()
+            .map(|profile| profile.sandbox.clone())
+            .unwrap_or_default();
 
         let profile_settings = AgentProfileSettings {
             name: name.into(),
@@ -77,6 +82,7 @@ impl AgentProfile {
             enable_all_context_servers,
             context_servers,
             default_model,
+            sandbox,
         };
 
         update_settings_file(fs, cx, {
@@ -109,6 +115,7 @@ pub struct AgentProfileSettings {
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
     /// Default language model to apply when this profile becomes active.
     pub default_model: Option<LanguageModelSelection>,
+    pub sandbox: TerminalSandbox,
 }
 
 impl AgentProfileSettings {
@@ -158,6 +165,7 @@ impl AgentProfileSettings {
                     })
                     .collect(),
                 default_model: self.default_model.clone(),
+                sandbox: self.sandbox.to_content(),
             },
         );
 
@@ -173,6 +181,7 @@ impl From<AgentProfileContent> for AgentProfileSettings {
             enable_all_context_servers,
             context_servers,
             default_model,
+            sandbox,
         } = content;
 
         Self {
@@ -184,6 +193,46 @@ impl From<AgentProfileContent> for AgentProfileSettings {
                 .map(|(server_id, preset)| (server_id, preset.into()))
                 .collect(),
             default_model,
+            sandbox: sandbox.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+/// How the agent's terminal commands are sandboxed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalSandbox {
+    pub enabled: bool,
+    pub allow_network: bool,
+    /// Paths that commands can write to besides the project's folders.
+    pub writable_paths: Vec<PathBuf>,
+}
+
+impl TerminalSandbox {
+    /// Returns the policy to run commands with, or `None` if they aren't sandboxed, which is
+    /// always the case on platforms other than Linux.
+    pub fn policy(&self) -> Option<SandboxPolicy> {
+        (self.enabled && SandboxPolicy::is_supported()).then(|| SandboxPolicy {
+            writable_paths: self.writable_paths.clone(),
+            allow_network: self.allow_network,
+        })
+    }
+
+    /// Returns the settings for this sandbox, or `None` if it's the default.
+    pub fn to_content(&self) -> Option<TerminalSandboxContent> {
+        (*self != Self::default()).then(|| TerminalSandboxContent {
+            enabled: Some(self.enabled),
+            allow_network: Some(self.allow_network),
+            writable_paths: Some(self.writable_paths.clone()),
+        })
+    }
+}
+
+impl From<TerminalSandboxContent> for TerminalSandbox {
+    fn from(content: TerminalSandboxContent) -> Self {
+        Self {
+            enabled: content.enabled.unwrap_or_default(),
+            allow_network: content.allow_network.unwrap_or_default(),
+            writable_paths: content.writable_paths.unwrap_or_default(),
         }
     }
 }
@@ -1,20 +1,26 @@
+mod agent_job;
 mod agent_profile;
 
 use std::path::{Component, Path};
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use agent_client_protocol::ModelId;
+use anyhow::Context as _;
 use collections::{HashSet, IndexMap};
 use gpui::{App, Pixels, px};
 use language_model::LanguageModel;
 use project::DisableAiSettings;
+use sandbox::SandboxPolicy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
     DefaultAgentView, DockPosition, DockSide, LanguageModelParameters, LanguageModelSelection,
     NotifyWhenAgentWaiting, RegisterSetting, Settings, ToolPermissionMode,
 };
+use util::ResultExt as _;
 
+pub use crate::agent_job::*;
 pub use crate::agent_profile::*;
 
 pub const SUMMARIZE_THREAD_PROMPT: &str = include_str!("prompts/summarize_thread_prompt.txt");
@@ -51,7 +57,13 @@ pub struct AgentSettings {
     pub use_modifier_to_send: bool,
     pub message_editor_min_lines: usize,
     pub show_turn_stats: bool,
+    pub enable_agent_sessions_server: bool,
     pub tool_permissions: ToolPermissions,
+    /// The `tool_permissions` of each external agent that has its own rules,
+    /// already combined with the global ones.
+    pub agent_tool_permissions: collections::HashMap<Arc<str>, ToolPermissions>,
+    pub session_budgets: SessionBudgets,
+    pub jobs: Vec<AgentJob>,
 }
 
 impl AgentSettings {
@@ -81,6 +93,27 @@ impl AgentSettings {
         self.message_editor_min_lines * 2
     }
 
+    /// Returns the permission rules that apply to the given external agent.
+    pub fn tool_permissions_for_agent(&self, agent_name: &str) -> &ToolPermissions {
+        self.agent_tool_permissions
+            .get(agent_name)
+            .unwrap_or(&self.tool_permissions)
+    }
+
+    /// Returns the limits for a new tab of the given agent.
+    pub fn session_budget(&self, agent_name: &str) -> SessionBudget {
+        self.session_budgets
+            .agents
+            .get(agent_name)
+            .copied()
+            .unwrap_or(self.session_budgets.default)
+    }
+
+    /// Returns the sandbox for the terminal commands run with the given profile, if any.
+    pub fn terminal_sandbox(&self, profile_id: &AgentProfileId) -> Option<SandboxPolicy> {
+        self.profiles.get(profile_id)?.sandbox.policy()
+    }
+
     pub fn favorite_model_ids(&self) -> HashSet<ModelId> {
         self.favorite_models
             .iter()
@@ -110,6 +143,29 @@ impl Default for AgentProfileId {
     }
 }
 
+/// Limits after which a running agent is stopped automatically.
+/// `None` means the corresponding limit is not enforced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionBudget {
+    pub max_duration: Option<Duration>,
+    pub max_tokens: Option<u64>,
+    pub max_tool_calls: Option<u64>,
+    pub max_files_modified: Option<u64>,
+}
+
+impl SessionBudget {
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SessionBudgets {
+    pub default: SessionBudget,
+    /// Per-agent budgets, with unset limits already filled in from `default`.
+    pub agents: collections::HashMap<Arc<str>, SessionBudget>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ToolPermissions {
     /// Global default permission when no tool-specific rules or patterns match.
@@ -439,11 +495,90 @@ impl Settings for AgentSettings {
             use_modifier_to_send: agent.use_modifier_to_send.unwrap(),
             message_editor_min_lines: agent.message_editor_min_lines.unwrap(),
             show_turn_stats: agent.show_turn_stats.unwrap(),
+            enable_agent_sessions_server: agent.enable_agent_sessions_server.unwrap(),
+            agent_tool_permissions: compile_agent_tool_permissions(agent.tool_permissions.as_ref()),
             tool_permissions: compile_tool_permissions(agent.tool_permissions),
+            jobs: compile_agent_jobs(agent.jobs, agent.session_budgets.as_ref()),
+            session_budgets: compile_session_budgets(agent.session_budgets),
         }
     }
 }
 
+fn compile_session_budgets(content: Option<settings::SessionBudgetsContent>) -> SessionBudgets {
+    let Some(content) = content else {
+        return SessionBudgets::default();
+    };
+
+    let default_content = content.default.unwrap_or_default();
+    let agents = content
+        .agents
+        .iter()
+        .map(|(agent_name, budget)| {
+            (
+                agent_name.clone(),
+                compile_session_budget(budget, &default_content),
+            )
+        })
+        .collect();
+
+    SessionBudgets {
+        default: compile_session_budget(&default_content, &Default::default()),
+        agents,
+    }
+}
+
+/// Compiles the jobs that are valid, logging the others.
+fn compile_agent_jobs(
+    content: Option<Vec<settings::AgentJobContent>>,
+    session_budgets: Option<&settings::SessionBudgetsContent>,
+) -> Vec<AgentJob> {
+    let default_budget = session_budgets
+        .and_then(|budgets| budgets.default.clone())
+        .unwrap_or_default();
+    content
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|job| {
+            // Unset limits fall back to the agent's budget, and then to the default one.
+            let agent_budget = session_budgets
+                .and_then(|budgets| budgets.agents.get(agent_job::job_agent_name(job)))
+                .map(|budget| compile_session_budget(budget, &default_budget))
+                .unwrap_or_else(|| compile_session_budget(&default_budget, &Default::default()));
+            let budget = job
+                .budget
+                .as_ref()
+                .map(|budget| compile_session_budget(budget, &session_budget_content(agent_budget)))
+                .unwrap_or(agent_budget);
+            agent_job::compile_agent_job(job, budget)
+                .with_context(|| format!("invalid agent job {:?}", job.name))
+                .log_err()
+        })
+        .collect()
+}
+
+fn session_budget_content(budget: SessionBudget) -> settings::SessionBudgetContent {
+    settings::SessionBudgetContent {
+        max_duration_minutes: budget.max_duration.map(|duration| duration.as_secs() / 60),
+        max_tokens: budget.max_tokens,
+        max_tool_calls: budget.max_tool_calls,
+        max_files_modified: budget.max_files_modified,
+    }
+}
+
+fn compile_session_budget(
+    content: &settings::SessionBudgetContent,
+    fallback: &settings::SessionBudgetContent,
+) -> SessionBudget {
+    let limit = |value: Option<u64>, fallback: Option<u64>| value.or(fallback).filter(|&n| n > 0);
+    SessionBudget {
+        max_duration: limit(content.max_duration_minutes, fallback.max_duration_minutes)
+            .map(|minutes| Duration::from_secs(minutes * 60)),
+        max_tokens: limit(content.max_tokens, fallback.max_tokens),
+        max_tool_calls: limit(content.max_tool_calls, fallback.max_tool_calls),
+        max_files_modified: limit(content.max_files_modified, fallback.max_files_modified),
+    }
+}
+
 fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -> ToolPermissions {
     let Some(content) = content else {
         return ToolPermissions::default();
@@ -453,49 +588,7 @@ fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -
         .tools
         .into_iter()
         .map(|(tool_name, rules_content)| {
-            let mut invalid_patterns = Vec::new();
-
-            let (always_allow, allow_errors) = compile_regex_rules(
-                rules_content.always_allow.map(|v| v.0).unwrap_or_default(),
-                "always_allow",
-            );
-            invalid_patterns.extend(allow_errors);
-
-            let (always_deny, deny_errors) = compile_regex_rules(
-                rules_content.always_deny.map(|v| v.0).unwrap_or_default(),
-                "always_deny",
-            );
-            invalid_patterns.extend(deny_errors);
-
-            let (always_confirm, confirm_errors) = compile_regex_rules(
-                rules_content
-                    .always_confirm
-                    .map(|v| v.0)
-                    .unwrap_or_default(),
-                "always_confirm",
-            );
-            invalid_patterns.extend(confirm_errors);
-
-            // Log invalid patterns for debugging. Users will see an error when they
-            // attempt to use a tool with invalid patterns in their settings.
-            for invalid in &invalid_patterns {
-                log::error!(
-                    "Invalid regex pattern in tool_permissions for '{}' tool ({}): '{}' - {}",
-                    tool_name,
-                    invalid.rule_type,
-                    invalid.pattern,
-                    invalid.error,
-                );
-            }
-
-            let rules = ToolRules {
-                // Preserve tool-specific default; None means fall back to global default at decision time
-                default: rules_content.default,
-                always_allow,
-                always_deny,
-                always_confirm,
-                invalid_patterns,
-            };
+            let rules = compile_tool_rules(&tool_name, rules_content);
             (tool_name, rules)
         })
         .collect();
@@ -506,6 +599,93 @@ fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -
     }
 }
 
+fn compile_agent_tool_permissions(
+    content: Option<&settings::ToolPermissionsContent>,
+) -> collections::HashMap<Arc<str>, ToolPermissions> {
+    let Some(content) = content else {
+        return collections::HashMap::default();
+    };
+
+    content
+        .agents
+        .iter()
+        .map(|(agent_name, agent_content)| {
+            let mut tools = content.tools.clone();
+            for (tool_name, agent_rules) in &agent_content.tools {
+                let rules = tools.entry(tool_name.clone()).or_default();
+                if agent_rules.default.is_some() {
+                    rules.default = agent_rules.default;
+                }
+                for (patterns, agent_patterns) in [
+                    (&mut rules.always_allow, &agent_rules.always_allow),
+                    (&mut rules.always_deny, &agent_rules.always_deny),
+                    (&mut rules.always_confirm, &agent_rules.always_confirm),
+                ] {
+                    if let Some(agent_patterns) = agent_patterns {
+                        patterns
+                            .get_or_insert_default()
+                            .0
+                            .extend(agent_patterns.0.iter().cloned());
+                    }
+                }
+            }
+
+            let permissions = compile_tool_permissions(Some(settings::ToolPermissionsContent {
+                default: agent_content.default.or(content.default),
+                tools,
+                agents: Default::default(),
+            }));
+            (agent_name.clone(), permissions)
+        })
+        .collect()
+}
+
+fn compile_tool_rules(tool_name: &str, rules_content: settings::ToolRulesContent) -> ToolRules {
+    let mut invalid_patterns = Vec::new();
+
+    let (always_allow, allow_errors) = compile_regex_rules(
+        rules_content.always_allow.map(|v| v.0).unwrap_or_default(),
+        "always_allow",
+    );
+    invalid_patterns.extend(allow_errors);
+
+    let (always_deny, deny_errors) = compile_regex_rules(
+        rules_content.always_deny.map(|v| v.0).unwrap_or_default(),
+        "always_deny",
+    );
+    invalid_patterns.extend(deny_errors);
+
+    let (always_confirm, confirm_errors) = compile_regex_rules(
+        rules_content
+            .always_confirm
+            .map(|v| v.0)
+            .unwrap_or_default(),
+        "always_confirm",
+    );
+    invalid_patterns.extend(confirm_errors);
+
+    // Log invalid patterns for debugging. Users will see an error when they
+    // attempt to use a tool with invalid patterns in their settings.
+    for invalid in &invalid_patterns {
+        log::error!(
+            "Invalid regex pattern in tool_permissions for '{}' tool ({}): '{}' - {}",
+            tool_name,
+            invalid.rule_type,
+            invalid.pattern,
+            invalid.error,
+        );
+    }
+
+    ToolRules {
+        // Preserve tool-specific default; None means fall back to global default at decision time
+        default: rules_content.default,
+        always_allow,
+        always_deny,
+        always_confirm,
+        invalid_patterns,
+    }
+}
+
 fn compile_regex_rules(
     rules: Vec<settings::ToolRegexRule>,
     rule_type: &str,
@@ -617,6 +797,171 @@ mod tests {
         assert_eq!(permissions.default, ToolPermissionMode::Confirm);
     }
 
+    #[test]
+    fn test_session_budgets_per_agent_overrides() {
+        let json = json!({
+            "default": {
+                "max_duration_minutes": 30,
+                "max_tool_calls": 100
+            },
+            "agents": {
+                "Claude Code": {
+                    "max_tool_calls": 0,
+                    "max_files_modified": 10
+                }
+            }
+        });
+
+        let content: settings::SessionBudgetsContent = serde_json::from_value(json).unwrap();
+        let budgets = compile_session_budgets(Some(content));
+
+        assert_eq!(
+            budgets.default,
+            SessionBudget {
+                max_duration: Some(Duration::from_secs(30 * 60)),
+                max_tokens: None,
+                max_tool_calls: Some(100),
+                max_files_modified: None,
+            }
+        );
+        assert_eq!(
+            budgets.agents.get("Claude Code"),
+            Some(&SessionBudget {
+                max_duration: Some(Duration::from_secs(30 * 60)),
+                max_tokens: None,
+                max_tool_calls: None,
+                max_files_modified: Some(10),
+            })
+        );
+        assert!(compile_session_budgets(None).default.is_unlimited());
+    }
+
+    #[test]
+    fn test_agent_jobs_budgets_and_invalid_jobs() {
+        let budgets: settings::SessionBudgetsContent = serde_json::from_value(json!({
+            "default": { "max_duration_minutes": 30 },
+            "agents": { "Claude Code": { "max_tool_calls": 100 } }
+        }))
+        .unwrap();
+        let jobs: Vec<settings::AgentJobContent> = serde_json::from_value(json!([
+            {
+                "name": "Fix lint errors",
+                "agent": "Claude Code",
+                "prompt": "Fix the lint errors in {file}.",
+                "trigger": { "on": "file_saved", "glob": "**/*.rs" },
+                "budget": { "max_tool_calls": 20, "max_duration_minutes": 0 }
+            },
+            {
+                "name": "Daily summary",
+                "prompt": "Summarize the commits made on {date}.",
+                "trigger": { "on": "schedule", "cron": "0 18 * * *" }
+            },
+            {
+                "name": "Broken",
+                "prompt": "",
+                "trigger": { "on": "schedule", "cron": "every day" }
+            }
+        ]))
+        .unwrap();
+
+        let jobs = compile_agent_jobs(Some(jobs), Some(&budgets));
+        assert_eq!(jobs.len(), 2);
+
+        assert_eq!(jobs[0].agent.as_ref(), "Claude Code");
+        assert!(matches!(
+            &jobs[0].trigger,
+            AgentJobTrigger::FileSaved(glob) if glob.is_match(util::rel_path::rel_path("src/main.rs"))
+        ));
+        assert_eq!(
+            jobs[0].budget,
+            SessionBudget {
+                max_duration: None,
+                max_tokens: None,
+                max_tool_calls: Some(20),
+                max_files_modified: None,
+            }
+        );
+        assert_eq!(
+            jobs[0].render_prompt(Some("src/main.rs"), None, "2026-10-16"),
+            "Fix the lint errors in src/main.rs."
+        );
+
+        assert_eq!(jobs[1].agent.as_ref(), "Zed Agent");
+        assert_eq!(
+            jobs[1].budget,
+            SessionBudget {
+                max_duration: Some(Duration::from_secs(30 * 60)),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_agent_tool_permissions_extend_global_rules() {
+        let json = json!({
+            "default": "confirm",
+            "tools": {
+                "terminal": {
+                    "always_deny": [{ "pattern": "rm\\s+-rf" }]
+                }
+            },
+            "agents": {
+                "Claude Code": {
+                    "default": "allow",
+                    "tools": {
+                        "terminal": {
+                            "default": "confirm",
+                            "always_allow": [{ "pattern": "^git\\s" }]
+                        }
+                    }
+                }
+            }
+        });
+
+        let content: ToolPermissionsContent = serde_json::from_value(json).unwrap();
+        let agent_permissions = compile_agent_tool_permissions(Some(&content));
+        let global_permissions = compile_tool_permissions(Some(content));
+
+        let global_terminal = global_permissions.tools.get("terminal").unwrap();
+        assert!(global_terminal.always_allow.is_empty());
+        assert_eq!(global_permissions.default, ToolPermissionMode::Confirm);
+
+        let claude = agent_permissions.get("Claude Code").unwrap();
+        assert_eq!(claude.default, ToolPermissionMode::Allow);
+        let terminal = claude.tools.get("terminal").unwrap();
+        assert_eq!(terminal.default, Some(ToolPermissionMode::Confirm));
+        assert!(terminal.always_deny[0].is_match("rm -rf /"));
+        assert!(terminal.always_allow[0].is_match("git status"));
+        assert!(agent_permissions.get("Codex").is_none());
+    }
+
+    #[test]
+    fn test_profile_terminal_sandbox() {
+        let json = json!({
+            "name": "Sandboxed",
+            "sandbox": {
+                "enabled": true,
+                "writable_paths": ["/home/me/.cache"]
+            }
+        });
+
+        let content: settings::AgentProfileContent = serde_json::from_value(json).unwrap();
+        let profile = AgentProfileSettings::from(content);
+        assert_eq!(
+            profile.sandbox,
+            TerminalSandbox {
+                enabled: true,
+                allow_network: false,
+                writable_paths: vec!["/home/me/.cache".into()],
+            }
+        );
+        assert_eq!(
+            profile.sandbox.policy().is_some(),
+            SandboxPolicy::is_supported()
+        );
+        assert_eq!(TerminalSandbox::default().to_content(), None);
+    }
+
     #[test]
     fn test_tool_rules_default_returns_confirm() {
         let default_rules = ToolRules::default();
@@ -1,9 +1,11 @@
 mod config_options;
 mod entry_view_state;
+mod handoff;
 mod message_editor;
 mod mode_selector;
 mod model_selector;
 mod model_selector_popover;
+mod session_index;
 mod thread_history;
 mod thread_view;
 
@@ -12,3 +14,6 @@ pub use model_selector::AcpModelSelector;
 pub use model_selector_popover::AcpModelSelectorPopover;
 pub use thread_history::*;
 pub use thread_view::AcpServerView;
+#[cfg(test)]
+pub(crate) use thread_view::tests::{StubAgentServer, init_test};
+pub(crate) use thread_view::{DiffStats, SessionBudgetKind, SessionBudgetOverride};
@@ -0,0 +1,340 @@
+use std::fmt::Write as _;
+
+use acp_thread::{AcpThread, AgentThreadEntry};
+use agent_client_protocol as acp;
+use agent_settings::{AgentSettings, SUMMARIZE_THREAD_DETAILED_PROMPT};
+use anyhow::{Context as _, Result, anyhow};
+use cloud_llm_client::CompletionIntent;
+use collections::HashSet;
+use futures::StreamExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use language_model::{
+    ConfiguredModel, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, Role,
+};
+
+/// Builds the draft that continues a thread with another agent.
+///
+/// The draft carries the conversation, either in full or summarized with the thread summary
+/// model, the unreviewed changes the agent made, and everything that was mentioned.
+pub(crate) fn handoff_prompt(
+    thread: &Entity<AcpThread>,
+    agent_name: SharedString,
+    summarize: bool,
+    cx: &mut App,
+) -> Task<Result<Vec<acp::ContentBlock>>> {
+    let thread = thread.read(cx);
+    let transcript = thread.to_markdown(cx);
+    let mentions = mentions(thread.entries());
+    let changed_files = thread
+        .action_log()
+        .read(cx)
+        .changed_buffers(cx)
+        .into_iter()
+        .map(|(buffer, diff)| {
+            let buffer = buffer.read(cx);
+            let path = buffer
+                .file()
+                .map(|file| file.full_path(cx).to_string_lossy().into_owned())
+                .unwrap_or_else(|| "untitled".to_string());
+            (path, diff.read(cx).base_text(cx).text(), buffer.text())
+        })
+        .collect::<Vec<_>>();
+
+    let summary = if summarize {
+        let Some(ConfiguredModel { model, .. }) =
+            LanguageModelRegistry::read_global(cx).thread_summary_model()
+        else {
+            return Task::ready(Err(anyhow!(
+                "No model is configured for summarizing threads."
+            )));
+        };
+        let request = LanguageModelRequest {
+            intent: Some(CompletionIntent::ThreadContextSummarization),
+            temperature: AgentSettings::temperature_for_model(&model, cx),
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![format!("{transcript}\n\n{SUMMARIZE_THREAD_DETAILED_PROMPT}").into()],
+                cache: false,
+                reasoning_details: None,
+            }],
+            ..Default::default()
+        };
+        Some(cx.spawn(async move |cx| {
+            let mut chunks = model.stream_completion_text(request, cx).await?.stream;
+            let mut summary = String::new();
+            while let Some(chunk) = chunks.next().await {
+                summary.push_str(&chunk?);
+            }
+            anyhow::Ok(summary)
+        }))
+    } else {
+        None
+    };
+
+    cx.spawn(async move |cx| {
+        let context = match summary {
+            Some(summary) => summary
+                .await
+                .context("Failed to summarize the conversation")?,
+            None => transcript,
+        };
+        let changes = cx
+            .background_spawn(async move {
+                changed_files
+                    .into_iter()
+                    .map(|(path, old_text, new_text)| {
+                        format!(
+                            "`{path}`:\n```diff\n{}```\n",
+                            language::unified_diff(&old_text, &new_text)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await;
+
+        let mut text = format!(
+            "I'm continuing a conversation that I had with {agent_name}. Here's {}:\n\n\
+             <conversation>\n{}\n</conversation>\n",
+            if summarize {
+                "a summary of it"
+            } else {
+                "the full conversation"
+            },
+            context.trim(),
+        );
+        if !changes.is_empty() {
+            writeln!(
+                text,
+                "\n{agent_name} made these changes, which haven't been reviewed yet:\n"
+            )?;
+            for change in changes {
+                writeln!(text, "{change}")?;
+            }
+        }
+        if !mentions.is_empty() {
+            text.push_str("\nThe conversation mentioned: ");
+        }
+
+        let mut prompt = vec![acp::ContentBlock::Text(acp::TextContent::new(text))];
+        for mention in mentions {
+            prompt.push(mention);
+            prompt.push(acp::ContentBlock::Text(acp::TextContent::new(" ")));
+        }
+        prompt.push(acp::ContentBlock::Text(acp::TextContent::new("\n\n")));
+        Ok(prompt)
+    })
+}
+
+/// Returns the files, symbols, and other context the user mentioned, without duplicates.
+fn mentions(entries: &[AgentThreadEntry]) -> Vec<acp::ContentBlock> {
+    let mut seen_uris = HashSet::default();
+    entries
+        .iter()
+        .filter_map(AgentThreadEntry::user_message)
+        .flat_map(|message| message.chunks.iter())
+        .filter(|chunk| {
+            let uri = match chunk {
+                acp::ContentBlock::ResourceLink(link) => &link.uri,
+                acp::ContentBlock::Resource(acp::EmbeddedResource {
+                    resource: acp::EmbeddedResourceResource::TextResourceContents(resource),
+                    ..
+                }) => &resource.uri,
+                _ => return false,
+            };
+            seen_uris.insert(uri.clone())
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use acp_thread::{AgentConnection as _, StubAgentConnection};
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use indoc::formatdoc;
+    use language_model::{
+        LanguageModelRegistry,
+        fake_provider::{FakeLanguageModel, FakeLanguageModelProvider},
+    };
+    use project::Project;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{path::Path, rc::Rc, sync::Arc};
+    use util::path;
+
+    fn resource_link(path: &str) -> acp::ContentBlock {
+        acp::ContentBlock::ResourceLink(acp::ResourceLink::new(path, format!("file://{path}")))
+    }
+
+    fn mention_uri(block: &acp::ContentBlock) -> &str {
+        match block {
+            acp::ContentBlock::ResourceLink(link) => &link.uri,
+            acp::ContentBlock::Resource(acp::EmbeddedResource {
+                resource: acp::EmbeddedResourceResource::TextResourceContents(resource),
+                ..
+            }) => &resource.uri,
+            _ => panic!("expected a mention"),
+        }
+    }
+
+    async fn new_thread(cx: &mut TestAppContext) -> (Entity<Project>, Entity<AcpThread>) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "a.rs": "fn foo() {}\n" }))
+            .await;
+        let project = Project::test(fs, [Path::new(path!("/project"))], cx).await;
+        let thread = cx
+            .update(|cx| {
+                Rc::new(StubAgentConnection::new()).new_session(
+                    project.clone(),
+                    Path::new(path!("/project")),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        (project, thread)
+    }
+
+    #[gpui::test]
+    async fn test_mentions(cx: &mut TestAppContext) {
+        let (_, thread) = new_thread(cx).await;
+        thread.update(cx, |thread, cx| {
+            thread.push_user_content_block(None, "Compare ".into(), cx);
+            thread.push_user_content_block(None, resource_link("/project/a.rs"), cx);
+            thread.push_user_content_block(None, resource_link("/project/b.rs"), cx);
+            thread.push_assistant_content_block("They differ.".into(), false, cx);
+            thread.push_user_content_block(None, "Now fix ".into(), cx);
+            thread.push_user_content_block(None, resource_link("/project/a.rs"), cx);
+            thread.push_user_content_block(
+                None,
+                acp::ContentBlock::Resource(acp::EmbeddedResource::new(
+                    acp::EmbeddedResourceResource::TextResourceContents(
+                        acp::TextResourceContents::new("fn c() {}", "file:///project/c.rs"),
+                    ),
+                )),
+                cx,
+            );
+            thread.push_assistant_content_block(resource_link("/project/d.rs"), false, cx);
+        });
+
+        // Mentions are taken from the user's messages only, and each only once.
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(
+                mentions(thread.entries())
+                    .iter()
+                    .map(mention_uri)
+                    .collect::<Vec<_>>(),
+                [
+                    "file:///project/a.rs",
+                    "file:///project/b.rs",
+                    "file:///project/c.rs"
+                ]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_handoff_prompt(cx: &mut TestAppContext) {
+        let (project, thread) = new_thread(cx).await;
+        thread.update(cx, |thread, cx| {
+            thread.push_user_content_block(None, "Rename foo in ".into(), cx);
+            thread.push_user_content_block(None, resource_link("/project/a.rs"), cx);
+            thread.push_assistant_content_block("Renamed it to bar.".into(), false, cx);
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone());
+        action_log.update(cx, |action_log, cx| {
+            action_log.buffer_read(buffer.clone(), cx)
+        });
+        buffer.update(cx, |buffer, cx| buffer.edit([(3..6, "bar")], None, cx));
+        action_log.update(cx, |action_log, cx| {
+            action_log.buffer_edited(buffer.clone(), cx)
+        });
+        cx.run_until_parked();
+
+        let transcript = thread.read_with(cx, |thread, cx| thread.to_markdown(cx));
+        let changes = formatdoc! {"
+            Claude Code made these changes, which haven't been reviewed yet:
+
+            `project/a.rs`:
+            ```diff
+            {}```
+        ", language::unified_diff("fn foo() {}\n", "fn bar() {}\n")};
+
+        let prompt = cx
+            .update(|cx| handoff_prompt(&thread, "Claude Code".into(), false, cx))
+            .await
+            .unwrap();
+        let acp::ContentBlock::Text(text) = &prompt[0] else {
+            panic!("expected the prompt to start with text");
+        };
+        assert_eq!(
+            text.text,
+            formatdoc! {"
+                I'm continuing a conversation that I had with Claude Code. Here's the full conversation:
+
+                <conversation>
+                {}
+                </conversation>
+
+                {changes}
+
+                The conversation mentioned: ",
+                transcript.trim()
+            }
+        );
+        assert_eq!(mention_uri(&prompt[1]), "file:///project/a.rs");
+        assert_eq!(prompt.len(), 4);
+
+        // Summarizing hands over the summary instead of the transcript, with the same changes.
+        let fake_model = Arc::new(FakeLanguageModel::default());
+        cx.update(|cx| {
+            let provider = Arc::new(FakeLanguageModelProvider::default());
+            LanguageModelRegistry::test(cx);
+            LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+                registry.set_thread_summary_model(
+                    Some(ConfiguredModel {
+                        provider,
+                        model: fake_model.clone(),
+                    }),
+                    cx,
+                );
+            });
+        });
+        let prompt = cx.update(|cx| handoff_prompt(&thread, "Claude Code".into(), true, cx));
+        cx.run_until_parked();
+        let request = fake_model.pending_completions().pop().unwrap();
+        assert!(request.messages[0].string_contents().contains(&transcript));
+        fake_model.send_last_completion_stream_text_chunk("They renamed foo to bar.");
+        fake_model.end_last_completion_stream();
+        let prompt = prompt.await.unwrap();
+        let acp::ContentBlock::Text(text) = &prompt[0] else {
+            panic!("expected the prompt to start with text");
+        };
+        assert_eq!(
+            text.text,
+            formatdoc! {"
+                I'm continuing a conversation that I had with Claude Code. Here's a summary of it:
+
+                <conversation>
+                They renamed foo to bar.
+                </conversation>
+
+                {changes}
+
+                The conversation mentioned: "
+            }
+        );
+    }
+}
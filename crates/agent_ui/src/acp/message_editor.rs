@@ -1,7 +1,7 @@
 use crate::SendImmediately;
 use crate::acp::AcpThreadHistory;
 use crate::{
-    ChatWithFollow,
+    AgentPanel, ChatWithFollow, ExternalAgent, FanOutPrompt,
     completion_provider::{
         PromptCompletionProvider, PromptCompletionProviderDelegate, PromptContextAction,
         PromptContextType, SlashCommandCompletion,
@@ -604,6 +604,46 @@ impl MessageEditor {
         cx.emit(MessageEditorEvent::Cancel)
     }
 
+    fn fan_out_prompt(
+        &mut self,
+        action: &FanOutPrompt,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.fan_out(action.agents.clone(), window, cx);
+    }
+
+    /// Sends the current prompt, with its mentions, to each of the given agents in a new
+    /// agent panel tab, leaving this thread untouched.
+    pub fn fan_out(
+        &mut self,
+        agents: Vec<ExternalAgent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if agents.is_empty() || self.is_empty(cx) {
+            return;
+        }
+
+        let contents = self.contents(false, cx);
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let (content, _) = contents.await?;
+            this.update_in(cx, |this, window, cx| {
+                let panel = workspace
+                    .upgrade()
+                    .and_then(|workspace| workspace.read(cx).panel::<AgentPanel>(cx))
+                    .ok_or_else(|| anyhow!("agent panel not found"))?;
+                this.clear(window, cx);
+                panel.update(cx, |panel, cx| {
+                    panel.fan_out_prompt(agents, content, window, cx)
+                });
+                anyhow::Ok(())
+            })?
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
         let Some(workspace) = self.workspace.upgrade() else {
             return;
@@ -1309,6 +1349,7 @@ impl Render for MessageEditor {
             .on_action(cx.listener(Self::send_immediately))
             .on_action(cx.listener(Self::chat_with_follow))
             .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::fan_out_prompt))
             .on_action(cx.listener(Self::paste_raw))
             .capture_action(cx.listener(Self::paste))
             .flex_1()
@@ -0,0 +1,111 @@
+use std::{path::Path, rc::Rc, sync::Arc};
+
+use acp_thread::{AcpThread, AgentConnection, AgentSessionList, AgentSessionListRequest};
+use agent::ThreadSearchEntry;
+use agent_client_protocol as acp;
+use anyhow::Result;
+use chrono::Utc;
+use collections::HashSet;
+use gpui::{App, AsyncApp, Entity, SharedString, Task};
+use project::Project;
+use util::ResultExt as _;
+
+/// How many sessions to load for indexing each time an agent connects, so that a long history
+/// doesn't keep the agent busy.
+const MAX_SESSIONS_TO_INDEX: usize = 10;
+
+/// Adds a thread of an external agent to the search index.
+///
+/// Threads of the Zed agent are indexed when they're saved to the database.
+pub(crate) fn index_external_thread(
+    thread: &Entity<AcpThread>,
+    source: SharedString,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    let thread = thread.read(cx);
+    let entry = ThreadSearchEntry {
+        source,
+        thread_id: thread.session_id().0.clone(),
+        title: thread.title(),
+        updated_at: Utc::now(),
+        documents: agent::acp_thread_search_documents(thread, cx),
+    };
+    agent::index_thread(entry, cx)
+}
+
+/// Indexes the sessions in the agent's history that changed since they were last indexed.
+///
+/// Sessions are loaded one at a time, skipping the ones that are open in `open_sessions`, since
+/// loading them again would detach them from their threads.
+pub(crate) async fn index_session_list(
+    connection: Rc<dyn AgentConnection>,
+    session_list: Rc<dyn AgentSessionList>,
+    source: SharedString,
+    open_sessions: HashSet<acp::SessionId>,
+    project: Entity<Project>,
+    default_cwd: Arc<Path>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let indexed_threads = cx
+        .update(|cx| agent::indexed_threads(source.clone(), cx))
+        .await?;
+    let sessions = cx
+        .update(|cx| session_list.list_sessions(AgentSessionListRequest::default(), cx))
+        .await?
+        .sessions;
+
+    let stale_sessions = sessions
+        .into_iter()
+        .filter(|session| !open_sessions.contains(&session.session_id))
+        .filter(|session| {
+            match (
+                indexed_threads.get(&session.session_id.0),
+                session.updated_at,
+            ) {
+                (None, _) => true,
+                (Some(indexed_at), Some(updated_at)) => updated_at > *indexed_at,
+                (Some(_), None) => false,
+            }
+        })
+        .take(MAX_SESSIONS_TO_INDEX)
+        .collect::<Vec<_>>();
+
+    for session in stale_sessions {
+        let session_id = session.session_id.clone();
+        let cwd = session
+            .cwd
+            .clone()
+            .map(Arc::from)
+            .unwrap_or(default_cwd.clone());
+        let load = cx.update(|cx| {
+            connection
+                .clone()
+                .load_session(session.clone(), project.clone(), &cwd, cx)
+        });
+        let Some(thread) = load.await.log_err() else {
+            continue;
+        };
+
+        cx.update(|cx| {
+            let thread = thread.read(cx);
+            let entry = ThreadSearchEntry {
+                source: source.clone(),
+                thread_id: session_id.0.clone(),
+                title: session.title.clone().unwrap_or_else(|| thread.title()),
+                updated_at: session.updated_at.unwrap_or_else(Utc::now),
+                documents: agent::acp_thread_search_documents(thread, cx),
+            };
+            agent::index_thread(entry, cx)
+        })
+        .await
+        .log_err();
+
+        if cx.update(|cx| connection.supports_close_session(cx)) {
+            cx.update(|cx| connection.close_session(&session_id, cx))
+                .await
+                .log_err();
+        }
+    }
+
+    Ok(())
+}
@@ -16,6 +16,7 @@ use ui::{
     ElementId, HighlightedLabel, IconButtonShape, ListItem, ListItemSpacing, Tab, Tooltip,
     WithScrollbar, prelude::*,
 };
+use util::ResultExt as _;
 
 const DEFAULT_TITLE: &SharedString = &SharedString::new_static("New Thread");
 
@@ -35,6 +36,8 @@ pub struct AcpThreadHistory {
     hovered_index: Option<usize>,
     search_editor: Entity<Editor>,
     search_query: SharedString,
+    /// The agent whose messages are searched, in addition to thread titles.
+    search_source: Option<SharedString>,
     visible_items: Vec<ListItemType>,
     local_timezone: UtcOffset,
     confirming_delete_history: bool,
@@ -53,6 +56,12 @@ enum ListItemType {
         entry: AgentSessionInfo,
         positions: Vec<usize>,
     },
+    MessageSearchResult {
+        entry: AgentSessionInfo,
+        turn: usize,
+        snippet: SharedString,
+        positions: Vec<usize>,
+    },
 }
 
 impl ListItemType {
@@ -60,6 +69,7 @@ impl ListItemType {
         match self {
             ListItemType::Entry { entry, .. } => Some(entry),
             ListItemType::SearchResult { entry, .. } => Some(entry),
+            ListItemType::MessageSearchResult { entry, .. } => Some(entry),
             _ => None,
         }
     }
@@ -67,6 +77,11 @@ impl ListItemType {
 
 pub enum ThreadHistoryEvent {
     Open(AgentSessionInfo),
+    /// Opens a thread at the given user message, counting from the start of the thread.
+    OpenAtMessage {
+        thread: AgentSessionInfo,
+        turn: usize,
+    },
 }
 
 impl EventEmitter<ThreadHistoryEvent> for AcpThreadHistory {}
@@ -109,6 +124,7 @@ impl AcpThreadHistory {
             )
             .unwrap(),
             search_query: SharedString::default(),
+            search_source: None,
             confirming_delete_history: false,
             _subscriptions: vec![search_editor_subscription],
             _update_task: Task::ready(()),
@@ -155,6 +171,15 @@ impl AcpThreadHistory {
         });
     }
 
+    pub fn set_search_source(&mut self, source: Option<SharedString>, cx: &mut Context<Self>) {
+        if self.search_source != source {
+            self.search_source = source;
+            if !self.search_query.is_empty() {
+                self.update_visible_items(true, cx);
+            }
+        }
+    }
+
     pub fn set_session_list(
         &mut self,
         session_list: Option<Rc<dyn AgentSessionList>>,
@@ -395,9 +420,13 @@ impl AcpThreadHistory {
     fn filter_search_results(
         &self,
         entries: Vec<AgentSessionInfo>,
-        cx: &App,
+        cx: &mut App,
     ) -> Task<Vec<ListItemType>> {
+        const MAX_MESSAGE_MATCHES: usize = 50;
+
         let query = self.search_query.clone();
+        let message_matches =
+            agent::search_threads(&query, self.search_source.clone(), MAX_MESSAGE_MATCHES, cx);
         cx.background_spawn({
             let executor = cx.background_executor().clone();
             async move {
@@ -420,13 +449,36 @@ impl AcpThreadHistory {
                 )
                 .await;
 
-                matches
+                let mut items = matches
                     .into_iter()
                     .map(|search_match| ListItemType::SearchResult {
                         entry: entries[search_match.candidate_id].clone(),
                         positions: search_match.positions,
                     })
-                    .collect()
+                    .collect::<Vec<_>>();
+
+                let message_matches = message_matches.await.log_err().unwrap_or_default();
+                items.extend(message_matches.into_iter().filter_map(|message_match| {
+                    let entry = entries
+                        .iter()
+                        .find(|entry| entry.session_id.0 == message_match.thread_id)?;
+                    let positions = message_match
+                        .highlights
+                        .iter()
+                        .flat_map(|range| {
+                            message_match.snippet[range.clone()]
+                                .char_indices()
+                                .map(move |(ix, _)| range.start + ix)
+                        })
+                        .collect();
+                    Some(ListItemType::MessageSearchResult {
+                        entry: entry.clone(),
+                        turn: message_match.turn,
+                        snippet: message_match.snippet,
+                        positions,
+                    })
+                }));
+                items
             }
         })
     }
@@ -519,10 +571,20 @@ impl AcpThreadHistory {
     }
 
     fn confirm_entry(&mut self, ix: usize, cx: &mut Context<Self>) {
-        let Some(entry) = self.get_history_entry(ix) else {
-            return;
-        };
-        cx.emit(ThreadHistoryEvent::Open(entry.clone()));
+        match self.visible_items.get(ix) {
+            Some(ListItemType::MessageSearchResult { entry, turn, .. }) => {
+                cx.emit(ThreadHistoryEvent::OpenAtMessage {
+                    thread: entry.clone(),
+                    turn: *turn,
+                });
+            }
+            Some(item) => {
+                if let Some(entry) = item.history_entry() {
+                    cx.emit(ThreadHistoryEvent::Open(entry.clone()));
+                }
+            }
+            None => {}
+        }
     }
 
     fn remove_selected_thread(
@@ -546,6 +608,10 @@ impl AcpThreadHistory {
         }
         let task = session_list.delete_session(&entry.session_id, cx);
         task.detach_and_log_err(cx);
+        if let Some(source) = self.search_source.clone() {
+            agent::remove_from_search_index(source, Some(entry.session_id.0.clone()), cx)
+                .detach_and_log_err(cx);
+        }
     }
 
     fn remove_history(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
@@ -556,6 +622,9 @@ impl AcpThreadHistory {
             return;
         }
         session_list.delete_sessions(cx).detach_and_log_err(cx);
+        if let Some(source) = self.search_source.clone() {
+            agent::remove_from_search_index(source, None, cx).detach_and_log_err(cx);
+        }
         self.confirming_delete_history = false;
         cx.notify();
     }
@@ -597,6 +666,12 @@ impl AcpThreadHistory {
                 positions.clone(),
                 cx,
             ),
+            ListItemType::MessageSearchResult {
+                entry,
+                snippet,
+                positions,
+                ..
+            } => self.render_message_search_result(entry, snippet, positions.clone(), ix, cx),
             ListItemType::BucketSeparator(bucket) => div()
                 .px(DynamicSpacing::Base06.rems(cx))
                 .pt_2()
@@ -699,6 +774,69 @@ impl AcpThreadHistory {
             )
             .into_any_element()
     }
+
+    fn render_message_search_result(
+        &self,
+        entry: &AgentSessionInfo,
+        snippet: &SharedString,
+        highlight_positions: Vec<usize>,
+        ix: usize,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let title = thread_title(entry).clone();
+        let tooltip_snippet = snippet.clone();
+
+        h_flex()
+            .w_full()
+            .pb_1()
+            .child(
+                ListItem::new(ix)
+                    .rounded()
+                    .toggle_state(ix == self.selected_index)
+                    .spacing(ListItemSpacing::Sparse)
+                    .start_slot(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .justify_between()
+                            .child(
+                                h_flex()
+                                    .min_w_0()
+                                    .gap_1p5()
+                                    .child(
+                                        Icon::new(IconName::Chat)
+                                            .color(Color::Muted)
+                                            .size(IconSize::XSmall),
+                                    )
+                                    .child(
+                                        HighlightedLabel::new(snippet.clone(), highlight_positions)
+                                            .size(LabelSize::Small)
+                                            .truncate(),
+                                    ),
+                            )
+                            .child(
+                                Label::new(title.clone())
+                                    .color(Color::Muted)
+                                    .size(LabelSize::XSmall)
+                                    .truncate(),
+                            ),
+                    )
+                    .tooltip(move |_, cx| {
+                        Tooltip::with_meta(title.clone(), None, tooltip_snippet.clone(), cx)
+                    })
+                    .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
+                        if *is_hovered {
+                            this.hovered_index = Some(ix);
+                        } else if this.hovered_index == Some(ix) {
+                            this.hovered_index = None;
+                        }
+
+                        cx.notify();
+                    }))
+                    .on_click(cx.listener(move |this, _, _, cx| this.confirm_entry(ix, cx))),
+            )
+            .into_any_element()
+    }
 }
 
 impl Focusable for AcpThreadHistory {
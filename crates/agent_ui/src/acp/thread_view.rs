@@ -1,15 +1,15 @@
 use acp_thread::{
-    AcpThread, AcpThreadEvent, AgentSessionInfo, AgentThreadEntry, AssistantMessage,
-    AssistantMessageChunk, AuthRequired, LoadError, MentionUri, PermissionOptionChoice,
-    PermissionOptions, RetryStatus, ThreadStatus, ToolCall, ToolCallContent, ToolCallStatus,
-    UserMessageId,
+    AcpThread, AcpThreadEvent, AgentSessionInfo, AgentTerminalSession, AgentThreadEntry,
+    AssistantMessage, AssistantMessageChunk, AuthRequired, FileConflictId, FileConflictResolution,
+    LoadError, MentionUri, PermissionOptionChoice, PermissionOptions, RetryStatus, ThreadStatus,
+    ToolCall, ToolCallContent, ToolCallStatus, UserMessageId,
 };
 use acp_thread::{AgentConnection, Plan};
 use action_log::{ActionLog, ActionLogTelemetry};
 use agent::{NativeAgentServer, NativeAgentSessionList, SharedThread, ThreadStore};
 use agent_client_protocol::{self as acp, PromptCapabilities};
 use agent_servers::{AgentServer, AgentServerDelegate};
-use agent_settings::{AgentProfileId, AgentSettings};
+use agent_settings::{AgentProfileId, AgentSettings, SessionBudget};
 use anyhow::{Result, anyhow};
 use arrayvec::ArrayVec;
 use audio::{Audio, Sound};
@@ -66,6 +66,8 @@ use zed_actions::assistant::OpenRulesLibrary;
 
 use super::config_options::ConfigOptionsView;
 use super::entry_view_state::EntryViewState;
+use super::handoff::handoff_prompt;
+use super::session_index;
 use super::thread_history::AcpThreadHistory;
 use crate::acp::AcpModelSelectorPopover;
 use crate::acp::ModeSelector;
@@ -76,18 +78,20 @@ use crate::profile_selector::{ProfileProvider, ProfileSelector};
 use crate::ui::{AgentNotification, AgentNotificationEvent};
 use crate::{
     AgentDiffPane, AgentPanel, AllowAlways, AllowOnce, AuthorizeToolCall, ClearMessageQueue,
-    CycleFavoriteModels, CycleModeSelector, CycleThinkingEffort, EditFirstQueuedMessage,
-    ExpandMessageEditor, ExternalAgentInitialContent, Follow, KeepAll, NewThread,
-    OpenAddContextMenu, OpenAgentDiff, OpenHistory, RejectAll, RejectOnce,
-    RemoveFirstQueuedMessage, SelectPermissionGranularity, SendImmediately, SendNextQueuedMessage,
-    ToggleProfileSelector, ToggleThinkingEffortMenu, ToggleThinkingMode,
+    ContinueInAgent, CycleFavoriteModels, CycleModeSelector, CycleThinkingEffort,
+    EditFirstQueuedMessage, ExpandMessageEditor, ExternalAgent, ExternalAgentInitialContent,
+    Follow, KeepAll, NewThread, OpenAddContextMenu, OpenAgentDiff, OpenHistory, RejectAll,
+    RejectOnce, RemoveFirstQueuedMessage, SelectPermissionGranularity, SendImmediately,
+    SendNextQueuedMessage, ToggleProfileSelector, ToggleThinkingEffortMenu, ToggleThinkingMode,
 };
 
 const STOPWATCH_THRESHOLD: Duration = Duration::from_secs(30);
 const TOKEN_THRESHOLD: u64 = 250;
 
 mod active_thread;
+mod session_budget;
 pub use active_thread::*;
+pub use session_budget::*;
 
 pub struct QueuedMessage {
     pub content: Vec<acp::ContentBlock>,
@@ -105,6 +109,7 @@ pub(crate) enum ThreadError {
     PaymentRequired,
     Refusal,
     AuthenticationRequired(SharedString),
+    SessionBudgetExceeded(SharedString),
     Other {
         message: SharedString,
         acp_error_code: Option<SharedString>,
@@ -177,6 +182,17 @@ pub struct AcpServerView {
     notifications: Vec<WindowHandle<AgentNotification>>,
     notification_subscriptions: HashMap<WindowHandle<AgentNotification>, Vec<Subscription>>,
     auth_task: Option<Task<()>>,
+    /// The session this view was opened to resume, if any.
+    restored_session: Option<AgentSessionInfo>,
+    restored_transcript: Option<String>,
+    /// Terminal sessions to reattach the resumed thread to once it's loaded.
+    restored_terminals: Vec<AgentTerminalSession>,
+    /// Replaces the agent's configured session budget for the threads in this view.
+    session_budget: Option<SessionBudget>,
+    /// The user message to scroll to once the resumed thread is loaded.
+    scroll_to_turn: Option<usize>,
+    root_dir: Option<Arc<Path>>,
+    _index_sessions_task: Option<Task<()>>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -188,6 +204,48 @@ impl AcpServerView {
         }
     }
 
+    fn continue_in_agent(
+        &mut self,
+        action: &ContinueInAgent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(active_thread) = self.active_thread().cloned() else {
+            return;
+        };
+        let (thread, agent_name) = {
+            let active_thread = active_thread.read(cx);
+            (
+                active_thread.thread.clone(),
+                active_thread.agent_name.clone(),
+            )
+        };
+        let handoff = handoff_prompt(&thread, agent_name, action.summarize, cx);
+        let agent = action.agent.clone();
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let draft = match handoff.await {
+                Ok(draft) => draft,
+                Err(error) => {
+                    active_thread.update(cx, |active_thread, cx| {
+                        active_thread.handle_any_thread_error(error, cx)
+                    });
+                    return anyhow::Ok(());
+                }
+            };
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| {
+                        panel.new_external_thread_with_draft(agent, draft, window, cx)
+                    });
+                    workspace.focus_panel::<AgentPanel>(window, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn parent_thread(&self, cx: &App) -> Option<Entity<AcpThreadView>> {
         match &self.server_state {
             ServerState::Connected(connected) => {
@@ -246,6 +304,14 @@ enum ServerState {
     Loading(Entity<LoadingView>),
     LoadError(LoadError),
     Connected(ConnectedServerState),
+    /// A restored session that the agent could not load or resume, shown read-only.
+    Transcript(RestoredTranscript),
+}
+
+struct RestoredTranscript {
+    title: SharedString,
+    markdown: Entity<Markdown>,
+    scroll_handle: ScrollHandle,
 }
 
 // current -> Entity
@@ -312,6 +378,7 @@ impl AcpServerView {
         agent: Rc<dyn AgentServer>,
         resume_thread: Option<AgentSessionInfo>,
         initial_content: Option<ExternalAgentInitialContent>,
+        root_dir: Option<Arc<Path>>,
         workspace: WeakEntity<Workspace>,
         project: Entity<Project>,
         thread_store: Option<Entity<ThreadStore>>,
@@ -352,11 +419,13 @@ impl AcpServerView {
             project: project.clone(),
             thread_store,
             prompt_store,
+            restored_session: resume_thread.clone(),
             server_state: Self::initial_state(
                 agent.clone(),
                 resume_thread,
                 project,
                 initial_content,
+                root_dir.clone(),
                 window,
                 cx,
             ),
@@ -364,12 +433,70 @@ impl AcpServerView {
             notifications: Vec::new(),
             notification_subscriptions: HashMap::default(),
             auth_task: None,
+            restored_transcript: None,
+            restored_terminals: Vec::new(),
+            session_budget: None,
+            scroll_to_turn: None,
+            root_dir,
+            _index_sessions_task: None,
             history,
             _subscriptions: subscriptions,
             focus_handle: cx.focus_handle(),
         }
     }
 
+    /// Sets the transcript to fall back to when the resumed session can't be
+    /// loaded or resumed by the agent, e.g. when restoring tabs after a restart.
+    pub fn with_restored_transcript(mut self, transcript: String) -> Self {
+        self.restored_transcript = Some(transcript);
+        self
+    }
+
+    /// Sets the commands of the resumed thread's agent that kept running in persistent terminal
+    /// sessions, to reattach to once the thread is loaded.
+    pub fn with_restored_terminals(mut self, sessions: Vec<AgentTerminalSession>) -> Self {
+        self.restored_terminals = sessions;
+        self
+    }
+
+    /// The session this view was opened to resume, while no thread is active, i.e. while it
+    /// is loading or when it couldn't be loaded.
+    pub fn pending_restored_session(&self) -> Option<&AgentSessionInfo> {
+        self.restored_session
+            .as_ref()
+            .filter(|_| self.active_thread().is_none())
+    }
+
+    /// The transcript shown, or to be shown, when the resumed session can't be loaded.
+    pub fn restored_transcript(&self) -> Option<&str> {
+        self.restored_transcript.as_deref()
+    }
+
+    /// The terminal sessions to reattach to once the resumed thread is loaded.
+    pub fn restored_terminals(&self) -> &[AgentTerminalSession] {
+        &self.restored_terminals
+    }
+
+    /// Scrolls to the given user message, counting from the start of the thread, once the
+    /// resumed thread is loaded.
+    pub fn scroll_to_turn_when_loaded(&mut self, turn: usize) {
+        self.scroll_to_turn = Some(turn);
+    }
+
+    /// Whether threads need to be indexed here to be searchable. The Zed agent indexes its
+    /// threads when saving them, and other agents' threads can only be reopened from search
+    /// results if the agent can load sessions.
+    fn indexes_external_threads(&self, connection: &Rc<dyn AgentConnection>, cx: &App) -> bool {
+        self.agent.clone().downcast::<NativeAgentServer>().is_none()
+            && connection.supports_load_session(cx)
+    }
+
+    /// Uses the given budget instead of the one configured for the agent.
+    pub fn with_session_budget(mut self, budget: SessionBudget) -> Self {
+        self.session_budget = Some(budget);
+        self
+    }
+
     fn set_server_state(&mut self, state: ServerState, cx: &mut Context<Self>) {
         if let Some(connected) = self.as_connected() {
             connected.close_all_sessions(cx).detach();
@@ -389,6 +516,7 @@ impl AcpServerView {
             resume_thread_metadata,
             self.project.clone(),
             None,
+            self.root_dir.clone(),
             window,
             cx,
         );
@@ -413,6 +541,7 @@ impl AcpServerView {
         resume_thread: Option<AgentSessionInfo>,
         project: Entity<Project>,
         initial_content: Option<ExternalAgentInitialContent>,
+        root_dir: Option<Arc<Path>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> ServerState {
@@ -442,7 +571,8 @@ impl AcpServerView {
                 }
             })
             .collect();
-        let root_dir = worktree_roots.first().cloned();
+        // An explicit root directory, such as an isolated git worktree, takes precedence.
+        let root_dir = root_dir.or_else(|| worktree_roots.first().cloned());
         let session_cwd = resume_thread
             .as_ref()
             .and_then(|resume| {
@@ -478,10 +608,14 @@ impl AcpServerView {
                 }
                 Err(err) => {
                     this.update_in(cx, |this, window, cx| {
-                        if err.downcast_ref::<LoadError>().is_some() {
-                            this.handle_load_error(err, window, cx);
-                        } else if let Some(active) = this.active_thread() {
-                            active.update(cx, |active, cx| active.handle_any_thread_error(err, cx));
+                        if !this.show_restored_transcript_instead(&err, cx) {
+                            if err.downcast_ref::<LoadError>().is_some() {
+                                this.handle_load_error(err, window, cx);
+                            } else if let Some(active) = this.active_thread() {
+                                active.update(cx, |active, cx| {
+                                    active.handle_any_thread_error(err, cx)
+                                });
+                            }
                         }
                         cx.notify();
                     })
@@ -548,6 +682,14 @@ impl AcpServerView {
             this.update_in(cx, |this, window, cx| {
                 match result {
                     Ok(thread) => {
+                        let terminal_sessions = std::mem::take(&mut this.restored_terminals);
+                        if !terminal_sessions.is_empty() {
+                            thread
+                                .update(cx, |thread, cx| {
+                                    thread.reattach_terminals(terminal_sessions, cx)
+                                })
+                                .detach();
+                        }
                         let current = this.new_thread_view(
                             None,
                             thread,
@@ -565,6 +707,10 @@ impl AcpServerView {
                                 .focus_handle(cx)
                                 .focus(window, cx);
                         }
+                        if let Some(turn) = this.scroll_to_turn.take() {
+                            current
+                                .update(cx, |current, cx| current.scroll_to_user_message(turn, cx));
+                        }
 
                         let id = current.read(cx).thread.read(cx).session_id().clone();
                         this.set_server_state(
@@ -578,7 +724,9 @@ impl AcpServerView {
                         );
                     }
                     Err(err) => {
-                        this.handle_load_error(err, window, cx);
+                        if !this.show_restored_transcript_instead(&err, cx) {
+                            this.handle_load_error(err, window, cx);
+                        }
                     }
                 };
             })
@@ -676,8 +824,40 @@ impl AcpServerView {
             None
         };
         self.history.update(cx, |history, cx| {
-            history.set_session_list(session_list, cx);
+            history.set_session_list(session_list.clone(), cx);
+            history.set_search_source(Some(agent_name.clone()), cx);
         });
+        if parent_id.is_none()
+            && self._index_sessions_task.is_none()
+            && let Some(session_list) = session_list
+            && self.indexes_external_threads(&connection, cx)
+        {
+            let mut open_sessions = self
+                .as_connected()
+                .map(|connected| connected.threads.keys().cloned().collect::<HashSet<_>>())
+                .unwrap_or_default();
+            open_sessions.insert(session_id.clone());
+            let project = self.project.clone();
+            let default_cwd = self
+                .root_dir
+                .clone()
+                .unwrap_or_else(|| paths::home_dir().as_path().into());
+            let source = agent_name.clone();
+            let connection = connection.clone();
+            self._index_sessions_task = Some(cx.spawn(async move |_, cx| {
+                session_index::index_session_list(
+                    connection,
+                    session_list,
+                    source,
+                    open_sessions,
+                    project,
+                    default_cwd,
+                    cx,
+                )
+                .await
+                .log_err();
+            }));
+        }
 
         // Check for config options first
         // Config options take precedence over legacy mode/model selectors
@@ -791,6 +971,10 @@ impl AcpServerView {
 
         let agent_icon = self.agent.logo();
 
+        let session_budget = self
+            .session_budget
+            .unwrap_or_else(|| AgentSettings::get_global(cx).session_budget(&agent_name));
+
         let weak = cx.weak_entity();
         cx.new(|cx| {
             AcpThreadView::new(
@@ -818,6 +1002,7 @@ impl AcpServerView {
                 self.history.clone(),
                 self.prompt_store.clone(),
                 initial_content,
+                session_budget,
                 subscriptions,
                 window,
                 cx,
@@ -930,6 +1115,43 @@ impl AcpServerView {
         self.set_server_state(ServerState::LoadError(load_error), cx);
     }
 
+    /// Shows the restored transcript, if there's one, in place of the session that failed to
+    /// load. Returns whether it did.
+    fn show_restored_transcript_instead(
+        &mut self,
+        err: &anyhow::Error,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(transcript) = self.restored_transcript.clone() else {
+            return false;
+        };
+        log::info!("showing restored transcript instead of session: {err:#}");
+        let title = self
+            .restored_session
+            .as_ref()
+            .and_then(|session| session.title.clone())
+            .unwrap_or_else(|| "Previous Thread".into());
+        self.show_restored_transcript(title, transcript, cx);
+        true
+    }
+
+    fn show_restored_transcript(
+        &mut self,
+        title: SharedString,
+        transcript: String,
+        cx: &mut Context<Self>,
+    ) {
+        let markdown = cx.new(|cx| Markdown::new(transcript.into(), None, None, cx));
+        self.set_server_state(
+            ServerState::Transcript(RestoredTranscript {
+                title,
+                markdown,
+                scroll_handle: ScrollHandle::new(),
+            }),
+            cx,
+        );
+    }
+
     fn handle_agent_servers_updated(
         &mut self,
         _agent_server_store: &Entity<project::AgentServerStore>,
@@ -941,7 +1163,7 @@ impl AcpServerView {
         // when agent.connect() fails during loading), retry loading the thread.
         // This handles the case where a thread is restored before authentication completes.
         let should_retry = match &self.server_state {
-            ServerState::Loading(_) => false,
+            ServerState::Loading(_) | ServerState::Transcript(_) => false,
             ServerState::LoadError(_) => true,
             ServerState::Connected(connected) => {
                 connected.auth_state.is_ok() && connected.has_thread_error(cx)
@@ -966,6 +1188,7 @@ impl AcpServerView {
         match &self.server_state {
             ServerState::Connected(_) => "New Thread".into(),
             ServerState::Loading(loading_view) => loading_view.read(cx).title.clone(),
+            ServerState::Transcript(transcript) => transcript.title.clone(),
             ServerState::LoadError(error) => match error {
                 LoadError::Unsupported { .. } => format!("Upgrade {}", self.agent.name()).into(),
                 LoadError::FailedToInstall(_) => {
@@ -1003,6 +1226,23 @@ impl AcpServerView {
         matches!(self.server_state, ServerState::Loading { .. })
     }
 
+    pub fn has_load_error(&self) -> bool {
+        matches!(self.server_state, ServerState::LoadError(_))
+    }
+
+    pub fn authorize_pending_tool_call(
+        &mut self,
+        kind: acp::PermissionOptionKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(active) = self.active_thread() {
+            active.update(cx, |active, cx| {
+                active.authorize_pending_tool_call(kind, window, cx);
+            });
+        }
+    }
+
     fn update_turn_tokens(&mut self, cx: &mut Context<Self>) {
         if let Some(active) = self.active_thread() {
             active.update(cx, |active, cx| {
@@ -1011,6 +1251,14 @@ impl AcpServerView {
         }
     }
 
+    fn check_session_budget(&mut self, session_id: &acp::SessionId, cx: &mut Context<Self>) {
+        if let Some(active) = self.thread_view(session_id) {
+            active.update(cx, |active, cx| {
+                active.check_session_budget(cx);
+            });
+        }
+    }
+
     fn send_queued_message_at_index(
         &mut self,
         index: usize,
@@ -1051,6 +1299,9 @@ impl AcpServerView {
                         );
                     });
                 }
+                if !is_subagent {
+                    self.check_session_budget(&thread_id, cx);
+                }
             }
             AcpThreadEvent::EntryUpdated(index) => {
                 if let Some(entry_view_state) = self
@@ -1061,6 +1312,9 @@ impl AcpServerView {
                         view_state.sync_entry(*index, thread, window, cx)
                     });
                 }
+                if !is_subagent {
+                    self.check_session_budget(&thread_id, cx);
+                }
             }
             AcpThreadEvent::EntriesRemoved(range) => {
                 if let Some(active) = self.thread_view(&thread_id) {
@@ -1079,6 +1333,14 @@ impl AcpServerView {
             AcpThreadEvent::ToolAuthorizationRequired => {
                 self.notify_with_sound("Waiting for tool confirmation", IconName::Info, window, cx);
             }
+            AcpThreadEvent::FileConflict(_) => {
+                self.notify_with_sound(
+                    "Waiting for a file conflict to be resolved",
+                    IconName::Warning,
+                    window,
+                    cx,
+                );
+            }
             AcpThreadEvent::Retry(retry) => {
                 if let Some(active) = self.thread_view(&thread_id) {
                     active.update(cx, |active, _cx| {
@@ -1096,6 +1358,13 @@ impl AcpServerView {
                     return;
                 }
 
+                if let Some(connected) = self.as_connected()
+                    && self.indexes_external_threads(&connected.connection, cx)
+                {
+                    session_index::index_external_thread(thread, self.agent.name(), cx)
+                        .detach_and_log_err(cx);
+                }
+
                 let used_tools = thread.read(cx).used_tools_since_last_user_message();
                 self.notify_with_sound(
                     if used_tools {
@@ -1206,6 +1475,12 @@ impl AcpServerView {
             AcpThreadEvent::TokenUsageUpdated => {
                 self.update_turn_tokens(cx);
                 self.emit_token_limit_telemetry_if_needed(thread, cx);
+                if !is_subagent && let Some(active) = self.thread_view(&thread_id) {
+                    active.update(cx, |active, cx| {
+                        active.record_token_usage(cx);
+                        active.check_session_budget(cx);
+                    });
+                }
             }
             AcpThreadEvent::AvailableCommandsUpdated(available_commands) => {
                 let mut available_commands = available_commands.clone();
@@ -1935,6 +2210,47 @@ impl AcpServerView {
             .into_any_element()
     }
 
+    fn render_restored_transcript(
+        &self,
+        transcript: &RestoredTranscript,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let style = MarkdownStyle::themed(MarkdownFont::Agent, window, cx);
+
+        v_flex()
+            .flex_1()
+            .size_full()
+            .child(
+                div()
+                    .id("restored-transcript")
+                    .flex_1()
+                    .min_h_0()
+                    .p_3()
+                    .overflow_y_scroll()
+                    .track_scroll(&transcript.scroll_handle)
+                    .child(self.render_markdown(transcript.markdown.clone(), style)),
+            )
+            .vertical_scrollbar_for(&transcript.scroll_handle, window, cx)
+            .child(
+                Callout::new()
+                    .severity(Severity::Info)
+                    .icon(IconName::Info)
+                    .title("Read-only transcript")
+                    .description(format!(
+                        "{} can't resume this session, so its previous conversation is shown as a transcript.",
+                        self.agent.name()
+                    ))
+                    .actions_slot(
+                        Button::new("start-new-thread", "Start New Thread")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.reset(window, cx);
+                            })),
+                    ),
+            )
+    }
+
     fn render_unsupported(
         &self,
         path: &SharedString,
@@ -2500,6 +2816,7 @@ impl Render for AcpServerView {
 
         v_flex()
             .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::continue_in_agent))
             .size_full()
             .bg(cx.theme().colors().panel_background)
             .child(match &self.server_state {
@@ -2514,6 +2831,9 @@ impl Render for AcpServerView {
                     .justify_end()
                     .child(self.render_load_error(e, window, cx))
                     .into_any(),
+                ServerState::Transcript(transcript) => self
+                    .render_restored_transcript(transcript, window, cx)
+                    .into_any_element(),
                 ServerState::Connected(ConnectedServerState {
                     connection,
                     auth_state:
@@ -2677,6 +2997,7 @@ pub(crate) mod tests {
                     Rc::new(StubAgentServer::default_response()),
                     None,
                     None,
+                    None,
                     workspace.downgrade(),
                     project,
                     Some(thread_store),
@@ -2748,6 +3069,7 @@ pub(crate) mod tests {
                     Rc::new(StubAgentServer::new(ResumeOnlyAgentConnection)),
                     Some(session),
                     None,
+                    None,
                     workspace.downgrade(),
                     project,
                     Some(thread_store),
@@ -2768,6 +3090,95 @@ pub(crate) mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_restored_transcript_shown_when_session_cannot_be_resumed(
+        cx: &mut TestAppContext,
+    ) {
+        init_test(cx);
+
+        let mut session = AgentSessionInfo::new(SessionId::new("restored-session"));
+        session.title = Some("Fix the websocket".into());
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let history = cx.update(|window, cx| cx.new(|cx| AcpThreadHistory::new(None, window, cx)));
+
+        let thread_view = cx.update(|window, cx| {
+            cx.new(|cx| {
+                AcpServerView::new(
+                    Rc::new(StubAgentServer::default_response()),
+                    Some(session),
+                    None,
+                    None,
+                    workspace.downgrade(),
+                    project,
+                    None,
+                    None,
+                    history,
+                    window,
+                    cx,
+                )
+                .with_restored_transcript("## User\n\nHello\n".into())
+            })
+        });
+
+        cx.run_until_parked();
+
+        thread_view.read_with(cx, |view, cx| {
+            assert!(view.active_thread().is_none());
+            assert!(matches!(view.server_state, ServerState::Transcript(_)));
+            assert_eq!(view.title(cx), "Fix the websocket");
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restored_transcript_shown_when_agent_fails_to_start(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let mut session = AgentSessionInfo::new(SessionId::new("restored-session"));
+        session.title = Some("Fix the websocket".into());
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let history = cx.update(|window, cx| cx.new(|cx| AcpThreadHistory::new(None, window, cx)));
+
+        let thread_view = cx.update(|window, cx| {
+            cx.new(|cx| {
+                AcpServerView::new(
+                    Rc::new(FailingAgentServer),
+                    Some(session),
+                    None,
+                    None,
+                    workspace.downgrade(),
+                    project,
+                    None,
+                    None,
+                    history,
+                    window,
+                    cx,
+                )
+                .with_restored_transcript("## User\n\nHello\n".into())
+            })
+        });
+
+        cx.run_until_parked();
+
+        thread_view.read_with(cx, |view, cx| {
+            assert!(matches!(view.server_state, ServerState::Transcript(_)));
+            assert_eq!(view.title(cx), "Fix the websocket");
+            assert_eq!(view.restored_transcript(), Some("## User\n\nHello\n"));
+            assert_eq!(
+                view.pending_restored_session()
+                    .map(|session| session.session_id.clone()),
+                Some(SessionId::new("restored-session"))
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_resume_thread_uses_session_cwd_when_inside_project(cx: &mut TestAppContext) {
         init_test(cx);
@@ -2801,6 +3212,7 @@ pub(crate) mod tests {
                     Rc::new(StubAgentServer::new(connection)),
                     Some(session),
                     None,
+                    None,
                     workspace.downgrade(),
                     project,
                     Some(thread_store),
@@ -2852,6 +3264,7 @@ pub(crate) mod tests {
                     Rc::new(StubAgentServer::new(connection)),
                     Some(session),
                     None,
+                    None,
                     workspace.downgrade(),
                     project,
                     Some(thread_store),
@@ -2903,6 +3316,7 @@ pub(crate) mod tests {
                     Rc::new(StubAgentServer::new(connection)),
                     Some(session),
                     None,
+                    None,
                     workspace.downgrade(),
                     project,
                     Some(thread_store),
@@ -3151,6 +3565,7 @@ pub(crate) mod tests {
                     Rc::new(agent),
                     None,
                     None,
+                    None,
                     workspace.downgrade(),
                     project,
                     Some(thread_store),
@@ -3209,18 +3624,18 @@ pub(crate) mod tests {
         }
     }
 
-    struct StubAgentServer<C> {
+    pub(crate) struct StubAgentServer<C> {
         connection: C,
     }
 
     impl<C> StubAgentServer<C> {
-        fn new(connection: C) -> Self {
+        pub(crate) fn new(connection: C) -> Self {
             Self { connection }
         }
     }
 
     impl StubAgentServer<StubAgentConnection> {
-        fn default_response() -> Self {
+        pub(crate) fn default_response() -> Self {
             let conn = StubAgentConnection::new();
             conn.set_next_prompt_updates(vec![acp::SessionUpdate::AgentMessageChunk(
                 acp::ContentChunk::new("Default response".into()),
@@ -3255,6 +3670,32 @@ pub(crate) mod tests {
         }
     }
 
+    /// An agent that fails to start, like one that isn't installed anymore.
+    struct FailingAgentServer;
+
+    impl AgentServer for FailingAgentServer {
+        fn logo(&self) -> ui::IconName {
+            ui::IconName::Ai
+        }
+
+        fn name(&self) -> SharedString {
+            "Failing".into()
+        }
+
+        fn connect(
+            &self,
+            _root_dir: Option<&Path>,
+            _delegate: AgentServerDelegate,
+            _cx: &mut App,
+        ) -> Task<gpui::Result<(Rc<dyn AgentConnection>, Option<task::SpawnInTerminal>)>> {
+            Task::ready(Err(anyhow!(LoadError::Other("agent not found".into()))))
+        }
+
+        fn into_any(self: Rc<Self>) -> Rc<dyn Any> {
+            self
+        }
+    }
+
     #[derive(Clone)]
     struct StubSessionList {
         sessions: Vec<AgentSessionInfo>,
@@ -3669,6 +4110,7 @@ pub(crate) mod tests {
                     Rc::new(StubAgentServer::new(connection.as_ref().clone())),
                     None,
                     None,
+                    None,
                     workspace.downgrade(),
                     project.clone(),
                     Some(thread_store.clone()),
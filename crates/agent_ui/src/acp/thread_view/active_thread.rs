@@ -1,9 +1,11 @@
 use gpui::{Corner, List};
 use language_model::LanguageModelEffortLevel;
+use project::agent_server_store::{CLAUDE_CODE_NAME, CODEX_NAME, GEMINI_NAME};
 use settings::update_settings_file;
 use ui::{ButtonLike, SplitButton, SplitButtonStyle, Tab};
 
 use super::*;
+use crate::agent_panel::AgentType;
 
 #[derive(Default)]
 struct ThreadFeedbackState {
@@ -129,13 +131,13 @@ impl ThreadFeedbackState {
 }
 
 #[derive(Default, Clone, Copy)]
-struct DiffStats {
-    lines_added: u32,
-    lines_removed: u32,
+pub(crate) struct DiffStats {
+    pub(crate) lines_added: u32,
+    pub(crate) lines_removed: u32,
 }
 
 impl DiffStats {
-    fn single_file(buffer: &Buffer, diff: &BufferDiff, cx: &App) -> Self {
+    pub(crate) fn single_file(buffer: &Buffer, diff: &BufferDiff, cx: &App) -> Self {
         let mut stats = DiffStats::default();
         let diff_snapshot = diff.snapshot(cx);
         let buffer_snapshot = buffer.snapshot();
@@ -154,7 +156,10 @@ impl DiffStats {
         stats
     }
 
-    fn all_files(changed_buffers: &BTreeMap<Entity<Buffer>, Entity<BufferDiff>>, cx: &App) -> Self {
+    pub(crate) fn all_files(
+        changed_buffers: &BTreeMap<Entity<Buffer>, Entity<BufferDiff>>,
+        cx: &App,
+    ) -> Self {
         let mut total = DiffStats::default();
         for (buffer, diff) in changed_buffers {
             let stats = DiffStats::single_file(buffer.read(cx), diff.read(cx), cx);
@@ -209,6 +214,7 @@ pub struct AcpThreadView {
     pub queued_message_editor_subscriptions: Vec<Subscription>,
     pub last_synced_queue_length: usize,
     pub turn_fields: TurnFields,
+    pub session_budget: SessionBudgetTracker,
     pub discarded_partial_edits: HashSet<agent_client_protocol::ToolCallId>,
     pub is_loading_contents: bool,
     pub new_server_version_available: Option<SharedString>,
@@ -228,6 +234,9 @@ pub struct AcpThreadView {
     pub message_editor: Entity<MessageEditor>,
     pub add_context_menu_handle: PopoverMenuHandle<ContextMenu>,
     pub thinking_effort_menu_handle: PopoverMenuHandle<ContextMenu>,
+    pub fan_out_menu_handle: PopoverMenuHandle<ContextMenu>,
+    /// Agents ticked in the fan-out menu, kept across openings of the menu.
+    fan_out_agents: Rc<RefCell<Vec<ExternalAgent>>>,
     pub project: WeakEntity<Project>,
     pub recent_history_entries: Vec<AgentSessionInfo>,
     pub hovered_recent_history_item: Option<usize>,
@@ -281,6 +290,7 @@ impl AcpThreadView {
         history: Entity<AcpThreadHistory>,
         prompt_store: Option<Entity<PromptStore>>,
         initial_content: Option<ExternalAgentInitialContent>,
+        session_budget: SessionBudget,
         mut subscriptions: Vec<Subscription>,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -293,6 +303,10 @@ impl AcpThreadView {
             this.update_recent_history_from_cache(&history, cx);
         });
 
+        let send_initial_prompt = matches!(
+            initial_content,
+            Some(ExternalAgentInitialContent::Prompt(_))
+        );
         let message_editor = cx.new(|cx| {
             let mut editor = MessageEditor::new(
                 workspace.clone(),
@@ -323,6 +337,10 @@ impl AcpThreadView {
                             cx,
                         );
                     }
+                    ExternalAgentInitialContent::Prompt(content)
+                    | ExternalAgentInitialContent::Draft(content) => {
+                        editor.set_message(content, window, cx);
+                    }
                 }
             }
             editor
@@ -344,7 +362,15 @@ impl AcpThreadView {
             Self::handle_message_editor_event,
         ));
 
+        let file_registry = thread.read(cx).file_registry().clone();
+        subscriptions.push(cx.observe(&file_registry, |_, _, cx| cx.notify()));
+
         let recent_history_entries = history.read(cx).get_recent_sessions(3);
+        let session_budget = SessionBudgetTracker::new(session_budget, thread.read(cx));
+
+        if send_initial_prompt {
+            cx.defer_in(window, |this, window, cx| this.send(window, cx));
+        }
 
         Self {
             id,
@@ -391,6 +417,7 @@ impl AcpThreadView {
             queued_message_editor_subscriptions: Vec::new(),
             last_synced_queue_length: 0,
             turn_fields: TurnFields::default(),
+            session_budget,
             discarded_partial_edits: HashSet::default(),
             is_loading_contents: false,
             new_server_version_available: None,
@@ -404,6 +431,8 @@ impl AcpThreadView {
             message_editor,
             add_context_menu_handle: PopoverMenuHandle::default(),
             thinking_effort_menu_handle: PopoverMenuHandle::default(),
+            fan_out_menu_handle: PopoverMenuHandle::default(),
+            fan_out_agents: Rc::default(),
             project,
             recent_history_entries,
             hovered_recent_history_item: None,
@@ -557,7 +586,11 @@ impl AcpThreadView {
         self.turn_fields._turn_timer_task = Some(cx.spawn(async move |this, cx| {
             loop {
                 cx.background_executor().timer(Duration::from_secs(1)).await;
-                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                let result = this.update(cx, |this, cx| {
+                    this.check_session_budget(cx);
+                    cx.notify();
+                });
+                if result.is_err() {
                     break;
                 }
             }
@@ -574,6 +607,9 @@ impl AcpThreadView {
             .turn_started_at
             .take()
             .map(|started| started.elapsed());
+        if let Some(duration) = self.turn_fields.last_turn_duration {
+            self.session_budget.finish_turn(duration);
+        }
         self.turn_fields.last_turn_tokens = self.turn_fields.turn_tokens.take();
         self.turn_fields._turn_timer_task = None;
     }
@@ -586,6 +622,35 @@ impl AcpThreadView {
         }
     }
 
+    pub fn record_token_usage(&mut self, cx: &App) {
+        if let Some(usage) = self.thread.read(cx).token_usage() {
+            self.session_budget.record_token_usage(usage);
+        }
+    }
+
+    /// Cancels the current turn if the tab has gone over its session budget.
+    pub fn check_session_budget(&mut self, cx: &mut Context<Self>) {
+        if self.is_subagent() || self.thread.read(cx).status() == ThreadStatus::Idle {
+            return;
+        }
+        let current_turn = self
+            .turn_fields
+            .turn_started_at
+            .map(|started| started.elapsed());
+        let Some(limit) =
+            self.session_budget
+                .exceeded_limit(self.thread.read(cx), current_turn, cx)
+        else {
+            return;
+        };
+
+        self.thread_retry_status.take();
+        self.user_interrupted_generation = true;
+        self._cancel_task = Some(self.thread.update(cx, |thread, cx| thread.cancel(cx)));
+        let message = limit.message(&self.agent_name);
+        self.handle_thread_error(ThreadError::SessionBudgetExceeded(message.into()), cx);
+    }
+
     // sending
 
     pub fn send(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -653,6 +718,27 @@ impl AcpThreadView {
         self.send_impl(message_editor, window, cx)
     }
 
+    /// Sends the text to the agent, or queues it while the agent is busy, leaving the user's
+    /// draft in the message editor alone.
+    pub fn send_message(
+        &mut self,
+        message: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if self.is_loading_contents {
+            anyhow::bail!("the previous message is still being sent");
+        }
+        let contents = vec![acp::ContentBlock::Text(acp::TextContent::new(message))];
+        if self.thread.read(cx).status() != ThreadStatus::Idle {
+            self.add_to_queue(contents, Vec::new(), cx);
+            cx.notify();
+            return Ok(());
+        }
+        self.send_content(Task::ready(Ok(Some((contents, Vec::new())))), window, cx);
+        Ok(())
+    }
+
     pub fn send_impl(
         &mut self,
         message_editor: Entity<MessageEditor>,
@@ -883,6 +969,9 @@ impl AcpThreadView {
                 ThreadError::AuthenticationRequired(message) => {
                     ("authentication_required", None, message.clone())
                 }
+                ThreadError::SessionBudgetExceeded(message) => {
+                    ("session_budget_exceeded", None, message.clone())
+                }
                 ThreadError::Other {
                     acp_error_code,
                     message,
@@ -1535,6 +1624,33 @@ impl AcpThreadView {
             .detach_and_log_err(cx);
     }
 
+    /// Continues the conversation from before the user message at `entry_ix` in a new tab.
+    pub fn fork_from_message(
+        &mut self,
+        entry_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(server_view) = self.server_view.upgrade() else {
+            return;
+        };
+        let Some(panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<AgentPanel>(cx))
+        else {
+            return;
+        };
+        let thread = self.thread.clone();
+        panel.update(cx, |panel, cx| {
+            panel.fork_thread(&server_view, &thread, entry_ix, window, cx)
+        });
+    }
+
+    pub fn has_thread_error(&self) -> bool {
+        self.thread_error.is_some()
+    }
+
     pub fn clear_thread_error(&mut self, cx: &mut Context<Self>) {
         self.thread_error = None;
         self.thread_error_markdown = None;
@@ -2412,6 +2528,7 @@ impl AcpThreadView {
                             .gap_0p5()
                             .child(self.render_add_context_button(cx))
                             .child(self.render_follow_toggle(cx))
+                            .child(self.render_fan_out_button(cx))
                             .children(self.render_thinking_control(cx)),
                     )
                     .child(
@@ -3230,6 +3347,110 @@ impl AcpThreadView {
                 this.toggle_following(window, cx);
             }))
     }
+
+    fn render_fan_out_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let weak_self = cx.weak_entity();
+
+        PopoverMenu::new("fan-out-menu")
+            .trigger_with_tooltip(
+                IconButton::new("fan-out", IconName::Split)
+                    .icon_size(IconSize::Small)
+                    .icon_color(Color::Muted),
+                Tooltip::text("Send Prompt to Several Agents"),
+            )
+            .anchor(Corner::BottomLeft)
+            .with_handle(self.fan_out_menu_handle.clone())
+            .offset(gpui::Point {
+                x: px(0.0),
+                y: px(-2.0),
+            })
+            .menu(move |window, cx| {
+                weak_self
+                    .update(cx, |this, cx| this.build_fan_out_menu(window, cx))
+                    .ok()
+            })
+    }
+
+    fn build_fan_out_menu(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ContextMenu> {
+        let mut agents = vec![
+            ExternalAgent::NativeAgent,
+            ExternalAgent::ClaudeCode,
+            ExternalAgent::Codex,
+            ExternalAgent::Gemini,
+        ];
+        let mut display_names = HashMap::default();
+        if let Some(project) = self.project.upgrade() {
+            let agent_server_store = project.read(cx).agent_server_store().read(cx);
+            for name in agent_server_store.external_agents() {
+                if name.0 == GEMINI_NAME || name.0 == CLAUDE_CODE_NAME || name.0 == CODEX_NAME {
+                    continue;
+                }
+                if let Some(display_name) = agent_server_store.agent_display_name(name) {
+                    display_names.insert(name.0.clone(), display_name);
+                }
+                agents.push(ExternalAgent::Custom {
+                    name: name.0.clone(),
+                });
+            }
+        }
+
+        let selected_agents = self.fan_out_agents.clone();
+        let message_editor = self.message_editor.clone();
+        let menu_handle = self.fan_out_menu_handle.clone();
+
+        ContextMenu::build_persistent(window, cx, move |mut menu, _window, _cx| {
+            menu = menu.header("Send Prompt To");
+            for agent in &agents {
+                let label = match agent {
+                    ExternalAgent::Custom { name } => display_names
+                        .get(name)
+                        .cloned()
+                        .unwrap_or_else(|| name.clone()),
+                    agent => AgentType::from(agent.clone()).label(),
+                };
+                let is_selected = selected_agents.borrow().contains(agent);
+                menu = menu.toggleable_entry(label, is_selected, IconPosition::Start, None, {
+                    let selected_agents = selected_agents.clone();
+                    let agent = agent.clone();
+                    move |_window, _cx| {
+                        let mut selected_agents = selected_agents.borrow_mut();
+                        if let Some(ix) = selected_agents.iter().position(|a| a == &agent) {
+                            selected_agents.remove(ix);
+                        } else {
+                            selected_agents.push(agent.clone());
+                        }
+                    }
+                });
+            }
+
+            let selected_count = selected_agents.borrow().len();
+            menu.separator().item(
+                ContextMenuEntry::new(match selected_count {
+                    0 | 1 => "Fan Out".to_string(),
+                    count => format!("Fan Out to {count} Agents"),
+                })
+                .icon(IconName::Send)
+                .icon_color(Color::Muted)
+                .disabled(selected_count == 0)
+                .handler({
+                    let selected_agents = selected_agents.clone();
+                    let message_editor = message_editor.clone();
+                    let menu_handle = menu_handle.clone();
+                    move |window, cx| {
+                        let agents = selected_agents.borrow().clone();
+                        menu_handle.hide(cx);
+                        message_editor.update(cx, |message_editor, cx| {
+                            message_editor.fan_out(agents, window, cx)
+                        });
+                    }
+                }),
+            )
+        })
+    }
 }
 
 impl AcpThreadView {
@@ -3302,6 +3523,18 @@ impl AcpThreadView {
                         .style(ButtonStyle::Transparent)
                 };
 
+                let fork_button = || {
+                    IconButton::new("fork", IconName::GitBranchAlt)
+                        .icon_color(Color::Muted)
+                        .icon_size(IconSize::XSmall)
+                        .tooltip(Tooltip::text(
+                            "Fork from here into a new tab and an isolated worktree",
+                        ))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.fork_from_message(entry_ix, window, cx);
+                        }))
+                };
+
                 v_flex()
                     .id(("user_message", entry_ix))
                     .map(|this| {
@@ -3405,6 +3638,7 @@ impl AcpThreadView {
                                 } else if message.id.is_some() {
                                     this.child(
                                         base_container
+                                            .child(fork_button())
                                             .child(
                                                 IconButton::new("cancel", IconName::Close)
                                                     .disabled(is_loading_contents)
@@ -3441,6 +3675,7 @@ impl AcpThreadView {
                                     this.child(
                                         base_container
                                             .border_dashed()
+                                            .child(fork_button())
                                             .child(
                                                 non_editable_icon()
                                                     .tooltip(Tooltip::element({
@@ -3884,6 +4119,28 @@ impl AcpThreadView {
         }
     }
 
+    /// Scrolls the given user message, counting from the start of the thread, to the top of the
+    /// viewport.
+    pub(crate) fn scroll_to_user_message(&mut self, turn: usize, cx: &mut Context<Self>) {
+        let Some(ix) = self
+            .thread
+            .read(cx)
+            .entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| matches!(entry, AgentThreadEntry::UserMessage(_)))
+            .nth(turn)
+            .map(|(ix, _)| ix)
+        else {
+            return;
+        };
+        self.list_state.scroll_to(ListOffset {
+            item_ix: ix,
+            offset_in_item: px(0.0),
+        });
+        cx.notify();
+    }
+
     pub fn scroll_to_bottom(&mut self, cx: &mut Context<Self>) {
         let entry_count = self.thread.read(cx).entries().len();
         self.list_state.reset(entry_count);
@@ -4360,6 +4617,7 @@ impl AcpThreadView {
         let working_dir = terminal_data.working_dir();
         let command = terminal_data.command();
         let started_at = terminal_data.started_at();
+        let is_sandboxed = terminal_data.sandbox().is_some();
 
         let tool_failed = matches!(
             &tool_call.status,
@@ -4374,6 +4632,7 @@ impl AcpThreadView {
 
         let command_failed = command_finished
             && output.is_some_and(|o| o.exit_status.is_some_and(|status| !status.success()));
+        let sandbox_denial = output.and_then(|output| output.sandbox_denial);
 
         let time_elapsed = if let Some(output) = output {
             output.ended_at.duration_since(started_at)
@@ -4509,6 +4768,26 @@ impl AcpThreadView {
                         .tooltip(Tooltip::text(tooltip)),
                 )
             })
+            .when(is_sandboxed, |header| {
+                header.child(
+                    h_flex()
+                        .id(("terminal-tool-sandboxed-label", terminal.entity_id()))
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::ShieldCheck)
+                                .size(IconSize::XSmall)
+                                .color(Color::Ignored),
+                        )
+                        .child(
+                            Label::new("Sandboxed")
+                                .color(Color::Muted)
+                                .size(LabelSize::XSmall),
+                        )
+                        .tooltip(Tooltip::text(
+                            "This command can only write to the project and the temporary directory.",
+                        )),
+                )
+            })
             .when(time_elapsed > Duration::from_secs(10), |header| {
                 header.child(
                     Label::new(format!("({})", duration_alt_display(time_elapsed)))
@@ -4578,7 +4857,25 @@ impl AcpThreadView {
                     .bg(header_bg)
                     .text_xs()
                     .child(header)
-                    .child(command_element),
+                    .child(command_element)
+                    .when_some(sandbox_denial, |this, denial| {
+                        this.child(
+                            h_flex()
+                                .px_1p5()
+                                .pb_1()
+                                .gap_1()
+                                .child(
+                                    Icon::new(IconName::Warning)
+                                        .size(IconSize::XSmall)
+                                        .color(Color::Warning),
+                                )
+                                .child(
+                                    Label::new(denial.message())
+                                        .color(Color::Muted)
+                                        .size(LabelSize::XSmall),
+                                ),
+                        )
+                    }),
             )
             .when(is_expanded && terminal_view.is_some(), |this| {
                 this.child(
@@ -6619,6 +6916,109 @@ impl AcpThreadView {
         rems_from_px(13.)
     }
 
+    fn render_file_conflicts(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let thread = self.thread.read(cx);
+        let session_id = thread.session_id();
+        let file_registry = thread.file_registry().read(cx);
+        let project = self.project.upgrade();
+        let conflicts = file_registry
+            .conflicts_for_session(session_id)
+            .map(|conflict| {
+                let path = project
+                    .as_ref()
+                    .and_then(|project| {
+                        let project = project.read(cx);
+                        let project_path = project.find_project_path(&conflict.path, cx)?;
+                        project.short_full_path_for_project_path(&project_path, cx)
+                    })
+                    .unwrap_or_else(|| conflict.path.display().to_string());
+                let is_writer = &conflict.writer == session_id;
+                let other_titles = if is_writer {
+                    conflict
+                        .other_sessions
+                        .iter()
+                        .filter_map(|other| file_registry.session_title(other, cx))
+                        .map(|title| title.to_string())
+                        .collect::<Vec<_>>()
+                } else {
+                    file_registry
+                        .session_title(&conflict.writer, cx)
+                        .map(|title| title.to_string())
+                        .into_iter()
+                        .collect()
+                };
+                (conflict.id, is_writer, path, other_titles.join(", "))
+            })
+            .collect::<Vec<_>>();
+        if conflicts.is_empty() {
+            return None;
+        }
+
+        let mut container = v_flex();
+        for (id, is_writer, path, other_titles) in conflicts {
+            let callout = if is_writer {
+                Callout::new()
+                    .severity(Severity::Warning)
+                    .icon(IconName::Warning)
+                    .title(format!("Conflicting write to {path}"))
+                    .description(format!(
+                        "{agent} wants to write this file, but another agent ({other_titles}) changed it since {agent} last read it.",
+                        agent = self.agent_name,
+                    ))
+                    .actions_slot(
+                        h_flex()
+                            .gap_0p5()
+                            .child(self.file_conflict_button(
+                                id,
+                                "Keep Mine",
+                                FileConflictResolution::KeepMine,
+                                cx,
+                            ))
+                            .child(self.file_conflict_button(
+                                id,
+                                "Keep Theirs",
+                                FileConflictResolution::KeepTheirs,
+                                cx,
+                            ))
+                            .child(self.file_conflict_button(
+                                id,
+                                "Merge",
+                                FileConflictResolution::Merge,
+                                cx,
+                            )),
+                    )
+            } else {
+                Callout::new()
+                    .severity(Severity::Info)
+                    .icon(IconName::Info)
+                    .title(format!("Pending conflict on {path}"))
+                    .description(format!(
+                        "Another agent ({other_titles}) wants to overwrite changes this thread made. \
+                        The decision is made in its tab."
+                    ))
+            };
+            container = container.child(callout);
+        }
+
+        Some(container)
+    }
+
+    fn file_conflict_button(
+        &self,
+        id: FileConflictId,
+        label: &'static str,
+        resolution: FileConflictResolution,
+        cx: &mut Context<Self>,
+    ) -> Button {
+        Button::new(SharedString::from(format!("{label}-{id:?}")), label)
+            .label_size(LabelSize::Small)
+            .on_click(cx.listener(move |this, _, _window, cx| {
+                this.thread.update(cx, |thread, cx| {
+                    thread.resolve_file_conflict(id, resolution, cx);
+                });
+            }))
+    }
+
     pub(crate) fn render_thread_error(
         &mut self,
         window: &mut Window,
@@ -6633,6 +7033,9 @@ impl AcpThreadView {
                 self.render_authentication_required_error(error.clone(), cx)
             }
             ThreadError::PaymentRequired => self.render_payment_required_error(cx),
+            ThreadError::SessionBudgetExceeded(message) => {
+                self.render_session_budget_exceeded_error(message.clone(), cx)
+            }
         };
 
         Some(div().child(content))
@@ -6693,6 +7096,35 @@ impl AcpThreadView {
             .dismiss_action(self.dismiss_error_button(cx))
     }
 
+    fn render_session_budget_exceeded_error(
+        &self,
+        message: SharedString,
+        cx: &mut Context<Self>,
+    ) -> Callout {
+        Callout::new()
+            .severity(Severity::Warning)
+            .icon(IconName::Warning)
+            .title("Session Budget Reached")
+            .description(message.clone())
+            .actions_slot(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        Button::new("lift-session-budget", "Remove Limits")
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::text(
+                                "Let the agent keep going in this thread without limits",
+                            ))
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.session_budget.lift_limits();
+                                this.clear_thread_error(cx);
+                            })),
+                    )
+                    .child(self.create_copy_button(message)),
+            )
+            .dismiss_action(self.dismiss_error_button(cx))
+    }
+
     fn upgrade_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
         Button::new("upgrade", "Upgrade")
             .label_size(LabelSize::Small)
@@ -7304,6 +7736,7 @@ impl Render for AcpThreadView {
                 this.child(self.render_codex_windows_warning(cx))
             })
             .children(self.render_thread_retry_status_callout())
+            .children(self.render_file_conflicts(cx))
             .children(self.render_thread_error(window, cx))
             .when_some(
                 match has_messages {
@@ -0,0 +1,322 @@
+use std::time::Duration;
+
+use acp_thread::{AcpThread, AgentThreadEntry, TokenUsage};
+use agent_settings::SessionBudget;
+use collections::HashSet;
+use gpui::{App, EntityId};
+use util::time::duration_alt_display;
+
+/// Tracks how much of its [`SessionBudget`] an agent tab has used.
+///
+/// Only what happens in the tab counts, so the tool calls and tokens of a
+/// resumed thread's history don't.
+pub struct SessionBudgetTracker {
+    budget: SessionBudget,
+    initial_entry_count: usize,
+    /// Input and output tokens of all the requests made in the tab, unlike the thread's token
+    /// usage, which is how full the context window is.
+    spent_tokens: u64,
+    /// The latest request that reported its usage, with the tokens it had spent so far.
+    last_request_usage: Option<(u64, u64)>,
+    /// Time spent generating in the turns that already finished.
+    generation_time: Duration,
+    modified_buffers: HashSet<EntityId>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionBudgetLimit {
+    Duration(Duration),
+    Tokens(u64),
+    ToolCalls(u64),
+    FilesModified(u64),
+}
+
+impl SessionBudgetLimit {
+    pub fn message(&self, agent_name: &str) -> String {
+        match self {
+            Self::Duration(limit) => format!(
+                "{agent_name} was stopped after generating for {}, this thread's time limit.",
+                duration_alt_display(*limit)
+            ),
+            Self::Tokens(limit) => {
+                format!(
+                    "{agent_name} was stopped after this thread spent more than {limit} tokens."
+                )
+            }
+            Self::ToolCalls(limit) => {
+                format!("{agent_name} was stopped after making more than {limit} tool calls.")
+            }
+            Self::FilesModified(limit) => {
+                format!("{agent_name} was stopped after modifying more than {limit} files.")
+            }
+        }
+    }
+}
+
+impl SessionBudgetTracker {
+    pub fn new(budget: SessionBudget, thread: &AcpThread) -> Self {
+        Self {
+            budget,
+            initial_entry_count: thread.entries().len(),
+            spent_tokens: 0,
+            last_request_usage: None,
+            generation_time: Duration::ZERO,
+            modified_buffers: HashSet::default(),
+        }
+    }
+
+    pub fn budget(&self) -> SessionBudget {
+        self.budget
+    }
+
+    pub fn lift_limits(&mut self) {
+        self.budget = SessionBudget::default();
+    }
+
+    pub fn finish_turn(&mut self, duration: Duration) {
+        self.generation_time += duration;
+    }
+
+    /// Counts the tokens a request spent since it last reported its usage. Requests report
+    /// their usage cumulatively, and usage reported again outside of a request isn't counted.
+    pub fn record_token_usage(&mut self, usage: &TokenUsage) {
+        let Some(request_id) = usage.request_id else {
+            return;
+        };
+        let tokens = usage.input_tokens + usage.output_tokens;
+        let already_counted = match self.last_request_usage {
+            Some((last_request_id, tokens)) if last_request_id == request_id => tokens,
+            _ => 0,
+        };
+        self.spent_tokens += tokens.saturating_sub(already_counted);
+        self.last_request_usage = Some((request_id, tokens.max(already_counted)));
+    }
+
+    /// Returns the first limit the tab went over, if any.
+    pub fn exceeded_limit(
+        &mut self,
+        thread: &AcpThread,
+        current_turn: Option<Duration>,
+        cx: &App,
+    ) -> Option<SessionBudgetLimit> {
+        let budget = self.budget;
+        if budget.is_unlimited() {
+            return None;
+        }
+
+        if let Some(limit) = budget.max_duration
+            && self.generation_time + current_turn.unwrap_or_default() >= limit
+        {
+            return Some(SessionBudgetLimit::Duration(limit));
+        }
+
+        if let Some(limit) = budget.max_tokens
+            && self.spent_tokens > limit
+        {
+            return Some(SessionBudgetLimit::Tokens(limit));
+        }
+
+        if let Some(limit) = budget.max_tool_calls {
+            let tool_calls = thread
+                .entries()
+                .iter()
+                .skip(self.initial_entry_count)
+                .filter(|entry| matches!(entry, AgentThreadEntry::ToolCall(_)))
+                .count();
+            if tool_calls as u64 > limit {
+                return Some(SessionBudgetLimit::ToolCalls(limit));
+            }
+        }
+
+        if let Some(limit) = budget.max_files_modified {
+            // Keep counting files whose changes were already accepted or rejected.
+            self.modified_buffers.extend(
+                thread
+                    .action_log()
+                    .read(cx)
+                    .changed_buffers(cx)
+                    .keys()
+                    .map(|buffer| buffer.entity_id()),
+            );
+            if self.modified_buffers.len() as u64 > limit {
+                return Some(SessionBudgetLimit::FilesModified(limit));
+            }
+        }
+
+        None
+    }
+}
+
+/// One of the limits of a [`SessionBudget`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionBudgetKind {
+    Duration,
+    Tokens,
+    ToolCalls,
+    FilesModified,
+}
+
+impl SessionBudgetKind {
+    pub const ALL: [Self; 4] = [
+        Self::Duration,
+        Self::Tokens,
+        Self::ToolCalls,
+        Self::FilesModified,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Duration => "Time",
+            Self::Tokens => "Tokens",
+            Self::ToolCalls => "Tool Calls",
+            Self::FilesModified => "Files Modified",
+        }
+    }
+
+    /// The values the new-thread menu offers for the limit, with their labels. Durations are
+    /// in seconds.
+    pub fn presets(self) -> &'static [(u64, &'static str)] {
+        match self {
+            Self::Duration => &[
+                (15 * 60, "15 minutes"),
+                (60 * 60, "1 hour"),
+                (4 * 60 * 60, "4 hours"),
+            ],
+            Self::Tokens => &[(100_000, "100k"), (1_000_000, "1M"), (10_000_000, "10M")],
+            Self::ToolCalls => &[(25, "25"), (100, "100"), (500, "500")],
+            Self::FilesModified => &[(5, "5"), (20, "20"), (100, "100")],
+        }
+    }
+}
+
+/// Changes to the configured session budget of an agent, chosen for the next thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionBudgetOverride {
+    /// One entry per limit, in the order of [`SessionBudgetKind::ALL`]: `None` keeps the
+    /// configured limit, `Some(None)` lifts it, and `Some(Some(value))` replaces it.
+    limits: [Option<Option<u64>>; 4],
+}
+
+impl SessionBudgetOverride {
+    /// Lifts all the limits.
+    pub fn unlimited() -> Self {
+        Self {
+            limits: [Some(None); 4],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn get(&self, kind: SessionBudgetKind) -> Option<Option<u64>> {
+        self.limits[kind as usize]
+    }
+
+    pub fn set(&mut self, kind: SessionBudgetKind, limit: Option<Option<u64>>) {
+        self.limits[kind as usize] = limit;
+    }
+
+    pub fn apply(&self, mut budget: SessionBudget) -> SessionBudget {
+        for kind in SessionBudgetKind::ALL {
+            let Some(limit) = self.get(kind) else {
+                continue;
+            };
+            match kind {
+                SessionBudgetKind::Duration => {
+                    budget.max_duration = limit.map(Duration::from_secs);
+                }
+                SessionBudgetKind::Tokens => budget.max_tokens = limit,
+                SessionBudgetKind::ToolCalls => budget.max_tool_calls = limit,
+                SessionBudgetKind::FilesModified => budget.max_files_modified = limit,
+            }
+        }
+        budget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_budget_override() {
+        let configured = SessionBudget {
+            max_duration: Some(Duration::from_secs(30 * 60)),
+            max_tokens: Some(500_000),
+            max_tool_calls: None,
+            max_files_modified: Some(10),
+        };
+        assert_eq!(
+            SessionBudgetOverride::default().apply(configured),
+            configured
+        );
+        assert!(
+            SessionBudgetOverride::unlimited()
+                .apply(configured)
+                .is_unlimited()
+        );
+
+        let mut budget_override = SessionBudgetOverride::default();
+        budget_override.set(SessionBudgetKind::Duration, Some(Some(4 * 60 * 60)));
+        budget_override.set(SessionBudgetKind::Tokens, Some(None));
+        budget_override.set(SessionBudgetKind::ToolCalls, Some(Some(100)));
+        assert!(!budget_override.is_empty());
+        assert_eq!(
+            budget_override.apply(configured),
+            SessionBudget {
+                max_duration: Some(Duration::from_secs(4 * 60 * 60)),
+                max_tokens: None,
+                max_tool_calls: Some(100),
+                max_files_modified: Some(10),
+            }
+        );
+
+        budget_override.set(SessionBudgetKind::Duration, None);
+        assert_eq!(budget_override.get(SessionBudgetKind::Duration), None);
+        assert_eq!(
+            budget_override.apply(configured).max_duration,
+            configured.max_duration
+        );
+    }
+
+    #[test]
+    fn test_spent_tokens_accumulate() {
+        let mut tracker = SessionBudgetTracker {
+            budget: SessionBudget {
+                max_tokens: Some(1_000),
+                ..SessionBudget::default()
+            },
+            initial_entry_count: 0,
+            spent_tokens: 0,
+            last_request_usage: None,
+            generation_time: Duration::ZERO,
+            modified_buffers: HashSet::default(),
+        };
+        let usage = |request_id, input_tokens, output_tokens| TokenUsage {
+            max_tokens: 200_000,
+            used_tokens: input_tokens + output_tokens,
+            input_tokens,
+            output_tokens,
+            request_id,
+        };
+
+        // A request reports its input when it starts and its total usage when it ends.
+        tracker.record_token_usage(&usage(Some(1), 300, 0));
+        tracker.record_token_usage(&usage(Some(1), 300, 100));
+        assert_eq!(tracker.spent_tokens, 400);
+
+        // Usage reported again, like after switching models, isn't new spending.
+        tracker.record_token_usage(&usage(None, 300, 100));
+        tracker.record_token_usage(&usage(Some(1), 300, 100));
+        assert_eq!(tracker.spent_tokens, 400);
+
+        // Every request spends tokens, even when the context window never fills up.
+        tracker.record_token_usage(&usage(Some(2), 450, 0));
+        tracker.record_token_usage(&usage(Some(2), 450, 50));
+        assert_eq!(tracker.spent_tokens, 900);
+        assert!(tracker.spent_tokens <= tracker.budget().max_tokens.unwrap());
+        tracker.record_token_usage(&usage(Some(3), 550, 0));
+        assert!(tracker.spent_tokens > tracker.budget().max_tokens.unwrap());
+    }
+}
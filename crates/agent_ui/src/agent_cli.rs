@@ -0,0 +1,155 @@
+use std::{sync::Arc, time::Duration};
+
+use acp_thread::{AgentThreadEntry, AssistantMessageChunk, ThreadStatus};
+use anyhow::{Result, anyhow};
+use gpui::{App, AsyncApp, Entity, SharedString};
+
+use crate::{ExternalAgent, acp::AcpServerView, agent_dashboard::DashboardSession};
+
+/// How often `zed agent wait` checks whether the agent finished its turn.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// An agent tab, as listed by `zed agent list`.
+#[derive(Clone, Debug)]
+pub struct AgentTabSummary {
+    /// The tab's id, which stays the same while other tabs open and close.
+    pub tab_id: usize,
+    /// The id of the agent's session, once the agent has started.
+    pub session_id: Option<Arc<str>>,
+    pub agent: SharedString,
+    pub title: SharedString,
+    pub status: &'static str,
+}
+
+impl AgentTabSummary {
+    pub(crate) fn new(tab_id: usize, session: &DashboardSession, cx: &App) -> Self {
+        Self {
+            tab_id,
+            session_id: session
+                .server_view
+                .read(cx)
+                .active_thread()
+                .map(|thread| thread.read(cx).thread.read(cx).session_id().0.clone()),
+            agent: session.agent.label(),
+            title: session.title.clone(),
+            status: session.status.label(),
+        }
+    }
+}
+
+/// Whether the name given on the command line refers to the agent, either by its display name
+/// or by a short name like `claude-code`, ignoring case and separators.
+pub(crate) fn cli_name_matches(name: &str, display_name: &str, agent: &ExternalAgent) -> bool {
+    let normalize = |name: &str| name.trim().to_lowercase().replace([' ', '_'], "-");
+    let name = normalize(name);
+    let short_name = match agent {
+        ExternalAgent::NativeAgent => "zed".into(),
+        ExternalAgent::ClaudeCode => "claude-code".into(),
+        ExternalAgent::Codex => "codex".into(),
+        ExternalAgent::Gemini => "gemini".into(),
+        ExternalAgent::Custom { name } => normalize(name),
+    };
+    name == short_name || name == normalize(display_name)
+}
+
+/// Waits until the agent in the tab finishes its turn and returns its final message.
+///
+/// Fails if the agent can't start, stops with an error, or if the tab is closed.
+pub async fn wait_for_agent_tab(
+    thread_view: Entity<AcpServerView>,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let thread_view = thread_view.downgrade();
+    loop {
+        let outcome = thread_view
+            .read_with(cx, |thread_view, cx| turn_outcome(thread_view, cx))
+            .map_err(|_| anyhow!("the agent's tab was closed"))?;
+        if let Some(outcome) = outcome {
+            return outcome;
+        }
+        cx.background_executor().timer(WAIT_POLL_INTERVAL).await;
+    }
+}
+
+/// Returns the agent's final message once it has finished a turn and has nothing left to send.
+fn turn_outcome(thread_view: &AcpServerView, cx: &App) -> Option<Result<String>> {
+    if thread_view.has_load_error() {
+        return Some(Err(anyhow!("the agent failed to start")));
+    }
+    let active_thread = thread_view.active_thread()?.read(cx);
+    let thread = active_thread.thread.read(cx);
+    let has_pending_message = active_thread.is_loading_contents
+        || active_thread.has_queued_messages()
+        || thread.entries().is_empty();
+    if thread.status() != ThreadStatus::Idle || has_pending_message {
+        return None;
+    }
+
+    let final_message = thread
+        .entries()
+        .iter()
+        .rev()
+        .take_while(|entry| !matches!(entry, AgentThreadEntry::UserMessage(_)))
+        .find_map(|entry| match entry {
+            AgentThreadEntry::AssistantMessage(message) => Some(
+                message
+                    .chunks
+                    .iter()
+                    .filter_map(|chunk| match chunk {
+                        AssistantMessageChunk::Message { block } => Some(block.to_markdown(cx)),
+                        AssistantMessageChunk::Thought { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    if active_thread.has_thread_error() {
+        Some(Err(anyhow!(
+            "the agent stopped with an error\n{final_message}"
+        )))
+    } else {
+        Some(Ok(final_message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_name_matches() {
+        assert!(cli_name_matches(
+            "claude-code",
+            "Claude Code",
+            &ExternalAgent::ClaudeCode
+        ));
+        assert!(cli_name_matches(
+            "Claude Code",
+            "Claude Code",
+            &ExternalAgent::ClaudeCode
+        ));
+        assert!(cli_name_matches(
+            "zed",
+            "Zed Agent",
+            &ExternalAgent::NativeAgent
+        ));
+        assert!(cli_name_matches(
+            "codex_cli",
+            "Codex CLI",
+            &ExternalAgent::Codex
+        ));
+        assert!(!cli_name_matches(
+            "codex",
+            "Gemini CLI",
+            &ExternalAgent::Gemini
+        ));
+
+        let custom = ExternalAgent::Custom {
+            name: "my_agent".into(),
+        };
+        assert!(cli_name_matches("my-agent", "My Agent", &custom));
+    }
+}
@@ -0,0 +1,402 @@
+use acp_thread::{AgentThreadEntry, AssistantMessageChunk, ThreadStatus};
+use gpui::{
+    AnyElement, App, Entity, FocusHandle, Focusable, ScrollHandle, Subscription, Task, WeakEntity,
+    Window,
+};
+use markdown::{Markdown, MarkdownElement, MarkdownFont, MarkdownStyle};
+use ui::{Chip, DiffStat, Divider, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{Workspace, notifications::DetachAndPromptErr};
+
+use crate::{
+    AgentDiffPane,
+    acp::{AcpServerView, DiffStats},
+    agent_dashboard::{DashboardSession, REFRESH_INTERVAL},
+    agent_panel::{AgentPanel, AgentType},
+};
+
+/// One agent's attempt at a prompt that was fanned out to several agents.
+struct Candidate {
+    agent: AgentType,
+    server_view: Entity<AcpServerView>,
+    scroll_handle: ScrollHandle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verdict {
+    Accepted,
+    Rejected,
+}
+
+/// Shows the edits and final message of every agent that received the same prompt
+/// side by side, so that one result can be kept and the others rejected. Each agent works
+/// in an isolated worktree of its own.
+pub(crate) struct AgentComparison {
+    panel: WeakEntity<AgentPanel>,
+    workspace: WeakEntity<Workspace>,
+    candidates: Vec<Candidate>,
+    pub(crate) accepted: Option<usize>,
+    focus_handle: FocusHandle,
+    _refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl AgentComparison {
+    pub fn new(
+        panel: WeakEntity<AgentPanel>,
+        workspace: WeakEntity<Workspace>,
+        candidates: Vec<(AgentType, Entity<AcpServerView>)>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let refresh_task = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+        let subscriptions = candidates
+            .iter()
+            .map(|(_, server_view)| cx.observe(server_view, |_, _, cx| cx.notify()))
+            .collect();
+
+        Self {
+            panel,
+            workspace,
+            candidates: candidates
+                .into_iter()
+                .map(|(agent, server_view)| Candidate {
+                    agent,
+                    server_view,
+                    scroll_handle: ScrollHandle::new(),
+                })
+                .collect(),
+            accepted: None,
+            focus_handle: cx.focus_handle(),
+            _refresh_task: refresh_task,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Merges the chosen agent's worktree back into the project, and stops the other agents.
+    /// Every candidate's tab is then closed and its worktree discarded.
+    pub(crate) fn accept(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(panel) = self.panel.upgrade() else {
+            return;
+        };
+        let Some(accepted) = self
+            .candidates
+            .get(ix)
+            .map(|candidate| candidate.server_view.clone())
+        else {
+            return;
+        };
+        let server_views = self
+            .candidates
+            .iter()
+            .map(|candidate| candidate.server_view.clone())
+            .collect::<Vec<_>>();
+        for server_view in &server_views {
+            if server_view != &accepted {
+                server_view.update(cx, |server_view, cx| server_view.cancel_generation(cx));
+            }
+        }
+
+        panel
+            .update(cx, |panel, cx| {
+                panel.accept_fan_out_candidate(&accepted, server_views, window, cx)
+            })
+            .detach_and_prompt_err(
+                "Failed to merge the agent's worktree",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+
+        self.accepted = Some(ix);
+        cx.notify();
+    }
+
+    fn thread(candidate: &Candidate, cx: &App) -> Option<Entity<acp_thread::AcpThread>> {
+        let active_thread = candidate.server_view.read(cx).active_thread()?;
+        Some(active_thread.read(cx).thread.clone())
+    }
+
+    fn verdict(&self, ix: usize) -> Option<Verdict> {
+        self.accepted.map(|accepted| {
+            if accepted == ix {
+                Verdict::Accepted
+            } else {
+                Verdict::Rejected
+            }
+        })
+    }
+
+    fn render_candidate(
+        &self,
+        ix: usize,
+        candidate: &Candidate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let panel = self.panel.upgrade();
+        let tab_id = panel.as_ref().and_then(|panel| {
+            panel
+                .read(cx)
+                .tab_id_for_thread_view(&candidate.server_view)
+        });
+        let session = DashboardSession::new(
+            tab_id.unwrap_or_default(),
+            candidate.agent.clone(),
+            candidate.server_view.clone(),
+            cx,
+        );
+        let thread = Self::thread(candidate, cx);
+        let is_generating = thread
+            .as_ref()
+            .is_some_and(|thread| thread.read(cx).status() == ThreadStatus::Generating);
+
+        let changed_buffers = thread
+            .as_ref()
+            .map(|thread| thread.read(cx).action_log().read(cx).changed_buffers(cx))
+            .unwrap_or_default();
+        let total_stats = DiffStats::all_files(&changed_buffers, cx);
+        let changed_files = changed_buffers
+            .iter()
+            .enumerate()
+            .filter_map(|(file_ix, (buffer, diff))| {
+                let file = buffer.read(cx).file()?;
+                let path = file.path().display(file.path_style(cx)).to_string();
+                let stats = DiffStats::single_file(buffer.read(cx), diff.read(cx), cx);
+                Some(
+                    h_flex()
+                        .gap_1()
+                        .justify_between()
+                        .child(
+                            h_flex().min_w_0().child(
+                                Label::new(path)
+                                    .size(LabelSize::Small)
+                                    .buffer_font(cx)
+                                    .truncate(),
+                            ),
+                        )
+                        .child(
+                            DiffStat::new(
+                                ("file", file_ix),
+                                stats.lines_added as usize,
+                                stats.lines_removed as usize,
+                            )
+                            .label_size(LabelSize::XSmall),
+                        )
+                        .into_any_element(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let final_message = thread
+            .as_ref()
+            .and_then(|thread| last_assistant_message(thread.read(cx).entries()));
+        let markdown_style = MarkdownStyle::themed(MarkdownFont::Agent, window, cx);
+
+        let verdict = self.verdict(ix);
+        let accept_button = Button::new(("accept", ix), "Accept")
+            .label_size(LabelSize::Small)
+            .style(ButtonStyle::Filled)
+            .disabled(verdict.is_some() || is_generating || thread.is_none() || tab_id.is_none())
+            .tooltip(Tooltip::text(
+                "Merge this agent's worktree back and discard the other agents' worktrees",
+            ))
+            .on_click(cx.listener(move |this, _, window, cx| this.accept(ix, window, cx)));
+        let review_button = thread.clone().map(|thread| {
+            let workspace = self.workspace.clone();
+            Button::new(("review", ix), "Review Diff")
+                .label_size(LabelSize::Small)
+                .disabled(changed_buffers.is_empty())
+                .on_click(move |_, window, cx| {
+                    AgentDiffPane::deploy(thread.clone(), workspace.clone(), window, cx).log_err();
+                })
+        });
+
+        v_flex()
+            .id(("agent-comparison-candidate", ix))
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .border_1()
+            .rounded_md()
+            .border_color(match verdict {
+                Some(Verdict::Accepted) => cx.theme().status().success_border,
+                _ => cx.theme().colors().border,
+            })
+            .when(verdict == Some(Verdict::Rejected), |this| this.opacity(0.6))
+            .child(
+                h_flex()
+                    .id(("agent-comparison-header", ix))
+                    .p_2()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .when_some(tab_id, |this, tab_id| {
+                        let panel = self.panel.clone();
+                        this.cursor_pointer()
+                            .hover(|style| style.bg(cx.theme().colors().element_hover))
+                            .on_click(move |_, window, cx| {
+                                panel
+                                    .update(cx, |panel, cx| panel.activate_tab(tab_id, window, cx))
+                                    .ok();
+                            })
+                    })
+                    .child(
+                        h_flex()
+                            .min_w_0()
+                            .gap_1p5()
+                            .child(
+                                Icon::new(session.agent.icon().unwrap_or(IconName::ZedAgent))
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(Label::new(session.agent.label()).truncate()),
+                    )
+                    .child(match verdict {
+                        Some(Verdict::Accepted) => {
+                            Chip::new("Accepted").label_color(Color::Success)
+                        }
+                        Some(Verdict::Rejected) => Chip::new("Rejected").label_color(Color::Muted),
+                        None if tab_id.is_none() => Chip::new("Closed").label_color(Color::Muted),
+                        None => {
+                            Chip::new(session.status.label()).label_color(session.status.color())
+                        }
+                    }),
+            )
+            .child(
+                v_flex()
+                    .p_2()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Label::new("Edits")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .when(!changed_buffers.is_empty(), |this| {
+                                this.child(
+                                    DiffStat::new(
+                                        "total",
+                                        total_stats.lines_added as usize,
+                                        total_stats.lines_removed as usize,
+                                    )
+                                    .label_size(LabelSize::XSmall),
+                                )
+                            }),
+                    )
+                    .when(changed_files.is_empty(), |this| {
+                        this.child(
+                            Label::new("No files changed")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .children(changed_files),
+            )
+            .child(
+                div().flex_1().min_h_0().child(
+                    v_flex()
+                        .id(("agent-comparison-message", ix))
+                        .track_scroll(&candidate.scroll_handle)
+                        .size_full()
+                        .p_2()
+                        .gap_2()
+                        .overflow_y_scroll()
+                        .map(|this| match final_message {
+                            Some(chunks) => this.children(chunks.into_iter().map(|markdown| {
+                                MarkdownElement::new(markdown, markdown_style.clone())
+                            })),
+                            None => this.child(
+                                Label::new(if is_generating {
+                                    "Waiting for a response…"
+                                } else {
+                                    "No response yet"
+                                })
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            ),
+                        }),
+                ),
+            )
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_1()
+                    .justify_end()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border)
+                    .children(review_button)
+                    .child(accept_button),
+            )
+            .into_any_element()
+    }
+}
+
+/// The text of the last assistant message in the thread, excluding its thinking.
+fn last_assistant_message(entries: &[AgentThreadEntry]) -> Option<Vec<Entity<Markdown>>> {
+    entries.iter().rev().find_map(|entry| {
+        let AgentThreadEntry::AssistantMessage(message) = entry else {
+            return None;
+        };
+        let chunks = message
+            .chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                AssistantMessageChunk::Message { block } => block.markdown().cloned(),
+                AssistantMessageChunk::Thought { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        (!chunks.is_empty()).then_some(chunks)
+    })
+}
+
+impl Focusable for AgentComparison {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for AgentComparison {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let columns = (0..self.candidates.len())
+            .map(|ix| self.render_candidate(ix, &self.candidates[ix], window, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("agent-comparison")
+            .key_context("AgentComparison")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                h_flex().p_2().justify_between().child(
+                    Label::new(format!(
+                        "Comparing {} agents on the same prompt",
+                        self.candidates.len()
+                    ))
+                    .color(Color::Muted),
+                ),
+            )
+            .child(Divider::horizontal())
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .p_2()
+                    .gap_2()
+                    .items_start()
+                    .children(columns),
+            )
+    }
+}
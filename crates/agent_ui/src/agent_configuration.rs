@@ -1388,6 +1388,7 @@ async fn open_new_agent_servers_entry_in_settings_editor(
                                 favorite_models: vec![],
                                 default_config_options: Default::default(),
                                 favorite_config_option_values: Default::default(),
+                                allowed_extension_methods: Vec::new(),
                             },
                         );
                 }
@@ -315,6 +315,7 @@ impl PickerDelegate for ToolPickerDelegate {
                             })
                             .collect(),
                         default_model: default_profile.default_model.clone(),
+                        sandbox: default_profile.sandbox.to_content(),
                     });
 
                 if let Some(server_id) = server_id {
@@ -0,0 +1,420 @@
+use std::time::Duration;
+
+use acp_thread::{ThreadStatus, TokenUsage};
+use agent_client_protocol as acp;
+use gpui::{
+    AnyElement, App, Entity, FocusHandle, Focusable, ScrollHandle, Subscription, Task, WeakEntity,
+    Window,
+};
+use ui::{Chip, Divider, Tooltip, WithScrollbar, prelude::*};
+use util::time::duration_alt_display;
+
+use crate::{
+    acp::AcpServerView,
+    agent_panel::{AgentPanel, AgentType},
+    text_thread_editor::humanize_token_count,
+};
+
+/// How often the dashboard re-renders so that elapsed times keep ticking.
+pub(crate) const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SessionStatus {
+    Loading,
+    Generating,
+    WaitingForPermission,
+    Idle,
+    Errored,
+}
+
+impl SessionStatus {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Loading => "Loading",
+            Self::Generating => "Generating",
+            Self::WaitingForPermission => "Waiting for Permission",
+            Self::Idle => "Idle",
+            Self::Errored => "Error",
+        }
+    }
+
+    pub(crate) fn color(self) -> Color {
+        match self {
+            Self::Loading | Self::Idle => Color::Muted,
+            Self::Generating => Color::Accent,
+            Self::WaitingForPermission => Color::Warning,
+            Self::Errored => Color::Error,
+        }
+    }
+}
+
+/// A snapshot of one open agent session, as shown in a dashboard row.
+pub(crate) struct DashboardSession {
+    pub tab_id: usize,
+    pub agent: AgentType,
+    pub title: SharedString,
+    pub status: SessionStatus,
+    /// Duration of the running turn, or of the last one if the agent is idle.
+    pub elapsed: Option<Duration>,
+    pub token_usage: Option<TokenUsage>,
+    pub changed_files: usize,
+    pub pending_permissions: usize,
+    pub server_view: Entity<AcpServerView>,
+}
+
+impl DashboardSession {
+    pub fn new(
+        tab_id: usize,
+        agent: AgentType,
+        server_view: Entity<AcpServerView>,
+        cx: &App,
+    ) -> Self {
+        let view = server_view.read(cx);
+        let mut session = Self {
+            tab_id,
+            title: view.title(cx),
+            agent,
+            status: if view.has_load_error() {
+                SessionStatus::Errored
+            } else {
+                SessionStatus::Loading
+            },
+            elapsed: None,
+            token_usage: None,
+            changed_files: 0,
+            pending_permissions: 0,
+            server_view: server_view.clone(),
+        };
+
+        let Some(active_thread) = view.active_thread() else {
+            return session;
+        };
+        let active_thread = active_thread.read(cx);
+        let thread = active_thread.thread.read(cx);
+
+        session.pending_permissions = thread.tool_calls_awaiting_confirmation();
+        session.status = if active_thread.has_thread_error() {
+            SessionStatus::Errored
+        } else if session.pending_permissions > 0 {
+            SessionStatus::WaitingForPermission
+        } else {
+            match thread.status() {
+                ThreadStatus::Generating => SessionStatus::Generating,
+                ThreadStatus::Idle => SessionStatus::Idle,
+            }
+        };
+        session.elapsed = match thread.status() {
+            ThreadStatus::Generating => active_thread
+                .turn_fields
+                .turn_started_at
+                .map(|started_at| started_at.elapsed()),
+            ThreadStatus::Idle => active_thread.turn_fields.last_turn_duration,
+        };
+        session.token_usage = thread.token_usage().cloned();
+        session.changed_files = thread.action_log().read(cx).changed_buffers(cx).len();
+        session
+    }
+}
+
+/// Lists every open agent session in the panel with its live status.
+pub(crate) struct AgentDashboard {
+    panel: WeakEntity<AgentPanel>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    _refresh_task: Task<()>,
+    _panel_subscription: Option<Subscription>,
+}
+
+impl AgentDashboard {
+    pub fn new(panel: WeakEntity<AgentPanel>, cx: &mut Context<Self>) -> Self {
+        let refresh_task = cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+        let panel_subscription = panel
+            .upgrade()
+            .map(|panel| cx.observe(&panel, |_, _, cx| cx.notify()));
+
+        Self {
+            panel,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+            _refresh_task: refresh_task,
+            _panel_subscription: panel_subscription,
+        }
+    }
+
+    fn render_session(&self, session: DashboardSession, cx: &mut Context<Self>) -> AnyElement {
+        let tab_id = session.tab_id;
+        let status = session.status;
+        let token_usage = session.token_usage.as_ref().map(|usage| {
+            format!(
+                "{} / {} tokens",
+                humanize_token_count(usage.used_tokens),
+                humanize_token_count(usage.max_tokens)
+            )
+        });
+        let changed_files = match session.changed_files {
+            0 => None,
+            1 => Some("1 file changed".to_string()),
+            count => Some(format!("{count} files changed")),
+        };
+
+        let panel = self.panel.clone();
+        let server_view = session.server_view;
+        let approve_button = (session.pending_permissions > 0).then(|| {
+            Button::new(("approve", tab_id), "Allow")
+                .label_size(LabelSize::Small)
+                .tooltip(Tooltip::text("Allow the pending tool call once"))
+                .on_click({
+                    let server_view = server_view.clone();
+                    move |_, window, cx| {
+                        cx.stop_propagation();
+                        server_view.update(cx, |server_view, cx| {
+                            server_view.authorize_pending_tool_call(
+                                acp::PermissionOptionKind::AllowOnce,
+                                window,
+                                cx,
+                            );
+                        });
+                    }
+                })
+        });
+        let cancel_button = matches!(
+            status,
+            SessionStatus::Generating | SessionStatus::WaitingForPermission
+        )
+        .then(|| {
+            Button::new(("cancel", tab_id), "Cancel")
+                .label_size(LabelSize::Small)
+                .on_click({
+                    let server_view = server_view.clone();
+                    move |_, _, cx| {
+                        cx.stop_propagation();
+                        server_view.update(cx, |server_view, cx| server_view.cancel_generation(cx));
+                    }
+                })
+        });
+
+        h_flex()
+            .id(("agent-dashboard-session", tab_id))
+            .w_full()
+            .px_2()
+            .py_1p5()
+            .gap_2()
+            .justify_between()
+            .rounded_sm()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .cursor_pointer()
+            .on_click(move |_, window, cx| {
+                panel
+                    .update(cx, |panel, cx| panel.activate_tab(tab_id, window, cx))
+                    .ok();
+            })
+            .child(
+                h_flex()
+                    .min_w_0()
+                    .gap_2()
+                    .child(
+                        Icon::new(session.agent.icon().unwrap_or(IconName::ZedAgent))
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        v_flex()
+                            .min_w_0()
+                            .child(Label::new(session.title).truncate())
+                            .child(
+                                h_flex()
+                                    .gap_1p5()
+                                    .child(
+                                        Label::new(session.agent.label())
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                    .when_some(session.elapsed, |this, elapsed| {
+                                        this.child(
+                                            Label::new(duration_alt_display(elapsed))
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                                    .when_some(token_usage, |this, token_usage| {
+                                        this.child(
+                                            Label::new(token_usage)
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                                    .when_some(changed_files, |this, changed_files| {
+                                        this.child(
+                                            Label::new(changed_files)
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        )
+                                    }),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_none()
+                    .gap_1()
+                    .when(session.pending_permissions > 1, |this| {
+                        this.child(
+                            Label::new(format!("{} pending", session.pending_permissions))
+                                .size(LabelSize::Small)
+                                .color(Color::Warning),
+                        )
+                    })
+                    .child(Chip::new(status.label()).label_color(status.color()))
+                    .children(approve_button)
+                    .children(cancel_button),
+            )
+            .into_any_element()
+    }
+}
+
+impl Focusable for AgentDashboard {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for AgentDashboard {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let sessions = self
+            .panel
+            .upgrade()
+            .map(|panel| panel.read(cx).dashboard_sessions(cx))
+            .unwrap_or_default();
+        let is_empty = sessions.is_empty();
+        let rows = sessions
+            .into_iter()
+            .map(|session| self.render_session(session, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("agent-dashboard")
+            .key_context("AgentDashboard")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                div().size_full().child(
+                    v_flex()
+                        .id("agent-dashboard-sessions")
+                        .track_scroll(&self.scroll_handle)
+                        .size_full()
+                        .p_1()
+                        .overflow_y_scroll()
+                        .when(is_empty, |this| {
+                            this.child(h_flex().p_2().child(
+                                Label::new("No agent sessions are open.").color(Color::Muted),
+                            ))
+                        })
+                        .children(itertools::intersperse_with(rows.into_iter(), || {
+                            Divider::horizontal().into_any_element()
+                        })),
+                ),
+            )
+            .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent_panel::tests::{open_agent_panel, open_stub_thread};
+    use acp_thread::{PermissionOptions, StubAgentConnection};
+    use collections::HashMap;
+    use gpui::{TestAppContext, VisualTestContext};
+
+    fn statuses(
+        panel: &Entity<AgentPanel>,
+        cx: &mut VisualTestContext,
+    ) -> Vec<(SessionStatus, usize)> {
+        panel.read_with(cx, |panel, cx| {
+            panel
+                .dashboard_sessions(cx)
+                .iter()
+                .map(|session| (session.status, session.pending_permissions))
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_dashboard_sessions(cx: &mut TestAppContext) {
+        let (panel, cx) = open_agent_panel(cx).await;
+
+        let tool_call_id = acp::ToolCallId::new("1");
+        let permission_connection =
+            StubAgentConnection::new().with_permission_requests(HashMap::from_iter([(
+                tool_call_id.clone(),
+                PermissionOptions::Flat(vec![acp::PermissionOption::new(
+                    "1",
+                    "Allow",
+                    acp::PermissionOptionKind::AllowOnce,
+                )]),
+            )]));
+        permission_connection.set_next_prompt_updates(vec![acp::SessionUpdate::ToolCall(
+            acp::ToolCall::new(tool_call_id, "Edit a file").kind(acp::ToolKind::Edit),
+        )]);
+        // Without updates to send, the stub agent keeps generating until the turn is ended.
+        let generating_connection = StubAgentConnection::new();
+        let stable_ids = panel.update_in(cx, |panel, window, cx| {
+            [
+                open_stub_thread(panel, permission_connection, window, cx),
+                open_stub_thread(panel, generating_connection, window, cx),
+            ]
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            statuses(&panel, cx),
+            [(SessionStatus::Idle, 0), (SessionStatus::Idle, 0)]
+        );
+
+        let server_views = panel.read_with(cx, |panel, _| {
+            stable_ids.map(|stable_id| panel.agent_tab(stable_id).unwrap())
+        });
+        let turns = server_views
+            .iter()
+            .map(|server_view| {
+                let thread = server_view.read_with(cx, |server_view, cx| {
+                    server_view.active_thread().unwrap().read(cx).thread.clone()
+                });
+                thread.update(cx, |thread, cx| thread.send_raw("Hello", cx))
+            })
+            .collect::<Vec<_>>();
+        cx.run_until_parked();
+        assert_eq!(
+            statuses(&panel, cx),
+            [
+                (SessionStatus::WaitingForPermission, 1),
+                (SessionStatus::Generating, 0)
+            ]
+        );
+
+        // The quick actions allow the pending tool call and cancel the generating turn.
+        server_views[0].update_in(cx, |server_view, window, cx| {
+            server_view.authorize_pending_tool_call(
+                acp::PermissionOptionKind::AllowOnce,
+                window,
+                cx,
+            )
+        });
+        server_views[1].update(cx, |server_view, cx| server_view.cancel_generation(cx));
+        cx.run_until_parked();
+        assert_eq!(
+            statuses(&panel, cx),
+            [(SessionStatus::Idle, 0), (SessionStatus::Idle, 0)]
+        );
+        for turn in turns {
+            turn.await.unwrap();
+        }
+    }
+}
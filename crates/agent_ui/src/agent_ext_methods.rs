@@ -0,0 +1,338 @@
+//! Built-in handlers for the ACP extension methods that agents may call on the editor.
+//!
+//! Agents only get to call these once they're listed in the agent's
+//! `allowed_extension_methods` setting.
+
+use agent_client_protocol as acp;
+use agent_servers::{AgentExtMethodRegistry, ExtMethodCall};
+use editor::Editor;
+use gpui::{App, Entity, Task, WindowHandle};
+use language::{DiagnosticSeverity, OffsetRangeExt as _, Point};
+use project::Project;
+use serde::Deserialize;
+use serde_json::json;
+use workspace::{NotificationSource, Toast, Workspace, notifications::NotificationId};
+
+pub const OPEN_FILE_METHOD: &str = "zed/openFile";
+pub const SHOW_NOTIFICATION_METHOD: &str = "zed/showNotification";
+pub const DIAGNOSTICS_METHOD: &str = "zed/diagnostics";
+
+pub fn init(cx: &mut App) {
+    AgentExtMethodRegistry::register(OPEN_FILE_METHOD, open_file, cx);
+    AgentExtMethodRegistry::register(SHOW_NOTIFICATION_METHOD, show_notification, cx);
+    AgentExtMethodRegistry::register(DIAGNOSTICS_METHOD, diagnostics, cx);
+}
+
+#[derive(Deserialize)]
+struct OpenFileParams {
+    /// An absolute path, or one relative to a worktree root.
+    path: String,
+    /// 1-based.
+    line: Option<u32>,
+    /// 1-based.
+    column: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ShowNotificationParams {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticsParams {
+    path: Option<String>,
+}
+
+struct AgentExtMethodToast;
+
+fn open_file(call: ExtMethodCall, cx: &mut App) -> Task<Result<serde_json::Value, acp::Error>> {
+    let params = match call.params::<OpenFileParams>() {
+        Ok(params) => params,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let Some(project) = call.project else {
+        return Task::ready(Err(
+            acp::Error::invalid_params().data("no project for this agent")
+        ));
+    };
+    let Some(project_path) = project.read(cx).find_project_path(&params.path, cx) else {
+        return Task::ready(Err(acp::Error::invalid_params()
+            .data(format!("could not find path {} in project", params.path))));
+    };
+    let Some(workspace_window) = workspace_window_for_project(&project, cx) else {
+        return Task::ready(Err(
+            acp::Error::internal_error().data("no open workspace for this project")
+        ));
+    };
+
+    let position = Point::new(
+        params.line.unwrap_or(1).saturating_sub(1),
+        params.column.unwrap_or(1).saturating_sub(1),
+    );
+    workspace_window
+        .update(cx, |workspace, window, cx| {
+            let open_task = workspace.open_path(project_path, None, true, window, cx);
+            window.spawn(cx, async move |cx| {
+                let item = open_task
+                    .await
+                    .map_err(|error| acp::Error::internal_error().data(error.to_string()))?;
+                if let Some(editor) = item.downcast::<Editor>() {
+                    editor
+                        .update_in(cx, |editor, window, cx| {
+                            editor.change_selections(
+                                Default::default(),
+                                window,
+                                cx,
+                                |selections| selections.select_ranges([position..position]),
+                            );
+                        })
+                        .ok();
+                }
+                Ok(serde_json::Value::Null)
+            })
+        })
+        .unwrap_or_else(|error| {
+            Task::ready(Err(acp::Error::internal_error().data(error.to_string())))
+        })
+}
+
+fn show_notification(
+    call: ExtMethodCall,
+    cx: &mut App,
+) -> Task<Result<serde_json::Value, acp::Error>> {
+    let params = match call.params::<ShowNotificationParams>() {
+        Ok(params) => params,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let Some(workspace_window) = call
+        .project
+        .as_ref()
+        .and_then(|project| workspace_window_for_project(project, cx))
+    else {
+        return Task::ready(Err(
+            acp::Error::internal_error().data("no open workspace for this agent")
+        ));
+    };
+
+    let toast = Toast::new(
+        NotificationId::composite::<AgentExtMethodToast>(call.agent_name.clone()),
+        format!("{}: {}", call.agent_name, params.message),
+    )
+    .autohide();
+    let result = workspace_window.update(cx, |workspace, _, cx| {
+        workspace.show_toast(toast, NotificationSource::Agent, cx)
+    });
+    Task::ready(
+        result
+            .map(|_| serde_json::Value::Null)
+            .map_err(|error| acp::Error::internal_error().data(error.to_string())),
+    )
+}
+
+/// Returns every diagnostic in a file, or the error and warning counts of each file in the
+/// project when no path is given.
+fn diagnostics(call: ExtMethodCall, cx: &mut App) -> Task<Result<serde_json::Value, acp::Error>> {
+    let params = match call.params::<DiagnosticsParams>() {
+        Ok(params) => params,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let Some(project) = call.project else {
+        return Task::ready(Err(
+            acp::Error::invalid_params().data("no project for this agent")
+        ));
+    };
+
+    let Some(path) = params.path.filter(|path| !path.is_empty()) else {
+        let project = project.read(cx);
+        let summaries = project
+            .diagnostic_summaries(true, cx)
+            .filter(|(_, _, summary)| summary.error_count > 0 || summary.warning_count > 0)
+            .filter_map(|(project_path, _, summary)| {
+                let worktree = project.worktree_for_id(project_path.worktree_id, cx)?;
+                Some(json!({
+                    "path": worktree.read(cx).absolutize(&project_path.path),
+                    "errors": summary.error_count,
+                    "warnings": summary.warning_count,
+                }))
+            })
+            .collect::<Vec<_>>();
+        return Task::ready(Ok(json!({ "summaries": summaries })));
+    };
+
+    let Some(project_path) = project.read(cx).find_project_path(&path, cx) else {
+        return Task::ready(Err(
+            acp::Error::invalid_params().data(format!("could not find path {path} in project"))
+        ));
+    };
+    let open_buffer_task = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    cx.spawn(async move |cx| {
+        let buffer = open_buffer_task
+            .await
+            .map_err(|error| acp::Error::internal_error().data(error.to_string()))?;
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let diagnostics = snapshot
+            .diagnostic_groups(None)
+            .into_iter()
+            .map(|(_, group)| {
+                let entry = &group.entries[group.primary_ix];
+                let range = entry.range.to_point(&snapshot);
+                let severity = match entry.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => "error",
+                    DiagnosticSeverity::WARNING => "warning",
+                    DiagnosticSeverity::INFORMATION => "information",
+                    _ => "hint",
+                };
+                json!({
+                    "severity": severity,
+                    "message": entry.diagnostic.message,
+                    "start": { "line": range.start.row + 1, "column": range.start.column + 1 },
+                    "end": { "line": range.end.row + 1, "column": range.end.column + 1 },
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({ "diagnostics": diagnostics }))
+    })
+}
+
+fn workspace_window_for_project(
+    project: &Entity<Project>,
+    cx: &App,
+) -> Option<WindowHandle<Workspace>> {
+    cx.windows()
+        .into_iter()
+        .filter_map(|window| window.downcast::<Workspace>())
+        .find(|window| {
+            window
+                .read(cx)
+                .is_ok_and(|workspace| workspace.project() == project)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use acp_thread::AgentThreadEntry;
+    use agent_servers::{
+        AgentServer as _, AgentServerDelegate,
+        mock_agent::{MockAgentScript, MockAgentServer},
+    };
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use language::{Diagnostic, DiagnosticEntry, LanguageServerId, PointUtf16, Unclipped};
+    use settings::SettingsStore;
+    use std::path::{Path, PathBuf};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_diagnostics_method(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let mut settings_store = SettingsStore::test(cx);
+            settings_store
+                .set_user_settings(
+                    &json!({
+                        "agent_servers": {
+                            "Mock Agent": {
+                                "type": "custom",
+                                "command": "mock_acp_agent",
+                                "allowed_extension_methods": [DIAGNOSTICS_METHOD]
+                            }
+                        }
+                    })
+                    .to_string(),
+                    cx,
+                )
+                .unwrap();
+            cx.set_global(settings_store);
+            init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "a.rs": "let x = y;\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let _buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/a.rs"), cx)
+            })
+            .await
+            .unwrap();
+        project.update(cx, |project, cx| {
+            project.lsp_store().update(cx, |lsp_store, cx| {
+                lsp_store
+                    .update_diagnostic_entries(
+                        LanguageServerId(0),
+                        PathBuf::from(path!("/project/a.rs")),
+                        None,
+                        None,
+                        vec![DiagnosticEntry {
+                            range: Unclipped(PointUtf16::new(0, 8))
+                                ..Unclipped(PointUtf16::new(0, 9)),
+                            diagnostic: Diagnostic {
+                                message: "cannot find value `y`".to_string(),
+                                is_primary: true,
+                                ..Diagnostic::default()
+                            },
+                        }],
+                        cx,
+                    )
+                    .unwrap();
+            })
+        });
+
+        let server = MockAgentServer::new(
+            MockAgentScript::from_json(indoc! {r#"
+                {
+                    "turns": [
+                        {
+                            "events": [
+                                {
+                                    "type": "ext_method",
+                                    "method": "_zed/diagnostics",
+                                    "params": { "path": "a.rs" },
+                                    "echo": true
+                                }
+                            ]
+                        }
+                    ]
+                }
+            "#})
+            .unwrap(),
+        );
+        let store = project.read_with(cx, |project, _| project.agent_server_store().clone());
+        let delegate = AgentServerDelegate::new(store, project.clone(), None, None);
+        let (connection, _) = cx
+            .update(|cx| server.connect(Some(Path::new(path!("/project"))), delegate, cx))
+            .await
+            .unwrap();
+        let thread = cx
+            .update(|cx| connection.new_session(project.clone(), Path::new(path!("/project")), cx))
+            .await
+            .unwrap();
+        thread
+            .update(cx, |thread, cx| thread.send_raw("Check a.rs", cx))
+            .await
+            .unwrap();
+
+        thread.read_with(cx, |thread, cx| {
+            let Some(AgentThreadEntry::AssistantMessage(message)) = thread.entries().last() else {
+                panic!("expected the agent to echo the diagnostics");
+            };
+            let markdown = message.to_markdown(cx);
+            let response: serde_json::Value =
+                serde_json::from_str(markdown.trim_start_matches("## Assistant")).unwrap();
+            assert_eq!(
+                response,
+                json!({
+                    "diagnostics": [
+                        {
+                            "severity": "error",
+                            "message": "cannot find value `y`",
+                            "start": { "line": 1, "column": 9 },
+                            "end": { "line": 1, "column": 10 },
+                        }
+                    ]
+                })
+            );
+        });
+    }
+}
@@ -0,0 +1,306 @@
+use std::{sync::Arc, time::Duration};
+
+use acp_thread::ThreadStatus;
+use agent_settings::{AgentJob, AgentJobTrigger, AgentSettings};
+use chrono::{Datelike as _, Local, Timelike as _};
+use collections::{HashMap, HashSet};
+use gpui::{App, Context, Entity, EventEmitter, Global, Subscription, Task, WeakEntity};
+use language::{Buffer, BufferEvent, BufferId};
+use project::{Project, ProjectPath, buffer_store::BufferStoreEvent, task_store::TaskFinished};
+use settings::{Settings as _, SettingsStore};
+use util::rel_path::RelPath;
+
+use crate::acp::AcpServerView;
+
+/// How long new errors in a file have to stay before they start a job, so that errors that only
+/// exist while the user is typing don't.
+const DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_secs(3);
+
+/// A job whose trigger fired, to be opened in a background agent tab.
+pub(crate) struct AgentJobRun {
+    pub job: AgentJob,
+    pub prompt: String,
+}
+
+/// The minute in which each scheduled job last ran, shared by the runners of all windows so that
+/// a job runs once at its time instead of once per window.
+#[derive(Default)]
+struct ScheduledJobRuns(HashMap<Arc<str>, i64>);
+
+impl Global for ScheduledJobRuns {}
+
+impl ScheduledJobRuns {
+    /// Records that the job runs in the given minute, returning false if another window already
+    /// ran it then.
+    fn claim(job_name: &Arc<str>, minute: i64, cx: &mut App) -> bool {
+        let runs = &mut cx.default_global::<Self>().0;
+        runs.insert(job_name.clone(), minute) != Some(minute)
+    }
+}
+
+/// Watches the project for the triggers of the jobs in the settings.
+pub(crate) struct AgentJobRunner {
+    project: Entity<Project>,
+    jobs: Vec<AgentJob>,
+    /// The latest run of each job, which keeps the job from starting again while it's running.
+    runs: HashMap<Arc<str>, WeakEntity<AcpServerView>>,
+    error_counts: HashMap<ProjectPath, usize>,
+    new_errors: HashSet<ProjectPath>,
+    buffer_subscriptions: HashMap<BufferId, Subscription>,
+    _diagnostics_debounce: Option<Task<()>>,
+    _schedule_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<AgentJobRun> for AgentJobRunner {}
+
+impl AgentJobRunner {
+    pub fn new(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let buffer_store = project.read(cx).buffer_store().clone();
+        let task_store = project.read(cx).task_store().clone();
+        let subscriptions = vec![
+            cx.observe_global::<SettingsStore>(|this, cx| {
+                this.jobs = AgentSettings::get_global(cx).jobs.clone();
+            }),
+            cx.subscribe(&buffer_store, |this, _, event, cx| match event {
+                BufferStoreEvent::BufferAdded(buffer) => this.watch_buffer(buffer, cx),
+                BufferStoreEvent::BufferDropped(buffer_id) => {
+                    this.buffer_subscriptions.remove(buffer_id);
+                }
+                _ => {}
+            }),
+            cx.subscribe(&task_store, |this, _, event: &TaskFinished, cx| {
+                this.task_finished(event, cx)
+            }),
+            cx.subscribe(&project, |this, _, event, cx| {
+                if let project::Event::DiagnosticsUpdated { paths, .. } = event {
+                    this.diagnostics_updated(paths, cx);
+                }
+            }),
+        ];
+
+        // Only errors that appear from now on start jobs.
+        let mut error_counts = HashMap::default();
+        for (path, _, summary) in project.read(cx).diagnostic_summaries(false, cx) {
+            *error_counts.entry(path).or_insert(0) += summary.error_count;
+        }
+
+        let mut this = Self {
+            project,
+            jobs: AgentSettings::get_global(cx).jobs.clone(),
+            runs: HashMap::default(),
+            error_counts,
+            new_errors: HashSet::default(),
+            buffer_subscriptions: HashMap::default(),
+            _diagnostics_debounce: None,
+            _schedule_task: Self::schedule(cx),
+            _subscriptions: subscriptions,
+        };
+        for buffer in buffer_store.read(cx).buffers().collect::<Vec<_>>() {
+            this.watch_buffer(&buffer, cx);
+        }
+        this
+    }
+
+    /// Records the tab that a run of the job was opened in.
+    pub fn run_started(&mut self, job_name: Arc<str>, thread_view: WeakEntity<AcpServerView>) {
+        self.runs.insert(job_name, thread_view);
+    }
+
+    /// Checks the scheduled jobs at the start of every minute.
+    fn schedule(cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            loop {
+                let seconds_left = 60 - u64::from(Local::now().second().min(59));
+                cx.background_executor()
+                    .timer(Duration::from_secs(seconds_left))
+                    .await;
+
+                let now = Local::now();
+                let minute = now.timestamp().div_euclid(60);
+                let result = this.update(cx, |this, cx| {
+                    let jobs = this
+                        .jobs
+                        .iter()
+                        .filter(|job| match &job.trigger {
+                            AgentJobTrigger::Schedule(schedule) => schedule.matches(
+                                now.minute(),
+                                now.hour(),
+                                now.day(),
+                                now.month(),
+                                now.weekday().num_days_from_sunday(),
+                            ),
+                            _ => false,
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    for job in jobs {
+                        if this.can_start(&job.name, cx)
+                            && ScheduledJobRuns::claim(&job.name, minute, cx)
+                        {
+                            this.start(job, None, None, cx);
+                        }
+                    }
+                });
+                if result.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn watch_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let subscription = cx.subscribe(buffer, |this, buffer, event, cx| {
+            if let BufferEvent::Saved = event {
+                this.buffer_saved(&buffer, cx);
+            }
+        });
+        self.buffer_subscriptions
+            .insert(buffer.read(cx).remote_id(), subscription);
+    }
+
+    fn buffer_saved(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(file) = buffer.read(cx).file() else {
+            return;
+        };
+        let path = file.path().clone();
+        let jobs = self
+            .jobs
+            .iter()
+            .filter(|job| {
+                matches!(&job.trigger, AgentJobTrigger::FileSaved(glob) if glob.is_match(&path))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for job in jobs {
+            self.start(job, Some(&path), None, cx);
+        }
+    }
+
+    fn task_finished(&mut self, event: &TaskFinished, cx: &mut Context<Self>) {
+        if event.success {
+            return;
+        }
+        let jobs = self
+            .jobs
+            .iter()
+            .filter(|job| match &job.trigger {
+                AgentJobTrigger::TaskFailed { label } => {
+                    label.as_ref().is_none_or(|label| *label == event.label)
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for job in jobs {
+            self.start(job, None, Some(&event.label), cx);
+        }
+    }
+
+    fn diagnostics_updated(&mut self, paths: &[ProjectPath], cx: &mut Context<Self>) {
+        for path in paths {
+            let error_count = self
+                .project
+                .read(cx)
+                .diagnostic_summary_for_path(path, cx)
+                .error_count;
+            let previous_count = self
+                .error_counts
+                .insert(path.clone(), error_count)
+                .unwrap_or(0);
+            if error_count > previous_count {
+                self.new_errors.insert(path.clone());
+            }
+        }
+        if self.new_errors.is_empty() {
+            return;
+        }
+
+        self._diagnostics_debounce = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(DIAGNOSTICS_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.start_diagnostic_jobs(cx))
+                .ok();
+        }));
+    }
+
+    fn start_diagnostic_jobs(&mut self, cx: &mut Context<Self>) {
+        let mut paths = std::mem::take(&mut self.new_errors)
+            .into_iter()
+            .filter(|path| self.error_counts.get(path).is_some_and(|count| *count > 0))
+            .collect::<Vec<_>>();
+        paths.sort_by(|a, b| a.path.cmp(&b.path));
+
+        for path in paths {
+            let jobs = self
+                .jobs
+                .iter()
+                .filter(|job| match &job.trigger {
+                    AgentJobTrigger::DiagnosticError(glob) => {
+                        glob.as_ref().is_none_or(|glob| glob.is_match(&path.path))
+                    }
+                    _ => false,
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            for job in jobs {
+                self.start(job, Some(&path.path), None, cx);
+            }
+        }
+    }
+
+    fn start(
+        &mut self,
+        job: AgentJob,
+        file: Option<&RelPath>,
+        task: Option<&str>,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_start(&job.name, cx) {
+            return;
+        }
+
+        let path_style = self.project.read(cx).path_style(cx);
+        let file = file.map(|file| file.display(path_style));
+        let date = Local::now().format("%Y-%m-%d").to_string();
+        let prompt = job.render_prompt(file.as_deref(), task, &date);
+        cx.emit(AgentJobRun { job, prompt });
+    }
+
+    fn can_start(&self, job_name: &str, cx: &Context<Self>) -> bool {
+        // Guests would run the job on the host's files with their own agent.
+        !self.project.read(cx).is_via_collab() && !self.is_running(job_name, cx)
+    }
+
+    fn is_running(&self, job_name: &str, cx: &Context<Self>) -> bool {
+        let Some(thread_view) = self.runs.get(job_name).and_then(|view| view.upgrade()) else {
+            return false;
+        };
+        let thread_view = thread_view.read(cx);
+        if thread_view.has_load_error() {
+            return false;
+        }
+        match thread_view.active_thread() {
+            Some(active_thread) => {
+                active_thread.read(cx).thread.read(cx).status() == ThreadStatus::Generating
+            }
+            // The agent is still starting.
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    fn test_scheduled_job_runs_once_per_minute(cx: &mut App) {
+        let backup: Arc<str> = "backup".into();
+        let review: Arc<str> = "review".into();
+        assert!(ScheduledJobRuns::claim(&backup, 100, cx));
+        // Another window checks the schedule in the same minute.
+        assert!(!ScheduledJobRuns::claim(&backup, 100, cx));
+        assert!(ScheduledJobRuns::claim(&review, 100, cx));
+        assert!(ScheduledJobRuns::claim(&backup, 101, cx));
+    }
+}
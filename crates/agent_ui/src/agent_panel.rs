@@ -1,6 +1,18 @@
-use std::{cmp::Ordering, ops::Range, path::Path, rc::Rc, sync::Arc, time::Duration};
+use std::{
+    cmp::Ordering,
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+    },
+    time::Duration,
+};
 
-use acp_thread::{AcpThread, AgentSessionInfo};
+use acp_thread::{
+    AcpThread, AgentFileRegistry, AgentSessionInfo, AgentTerminalSession, MentionUri,
+};
 use agent::{ContextServerRegistry, SharedThread, ThreadStore};
 use agent_client_protocol as acp;
 use agent_servers::AgentServer;
@@ -18,31 +30,40 @@ use crate::ManageProfiles;
 use crate::ui::{AcpOnboardingModal, ClaudeCodeOnboardingModal};
 use crate::{
     AddContextServer, AgentDiffPane, CopyThreadToClipboard, EditTitle, Follow, InlineAssistant,
-    LoadThreadFromClipboard, NewTextThread, NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff,
+    LoadThreadFromClipboard, NewTextThread, NewThread, OpenActiveThreadAsMarkdown,
+    OpenAgentDashboard, OpenAgentDiff, OpenAuditLog, OpenCheckpointTimeline, OpenFanOutComparison,
     OpenHistory, ResetTrialEndUpsell, ResetTrialUpsell, ToggleNavigationMenu, ToggleNewThreadMenu,
     ToggleOptionsMenu,
-    acp::AcpServerView,
+    acp::{AcpServerView, SessionBudgetKind, SessionBudgetOverride},
+    agent_cli::{self, AgentTabSummary},
+    agent_comparison::AgentComparison,
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
+    agent_dashboard::{AgentDashboard, DashboardSession},
+    agent_jobs::{AgentJobRun, AgentJobRunner},
+    agent_sessions_server::start_agent_sessions_server,
+    checkpoint_timeline::CheckpointTimeline,
     slash_command::SlashCommandCompletionProvider,
     text_thread_editor::{AgentPanelDelegate, TextThreadEditor, make_lsp_adapter_delegate},
     ui::{AgentOnboardingModal, EndTrialUpsell},
 };
+use crate::{
+    ContinueInAgent, ExternalAgent, ExternalAgentInitialContent, NewExternalAgentThread,
+    NewIsolatedAgentThread, NewNativeAgentThreadFromSummary,
+};
 use crate::{
     ExpandMessageEditor,
     acp::{AcpThreadHistory, ThreadHistoryEvent},
     text_thread_history::{TextThreadHistory, TextThreadHistoryEvent},
 };
-use crate::{
-    ExternalAgent, ExternalAgentInitialContent, NewExternalAgentThread,
-    NewNativeAgentThreadFromSummary,
-};
-use agent_settings::AgentSettings;
+use agent_settings::{AgentSettings, SessionBudget};
 use ai_onboarding::AgentPanelOnboarding;
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use assistant_slash_command::SlashCommandWorkingSet;
 use assistant_text_thread::{TextThread, TextThreadEvent, TextThreadSummary};
 use client::UserStore;
 use cloud_api_types::Plan;
+use collections::HashSet;
+use context_server::listener::McpServer;
 use editor::{Anchor, AnchorRangeExt as _, Editor, EditorEvent, MultiBuffer, actions::Cancel};
 use extension::ExtensionEvents;
 use extension_host::ExtensionStore;
@@ -56,11 +77,15 @@ use gpui::{
 use language::LanguageRegistry;
 use language_model::{ConfigurationError, LanguageModelRegistry};
 use menu::Confirm;
-use project::{Project, ProjectPath, Worktree};
+use project::{
+    Project, ProjectPath, Worktree,
+    git_store::Repository,
+    trusted_worktrees::{PathTrust, TrustedWorktrees},
+};
 use prompt_store::{PromptBuilder, PromptStore, UserPromptId};
 use rules_library::{RulesLibrary, open_rules_library};
 use search::{BufferSearchBar, buffer_search};
-use settings::{Settings, update_settings_file};
+use settings::{Settings, SettingsStore, update_settings_file};
 use theme::ThemeSettings;
 use ui::{
     Callout, ContextMenu, ContextMenuEntry, KeyBinding, PopoverMenu, PopoverMenuHandle, Tab,
@@ -71,6 +96,7 @@ use workspace::{
     CollaboratorId, DraggedSelection, DraggedTab, NotificationSource, ToggleZoom, ToolbarItemView,
     Workspace,
     dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr,
 };
 use zed_actions::{
     DecreaseBufferFontSize, IncreaseBufferFontSize, ResetBufferFontSize,
@@ -83,6 +109,9 @@ use zed_actions::{
 const AGENT_PANEL_KEY: &str = "agent_panel";
 const RECENTLY_UPDATED_MENU_LIMIT: usize = 6;
 const DEFAULT_THREAD_TITLE: &str = "New Thread";
+const SERIALIZATION_DEBOUNCE: Duration = Duration::from_millis(500);
+const ISOLATED_WORKTREE_BRANCH_PREFIX: &str = "zed-agent-";
+const FORK_WITHOUT_CHECKPOINT: &str = "This message has no checkpoint, so the fork starts from the last commit instead of the project as it was when the message was sent.";
 
 #[derive(Serialize, Deserialize, Debug)]
 struct SerializedAgentPanel {
@@ -90,7 +119,79 @@ struct SerializedAgentPanel {
     selected_agent: Option<AgentType>,
 }
 
+/// The tabs of a workspace's agent panel, saved separately from the panel's shared settings.
+#[derive(Serialize, Deserialize, Debug)]
+struct SerializedAgentPanelTabs {
+    #[serde(default)]
+    tabs: Vec<SerializedAgentPanelTab>,
+    #[serde(default)]
+    active_tab: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SerializedAgentPanelTab {
+    agent: AgentType,
+    identity: AgentPanelTabIdentity,
+    #[serde(default)]
+    title: Option<SharedString>,
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+    /// Markdown transcript of the conversation, only kept for agents that can
+    /// neither load nor resume sessions.
+    #[serde(default)]
+    transcript: Option<String>,
+    #[serde(default)]
+    isolated_worktree: Option<IsolatedWorktree>,
+    /// Commands of the thread's agent that keep running in persistent terminal sessions, to
+    /// reattach to once the thread is restored.
+    #[serde(default)]
+    terminal_sessions: Vec<AgentTerminalSession>,
+}
+
+/// A git worktree created for a single agent thread, checked out on its own branch.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct IsolatedWorktree {
+    /// Working directory of the repository the worktree was created from.
+    repository_path: Arc<Path>,
+    path: Arc<Path>,
+    branch: String,
+}
+
+impl IsolatedWorktree {
+    /// Worktrees are created next to the repository, in `<repository>-worktrees/<branch>`.
+    fn new(repository_path: Arc<Path>) -> Option<Self> {
+        let repository_name = repository_path.file_name()?.to_string_lossy();
+        let worktrees_dir = repository_path
+            .parent()?
+            .join(format!("{repository_name}-worktrees"));
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let branch = format!("{ISOLATED_WORKTREE_BRANCH_PREFIX}{}", &id[..8]);
+        Some(Self {
+            path: worktrees_dir.join(&branch).into(),
+            repository_path,
+            branch,
+        })
+    }
+}
+
 pub fn init(cx: &mut App) {
+    // Sessions of agent terminals that no workspace's restored tabs reattach to have nothing to
+    // show them.
+    cx.background_spawn(async {
+        let session_ids = KEY_VALUE_STORE
+            .read_kvps_with_prefix(&format!("{AGENT_PANEL_KEY}-"))?
+            .into_iter()
+            .filter_map(|(_, tabs)| {
+                serde_json::from_str::<SerializedAgentPanelTabs>(&tabs).log_err()
+            })
+            .flat_map(|panel| panel.tabs)
+            .flat_map(|tab| tab.terminal_sessions)
+            .map(|session| session.session_id)
+            .collect();
+        terminal_session::end_unreferenced(terminal_session::SessionKind::Agent, &session_ids)
+    })
+    .detach_and_log_err(cx);
+
     cx.observe_new(
         |workspace: &mut Workspace, _window, _cx: &mut Context<Workspace>| {
             workspace
@@ -122,6 +223,18 @@ pub fn init(cx: &mut App) {
                         panel.update(cx, |panel, cx| panel.open_history(window, cx));
                     }
                 })
+                .register_action(|workspace, _: &OpenAgentDashboard, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        workspace.focus_panel::<AgentPanel>(window, cx);
+                        panel.update(cx, |panel, cx| panel.open_dashboard(window, cx));
+                    }
+                })
+                .register_action(|workspace, _: &OpenFanOutComparison, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        workspace.focus_panel::<AgentPanel>(window, cx);
+                        panel.update(cx, |panel, cx| panel.open_fan_out_comparison(window, cx));
+                    }
+                })
                 .register_action(|workspace, _: &OpenSettings, window, cx| {
                     if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
                         workspace.focus_panel::<AgentPanel>(window, cx);
@@ -142,6 +255,14 @@ pub fn init(cx: &mut App) {
                         });
                     }
                 })
+                .register_action(|workspace, action: &NewIsolatedAgentThread, window, cx| {
+                    if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                        workspace.focus_panel::<AgentPanel>(window, cx);
+                        panel.update(cx, |panel, cx| {
+                            panel.new_isolated_agent_thread(action.agent.clone(), window, cx)
+                        });
+                    }
+                })
                 .register_action(|workspace, action: &OpenRulesLibrary, window, cx| {
                     if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
                         workspace.focus_panel::<AgentPanel>(window, cx);
@@ -168,6 +289,26 @@ pub fn init(cx: &mut App) {
                         AgentDiffPane::deploy_in_workspace(thread, workspace, window, cx);
                     }
                 })
+                .register_action(|workspace, _: &OpenCheckpointTimeline, window, cx| {
+                    let Some(panel) = workspace.panel::<AgentPanel>(cx) else {
+                        return;
+                    };
+                    let thread = panel
+                        .read(cx)
+                        .active_thread_view()
+                        .and_then(|thread_view| thread_view.read(cx).active_thread())
+                        .map(|active_thread| active_thread.read(cx).thread.clone());
+
+                    if let Some(thread) = thread {
+                        CheckpointTimeline::deploy_in_workspace(
+                            thread,
+                            panel.downgrade(),
+                            workspace,
+                            window,
+                            cx,
+                        );
+                    }
+                })
                 .register_action(|workspace, _: &ToggleNavigationMenu, window, cx| {
                     if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
                         workspace.focus_panel::<AgentPanel>(window, cx);
@@ -265,18 +406,38 @@ enum ActiveView {
         kind: HistoryKind,
     },
     Configuration,
+    Dashboard {
+        dashboard: Entity<AgentDashboard>,
+    },
+    Comparison {
+        comparison: Entity<AgentComparison>,
+    },
 }
 
+/// The index of a tab in [`AgentPanel::tabs`], which shifts as the tabs before it close.
 type TabId = usize;
 
+static NEXT_STABLE_TAB_ID: AtomicUsize = AtomicUsize::new(1);
+
 struct AgentPanelTab {
+    /// Identifies the tab for as long as it's open, unlike its index. `zed agent` refers to tabs
+    /// by it.
+    stable_id: usize,
     view: ActiveView,
     agent: AgentType,
+    isolated_worktree: Option<IsolatedWorktree>,
+    _observe_view: Option<Subscription>,
 }
 
 impl AgentPanelTab {
     fn new(view: ActiveView, agent: AgentType) -> Self {
-        Self { view, agent }
+        Self {
+            stable_id: NEXT_STABLE_TAB_ID.fetch_add(1, atomic::Ordering::Relaxed),
+            view,
+            agent,
+            isolated_worktree: None,
+            _observe_view: None,
+        }
     }
 
     fn view(&self) -> &ActiveView {
@@ -293,7 +454,7 @@ struct TabLabelRender {
     tooltip: Option<SharedString>,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 enum AgentPanelTabIdentity {
     AcpThread(acp::SessionId),
     TextThread(Arc<Path>),
@@ -322,7 +483,7 @@ pub enum AgentType {
 }
 
 impl AgentType {
-    fn label(&self) -> SharedString {
+    pub(crate) fn label(&self) -> SharedString {
         match self {
             Self::NativeAgent | Self::TextThread => "Zed Agent".into(),
             Self::Gemini => "Gemini CLI".into(),
@@ -332,7 +493,7 @@ impl AgentType {
         }
     }
 
-    fn icon(&self) -> Option<IconName> {
+    pub(crate) fn icon(&self) -> Option<IconName> {
         match self {
             Self::NativeAgent | Self::TextThread => None,
             Self::Gemini => Some(IconName::AiGemini),
@@ -341,6 +502,17 @@ impl AgentType {
             Self::Custom { .. } => Some(IconName::Sparkle),
         }
     }
+
+    fn external_agent(&self) -> Option<ExternalAgent> {
+        match self {
+            Self::NativeAgent => Some(ExternalAgent::NativeAgent),
+            Self::Gemini => Some(ExternalAgent::Gemini),
+            Self::ClaudeCode => Some(ExternalAgent::ClaudeCode),
+            Self::Codex => Some(ExternalAgent::Codex),
+            Self::Custom { name } => Some(ExternalAgent::Custom { name: name.clone() }),
+            Self::TextThread => None,
+        }
+    }
 }
 
 impl From<ExternalAgent> for AgentType {
@@ -360,7 +532,9 @@ impl ActiveView {
         match self {
             ActiveView::Uninitialized
             | ActiveView::AgentThread { .. }
-            | ActiveView::History { .. } => WhichFontSize::AgentFont,
+            | ActiveView::History { .. }
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => WhichFontSize::AgentFont,
             ActiveView::TextThread { .. } => WhichFontSize::BufferFont,
             ActiveView::Configuration => WhichFontSize::None,
         }
@@ -472,15 +646,29 @@ pub struct AgentPanel {
     overlay_view: Option<ActiveView>,
     overlay_previous_tab_id: Option<TabId>,
     new_thread_menu_handle: PopoverMenuHandle<ContextMenu>,
+    /// Changes to the configured session budget, for the next thread.
+    session_budget_override: SessionBudgetOverride,
+    /// The budget of the next thread, replacing the one configured for its agent.
+    next_session_budget: Option<SessionBudget>,
+    /// Whether the next tab opens without becoming the active one.
+    open_next_tab_in_background: bool,
     agent_panel_menu_handle: PopoverMenuHandle<ContextMenu>,
     agent_navigation_menu_handle: PopoverMenuHandle<ContextMenu>,
     agent_navigation_menu: Option<Entity<ContextMenu>>,
     panel_focus_handle: FocusHandle,
     _extension_subscription: Option<Subscription>,
+    _file_registry_subscription: Subscription,
+    agent_jobs: Entity<AgentJobRunner>,
+    _agent_jobs_subscription: Subscription,
+    /// The MCP server through which agents see each other's sessions, once it's started.
+    agent_sessions_server: Option<McpServer>,
+    agent_sessions_server_enabled: bool,
     width: Option<Pixels>,
     height: Option<Pixels>,
     zoomed: bool,
     pending_serialization: Option<Task<Result<()>>>,
+    debounced_serialization: Option<Task<()>>,
+    pending_restoration: Option<Task<Result<()>>>,
     onboarding: Entity<AgentPanelOnboarding>,
     selected_agent: AgentType,
     show_trust_workspace_message: bool,
@@ -490,12 +678,24 @@ pub struct AgentPanel {
     active_tab_id: TabId,
     tab_bar_scroll_handle: ScrollHandle,
     title_edit_overlay_tab_id: Option<TabId>,
+    fan_out_comparison: Option<Entity<AgentComparison>>,
 }
 
 impl AgentPanel {
     fn serialize(&mut self, cx: &mut Context<Self>) {
+        // Don't overwrite the saved tabs before they've been restored.
+        if self.pending_restoration.is_some() {
+            return;
+        }
+
         let width = self.width;
         let selected_agent = self.selected_agent.clone();
+        let tabs_serialization_key = self
+            .workspace
+            .read_with(cx, |workspace, _| Self::tabs_serialization_key(workspace))
+            .ok()
+            .flatten();
+        let (tabs, active_tab) = self.serialized_tabs(cx);
         self.pending_serialization = Some(cx.background_spawn(async move {
             KEY_VALUE_STORE
                 .write_kvp(
@@ -506,10 +706,335 @@ impl AgentPanel {
                     })?,
                 )
                 .await?;
+            if let Some(tabs_serialization_key) = tabs_serialization_key {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        tabs_serialization_key,
+                        serde_json::to_string(&SerializedAgentPanelTabs { tabs, active_tab })?,
+                    )
+                    .await?;
+            }
             anyhow::Ok(())
         }));
     }
 
+    /// Each workspace restores its own tabs, while the width and selected agent are shared.
+    fn tabs_serialization_key(workspace: &Workspace) -> Option<String> {
+        workspace
+            .database_id()
+            .map(|id| format!("{AGENT_PANEL_KEY}-{}", i64::from(id)))
+    }
+
+    fn serialize_debounced(&mut self, cx: &mut Context<Self>) {
+        self.debounced_serialization = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SERIALIZATION_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.serialize(cx)).ok();
+        }));
+    }
+
+    fn serialized_tabs(&self, cx: &App) -> (Vec<SerializedAgentPanelTab>, Option<usize>) {
+        let mut tabs = Vec::with_capacity(self.tabs.len());
+        let mut active_tab = None;
+        for (index, tab) in self.tabs.iter().enumerate() {
+            let Some(serialized_tab) = Self::serialize_tab(tab, cx) else {
+                continue;
+            };
+            if index == self.active_tab_id {
+                active_tab = Some(tabs.len());
+            }
+            tabs.push(serialized_tab);
+        }
+        (tabs, active_tab)
+    }
+
+    fn serialize_tab(tab: &AgentPanelTab, cx: &App) -> Option<SerializedAgentPanelTab> {
+        let identity = Self::view_identity(tab.view(), cx)?;
+        let mut serialized_tab = SerializedAgentPanelTab {
+            agent: tab.agent().clone(),
+            identity,
+            title: None,
+            cwd: None,
+            transcript: None,
+            isolated_worktree: tab.isolated_worktree.clone(),
+            terminal_sessions: Vec::new(),
+        };
+
+        if let ActiveView::AgentThread { thread_view } = tab.view() {
+            let thread_view = thread_view.read(cx);
+            if let Some(active_thread) = thread_view.active_thread() {
+                let active_thread = active_thread.read(cx);
+                let thread = active_thread.thread.read(cx);
+                let connection = thread.connection();
+
+                serialized_tab.title = Some(thread.title());
+                serialized_tab.terminal_sessions = thread.running_terminal_sessions(cx);
+                serialized_tab.cwd = active_thread
+                    .resume_thread_metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.cwd.clone());
+                if !connection.supports_load_session(cx) && !connection.supports_resume_session(cx)
+                {
+                    serialized_tab.transcript = Some(thread.to_markdown(cx));
+                }
+            } else if let Some(session) = thread_view.pending_restored_session() {
+                // Keep what was restored while the thread loads, or when it can't be loaded,
+                // so that it's still there after the next restart.
+                serialized_tab.title = session.title.clone();
+                serialized_tab.cwd = session.cwd.clone();
+                serialized_tab.transcript = thread_view.restored_transcript().map(Into::into);
+                serialized_tab.terminal_sessions = thread_view.restored_terminals().to_vec();
+            }
+        }
+
+        Some(serialized_tab)
+    }
+
+    /// Builds the submenu of the new thread menu that changes the limits of the next thread's
+    /// session budget.
+    fn session_budget_submenu(
+        budget_override: SessionBudgetOverride,
+        workspace: WeakEntity<Workspace>,
+    ) -> impl Fn(ContextMenu, &mut Window, &mut Context<ContextMenu>) -> ContextMenu + 'static {
+        move |mut menu, _window, _cx| {
+            let set_override = {
+                let workspace = workspace.clone();
+                move |budget_override: SessionBudgetOverride, cx: &mut App| {
+                    if let Some(panel) = workspace
+                        .upgrade()
+                        .and_then(|workspace| workspace.read(cx).panel::<AgentPanel>(cx))
+                    {
+                        panel.update(cx, |panel, cx| {
+                            panel.session_budget_override = budget_override;
+                            cx.notify();
+                        });
+                    }
+                }
+            };
+
+            menu = menu
+                .toggleable_entry(
+                    "Configured Limits",
+                    budget_override.is_empty(),
+                    IconPosition::Start,
+                    None,
+                    {
+                        let set_override = set_override.clone();
+                        move |_window, cx| set_override(SessionBudgetOverride::default(), cx)
+                    },
+                )
+                .toggleable_entry(
+                    "No Limits",
+                    budget_override == SessionBudgetOverride::unlimited(),
+                    IconPosition::Start,
+                    None,
+                    {
+                        let set_override = set_override.clone();
+                        move |_window, cx| set_override(SessionBudgetOverride::unlimited(), cx)
+                    },
+                );
+
+            for kind in SessionBudgetKind::ALL {
+                menu = menu.separator().header(kind.label());
+                let choices = [(None, "Configured"), (Some(None), "No Limit")]
+                    .into_iter()
+                    .chain(
+                        kind.presets()
+                            .iter()
+                            .map(|(limit, label)| (Some(Some(*limit)), *label)),
+                    );
+                for (limit, label) in choices {
+                    let set_override = set_override.clone();
+                    menu = menu.toggleable_entry(
+                        label,
+                        budget_override.get(kind) == limit,
+                        IconPosition::Start,
+                        None,
+                        move |_window, cx| {
+                            let mut budget_override = budget_override;
+                            budget_override.set(kind, limit);
+                            set_override(budget_override, cx);
+                        },
+                    );
+                }
+            }
+            menu
+        }
+    }
+
+    fn restore_tabs(
+        &mut self,
+        serialized_tabs: Vec<SerializedAgentPanelTab>,
+        active_tab: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if serialized_tabs.is_empty() {
+            return;
+        }
+
+        self.pending_restoration = Some(cx.spawn_in(window, async move |this, cx| {
+            let mut restored_tabs = Vec::with_capacity(serialized_tabs.len());
+            for serialized_tab in serialized_tabs {
+                match serialized_tab.identity {
+                    AgentPanelTabIdentity::TextThread(path) => {
+                        let open_task = this.update(cx, |this, cx| {
+                            this.text_thread_store
+                                .update(cx, |store, cx| store.open_local(path, cx))
+                        })?;
+                        let Some(text_thread) = open_task.await.log_err() else {
+                            continue;
+                        };
+                        let tab = this.update_in(cx, |this, window, cx| {
+                            AgentPanelTab::new(
+                                this.text_thread_view(text_thread, window, cx),
+                                AgentType::TextThread,
+                            )
+                        })?;
+                        restored_tabs.push(tab);
+                    }
+                    AgentPanelTabIdentity::AcpThread(session_id) => {
+                        let Some(ext_agent) = serialized_tab.agent.external_agent() else {
+                            continue;
+                        };
+                        let resume_thread = AgentSessionInfo {
+                            session_id,
+                            cwd: serialized_tab.cwd,
+                            title: serialized_tab.title,
+                            updated_at: None,
+                            meta: None,
+                        };
+                        let project = match &serialized_tab.isolated_worktree {
+                            Some(isolated_worktree) => {
+                                let project = this.update(cx, |this, cx| {
+                                    this.isolated_agent_project(&ext_agent, isolated_worktree, cx)
+                                })?;
+                                let Some(project) = project.await.log_err() else {
+                                    continue;
+                                };
+                                project
+                            }
+                            None => this.read_with(cx, |this, _| this.project.clone())?,
+                        };
+                        let tab = this.update_in(cx, |this, window, cx| {
+                            let thread_view = this.restored_thread_view(
+                                ext_agent,
+                                project,
+                                resume_thread,
+                                serialized_tab.transcript,
+                                serialized_tab.terminal_sessions,
+                                serialized_tab
+                                    .isolated_worktree
+                                    .as_ref()
+                                    .map(|worktree| worktree.path.clone()),
+                                window,
+                                cx,
+                            );
+                            let mut tab = this.tab_for_view(
+                                ActiveView::AgentThread { thread_view },
+                                serialized_tab.agent,
+                                cx,
+                            );
+                            tab.isolated_worktree = serialized_tab.isolated_worktree;
+                            tab
+                        })?;
+                        restored_tabs.push(tab);
+                    }
+                }
+            }
+
+            this.update_in(cx, |this, window, cx| {
+                this.pending_restoration = None;
+                if restored_tabs.is_empty() {
+                    let is_visible = this
+                        .workspace
+                        .upgrade()
+                        .is_some_and(|workspace| !Self::is_hidden(&workspace, cx));
+                    if is_visible && matches!(this.active_view(), ActiveView::Uninitialized) {
+                        let selected_agent = this.selected_agent.clone();
+                        this.new_agent_thread(selected_agent, window, cx);
+                    }
+                    return;
+                }
+
+                // Threads opened while we were restoring stay after the restored ones.
+                let opened_tabs = std::mem::take(&mut this.tabs)
+                    .into_iter()
+                    .filter(|tab| !matches!(tab.view(), ActiveView::Uninitialized));
+                let active_tab_id = active_tab.unwrap_or_default().min(restored_tabs.len() - 1);
+                this.tabs = restored_tabs;
+                this.tabs.extend(opened_tabs);
+                this.active_tab_id = active_tab_id;
+                this.selected_agent = this.tabs[active_tab_id].agent().clone();
+                this.tab_bar_scroll_handle.scroll_to_item(active_tab_id);
+                if this.overlay_view.is_none() && this.focus_handle.is_focused(window) {
+                    this.focus_active_panel_thread(window, cx);
+                }
+                this.serialize(cx);
+                cx.notify();
+            })
+        }));
+    }
+
+    fn restored_thread_view(
+        &self,
+        ext_agent: ExternalAgent,
+        project: Entity<Project>,
+        resume_thread: AgentSessionInfo,
+        transcript: Option<String>,
+        terminal_sessions: Vec<AgentTerminalSession>,
+        root_dir: Option<Arc<Path>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<AcpServerView> {
+        let server = ext_agent.server(self.fs.clone(), self.thread_store.clone());
+        let thread_store =
+            matches!(ext_agent, ExternalAgent::NativeAgent).then(|| self.thread_store.clone());
+        let workspace = self.workspace.clone();
+        let prompt_store = self.prompt_store.clone();
+        let history = self.acp_history.clone();
+
+        cx.new(|cx| {
+            let thread_view = AcpServerView::new(
+                server,
+                Some(resume_thread),
+                None,
+                root_dir,
+                workspace,
+                project,
+                thread_store,
+                prompt_store,
+                history,
+                window,
+                cx,
+            );
+            let thread_view = thread_view.with_restored_terminals(terminal_sessions);
+            match transcript {
+                Some(transcript) => thread_view.with_restored_transcript(transcript),
+                None => thread_view,
+            }
+        })
+    }
+
+    fn tab_for_view(
+        &self,
+        view: ActiveView,
+        agent: AgentType,
+        cx: &mut Context<Self>,
+    ) -> AgentPanelTab {
+        let observe_view = match &view {
+            ActiveView::AgentThread { thread_view } => {
+                Some(cx.observe(thread_view, |this, _, cx| this.serialize_debounced(cx)))
+            }
+            _ => None,
+        };
+        AgentPanelTab {
+            view,
+            agent,
+            isolated_worktree: None,
+            _observe_view: observe_view,
+        }
+    }
+
     pub fn load(
         workspace: WeakEntity<Workspace>,
         prompt_builder: Arc<PromptBuilder>,
@@ -531,6 +1056,20 @@ impl AgentPanel {
             } else {
                 None
             };
+            let serialized_tabs = if let Some(tabs_serialization_key) =
+                workspace.read_with(cx, |workspace, _| Self::tabs_serialization_key(workspace))?
+                && let Some(tabs) = cx
+                    .background_spawn(
+                        async move { KEY_VALUE_STORE.read_kvp(&tabs_serialization_key) },
+                    )
+                    .await
+                    .log_err()
+                    .flatten()
+            {
+                serde_json::from_str::<SerializedAgentPanelTabs>(&tabs).log_err()
+            } else {
+                None
+            };
 
             let slash_commands = Arc::new(SlashCommandWorkingSet::default());
             let text_thread_store = workspace
@@ -558,6 +1097,16 @@ impl AgentPanel {
                         cx.notify();
                     });
                 }
+                if let Some(serialized_tabs) = serialized_tabs {
+                    panel.update(cx, |panel, cx| {
+                        panel.restore_tabs(
+                            serialized_tabs.tabs,
+                            serialized_tabs.active_tab,
+                            window,
+                            cx,
+                        );
+                    });
+                }
                 panel
             })?;
 
@@ -593,6 +1142,9 @@ impl AgentPanel {
                 ThreadHistoryEvent::Open(thread) => {
                     this.load_agent_thread(thread.clone(), window, cx);
                 }
+                ThreadHistoryEvent::OpenAtMessage { thread, turn } => {
+                    this.load_agent_thread_at_message(thread.clone(), *turn, window, cx);
+                }
             },
         )
         .detach();
@@ -690,6 +1242,37 @@ impl AgentPanel {
             cx.notify();
         })
         .detach();
+        cx.on_app_quit(|this, cx| {
+            this.serialize(cx);
+            let pending_serialization = this.pending_serialization.take();
+            async move {
+                if let Some(pending_serialization) = pending_serialization {
+                    pending_serialization.await.log_err();
+                }
+            }
+        })
+        .detach();
+
+        let file_registry = AgentFileRegistry::for_project(&project, cx);
+        let file_registry_subscription = cx.observe(&file_registry, |_, _, cx| cx.notify());
+
+        let project_id = project.entity_id();
+        cx.observe_global_in::<SettingsStore>(window, |this, window, cx| {
+            this.sync_agent_sessions_server(window, cx)
+        })
+        .detach();
+        cx.on_release(move |_, cx| agent_servers::unregister_agent_sessions_server(project_id, cx))
+            .detach();
+
+        let agent_jobs = cx.new(|cx| AgentJobRunner::new(project.clone(), cx));
+        let agent_jobs_subscription =
+            cx.subscribe_in(&agent_jobs, window, |this, agent_jobs, run, window, cx| {
+                if let Some(thread_view) = this.run_agent_job(run, window, cx) {
+                    agent_jobs.update(cx, |agent_jobs, _| {
+                        agent_jobs.run_started(run.job.name.clone(), thread_view.downgrade())
+                    });
+                }
+            });
 
         let mut panel = Self {
             overlay_view: None,
@@ -706,15 +1289,25 @@ impl AgentPanel {
             context_server_registry,
             overlay_previous_tab_id: None,
             new_thread_menu_handle: PopoverMenuHandle::default(),
+            session_budget_override: SessionBudgetOverride::default(),
+            next_session_budget: None,
+            open_next_tab_in_background: false,
             agent_panel_menu_handle: PopoverMenuHandle::default(),
             agent_navigation_menu_handle: PopoverMenuHandle::default(),
             agent_navigation_menu: None,
             panel_focus_handle,
             _extension_subscription: extension_subscription,
+            _file_registry_subscription: file_registry_subscription,
+            agent_jobs,
+            _agent_jobs_subscription: agent_jobs_subscription,
+            agent_sessions_server: None,
+            agent_sessions_server_enabled: false,
             width: None,
             height: None,
             zoomed: false,
             pending_serialization: None,
+            debounced_serialization: None,
+            pending_restoration: None,
             onboarding,
             acp_history,
             text_thread_history,
@@ -727,13 +1320,46 @@ impl AgentPanel {
             active_tab_id: 0,
             tab_bar_scroll_handle: ScrollHandle::new(),
             title_edit_overlay_tab_id: None,
+            fan_out_comparison: None,
         };
 
         // Initial sync of agent servers from extensions
         panel.sync_agent_servers_from_extensions(cx);
+        panel.sync_agent_sessions_server(window, cx);
         panel
     }
 
+    /// Starts or stops the agent sessions server, as the settings say.
+    fn sync_agent_sessions_server(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let enabled = AgentSettings::get_global(cx).enable_agent_sessions_server
+            && self.project.read(cx).is_local();
+        if enabled == self.agent_sessions_server_enabled {
+            return;
+        }
+        self.agent_sessions_server_enabled = enabled;
+
+        let project_id = self.project.entity_id();
+        if !enabled {
+            self.agent_sessions_server = None;
+            agent_servers::unregister_agent_sessions_server(project_id, cx);
+            return;
+        }
+
+        let server = start_agent_sessions_server(&self.project, window.window_handle(), cx);
+        cx.spawn(async move |this, cx| {
+            let server = server.await?;
+            this.update(cx, |this, cx| {
+                // The server was turned off while it started.
+                if this.agent_sessions_server_enabled {
+                    this.agent_sessions_server = Some(server);
+                } else {
+                    agent_servers::unregister_agent_sessions_server(project_id, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn toggle_focus(
         workspace: &mut Workspace,
         _: &ToggleFocus,
@@ -816,15 +1442,21 @@ impl AgentPanel {
             ActiveView::Uninitialized
             | ActiveView::TextThread { .. }
             | ActiveView::History { .. }
-            | ActiveView::Configuration => None,
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => None,
         }
     }
 
     fn view_identity(view: &ActiveView, cx: &App) -> Option<AgentPanelTabIdentity> {
         match view {
             ActiveView::AgentThread { thread_view, .. } => {
-                let active = thread_view.read(cx).active_thread()?;
-                Some(AgentPanelTabIdentity::AcpThread(active.read(cx).id.clone()))
+                let thread_view = thread_view.read(cx);
+                let session_id = match thread_view.active_thread() {
+                    Some(active) => active.read(cx).id.clone(),
+                    None => thread_view.pending_restored_session()?.session_id.clone(),
+                };
+                Some(AgentPanelTabIdentity::AcpThread(session_id))
             }
             ActiveView::TextThread {
                 text_thread_editor, ..
@@ -836,9 +1468,11 @@ impl AgentPanel {
                     .cloned()
                     .map(AgentPanelTabIdentity::TextThread)
             }
-            ActiveView::Uninitialized | ActiveView::History { .. } | ActiveView::Configuration => {
-                None
-            }
+            ActiveView::Uninitialized
+            | ActiveView::History { .. }
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => None,
         }
     }
 
@@ -988,6 +1622,7 @@ impl AgentPanel {
                     server,
                     resume_thread,
                     initial_content,
+                    None,
                     workspace,
                     project,
                     ext_agent,
@@ -1075,55 +1710,414 @@ impl AgentPanel {
         cx.notify();
     }
 
-    pub(crate) fn open_saved_text_thread(
+    fn open_dashboard(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let ActiveView::Dashboard { .. } = self.active_view() {
+            if let Some(previous_tab_id) = self.overlay_previous_tab_id.take() {
+                self.set_active_tab_by_id(previous_tab_id, window, cx);
+            }
+            return;
+        }
+
+        let panel = cx.entity().downgrade();
+        let dashboard = cx.new(|cx| AgentDashboard::new(panel, cx));
+        self.set_overlay_view(ActiveView::Dashboard { dashboard }, window, cx);
+        cx.notify();
+    }
+
+    pub(crate) fn dashboard_sessions(&self, cx: &App) -> Vec<DashboardSession> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .filter_map(|(tab_id, tab)| match tab.view() {
+                ActiveView::AgentThread { thread_view } => Some(DashboardSession::new(
+                    tab_id,
+                    tab.agent().clone(),
+                    thread_view.clone(),
+                    cx,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The threads of every agent tab.
+    pub(crate) fn agent_threads(&self, cx: &App) -> Vec<Entity<AcpThread>> {
+        self.tabs
+            .iter()
+            .filter_map(|tab| match tab.view() {
+                ActiveView::AgentThread { thread_view } => {
+                    let active_thread = thread_view.read(cx).active_thread()?;
+                    Some(active_thread.read(cx).thread.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(crate) fn activate_tab(&mut self, id: TabId, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_active_tab_by_id(id, window, cx);
+        self.focus_active_panel_thread(window, cx);
+    }
+
+    /// Opens a new tab for each agent in an isolated worktree of its own, sends it the same
+    /// prompt, and shows the comparison of their results.
+    pub(crate) fn fan_out_prompt(
         &mut self,
-        path: Arc<Path>,
+        agents: Vec<ExternalAgent>,
+        content: Vec<acp::ContentBlock>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Task<Result<()>> {
-        let text_thread_task = self
-            .text_thread_store
-            .update(cx, |store, cx| store.open_local(path, cx));
-        cx.spawn_in(window, async move |this, cx| {
-            let text_thread = text_thread_task.await?;
-            this.update_in(cx, |this, window, cx| {
-                this.open_text_thread(text_thread, window, cx);
+    ) {
+        let servers = agents
+            .into_iter()
+            .map(|ext_agent| {
+                let server = ext_agent.server(self.fs.clone(), self.thread_store.clone());
+                (ext_agent, server)
             })
-        })
+            .collect();
+        self.fan_out_prompt_to_servers(servers, content, window, cx);
     }
 
-    pub(crate) fn open_text_thread(
+    fn fan_out_prompt_to_servers(
         &mut self,
-        text_thread: Entity<TextThread>,
+        servers: Vec<(ExternalAgent, Rc<dyn AgentServer>)>,
+        content: Vec<acp::ContentBlock>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let lsp_adapter_delegate = make_lsp_adapter_delegate(&self.project.clone(), cx)
-            .log_err()
-            .flatten();
-        let editor = cx.new(|cx| {
-            TextThreadEditor::for_text_thread(
-                text_thread,
-                self.fs.clone(),
-                self.workspace.clone(),
-                self.project.clone(),
-                lsp_adapter_delegate,
-                window,
+        if servers.is_empty() {
+            return;
+        }
+        let Some(repository) = self.project.read(cx).active_repository(cx) else {
+            self.show_isolated_worktree_toast(
+                "Comparing agents requires a git repository, so that each agent works in an isolated worktree.",
                 cx,
-            )
-        });
-
-        if self.selected_agent != AgentType::TextThread {
-            self.selected_agent = AgentType::TextThread;
-            self.serialize(cx);
+            );
+            return;
+        };
+        let repository_snapshot = repository.read(cx);
+        let work_directory = repository_snapshot.work_directory_abs_path.clone();
+        let Some(head_sha) = repository_snapshot
+            .head_commit
+            .as_ref()
+            .map(|commit| commit.sha.to_string())
+        else {
+            self.show_isolated_worktree_toast(
+                "Comparing agents requires a repository with at least one commit.",
+                cx,
+            );
+            return;
+        };
+
+        let mut candidates = Vec::with_capacity(servers.len());
+        for (ext_agent, server) in servers {
+            let Some(isolated_worktree) = IsolatedWorktree::new(work_directory.clone()) else {
+                self.show_isolated_worktree_toast(
+                    "Can't create a worktree for this repository.",
+                    cx,
+                );
+                return;
+            };
+            let create_worktree = self.create_isolated_worktree(
+                &repository,
+                &isolated_worktree,
+                head_sha.clone(),
+                cx,
+            );
+            candidates.push((ext_agent, server, isolated_worktree, create_worktree));
         }
 
-        self.push_tab(
-            ActiveView::text_thread(editor, self.language_registry.clone(), window, cx),
-            AgentType::TextThread,
+        cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let mut isolated_candidates = Vec::with_capacity(candidates.len());
+                for (ext_agent, server, isolated_worktree, create_worktree) in candidates {
+                    create_worktree.await?;
+                    let project = this
+                        .update(cx, |this, cx| {
+                            this.isolated_agent_project(&ext_agent, &isolated_worktree, cx)
+                        })?
+                        .await?;
+                    isolated_candidates.push((ext_agent, server, isolated_worktree, project));
+                }
+                anyhow::Ok(isolated_candidates)
+            }
+            .await;
+
+            this.update_in(cx, |this, window, cx| match result {
+                Ok(isolated_candidates) => {
+                    let candidates = isolated_candidates
+                        .into_iter()
+                        .map(|(ext_agent, server, isolated_worktree, project)| {
+                            let agent = AgentType::from(ext_agent.clone());
+                            let thread_view = this._external_thread(
+                                server,
+                                None,
+                                Some(ExternalAgentInitialContent::Prompt(content.clone())),
+                                Some(isolated_worktree),
+                                this.workspace.clone(),
+                                project,
+                                ext_agent,
+                                window,
+                                cx,
+                            );
+                            (agent, thread_view)
+                        })
+                        .collect();
+
+                    let panel = cx.entity().downgrade();
+                    let workspace = this.workspace.clone();
+                    this.fan_out_comparison =
+                        Some(cx.new(|cx| AgentComparison::new(panel, workspace, candidates, cx)));
+                    this.open_fan_out_comparison(window, cx);
+                }
+                Err(error) => this.show_isolated_worktree_toast(
+                    format!("Failed to create isolated worktrees for the agents: {error}"),
+                    cx,
+                ),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Merges the worktree of the accepted fan-out candidate back, then closes every
+    /// candidate's tab and discards its worktree.
+    pub(crate) fn accept_fan_out_candidate(
+        &mut self,
+        accepted: &Entity<AcpServerView>,
+        candidates: Vec<Entity<AcpServerView>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let isolated_worktree = |thread_view: &Entity<AcpServerView>| {
+            let id = self.tab_id_for_thread_view(thread_view)?;
+            self.tabs[id].isolated_worktree.clone()
+        };
+        let Some(accepted_worktree) = isolated_worktree(accepted) else {
+            return Task::ready(Err(anyhow!("The accepted agent's tab was closed")));
+        };
+        let candidates = candidates
+            .into_iter()
+            .filter_map(|thread_view| Some((isolated_worktree(&thread_view)?, thread_view)))
+            .collect::<Vec<_>>();
+        let merge = self.merge_isolated_worktree(&accepted_worktree, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            merge.await?;
+            for (isolated_worktree, thread_view) in candidates {
+                this.update_in(cx, |this, window, cx| {
+                    this.discard_isolated_worktree(isolated_worktree, &thread_view, window, cx)
+                })?
+                .await?;
+            }
+            anyhow::Ok(())
+        })
+    }
+
+    /// Opens a background tab that sends the job's prompt to its agent, within the job's budget.
+    fn run_agent_job(
+        &mut self,
+        run: &AgentJobRun,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<AcpServerView>> {
+        let Some(ext_agent) = self
+            .agents_by_name(cx)
+            .into_iter()
+            .find(|(name, _)| name.as_ref() == run.job.agent.as_ref())
+            .map(|(_, agent)| agent)
+        else {
+            log::error!(
+                "agent job {:?} uses unknown agent {:?}",
+                run.job.name,
+                run.job.agent
+            );
+            return None;
+        };
+
+        let server = ext_agent.server(self.fs.clone(), self.thread_store.clone());
+        let prompt = vec![acp::ContentBlock::Text(acp::TextContent::new(
+            run.prompt.clone(),
+        ))];
+        self.next_session_budget = Some(run.job.budget);
+        self.open_next_tab_in_background = true;
+        let thread_view = self._external_thread(
+            server,
+            None,
+            Some(ExternalAgentInitialContent::Prompt(prompt)),
+            None,
+            self.workspace.clone(),
+            self.project.clone(),
+            ext_agent,
             window,
             cx,
         );
+        Some(thread_view)
+    }
+
+    /// The agent tabs, as listed by `zed agent list`.
+    pub fn agent_tab_summaries(&self, cx: &App) -> Vec<AgentTabSummary> {
+        self.dashboard_sessions(cx)
+            .iter()
+            .map(|session| AgentTabSummary::new(self.tabs[session.tab_id].stable_id, session, cx))
+            .collect()
+    }
+
+    /// Returns the stable id of the tab and the thread of the agent session with the given id.
+    pub(crate) fn agent_thread_for_session(
+        &self,
+        session_id: &str,
+        cx: &App,
+    ) -> Option<(usize, Entity<AcpThread>)> {
+        self.tabs.iter().find_map(|tab| {
+            let ActiveView::AgentThread { thread_view } = tab.view() else {
+                return None;
+            };
+            let thread = thread_view
+                .read(cx)
+                .active_thread()?
+                .read(cx)
+                .thread
+                .clone();
+            (thread.read(cx).session_id().0.as_ref() == session_id)
+                .then_some((tab.stable_id, thread))
+        })
+    }
+
+    /// Returns the thread view of the agent tab with the given stable id.
+    pub fn agent_tab(&self, stable_id: usize) -> Result<Entity<AcpServerView>> {
+        let tab = self.tabs.iter().find(|tab| tab.stable_id == stable_id);
+        match tab.map(|tab| tab.view()) {
+            Some(ActiveView::AgentThread { thread_view }) => Ok(thread_view.clone()),
+            _ => Err(anyhow!("there is no agent tab {stable_id}")),
+        }
+    }
+
+    /// Opens a tab that sends the prompt to the agent with the given name, or to the selected
+    /// agent, and returns the tab's stable id.
+    pub fn new_agent_tab_with_prompt(
+        &mut self,
+        agent_name: Option<&str>,
+        prompt: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<usize> {
+        let ext_agent = match agent_name {
+            Some(agent_name) => self
+                .agents_by_name(cx)
+                .into_iter()
+                .find(|(display_name, agent)| {
+                    agent_cli::cli_name_matches(agent_name, display_name, agent)
+                })
+                .map(|(_, agent)| agent)
+                .with_context(|| format!("unknown agent {agent_name:?}"))?,
+            None => self
+                .selected_external_agent()
+                .unwrap_or(ExternalAgent::NativeAgent),
+        };
+
+        let server = ext_agent.server(self.fs.clone(), self.thread_store.clone());
+        let prompt = vec![acp::ContentBlock::Text(acp::TextContent::new(prompt))];
+        let thread_view = self._external_thread(
+            server,
+            None,
+            Some(ExternalAgentInitialContent::Prompt(prompt)),
+            None,
+            self.workspace.clone(),
+            self.project.clone(),
+            ext_agent,
+            window,
+            cx,
+        );
+        self.tab_id_for_thread_view(&thread_view)
+            .map(|tab_id| self.tabs[tab_id].stable_id)
+            .context("the agent's tab was closed")
+    }
+
+    /// Sends a message to the agent in the tab with the given stable id, queueing it while the
+    /// agent is busy. The user's draft in the tab is left alone.
+    pub fn send_to_agent_tab(
+        &mut self,
+        stable_id: usize,
+        message: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let thread_view = self.agent_tab(stable_id)?;
+        let active_thread = thread_view
+            .read(cx)
+            .active_thread()
+            .cloned()
+            .with_context(|| format!("the agent in tab {stable_id} hasn't started yet"))?;
+        active_thread.update(cx, |active_thread, cx| {
+            active_thread.send_message(message, window, cx)
+        })
+    }
+
+    fn open_fan_out_comparison(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(comparison) = self.fan_out_comparison.clone() else {
+            return;
+        };
+        if !matches!(self.active_view(), ActiveView::Comparison { .. }) {
+            self.set_overlay_view(ActiveView::Comparison { comparison }, window, cx);
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn open_saved_text_thread(
+        &mut self,
+        path: Arc<Path>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let text_thread_task = self
+            .text_thread_store
+            .update(cx, |store, cx| store.open_local(path, cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let text_thread = text_thread_task.await?;
+            this.update_in(cx, |this, window, cx| {
+                this.open_text_thread(text_thread, window, cx);
+            })
+        })
+    }
+
+    pub(crate) fn open_text_thread(
+        &mut self,
+        text_thread: Entity<TextThread>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let view = self.text_thread_view(text_thread, window, cx);
+
+        if self.selected_agent != AgentType::TextThread {
+            self.selected_agent = AgentType::TextThread;
+            self.serialize(cx);
+        }
+
+        self.push_tab(view, AgentType::TextThread, window, cx);
+    }
+
+    fn text_thread_view(
+        &self,
+        text_thread: Entity<TextThread>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ActiveView {
+        let lsp_adapter_delegate = make_lsp_adapter_delegate(&self.project.clone(), cx)
+            .log_err()
+            .flatten();
+        let editor = cx.new(|cx| {
+            TextThreadEditor::for_text_thread(
+                text_thread,
+                self.fs.clone(),
+                self.workspace.clone(),
+                self.project.clone(),
+                lsp_adapter_delegate,
+                window,
+                cx,
+            )
+        });
+
+        ActiveView::text_thread(editor, self.language_registry.clone(), window, cx)
     }
 
     pub fn go_back(&mut self, _: &workspace::GoBack, window: &mut Window, cx: &mut Context<Self>) {
@@ -1133,7 +2127,10 @@ impl AgentPanel {
         }
 
         match self.active_view() {
-            ActiveView::Configuration | ActiveView::History { .. } => {
+            ActiveView::Configuration
+            | ActiveView::History { .. }
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => {
                 if let Some(previous_tab_id) = self.overlay_previous_tab_id.take() {
                     self.active_tab_id = previous_tab_id;
                     self.overlay_view = None;
@@ -1590,11 +2587,8 @@ impl AgentPanel {
         self.title_edit_overlay_tab_id = None;
         self.active_tab_id = new_id;
         self.tab_bar_scroll_handle.scroll_to_item(new_id);
-
-        if self.selected_agent != tab_agent {
-            self.selected_agent = tab_agent.clone();
-            self.serialize(cx);
-        }
+        self.selected_agent = tab_agent;
+        self.serialize(cx);
 
         if let Some(text_thread_editor) = text_thread_editor {
             if let Some(path) = text_thread_editor.read(cx).text_thread().read(cx).path() {
@@ -1621,6 +2615,7 @@ impl AgentPanel {
         cx: &mut Context<Self>,
     ) {
         let view_identity = Self::view_identity(&new_view, cx);
+        let open_in_background = std::mem::take(&mut self.open_next_tab_in_background);
 
         if let Some(identity) = view_identity.as_ref() {
             if let Some(existing_id) = self.find_tab_by_identity(identity, cx) {
@@ -1631,8 +2626,14 @@ impl AgentPanel {
 
         match &new_view {
             ActiveView::TextThread { .. } | ActiveView::AgentThread { .. } => {
-                self.tabs.push(AgentPanelTab::new(new_view, agent));
+                let tab = self.tab_for_view(new_view, agent, cx);
+                self.tabs.push(tab);
                 let new_id = self.tabs.len() - 1;
+                if open_in_background {
+                    self.serialize(cx);
+                    cx.notify();
+                    return;
+                }
                 self.set_active_tab_by_id(new_id, window, cx);
 
                 if let Some(pending_id) = self.pending_tab_removal.take() {
@@ -1643,7 +2644,10 @@ impl AgentPanel {
                     }
                 }
             }
-            ActiveView::History { .. } | ActiveView::Configuration => {
+            ActiveView::History { .. }
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => {
                 self.set_overlay_view(new_view, window, cx);
             }
             ActiveView::Uninitialized => {}
@@ -1677,12 +2681,27 @@ impl AgentPanel {
 
             if new_id == self.active_tab_id {
                 self.tab_bar_scroll_handle.scroll_to_item(new_id);
+                self.serialize(cx);
             } else {
                 self.set_active_tab_by_id(new_id, window, cx);
             }
         }
     }
 
+    /// Whether the tab's session is writing to a file that another session edited, or edited a
+    /// file that another session is writing to, while the user hasn't resolved the conflict.
+    fn has_file_conflicts(thread_view: &Entity<AcpServerView>, cx: &App) -> bool {
+        thread_view.read(cx).active_thread().is_some_and(|active| {
+            let thread = active.read(cx).thread.read(cx);
+            thread
+                .file_registry()
+                .read(cx)
+                .conflicts_for_session(thread.session_id())
+                .next()
+                .is_some()
+        })
+    }
+
     fn display_tab_label(
         title: impl Into<SharedString>,
         is_active: bool,
@@ -1709,7 +2728,11 @@ impl AgentPanel {
             } => {
                 text_thread_editor.focus_handle(cx).focus(window, cx);
             }
-            ActiveView::Uninitialized | ActiveView::History { .. } | ActiveView::Configuration => {}
+            ActiveView::Uninitialized
+            | ActiveView::History { .. }
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => {}
         }
         cx.notify();
     }
@@ -1740,7 +2763,9 @@ impl AgentPanel {
                 }
                 ActiveView::Uninitialized
                 | ActiveView::History { .. }
-                | ActiveView::Configuration => {}
+                | ActiveView::Configuration
+                | ActiveView::Dashboard { .. }
+                | ActiveView::Comparison { .. } => {}
             }
         }
         cx.notify();
@@ -1840,14 +2865,7 @@ impl AgentPanel {
     }
 
     fn selected_external_agent(&self) -> Option<ExternalAgent> {
-        match &self.selected_agent {
-            AgentType::NativeAgent => Some(ExternalAgent::NativeAgent),
-            AgentType::Gemini => Some(ExternalAgent::Gemini),
-            AgentType::ClaudeCode => Some(ExternalAgent::ClaudeCode),
-            AgentType::Codex => Some(ExternalAgent::Codex),
-            AgentType::Custom { name } => Some(ExternalAgent::Custom { name: name.clone() }),
-            AgentType::TextThread => None,
-        }
+        self.selected_agent.external_agent()
     }
 
     fn sync_agent_servers_from_extensions(&mut self, cx: &mut Context<Self>) {
@@ -1890,6 +2908,23 @@ impl AgentPanel {
         );
     }
 
+    /// Opens a new thread with the given agent and leaves the draft in its message editor.
+    pub(crate) fn new_external_thread_with_draft(
+        &mut self,
+        agent: ExternalAgent,
+        draft: Vec<acp::ContentBlock>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.external_thread(
+            Some(agent),
+            None,
+            Some(ExternalAgentInitialContent::Draft(draft)),
+            window,
+            cx,
+        );
+    }
+
     pub fn new_agent_thread(
         &mut self,
         agent: AgentType,
@@ -1948,49 +2983,612 @@ impl AgentPanel {
         self.external_thread(Some(agent), Some(thread), None, window, cx);
     }
 
-    fn _external_thread(
+    /// Loads a thread and scrolls to the given user message, counting from the start of the thread.
+    fn load_agent_thread_at_message(
+        &mut self,
+        thread: AgentSessionInfo,
+        turn: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(agent) = self.selected_external_agent() else {
+            return;
+        };
+        let server = agent.server(self.fs.clone(), self.thread_store.clone());
+        let thread_view = self._external_thread(
+            server,
+            Some(thread),
+            None,
+            None,
+            self.workspace.clone(),
+            self.project.clone(),
+            agent,
+            window,
+            cx,
+        );
+        thread_view.update(cx, |thread_view, _| {
+            thread_view.scroll_to_turn_when_loaded(turn);
+        });
+    }
+
+    fn _external_thread(
+        &mut self,
+        server: Rc<dyn AgentServer>,
+        resume_thread: Option<AgentSessionInfo>,
+        initial_content: Option<ExternalAgentInitialContent>,
+        isolated_worktree: Option<IsolatedWorktree>,
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        ext_agent: ExternalAgent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<AcpServerView> {
+        let selected_agent = AgentType::from(ext_agent);
+        if self.selected_agent != selected_agent && !self.open_next_tab_in_background {
+            self.selected_agent = selected_agent.clone();
+            self.serialize(cx);
+        }
+        let thread_store = server
+            .clone()
+            .downcast::<agent::NativeAgentServer>()
+            .is_some()
+            .then(|| self.thread_store.clone());
+
+        let session_budget = match self.next_session_budget.take() {
+            Some(budget) => Some(budget),
+            None => {
+                let budget_override = std::mem::take(&mut self.session_budget_override);
+                (!budget_override.is_empty()).then(|| {
+                    budget_override
+                        .apply(AgentSettings::get_global(cx).session_budget(&server.name()))
+                })
+            }
+        };
+        let thread_view = cx.new(|cx| {
+            let thread_view = crate::acp::AcpServerView::new(
+                server,
+                resume_thread,
+                initial_content,
+                isolated_worktree
+                    .as_ref()
+                    .map(|worktree| worktree.path.clone()),
+                workspace.clone(),
+                project,
+                thread_store,
+                self.prompt_store.clone(),
+                self.acp_history.clone(),
+                window,
+                cx,
+            );
+            match session_budget {
+                Some(budget) => thread_view.with_session_budget(budget),
+                None => thread_view,
+            }
+        });
+
+        self.push_tab(
+            ActiveView::AgentThread {
+                thread_view: thread_view.clone(),
+            },
+            selected_agent,
+            window,
+            cx,
+        );
+
+        if let Some(isolated_worktree) = isolated_worktree
+            && let Some(id) = self.tab_id_for_thread_view(&thread_view)
+        {
+            self.tabs[id].isolated_worktree = Some(isolated_worktree);
+            self.serialize(cx);
+        }
+
+        thread_view
+    }
+
+    pub(crate) fn tab_id_for_thread_view(
+        &self,
+        thread_view: &Entity<AcpServerView>,
+    ) -> Option<TabId> {
+        self.tabs.iter().position(|tab| {
+            matches!(tab.view(), ActiveView::AgentThread { thread_view: view } if view == thread_view)
+        })
+    }
+
+    fn new_isolated_agent_thread(
+        &mut self,
+        agent: Option<ExternalAgent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ext_agent = agent
+            .or_else(|| self.selected_external_agent())
+            .unwrap_or(ExternalAgent::NativeAgent);
+        let Some(repository) = self.project.read(cx).active_repository(cx) else {
+            self.show_isolated_worktree_toast("Isolated worktrees require a git repository.", cx);
+            return;
+        };
+        let repository_snapshot = repository.read(cx);
+        let Some(head_sha) = repository_snapshot
+            .head_commit
+            .as_ref()
+            .map(|commit| commit.sha.to_string())
+        else {
+            self.show_isolated_worktree_toast(
+                "Isolated worktrees require a repository with at least one commit.",
+                cx,
+            );
+            return;
+        };
+        let Some(isolated_worktree) =
+            IsolatedWorktree::new(repository_snapshot.work_directory_abs_path.clone())
+        else {
+            self.show_isolated_worktree_toast("Can't create a worktree for this repository.", cx);
+            return;
+        };
+
+        let create_worktree =
+            self.create_isolated_worktree(&repository, &isolated_worktree, head_sha, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                create_worktree.await?;
+                this.update(cx, |this, cx| {
+                    this.isolated_agent_project(&ext_agent, &isolated_worktree, cx)
+                })?
+                .await
+            }
+            .await;
+            this.update_in(cx, |this, window, cx| match result {
+                Ok(project) => {
+                    let server = ext_agent.server(this.fs.clone(), this.thread_store.clone());
+                    this._external_thread(
+                        server,
+                        None,
+                        None,
+                        Some(isolated_worktree),
+                        this.workspace.clone(),
+                        project,
+                        ext_agent,
+                        window,
+                        cx,
+                    );
+                }
+                Err(error) => this.show_isolated_worktree_toast(
+                    format!("Failed to create an isolated worktree: {error}"),
+                    cx,
+                ),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens a new tab that continues the thread from before the user message at `entry_ix`,
+    /// in an isolated worktree with the project as it was when the message was sent. Messages
+    /// without a checkpoint fork from the last commit, which the user is told about.
+    ///
+    /// Agents that can fork their sessions, like the native agent, get a copy of the thread.
+    /// Others get a new session with the conversation before the message attached to the draft.
+    pub(crate) fn fork_thread(
+        &mut self,
+        thread_view: &Entity<AcpServerView>,
+        thread: &Entity<AcpThread>,
+        entry_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab_id) = self.tab_id_for_thread_view(thread_view) else {
+            return;
+        };
+        let Some(ext_agent) = self.tabs[tab_id].agent().external_agent() else {
+            return;
+        };
+        let Some(fork_point) = thread.read(cx).fork_point(entry_ix, cx) else {
+            return;
+        };
+
+        let Some(repository) = self.project.read(cx).active_repository(cx) else {
+            self.show_isolated_worktree_toast("Forking a thread requires a git repository.", cx);
+            return;
+        };
+        let repository_snapshot = repository.read(cx);
+        let work_directory = repository_snapshot.work_directory_abs_path.clone();
+        // A thread that already runs in an isolated worktree checkpointed that worktree, whose
+        // commits live in the same object store as the main repository's.
+        let checkpoint_directory = self.tabs[tab_id]
+            .isolated_worktree
+            .as_ref()
+            .map_or_else(|| work_directory.clone(), |worktree| worktree.path.clone());
+        let checkpoint_sha = fork_point
+            .checkpoint
+            .as_ref()
+            .and_then(|checkpoint| checkpoint.repository_checkpoint(&checkpoint_directory))
+            .map(|checkpoint| checkpoint.commit_sha.to_string());
+        if checkpoint_sha.is_none() {
+            self.show_isolated_worktree_toast(FORK_WITHOUT_CHECKPOINT, cx);
+        }
+        let Some(commit_sha) = checkpoint_sha.or_else(|| {
+            repository_snapshot
+                .head_commit
+                .as_ref()
+                .map(|commit| commit.sha.to_string())
+        }) else {
+            self.show_isolated_worktree_toast(
+                "Forking a thread requires a repository with at least one commit.",
+                cx,
+            );
+            return;
+        };
+        let Some(isolated_worktree) = IsolatedWorktree::new(work_directory.clone()) else {
+            self.show_isolated_worktree_toast("Can't create a worktree for this repository.", cx);
+            return;
+        };
+
+        // Forking at the first message needs no history, so it starts a new session.
+        let fork_session = thread
+            .read(cx)
+            .connection()
+            .fork(thread.read(cx).session_id(), cx)
+            .zip(fork_point.message_id.clone())
+            .filter(|_| !fork_point.transcript.is_empty())
+            .map(|(fork_session, message_id)| fork_session.run(message_id, cx));
+        let draft = if fork_session.is_some() || fork_point.transcript.is_empty() {
+            fork_point.message
+        } else {
+            let thread = thread.read(cx);
+            let transcript = if thread.prompt_capabilities().embedded_context {
+                let uri = MentionUri::Thread {
+                    id: thread.session_id().clone(),
+                    name: format!("{} (before fork)", thread.title()),
+                };
+                acp::ContentBlock::Resource(acp::EmbeddedResource::new(
+                    acp::EmbeddedResourceResource::TextResourceContents(
+                        acp::TextResourceContents::new(
+                            fork_point.transcript,
+                            uri.to_uri().to_string(),
+                        ),
+                    ),
+                ))
+            } else {
+                acp::ContentBlock::Text(acp::TextContent::new(fork_point.transcript))
+            };
+            let mut draft = vec![
+                transcript,
+                acp::ContentBlock::Text(acp::TextContent::new("\n\n")),
+            ];
+            draft.extend(fork_point.message);
+            draft
+        };
+        let create_worktree =
+            self.create_isolated_worktree(&repository, &isolated_worktree, commit_sha, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                create_worktree.await?;
+                let project = this
+                    .update(cx, |this, cx| {
+                        this.isolated_agent_project(&ext_agent, &isolated_worktree, cx)
+                    })?
+                    .await?;
+                let session = match fork_session {
+                    Some(fork_session) => Some(fork_session.await?),
+                    None => None,
+                };
+                anyhow::Ok((project, session))
+            }
+            .await;
+
+            this.update_in(cx, |this, window, cx| match result {
+                Ok((project, session)) => {
+                    let server = ext_agent.server(this.fs.clone(), this.thread_store.clone());
+                    this._external_thread(
+                        server,
+                        session,
+                        Some(ExternalAgentInitialContent::Draft(draft)),
+                        Some(isolated_worktree),
+                        this.workspace.clone(),
+                        project,
+                        ext_agent,
+                        window,
+                        cx,
+                    );
+                }
+                Err(error) => this.show_isolated_worktree_toast(
+                    format!("Failed to fork the thread: {error}"),
+                    cx,
+                ),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Creates the isolated worktree at the given commit and adds it to the project.
+    fn create_isolated_worktree(
+        &self,
+        repository: &Entity<Repository>,
+        isolated_worktree: &IsolatedWorktree,
+        commit_sha: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let create_worktree = repository.update(cx, |repository, _| {
+            repository.create_worktree(
+                isolated_worktree.branch.clone(),
+                isolated_worktree
+                    .path
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+                Some(commit_sha),
+            )
+        });
+        let project = self.project.clone();
+        let isolated_worktree = isolated_worktree.clone();
+
+        cx.spawn(async move |_, cx| {
+            create_worktree.await??;
+            cx.update(|cx| {
+                Self::trust_isolated_worktree(&project, &project, &isolated_worktree, cx)
+            });
+            project
+                .update(cx, |project, cx| {
+                    project.find_or_create_worktree(&isolated_worktree.path, true, cx)
+                })
+                .await?;
+            anyhow::Ok(())
+        })
+    }
+
+    /// Returns the project an agent works in when its thread is isolated in a worktree.
+    ///
+    /// External agents are started in the worktree's directory, so they share the panel's
+    /// project. The Zed agent resolves paths against every worktree of its project instead, so
+    /// it gets a project of its own with only the isolated worktree in it.
+    fn isolated_agent_project(
+        &self,
+        ext_agent: &ExternalAgent,
+        isolated_worktree: &IsolatedWorktree,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Project>>> {
+        if !matches!(ext_agent, ExternalAgent::NativeAgent) {
+            return Task::ready(Ok(self.project.clone()));
+        }
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("The workspace was closed")));
+        };
+        let app_state = workspace.read(cx).app_state().clone();
+        let agent_project = Project::local(
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.user_store.clone(),
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            None,
+            Default::default(),
+            cx,
+        );
+        Self::trust_isolated_worktree(&self.project, &agent_project, isolated_worktree, cx);
+        let path = isolated_worktree.path.clone();
+
+        cx.spawn(async move |_, cx| {
+            agent_project
+                .update(cx, |project, cx| {
+                    project.find_or_create_worktree(&path, true, cx)
+                })
+                .await?;
+            anyhow::Ok(agent_project)
+        })
+    }
+
+    /// Trusts the isolated worktree in `agent_project` when the worktree of `project` it was
+    /// created from can be trusted.
+    fn trust_isolated_worktree(
+        project: &Entity<Project>,
+        agent_project: &Entity<Project>,
+        isolated_worktree: &IsolatedWorktree,
+        cx: &mut App,
+    ) {
+        let Some(trusted_worktrees) = TrustedWorktrees::try_get_global(cx) else {
+            return;
+        };
+        let Some((parent_worktree, _)) = project
+            .read(cx)
+            .find_worktree(&isolated_worktree.repository_path, cx)
+        else {
+            return;
+        };
+        let parent_worktree_id = parent_worktree.read(cx).id();
+        let worktree_store = project.read(cx).worktree_store();
+        let agent_worktree_store = agent_project.read(cx).worktree_store();
+        trusted_worktrees.update(cx, |trusted_worktrees, cx| {
+            if trusted_worktrees.can_trust(&worktree_store, parent_worktree_id, cx) {
+                trusted_worktrees.trust(
+                    &agent_worktree_store,
+                    HashSet::from_iter([PathTrust::AbsPath(isolated_worktree.path.to_path_buf())]),
+                    cx,
+                );
+            }
+        });
+    }
+
+    fn show_isolated_worktree_toast(
+        &self,
+        message: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let message = message.into();
+        workspace.update(cx, |workspace, cx| {
+            struct IsolatedWorktreeToast;
+            workspace.show_toast(
+                workspace::Toast::new(
+                    workspace::notifications::NotificationId::unique::<IsolatedWorktreeToast>(),
+                    message,
+                ),
+                NotificationSource::Agent,
+                cx,
+            );
+        });
+    }
+
+    /// Closes a tab, first asking what to do with its isolated worktree if it has one.
+    fn close_tab(&mut self, id: TabId, window: &mut Window, cx: &mut Context<Self>) {
+        let isolated_thread = self.tabs.get(id).and_then(|tab| match tab.view() {
+            ActiveView::AgentThread { thread_view } if self.tabs.len() > 1 => tab
+                .isolated_worktree
+                .clone()
+                .map(|worktree| (worktree, thread_view.clone())),
+            _ => None,
+        });
+        let Some((isolated_worktree, thread_view)) = isolated_thread else {
+            self.remove_tab_by_id(id, window, cx);
+            return;
+        };
+
+        let answer = window.prompt(
+            gpui::PromptLevel::Info,
+            &format!(
+                "This thread worked in the isolated worktree \"{}\"",
+                isolated_worktree.branch
+            ),
+            Some(
+                "Merge its changes back into your working tree, review them first, \
+                or discard the worktree and everything in it.",
+            ),
+            &["Merge Back", "Open Diff", "Discard Worktree", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            match answer.await? {
+                0 => {
+                    this.update(cx, |this, cx| {
+                        this.merge_isolated_worktree(&isolated_worktree, cx)
+                    })?
+                    .await?;
+                    this.update_in(cx, |this, window, cx| {
+                        this.discard_isolated_worktree(isolated_worktree, &thread_view, window, cx)
+                    })?
+                    .await?;
+                }
+                1 => {
+                    this.update_in(cx, |this, window, cx| {
+                        if let Some(id) = this.tab_id_for_thread_view(&thread_view) {
+                            this.set_active_tab_by_id(id, window, cx);
+                        }
+                        let thread = thread_view
+                            .read(cx)
+                            .active_thread()
+                            .map(|active| active.read(cx).thread.clone());
+                        if let Some(thread) = thread
+                            && let Some(workspace) = this.workspace.upgrade()
+                        {
+                            workspace.update(cx, |workspace, cx| {
+                                AgentDiffPane::deploy_in_workspace(thread, workspace, window, cx);
+                            });
+                        }
+                    })?;
+                }
+                2 => {
+                    this.update_in(cx, |this, window, cx| {
+                        this.discard_isolated_worktree(isolated_worktree, &thread_view, window, cx)
+                    })?
+                    .await?;
+                }
+                _ => {}
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err(
+            "Failed to close the worktree thread",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    }
+
+    fn repository_for_work_directory(&self, path: &Path, cx: &App) -> Option<Entity<Repository>> {
+        self.project
+            .read(cx)
+            .repositories(cx)
+            .values()
+            .find(|repository| repository.read(cx).work_directory_abs_path.as_ref() == path)
+            .cloned()
+    }
+
+    /// Applies everything the agent changed in the worktree, committed or not, to the
+    /// original repository's working tree without committing it.
+    fn merge_isolated_worktree(
+        &self,
+        isolated_worktree: &IsolatedWorktree,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let (Some(repository), Some(worktree_repository)) = (
+            self.repository_for_work_directory(&isolated_worktree.repository_path, cx),
+            self.repository_for_work_directory(&isolated_worktree.path, cx),
+        ) else {
+            return Task::ready(Err(anyhow!(
+                "Can't find the repository for worktree \"{}\"",
+                isolated_worktree.branch
+            )));
+        };
+
+        let checkpoint = worktree_repository.update(cx, |repository, _| repository.checkpoint());
+        cx.spawn(async move |_, cx| {
+            let checkpoint = checkpoint.await??;
+            repository
+                .update(cx, |repository, _| {
+                    repository.squash_merge(checkpoint.commit_sha.to_string())
+                })
+                .await??;
+            anyhow::Ok(())
+        })
+    }
+
+    /// Closes the thread's tab and deletes its worktree along with any changes in it.
+    fn discard_isolated_worktree(
         &mut self,
-        server: Rc<dyn AgentServer>,
-        resume_thread: Option<AgentSessionInfo>,
-        initial_content: Option<ExternalAgentInitialContent>,
-        workspace: WeakEntity<Workspace>,
-        project: Entity<Project>,
-        ext_agent: ExternalAgent,
+        isolated_worktree: IsolatedWorktree,
+        thread_view: &Entity<AcpServerView>,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
-        let selected_agent = AgentType::from(ext_agent);
-        if self.selected_agent != selected_agent {
-            self.selected_agent = selected_agent.clone();
-            self.serialize(cx);
+    ) -> Task<Result<()>> {
+        // Close the session before its working directory goes away.
+        if let Some(id) = self.tab_id_for_thread_view(thread_view) {
+            self.remove_tab_by_id(id, window, cx);
         }
-        let thread_store = server
-            .clone()
-            .downcast::<agent::NativeAgentServer>()
-            .is_some()
-            .then(|| self.thread_store.clone());
 
-        let thread_view = cx.new(|cx| {
-            crate::acp::AcpServerView::new(
-                server,
-                resume_thread,
-                initial_content,
-                workspace.clone(),
-                project,
-                thread_store,
-                self.prompt_store.clone(),
-                self.acp_history.clone(),
-                window,
-                cx,
-            )
-        });
+        let Some(repository) =
+            self.repository_for_work_directory(&isolated_worktree.repository_path, cx)
+        else {
+            return Task::ready(Err(anyhow!(
+                "Can't find the repository for worktree \"{}\"",
+                isolated_worktree.branch
+            )));
+        };
 
-        self.push_tab(
-            ActiveView::AgentThread { thread_view },
-            selected_agent,
-            window,
-            cx,
-        );
+        self.project.update(cx, |project, cx| {
+            let worktree_id = project
+                .worktrees(cx)
+                .find(|worktree| worktree.read(cx).abs_path() == isolated_worktree.path)
+                .map(|worktree| worktree.read(cx).id());
+            if let Some(worktree_id) = worktree_id {
+                project.remove_worktree(worktree_id, cx);
+            }
+        });
+        let remove_worktree = repository.update(cx, |repository, _| {
+            repository.remove_worktree(isolated_worktree.path.to_path_buf(), true)
+        });
+        cx.spawn(async move |_, cx| {
+            remove_worktree.await??;
+            // The branch's commits were either squash merged or discarded, so git can't tell
+            // that nothing is lost by deleting it.
+            repository
+                .update(cx, |repository, _| {
+                    repository.delete_branch(isolated_worktree.branch, true)
+                })
+                .await??;
+            anyhow::Ok(())
+        })
     }
 }
 
@@ -2013,6 +3611,8 @@ impl Focusable for AgentPanel {
                     self.focus_handle.clone()
                 }
             }
+            ActiveView::Dashboard { dashboard } => dashboard.focus_handle(cx),
+            ActiveView::Comparison { comparison } => comparison.focus_handle(cx),
         }
     }
 }
@@ -2069,7 +3669,10 @@ impl Panel for AgentPanel {
     }
 
     fn set_active(&mut self, active: bool, window: &mut Window, cx: &mut Context<Self>) {
-        if active && matches!(self.active_view(), ActiveView::Uninitialized) {
+        if active
+            && self.pending_restoration.is_none()
+            && matches!(self.active_view(), ActiveView::Uninitialized)
+        {
             let selected_agent = self.selected_agent.clone();
             self.new_agent_thread(selected_agent, window, cx);
         }
@@ -2230,6 +3833,10 @@ impl AgentPanel {
                 Label::new(title).truncate().into_any_element()
             }
             ActiveView::Configuration => Label::new("Settings").truncate().into_any_element(),
+            ActiveView::Dashboard { .. } => Label::new("Dashboard").truncate().into_any_element(),
+            ActiveView::Comparison { .. } => {
+                Label::new("Compare Agents").truncate().into_any_element()
+            }
             ActiveView::Uninitialized => Label::new("Agent").truncate().into_any_element(),
         };
 
@@ -2263,6 +3870,65 @@ impl AgentPanel {
         });
     }
 
+    /// Returns the agents that the active thread can be handed off to.
+    fn handoff_agents(&self, cx: &App) -> Vec<(SharedString, ExternalAgent)> {
+        let active_agent = self.tabs.get(self.active_tab_id).map(|tab| tab.agent());
+        self.agents_by_name(cx)
+            .into_iter()
+            .filter(|(_, agent)| active_agent != Some(&AgentType::from(agent.clone())))
+            .collect()
+    }
+
+    /// Returns every agent with the name that the user knows it by, as registered in the
+    /// project's agent server store.
+    fn agents_by_name(&self, cx: &App) -> Vec<(SharedString, ExternalAgent)> {
+        let agent_server_store = self.project.read(cx).agent_server_store().read(cx);
+        let mut external_agents = agent_server_store
+            .external_agents()
+            .map(|name| match name.0.as_ref() {
+                CLAUDE_CODE_NAME => ("Claude Code".into(), ExternalAgent::ClaudeCode),
+                CODEX_NAME => ("Codex CLI".into(), ExternalAgent::Codex),
+                GEMINI_NAME => ("Gemini CLI".into(), ExternalAgent::Gemini),
+                _ => (
+                    agent_server_store
+                        .agent_display_name(name)
+                        .unwrap_or_else(|| name.0.clone()),
+                    ExternalAgent::Custom {
+                        name: name.0.clone(),
+                    },
+                ),
+            })
+            .collect::<Vec<_>>();
+        // Like in the new thread menu, the built-in agents come before the custom ones.
+        external_agents.sort_by_key(|(display_name, agent)| {
+            (
+                matches!(agent, ExternalAgent::Custom { .. }),
+                display_name.clone(),
+            )
+        });
+
+        let mut agents = vec![("Zed Agent".into(), ExternalAgent::NativeAgent)];
+        agents.extend(external_agents);
+        agents
+    }
+
+    fn handoff_menu(
+        mut menu: ContextMenu,
+        agents: &[(SharedString, ExternalAgent)],
+        summarize: bool,
+    ) -> ContextMenu {
+        for (name, agent) in agents {
+            menu = menu.action(
+                name.clone(),
+                Box::new(ContinueInAgent {
+                    agent: agent.clone(),
+                    summarize,
+                }),
+            );
+        }
+        menu
+    }
+
     fn render_panel_options_menu(
         &self,
         window: &mut Window,
@@ -2277,6 +3943,7 @@ impl AgentPanel {
         };
 
         let selected_agent = self.selected_agent.clone();
+        let has_fan_out_comparison = self.fan_out_comparison.is_some();
 
         let text_thread_view = match self.active_view() {
             ActiveView::TextThread {
@@ -2306,6 +3973,7 @@ impl AgentPanel {
             }
             _ => false,
         };
+        let handoff_agents = self.handoff_agents(cx);
 
         PopoverMenu::new("agent-options-menu")
             .trigger_with_tooltip(
@@ -2358,6 +4026,29 @@ impl AgentPanel {
                                             );
                                         }
                                     })
+                                    .action("Checkpoint Timeline", Box::new(OpenCheckpointTimeline))
+                                    .submenu("Continue In…", {
+                                        let handoff_agents = handoff_agents.clone();
+                                        let focus_handle = focus_handle.clone();
+                                        move |menu, _, _| {
+                                            Self::handoff_menu(
+                                                menu.context(focus_handle.clone()),
+                                                &handoff_agents,
+                                                false,
+                                            )
+                                        }
+                                    })
+                                    .submenu("Continue From Summary In…", {
+                                        let handoff_agents = handoff_agents.clone();
+                                        let focus_handle = focus_handle.clone();
+                                        move |menu, _, _| {
+                                            Self::handoff_menu(
+                                                menu.context(focus_handle.clone()),
+                                                &handoff_agents,
+                                                true,
+                                            )
+                                        }
+                                    })
                                     .separator();
                             }
                         }
@@ -2379,6 +4070,11 @@ impl AgentPanel {
                             .action("Profiles", Box::new(ManageProfiles::default()))
                             .action("Settings", Box::new(OpenSettings))
                             .separator()
+                            .action("Session Dashboard", Box::new(OpenAgentDashboard))
+                            .action("Audit Log", Box::new(OpenAuditLog))
+                            .when(has_fan_out_comparison, |menu| {
+                                menu.action("Compare Agents", Box::new(OpenFanOutComparison))
+                            })
                             .action("Edit Title", Box::new(EditTitle))
                             .action(full_screen_label, Box::new(ToggleZoom));
 
@@ -2475,11 +4171,20 @@ impl AgentPanel {
                     });
 
                 let (label_text, tooltip) = Self::display_tab_label(text, is_active);
+                let has_file_conflicts = Self::has_file_conflicts(thread_view, cx);
 
                 TabLabelRender {
-                    element: Label::new(label_text)
-                        .color(Color::Muted)
-                        .truncate()
+                    element: h_flex()
+                        .gap_1()
+                        .min_w_0()
+                        .when(has_file_conflicts, |this| {
+                            this.child(
+                                Icon::new(IconName::Warning)
+                                    .size(IconSize::Small)
+                                    .color(Color::Warning),
+                            )
+                        })
+                        .child(Label::new(label_text).color(Color::Muted).truncate())
                         .into_any_element(),
                     tooltip,
                 }
@@ -2547,6 +4252,14 @@ impl AgentPanel {
                 element: Label::new("Settings").truncate().into_any_element(),
                 tooltip: None,
             },
+            ActiveView::Dashboard { .. } => TabLabelRender {
+                element: Label::new("Dashboard").truncate().into_any_element(),
+                tooltip: None,
+            },
+            ActiveView::Comparison { .. } => TabLabelRender {
+                element: Label::new("Compare Agents").truncate().into_any_element(),
+                tooltip: None,
+            },
         }
     }
 
@@ -2613,7 +4326,9 @@ impl AgentPanel {
             ActiveView::Uninitialized
             | ActiveView::TextThread { .. }
             | ActiveView::History { .. }
-            | ActiveView::Configuration => None,
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => None,
         };
 
         let new_thread_menu = PopoverMenu::new("new_thread_menu")
@@ -2636,6 +4351,8 @@ impl AgentPanel {
             .menu({
                 let selected_agent = self.selected_agent.clone();
                 let is_agent_selected = move |agent_type: AgentType| selected_agent == agent_type;
+                let can_isolate = self.selected_agent != AgentType::NativeAgent;
+                let session_budget_override = self.session_budget_override;
 
                 let workspace = self.workspace.clone();
                 let is_via_collab = workspace
@@ -2672,6 +4389,18 @@ impl AgentPanel {
                                     this
                                 }
                             })
+                            .item(
+                                ContextMenuEntry::new("New in Isolated Worktree")
+                                    .icon(IconName::GitBranch)
+                                    .icon_color(Color::Muted)
+                                    .disabled(is_via_collab || !can_isolate)
+                                    .handler(move |window, cx| {
+                                        window.dispatch_action(
+                                            Box::new(NewIsolatedAgentThread { agent: None }),
+                                            cx,
+                                        );
+                                    }),
+                            )
                             .item(
                                 ContextMenuEntry::new("Zed Agent")
                                     .when(
@@ -2897,6 +4626,13 @@ impl AgentPanel {
                                 menu
                             })
                             .separator()
+                            .submenu(
+                                "Session Budget",
+                                Self::session_budget_submenu(
+                                    session_budget_override,
+                                    workspace.clone(),
+                                ),
+                            )
                             .item(
                                 ContextMenuEntry::new("Add More Agents")
                                     .icon(IconName::Plus)
@@ -2968,7 +4704,10 @@ impl AgentPanel {
                     .gap(DynamicSpacing::Base04.rems(cx))
                     .pl(DynamicSpacing::Base04.rems(cx))
                     .child(match self.active_view() {
-                        ActiveView::History { .. } | ActiveView::Configuration => {
+                        ActiveView::History { .. }
+                        | ActiveView::Configuration
+                        | ActiveView::Dashboard { .. }
+                        | ActiveView::Comparison { .. } => {
                             self.render_toolbar_back_button(cx).into_any_element()
                         }
                         _ => selected_agent.into_any_element(),
@@ -3027,6 +4766,8 @@ impl AgentPanel {
             .menu({
                 let selected_agent = self.selected_agent.clone();
                 let is_agent_selected = move |agent_type: AgentType| selected_agent == agent_type;
+                let can_isolate = self.selected_agent != AgentType::NativeAgent;
+                let session_budget_override = self.session_budget_override;
                 let workspace = self.workspace.clone();
                 let is_via_collab = workspace
                     .update(cx, |workspace, cx| {
@@ -3061,6 +4802,18 @@ impl AgentPanel {
                                     this
                                 }
                             })
+                            .item(
+                                ContextMenuEntry::new("New in Isolated Worktree")
+                                    .icon(IconName::GitBranch)
+                                    .icon_color(Color::Muted)
+                                    .disabled(is_via_collab || !can_isolate)
+                                    .handler(move |window, cx| {
+                                        window.dispatch_action(
+                                            Box::new(NewIsolatedAgentThread { agent: None }),
+                                            cx,
+                                        );
+                                    }),
+                            )
                             .item(
                                 ContextMenuEntry::new("Zed Agent")
                                     .when(
@@ -3276,6 +5029,13 @@ impl AgentPanel {
                                 menu
                             })
                             .separator()
+                            .submenu(
+                                "Session Budget",
+                                Self::session_budget_submenu(
+                                    session_budget_override,
+                                    workspace.clone(),
+                                ),
+                            )
                             .item(
                                 ContextMenuEntry::new("Add More Agents")
                                     .icon(IconName::Plus)
@@ -3420,7 +5180,7 @@ impl AgentPanel {
                         .icon_size(IconSize::Small)
                         .visible_on_hover("")
                         .on_click(cx.listener(move |this: &mut Self, _, window, cx| {
-                            this.remove_tab_by_id(index, window, cx);
+                            this.close_tab(index, window, cx);
                         }))
                         .tooltip(|_window, cx| cx.new(|_| Tooltip::new("Close Thread")).into()),
                 );
@@ -3454,7 +5214,9 @@ impl AgentPanel {
             ActiveView::Uninitialized
             | ActiveView::AgentThread { .. }
             | ActiveView::History { .. }
-            | ActiveView::Configuration => return false,
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => return false,
         }
 
         let plan = self.user_store.read(cx).plan();
@@ -3481,9 +5243,11 @@ impl AgentPanel {
         }
 
         match self.active_view() {
-            ActiveView::Uninitialized | ActiveView::History { .. } | ActiveView::Configuration => {
-                false
-            }
+            ActiveView::Uninitialized
+            | ActiveView::History { .. }
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => false,
             ActiveView::AgentThread { thread_view, .. }
                 if thread_view.read(cx).as_native_thread(cx).is_none() =>
             {
@@ -3775,7 +5539,11 @@ impl AgentPanel {
                     );
                 });
             }
-            ActiveView::Uninitialized | ActiveView::History { .. } | ActiveView::Configuration => {}
+            ActiveView::Uninitialized
+            | ActiveView::History { .. }
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => {}
         }
     }
 
@@ -3817,7 +5585,11 @@ impl AgentPanel {
         match self.active_view() {
             ActiveView::AgentThread { .. } => key_context.add("acp_thread"),
             ActiveView::TextThread { .. } => key_context.add("text_thread"),
-            ActiveView::Uninitialized | ActiveView::History { .. } | ActiveView::Configuration => {}
+            ActiveView::Uninitialized
+            | ActiveView::History { .. }
+            | ActiveView::Configuration
+            | ActiveView::Dashboard { .. }
+            | ActiveView::Comparison { .. } => {}
         }
         key_context
     }
@@ -3846,6 +5618,12 @@ impl Render for AgentPanel {
             .on_action(cx.listener(|this, _: &OpenHistory, window, cx| {
                 this.open_history(window, cx);
             }))
+            .on_action(cx.listener(|this, _: &OpenAgentDashboard, window, cx| {
+                this.open_dashboard(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &OpenFanOutComparison, window, cx| {
+                this.open_fan_out_comparison(window, cx);
+            }))
             .on_action(cx.listener(|this, _: &OpenSettings, window, cx| {
                 this.open_configuration(window, cx);
             }))
@@ -3917,6 +5695,8 @@ impl Render for AgentPanel {
                             ))
                     }
                     ActiveView::Configuration => parent.children(self.configuration.clone()),
+                    ActiveView::Dashboard { dashboard } => parent.child(dashboard.clone()),
+                    ActiveView::Comparison { comparison } => parent.child(comparison.clone()),
                 }
             })
             .children(self.render_trial_end_upsell(window, cx));
@@ -4130,7 +5910,7 @@ impl AgentPanel {
         };
 
         self._external_thread(
-            server, None, None, workspace, project, ext_agent, window, cx,
+            server, None, None, None, workspace, project, ext_agent, window, cx,
         );
     }
 
@@ -4142,3 +5922,606 @@ impl AgentPanel {
         self.active_thread_view()
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use acp_thread::{StubAgentConnection, ThreadStatus};
+    use agent_servers::mock_agent::{MockAgentScript, MockAgentServer};
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualTestContext};
+    use indoc::indoc;
+    use language::Buffer;
+    use util::path;
+    use workspace::Workspace;
+
+    use super::*;
+    use crate::acp::{StubAgentServer, init_test};
+
+    pub(crate) async fn open_agent_panel(
+        cx: &mut TestAppContext,
+    ) -> (Entity<AgentPanel>, &mut VisualTestContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        open_agent_panel_for_project(project, cx)
+    }
+
+    /// Opens the panel of a project with a git repository at `/project`, whose HEAD has `a.txt`.
+    async fn open_agent_panel_in_repository(
+        cx: &mut TestAppContext,
+    ) -> (Arc<FakeFs>, Entity<AgentPanel>, &mut VisualTestContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            serde_json::json!({ ".git": {}, "a.txt": "one\n" }),
+        )
+        .await;
+        fs.set_head_and_index_for_repo(
+            Path::new(path!("/project/.git")),
+            &[("a.txt", "one\n".into())],
+        );
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (panel, cx) = open_agent_panel_for_project(project, cx);
+        cx.run_until_parked();
+        (fs, panel, cx)
+    }
+
+    fn open_agent_panel_for_project(
+        project: Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> (Entity<AgentPanel>, &mut VisualTestContext) {
+        cx.update(agent::ThreadStore::init_global);
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let panel = workspace.update_in(cx, |workspace, window, cx| {
+            let text_thread_store =
+                cx.new(|cx| assistant_text_thread::TextThreadStore::fake(project.clone(), cx));
+            cx.new(|cx| AgentPanel::new(workspace, text_thread_store, None, window, cx))
+        });
+        (panel, cx)
+    }
+
+    /// Opens a tab with a thread of the stub agent, returning the tab's stable id.
+    pub(crate) fn open_stub_thread(
+        panel: &mut AgentPanel,
+        connection: StubAgentConnection,
+        window: &mut Window,
+        cx: &mut Context<AgentPanel>,
+    ) -> usize {
+        let thread_view = panel._external_thread(
+            Rc::new(StubAgentServer::new(connection)),
+            None,
+            None,
+            None,
+            panel.workspace.clone(),
+            panel.project.clone(),
+            ExternalAgent::Custom {
+                name: "Test".into(),
+            },
+            window,
+            cx,
+        );
+        let tab_id = panel.tab_id_for_thread_view(&thread_view).unwrap();
+        panel.tabs[tab_id].stable_id
+    }
+
+    #[gpui::test]
+    async fn test_fan_out_prompt(cx: &mut TestAppContext) {
+        let (_fs, panel, cx) = open_agent_panel_in_repository(cx).await;
+        let tab_count = panel.read_with(cx, |panel, _| panel.tabs.len());
+
+        let connection = StubAgentConnection::new();
+        panel.update_in(cx, |panel, window, cx| {
+            let servers = (0..3)
+                .map(|ix| {
+                    (
+                        ExternalAgent::Custom {
+                            name: format!("Agent {ix}").into(),
+                        },
+                        Rc::new(StubAgentServer::new(connection.clone())) as Rc<dyn AgentServer>,
+                    )
+                })
+                .collect();
+            panel.fan_out_prompt_to_servers(servers, vec!["Fix the bug".into()], window, cx);
+        });
+        cx.run_until_parked();
+
+        // Each agent gets its own tab and worktree, and is sent the same prompt.
+        panel.read_with(cx, |panel, cx| {
+            assert_eq!(panel.tabs.len(), tab_count + 3);
+            let mut session_ids = Vec::new();
+            let mut worktree_paths = HashSet::default();
+            for tab in &panel.tabs[tab_count..] {
+                let isolated_worktree = tab.isolated_worktree.as_ref().unwrap();
+                assert!(worktree_paths.insert(isolated_worktree.path.clone()));
+                let ActiveView::AgentThread { thread_view } = tab.view() else {
+                    panic!("expected an agent thread tab");
+                };
+                let thread = thread_view.read(cx).active_thread().unwrap().read(cx);
+                let thread = thread.thread.read(cx);
+                assert_eq!(thread.to_markdown(cx), "## User\n\nFix the bug\n\n");
+                assert_eq!(thread.status(), ThreadStatus::Generating);
+                session_ids.push(thread.session_id().0.to_string());
+            }
+            assert_eq!(session_ids, ["0", "1", "2"]);
+            assert!(panel.fan_out_comparison.is_some());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_fan_out_comparison_accept(cx: &mut TestAppContext) {
+        let (fs, panel, cx) = open_agent_panel_in_repository(cx).await;
+        let tab_count = panel.read_with(cx, |panel, _| panel.tabs.len());
+
+        let connection = StubAgentConnection::new();
+        panel.update_in(cx, |panel, window, cx| {
+            let servers = (0..3)
+                .map(|ix| {
+                    (
+                        ExternalAgent::Custom {
+                            name: format!("Agent {ix}").into(),
+                        },
+                        Rc::new(StubAgentServer::new(connection.clone())) as Rc<dyn AgentServer>,
+                    )
+                })
+                .collect();
+            panel.fan_out_prompt_to_servers(servers, vec!["Fix the bug".into()], window, cx);
+        });
+        cx.run_until_parked();
+
+        let (isolated_worktrees, comparison) = panel.read_with(cx, |panel, _| {
+            let isolated_worktrees = panel.tabs[tab_count..]
+                .iter()
+                .map(|tab| tab.isolated_worktree.clone().unwrap())
+                .collect::<Vec<_>>();
+            (
+                isolated_worktrees,
+                panel.fan_out_comparison.clone().unwrap(),
+            )
+        });
+
+        // Every agent edits the same file in its own worktree.
+        for (ix, isolated_worktree) in isolated_worktrees.iter().enumerate() {
+            fs.insert_file(
+                isolated_worktree.path.join("a.txt"),
+                format!("agent {ix}\n").into_bytes(),
+            )
+            .await;
+        }
+        cx.run_until_parked();
+
+        // Accepting merges the chosen worktree back and discards all of them.
+        comparison.update_in(cx, |comparison, window, cx| {
+            comparison.accept(1, window, cx)
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            fs.load(Path::new(path!("/project/a.txt"))).await.unwrap(),
+            "agent 1\n"
+        );
+        for isolated_worktree in &isolated_worktrees {
+            assert!(!fs.is_dir(&isolated_worktree.path).await);
+        }
+        comparison.read_with(cx, |comparison, _| assert_eq!(comparison.accepted, Some(1)));
+        panel.read_with(cx, |panel, _| {
+            assert_eq!(panel.tabs.len(), tab_count);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_concurrent_mock_agents(cx: &mut TestAppContext) {
+        let (panel, cx) = open_agent_panel(cx).await;
+        let tab_count = panel.read_with(cx, |panel, _| panel.tabs.len());
+
+        let slow_script = indoc! {r#"
+            {
+                "turns": [
+                    {
+                        "events": [
+                            { "type": "message", "text": "Thinking" },
+                            { "type": "sleep", "ms": 1000 },
+                            { "type": "message", "text": " slowly" }
+                        ]
+                    }
+                ]
+            }
+        "#};
+        let fast_script = indoc! {r#"
+            {
+                "turns": [
+                    { "events": [{ "type": "message", "text": "Quick" }] }
+                ]
+            }
+        "#};
+        panel.update_in(cx, |panel, window, cx| {
+            for (name, script) in [("Slow Agent", slow_script), ("Fast Agent", fast_script)] {
+                panel._external_thread(
+                    Rc::new(MockAgentServer::new(
+                        MockAgentScript::from_json(script).unwrap(),
+                    )),
+                    None,
+                    None,
+                    None,
+                    panel.workspace.clone(),
+                    panel.project.clone(),
+                    ExternalAgent::Custom { name: name.into() },
+                    window,
+                    cx,
+                );
+            }
+        });
+        cx.run_until_parked();
+
+        let threads = panel.read_with(cx, |panel, cx| {
+            panel.tabs[tab_count..]
+                .iter()
+                .map(|tab| {
+                    let ActiveView::AgentThread { thread_view } = tab.view() else {
+                        panic!("expected an agent thread tab");
+                    };
+                    let active = thread_view.read(cx).active_thread().unwrap().read(cx);
+                    active.thread.clone()
+                })
+                .collect::<Vec<_>>()
+        });
+        let tab_states = |panel: &AgentPanel, cx: &App| {
+            panel.tabs[tab_count..]
+                .iter()
+                .zip(&threads)
+                .map(|(tab, thread)| {
+                    let thread = thread.read(cx);
+                    (tab.agent.clone(), thread.to_markdown(cx), thread.status())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Both agents work at the same time, and each tab only shows its own agent's reply.
+        let slow_turn = threads[0].update(cx, |thread, cx| thread.send_raw("Be slow", cx));
+        let fast_turn = threads[1].update(cx, |thread, cx| thread.send_raw("Be fast", cx));
+        fast_turn.await.unwrap();
+        cx.run_until_parked();
+        panel.read_with(cx, |panel, cx| {
+            assert_eq!(
+                tab_states(panel, cx),
+                [
+                    (
+                        AgentType::Custom {
+                            name: "Slow Agent".into()
+                        },
+                        "## User\n\nBe slow\n\n## Assistant\n\nThinking\n\n".to_string(),
+                        ThreadStatus::Generating,
+                    ),
+                    (
+                        AgentType::Custom {
+                            name: "Fast Agent".into()
+                        },
+                        "## User\n\nBe fast\n\n## Assistant\n\nQuick\n\n".to_string(),
+                        ThreadStatus::Idle,
+                    ),
+                ]
+            );
+        });
+
+        cx.executor().advance_clock(Duration::from_secs(1));
+        slow_turn.await.unwrap();
+        cx.run_until_parked();
+        panel.read_with(cx, |panel, cx| {
+            let states = tab_states(panel, cx);
+            assert_eq!(
+                states[0].1,
+                "## User\n\nBe slow\n\n## Assistant\n\nThinking slowly\n\n"
+            );
+            assert_eq!(states[0].2, ThreadStatus::Idle);
+            assert_eq!(
+                states[1].1,
+                "## User\n\nBe fast\n\n## Assistant\n\nQuick\n\n"
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_file_conflict_badges(cx: &mut TestAppContext) {
+        let (panel, cx) = open_agent_panel(cx).await;
+
+        let connection = StubAgentConnection::new();
+        panel.update_in(cx, |panel, window, cx| {
+            open_stub_thread(panel, connection.clone(), window, cx);
+            open_stub_thread(panel, connection.clone(), window, cx);
+        });
+        cx.run_until_parked();
+
+        let tabs_with_conflicts = |panel: &AgentPanel, cx: &App| {
+            panel
+                .tabs
+                .iter()
+                .filter_map(|tab| match tab.view() {
+                    ActiveView::AgentThread { thread_view } => Some(thread_view),
+                    _ => None,
+                })
+                .filter(|thread_view| AgentPanel::has_file_conflicts(thread_view, cx))
+                .filter_map(|thread_view| {
+                    let active = thread_view.read(cx).active_thread()?;
+                    Some(active.read(cx).thread.read(cx).session_id().0.to_string())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The session whose write is on hold and the session it would overwrite both show it.
+        let buffer = cx.new(|cx| Buffer::local("", cx));
+        let project = panel.read_with(cx, |panel, _| panel.project.clone());
+        let file_registry = cx.update(|_, cx| AgentFileRegistry::for_project(&project, cx));
+        let conflict_id = file_registry.update(cx, |registry, cx| {
+            registry.add_conflict(
+                PathBuf::from("/project/a.txt"),
+                &buffer,
+                acp::SessionId::new("1"),
+                vec![acp::SessionId::new("0")],
+                cx,
+            )
+        });
+        panel.read_with(cx, |panel, cx| {
+            assert_eq!(tabs_with_conflicts(panel, cx), ["0", "1"]);
+        });
+
+        file_registry.update(cx, |registry, cx| registry.remove_conflict(conflict_id, cx));
+        panel.read_with(cx, |panel, cx| {
+            assert!(tabs_with_conflicts(panel, cx).is_empty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_isolated_worktree_merge_back_and_discard(cx: &mut TestAppContext) {
+        let (fs, panel, cx) = open_agent_panel_in_repository(cx).await;
+        let dot_git = Path::new(path!("/project/.git"));
+
+        let mut isolated_worktrees = Vec::new();
+        for _ in 0..2 {
+            let (create_worktree, isolated_worktree) = panel.update(cx, |panel, cx| {
+                let repository = panel.project.read(cx).active_repository(cx).unwrap();
+                let isolated_worktree =
+                    IsolatedWorktree::new(Path::new(path!("/project")).into()).unwrap();
+                let create_worktree = panel.create_isolated_worktree(
+                    &repository,
+                    &isolated_worktree,
+                    "abc".into(),
+                    cx,
+                );
+                (create_worktree, isolated_worktree)
+            });
+            create_worktree.await.unwrap();
+            panel.update_in(cx, |panel, window, cx| {
+                panel._external_thread(
+                    Rc::new(StubAgentServer::new(StubAgentConnection::new())),
+                    None,
+                    None,
+                    Some(isolated_worktree.clone()),
+                    panel.workspace.clone(),
+                    panel.project.clone(),
+                    ExternalAgent::Custom {
+                        name: "Test".into(),
+                    },
+                    window,
+                    cx,
+                );
+            });
+            isolated_worktrees.push(isolated_worktree);
+        }
+        let [merged, discarded] = [&isolated_worktrees[0], &isolated_worktrees[1]];
+        assert_eq!(fs.load(&merged.path.join("a.txt")).await.unwrap(), "one\n");
+
+        // Both agents change their worktree and commit to their branch.
+        fs.insert_file(merged.path.join("b.txt"), b"two\n".to_vec())
+            .await;
+        fs.insert_file(discarded.path.join("c.txt"), b"three\n".to_vec())
+            .await;
+        fs.with_git_state(dot_git, true, |state| {
+            state.unmerged_branches.insert(merged.branch.clone());
+            state.unmerged_branches.insert(discarded.branch.clone());
+        })
+        .unwrap();
+        cx.run_until_parked();
+
+        let close_tab =
+            |isolated_worktree: &IsolatedWorktree, answer: &str, cx: &mut VisualTestContext| {
+                panel.update_in(cx, |panel, window, cx| {
+                    let id = panel
+                        .tabs
+                        .iter()
+                        .position(|tab| {
+                            tab.isolated_worktree
+                                .as_ref()
+                                .is_some_and(|worktree| worktree.path == isolated_worktree.path)
+                        })
+                        .unwrap();
+                    panel.close_tab(id, window, cx);
+                });
+                cx.simulate_prompt_answer(answer);
+                cx.run_until_parked();
+            };
+        let branches = || {
+            fs.with_git_state(dot_git, false, |state| state.branches.clone())
+                .unwrap()
+        };
+
+        // Merging applies the worktree's changes without committing them, and deletes the
+        // branch even though git doesn't consider it merged.
+        close_tab(merged, "Merge Back", cx);
+        assert_eq!(
+            fs.load(Path::new(path!("/project/b.txt"))).await.unwrap(),
+            "two\n"
+        );
+        assert!(!fs.is_dir(&merged.path).await);
+        assert!(!branches().contains(&merged.branch));
+        assert!(branches().contains(&discarded.branch));
+
+        close_tab(discarded, "Discard Worktree", cx);
+        assert!(!fs.is_file(Path::new(path!("/project/c.txt"))).await);
+        assert!(!fs.is_dir(&discarded.path).await);
+        assert!(!branches().contains(&discarded.branch));
+        panel.read_with(cx, |panel, _| {
+            assert!(panel.tabs.iter().all(|tab| tab.isolated_worktree.is_none()));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_send_to_agent_tab_keeps_draft(cx: &mut TestAppContext) {
+        let (panel, cx) = open_agent_panel(cx).await;
+
+        let connection = StubAgentConnection::new();
+        let stable_id = panel.update_in(cx, |panel, window, cx| {
+            open_stub_thread(panel, connection.clone(), window, cx)
+        });
+        cx.run_until_parked();
+
+        let active_thread = panel.read_with(cx, |panel, cx| {
+            let thread_view = panel.agent_tab(stable_id).unwrap();
+            thread_view.read(cx).active_thread().cloned().unwrap()
+        });
+        let message_editor =
+            active_thread.read_with(cx, |active_thread, _| active_thread.message_editor.clone());
+        message_editor.update_in(cx, |editor, window, cx| {
+            editor.set_text("Draft", window, cx)
+        });
+
+        // The first message is sent, and the second waits in the queue while the agent works.
+        for message in ["First", "Second"] {
+            panel
+                .update_in(cx, |panel, window, cx| {
+                    panel.send_to_agent_tab(stable_id, message.into(), window, cx)
+                })
+                .unwrap();
+            cx.run_until_parked();
+        }
+        active_thread.read_with(cx, |active_thread, cx| {
+            let thread = active_thread.thread.read(cx);
+            assert_eq!(thread.to_markdown(cx), "## User\n\nFirst\n\n");
+            assert!(active_thread.has_queued_messages());
+        });
+        message_editor.read_with(cx, |editor, cx| assert_eq!(editor.text(cx), "Draft"));
+
+        // A message that can't be sent is reported instead of dropped.
+        active_thread.update(cx, |active_thread, _| {
+            active_thread.is_loading_contents = true
+        });
+        panel
+            .update_in(cx, |panel, window, cx| {
+                panel.send_to_agent_tab(stable_id, "Third".into(), window, cx)
+            })
+            .unwrap_err();
+    }
+
+    #[gpui::test]
+    async fn test_agent_thread_for_session(cx: &mut TestAppContext) {
+        let (panel, cx) = open_agent_panel(cx).await;
+
+        // The stub agent numbers the sessions it starts.
+        let connection = StubAgentConnection::new();
+        let stable_ids = panel.update_in(cx, |panel, window, cx| {
+            [
+                open_stub_thread(panel, connection.clone(), window, cx),
+                open_stub_thread(panel, connection.clone(), window, cx),
+            ]
+        });
+        cx.run_until_parked();
+
+        panel.read_with(cx, |panel, cx| {
+            for (session_id, stable_id) in ["0", "1"].into_iter().zip(stable_ids) {
+                let (found_stable_id, thread) = panel
+                    .agent_thread_for_session(session_id, cx)
+                    .expect("the session should have a tab");
+                assert_eq!(found_stable_id, stable_id);
+                assert_eq!(thread.read(cx).session_id().0.as_ref(), session_id);
+            }
+            assert!(panel.agent_thread_for_session("2", cx).is_none());
+        });
+
+        // Closing a tab forgets its session, while the other tab keeps its stable id.
+        panel.update_in(cx, |panel, window, cx| {
+            let tab_id = panel
+                .tabs
+                .iter()
+                .position(|tab| tab.stable_id == stable_ids[0])
+                .unwrap();
+            panel.remove_tab_by_id(tab_id, window, cx);
+        });
+        panel.read_with(cx, |panel, cx| {
+            assert!(panel.agent_thread_for_session("0", cx).is_none());
+            let (found_stable_id, _) = panel.agent_thread_for_session("1", cx).unwrap();
+            assert_eq!(found_stable_id, stable_ids[1]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_restored_transcript_tab_survives_another_restart(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let history = cx.update(|window, cx| cx.new(|cx| AcpThreadHistory::new(None, window, cx)));
+
+        let agent = AgentType::Custom {
+            name: "Test".into(),
+        };
+        let mut serialized_tab = SerializedAgentPanelTab {
+            agent: agent.clone(),
+            identity: AgentPanelTabIdentity::AcpThread(acp::SessionId::new("restored-session")),
+            title: Some("Fix the websocket".into()),
+            cwd: Some(PathBuf::from("/project")),
+            transcript: Some("## User\n\nHello\n".into()),
+            isolated_worktree: None,
+            terminal_sessions: Vec::new(),
+        };
+
+        // Restore the tab twice, serializing it in between, both while the agent is still
+        // loading and once it showed the transcript, since the agent can't load sessions.
+        for _ in 0..2 {
+            let AgentPanelTabIdentity::AcpThread(session_id) = serialized_tab.identity.clone()
+            else {
+                panic!("expected an agent thread tab");
+            };
+            let session = AgentSessionInfo {
+                session_id,
+                cwd: serialized_tab.cwd.clone(),
+                title: serialized_tab.title.clone(),
+                updated_at: None,
+                meta: None,
+            };
+            let transcript = serialized_tab.transcript.clone().unwrap();
+            let thread_view = cx.update(|window, cx| {
+                cx.new(|cx| {
+                    AcpServerView::new(
+                        Rc::new(StubAgentServer::new(StubAgentConnection::new())),
+                        Some(session),
+                        None,
+                        None,
+                        workspace.downgrade(),
+                        project.clone(),
+                        None,
+                        None,
+                        history.clone(),
+                        window,
+                        cx,
+                    )
+                    .with_restored_transcript(transcript)
+                })
+            });
+            let tab = AgentPanelTab::new(ActiveView::AgentThread { thread_view }, agent.clone());
+
+            for _ in 0..2 {
+                let reserialized_tab = cx
+                    .update(|_, cx| AgentPanel::serialize_tab(&tab, cx))
+                    .expect("restored tab should be serialized");
+                assert_eq!(reserialized_tab.identity, serialized_tab.identity);
+                assert_eq!(reserialized_tab.title, serialized_tab.title);
+                assert_eq!(reserialized_tab.cwd, serialized_tab.cwd);
+                assert_eq!(reserialized_tab.transcript, serialized_tab.transcript);
+                serialized_tab = reserialized_tab;
+                cx.run_until_parked();
+            }
+        }
+    }
+}
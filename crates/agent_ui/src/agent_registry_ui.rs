@@ -588,6 +588,7 @@ impl AgentRegistryPage {
                                     favorite_models: Vec::new(),
                                     default_config_options: HashMap::default(),
                                     favorite_config_option_values: HashMap::default(),
+                                    allowed_extension_methods: Vec::new(),
                                 }
                             });
                         });
@@ -0,0 +1,512 @@
+use std::{cell::RefCell, collections::BTreeMap, path::Path, rc::Rc};
+
+use acp_thread::ThreadStatus;
+use anyhow::{Context as _, Result, bail};
+use context_server::{
+    listener::{ConnectionId, McpServer, McpServerTool, ToolResponse},
+    types::{ToolAnnotations, ToolResponseContent},
+};
+use gpui::{AnyWindowHandle, AsyncApp, Context, Entity, Task, WeakEntity};
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::rel_path::RelPath;
+
+use crate::agent_panel::AgentPanel;
+
+/// Starts the MCP server through which the agents of the panel's project can see and message
+/// each other's sessions, and offers it to the agents that start from then on.
+pub(crate) fn start_agent_sessions_server(
+    project: &Entity<Project>,
+    window: AnyWindowHandle,
+    cx: &mut Context<AgentPanel>,
+) -> Task<Result<McpServer>> {
+    let project_id = project.entity_id();
+    let tools = SessionTools {
+        panel: cx.weak_entity(),
+        project: project.downgrade(),
+        window,
+        claims: Rc::default(),
+    };
+    let server = McpServer::new(&cx.to_async());
+    cx.spawn(async move |_, cx| {
+        let mut server = server.await?;
+        server.add_tool(ListAgentSessions(tools.clone()));
+        server.add_tool(ReadSessionTranscript(tools.clone()));
+        server.add_tool(SendMessageToSession(tools.clone()));
+        server.add_tool(ClaimFile(tools.clone()));
+        server.add_tool(ReleaseFile(tools.clone()));
+        // Every agent session runs its own copy of the server, so the session ended when its
+        // connection closes.
+        server.on_connection_closed(move |connection, _| {
+            tools.claims.borrow_mut().release_all(connection)
+        });
+
+        cx.update(|cx| {
+            agent_servers::register_agent_sessions_server(
+                project_id,
+                server.socket_path().to_path_buf(),
+                cx,
+            )
+        });
+        Ok(server)
+    })
+}
+
+#[derive(Clone)]
+struct SessionTools {
+    panel: WeakEntity<AgentPanel>,
+    project: WeakEntity<Project>,
+    window: AnyWindowHandle,
+    claims: Rc<RefCell<FileClaims>>,
+}
+
+impl SessionTools {
+    fn panel(&self) -> Result<Entity<AgentPanel>> {
+        self.panel.upgrade().context("the agent panel was closed")
+    }
+
+    /// Resolves a path that an agent gave to the project path it refers to, along with the path
+    /// to show to agents.
+    fn resolve_path(&self, path: &str, cx: &AsyncApp) -> Result<(ProjectPath, String)> {
+        let project = self.project.upgrade().context("the project was closed")?;
+        let path = path.trim();
+        project.read_with(cx, |project, cx| {
+            let project_path = project
+                .find_project_path(path, cx)
+                .or_else(|| {
+                    // Files that don't exist yet are relative to the first worktree.
+                    let worktree = project.visible_worktrees(cx).next()?;
+                    let path = RelPath::new(Path::new(path), project.path_style(cx)).ok()?;
+                    Some(ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path: path.into_arc(),
+                    })
+                })
+                .with_context(|| format!("{path} isn't in the project"))?;
+            let display_path = project
+                .short_full_path_for_project_path(&project_path, cx)
+                .unwrap_or_else(|| path.to_string());
+            Ok((project_path, display_path))
+        })
+    }
+}
+
+/// The files that agents said they're editing. Each claim belongs to the connection, and so the
+/// agent session, that made it.
+#[derive(Default)]
+struct FileClaims(BTreeMap<ProjectPath, FileClaim>);
+
+struct FileClaim {
+    display_path: String,
+    owner: String,
+    connection: ConnectionId,
+}
+
+impl FileClaims {
+    fn claim(
+        &mut self,
+        path: ProjectPath,
+        display_path: String,
+        owner: String,
+        connection: ConnectionId,
+    ) -> Result<()> {
+        if let Some(claim) = self.0.get(&path)
+            && claim.connection != connection
+        {
+            bail!(
+                "{} is already claimed by {}",
+                claim.display_path,
+                claim.owner
+            )
+        }
+        self.0.insert(
+            path,
+            FileClaim {
+                display_path,
+                owner,
+                connection,
+            },
+        );
+        Ok(())
+    }
+
+    /// Releases the claim on the file, returning whether there was one.
+    fn release(&mut self, path: &ProjectPath, connection: ConnectionId) -> Result<bool> {
+        match self.0.get(path) {
+            None => Ok(false),
+            Some(claim) if claim.connection != connection => {
+                bail!(
+                    "{} is claimed by {}, and only they can release it",
+                    claim.display_path,
+                    claim.owner
+                )
+            }
+            Some(_) => {
+                self.0.remove(path);
+                Ok(true)
+            }
+        }
+    }
+
+    fn release_all(&mut self, connection: ConnectionId) {
+        self.0.retain(|_, claim| claim.connection != connection);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &FileClaim> {
+        self.0.values()
+    }
+}
+
+fn text_response<T>(text: impl Into<String>, structured_content: T) -> ToolResponse<T> {
+    ToolResponse {
+        content: vec![ToolResponseContent::Text { text: text.into() }],
+        structured_content,
+    }
+}
+
+fn read_only() -> ToolAnnotations {
+    ToolAnnotations {
+        title: None,
+        read_only_hint: Some(true),
+        destructive_hint: Some(false),
+        idempotent_hint: Some(true),
+        open_world_hint: Some(false),
+    }
+}
+
+/// Lists the agent sessions that are open in Zed for this project, including your own, along
+/// with the files that agents have claimed.
+#[derive(Deserialize, JsonSchema)]
+struct ListAgentSessionsInput {}
+
+#[derive(Serialize, JsonSchema)]
+struct ListAgentSessionsOutput {
+    sessions: Vec<AgentSessionOutput>,
+    claimed_files: Vec<FileClaimOutput>,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct AgentSessionOutput {
+    session_id: String,
+    agent: String,
+    title: String,
+    status: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+struct FileClaimOutput {
+    path: String,
+    owner: String,
+}
+
+#[derive(Clone)]
+struct ListAgentSessions(SessionTools);
+
+impl McpServerTool for ListAgentSessions {
+    type Input = ListAgentSessionsInput;
+    type Output = ListAgentSessionsOutput;
+
+    const NAME: &'static str = "list_agent_sessions";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only()
+    }
+
+    async fn run(
+        &self,
+        _input: Self::Input,
+        _connection: ConnectionId,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<Self::Output>> {
+        let tabs = self
+            .0
+            .panel()?
+            .read_with(cx, |panel, cx| panel.agent_tab_summaries(cx));
+        let output = ListAgentSessionsOutput {
+            sessions: tabs
+                .into_iter()
+                .filter_map(|tab| {
+                    Some(AgentSessionOutput {
+                        session_id: tab.session_id?.to_string(),
+                        agent: tab.agent.to_string(),
+                        title: tab.title.to_string(),
+                        status: tab.status.to_string(),
+                    })
+                })
+                .collect(),
+            claimed_files: self
+                .0
+                .claims
+                .borrow()
+                .iter()
+                .map(|claim| FileClaimOutput {
+                    path: claim.display_path.clone(),
+                    owner: claim.owner.clone(),
+                })
+                .collect(),
+        };
+        Ok(text_response(
+            serde_json::to_string_pretty(&output)?,
+            output,
+        ))
+    }
+}
+
+/// Returns the transcript of an agent session as Markdown.
+#[derive(Deserialize, JsonSchema)]
+struct ReadSessionTranscriptInput {
+    /// The id of the session, from `list_agent_sessions`.
+    session_id: String,
+}
+
+#[derive(Clone)]
+struct ReadSessionTranscript(SessionTools);
+
+impl McpServerTool for ReadSessionTranscript {
+    type Input = ReadSessionTranscriptInput;
+    type Output = ();
+
+    const NAME: &'static str = "read_session_transcript";
+
+    fn annotations(&self) -> ToolAnnotations {
+        read_only()
+    }
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        _connection: ConnectionId,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<()>> {
+        let transcript = self.0.panel()?.read_with(cx, |panel, cx| {
+            let (_, thread) = panel.agent_thread_for_session(&input.session_id, cx)?;
+            Some(thread.read(cx).to_markdown(cx))
+        });
+        let transcript =
+            transcript.with_context(|| format!("no session with id {:?}", input.session_id))?;
+        Ok(text_response(transcript, ()))
+    }
+}
+
+/// Sends a message to another agent session as the user, without touching what the user is typing
+/// in it. The message is queued if the agent is busy.
+#[derive(Deserialize, JsonSchema)]
+struct SendMessageToSessionInput {
+    /// The id of the session, from `list_agent_sessions`.
+    session_id: String,
+    message: String,
+}
+
+#[derive(Clone)]
+struct SendMessageToSession(SessionTools);
+
+impl McpServerTool for SendMessageToSession {
+    type Input = SendMessageToSessionInput;
+    type Output = ();
+
+    const NAME: &'static str = "send_message_to_session";
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        _connection: ConnectionId,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<()>> {
+        let panel = self.0.panel()?;
+        let queued = self.0.window.update(cx, |_, window, cx| {
+            panel.update(cx, |panel, cx| {
+                let (stable_tab_id, thread) = panel
+                    .agent_thread_for_session(&input.session_id, cx)
+                    .with_context(|| format!("no session with id {:?}", input.session_id))?;
+                let queued = thread.read(cx).status() != ThreadStatus::Idle;
+                panel.send_to_agent_tab(stable_tab_id, input.message, window, cx)?;
+                anyhow::Ok(queued)
+            })
+        })??;
+        Ok(text_response(
+            if queued {
+                "The agent is busy, so the message was queued until it finishes its turn."
+            } else {
+                "Message sent."
+            },
+            (),
+        ))
+    }
+}
+
+/// Claims a file that you're about to edit, so that other agents know to leave it alone. Fails if
+/// another agent claimed the file. Check `list_agent_sessions` for the claimed files before
+/// editing, and release your claims when you're done. Your claims are released when your session
+/// ends.
+#[derive(Deserialize, JsonSchema)]
+struct ClaimFileInput {
+    /// The path of the file, relative to the project root.
+    path: String,
+    /// A name that identifies you to the other agents, like your role in the task.
+    owner: String,
+}
+
+#[derive(Clone)]
+struct ClaimFile(SessionTools);
+
+impl McpServerTool for ClaimFile {
+    type Input = ClaimFileInput;
+    type Output = ();
+
+    const NAME: &'static str = "claim_file";
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        connection: ConnectionId,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<()>> {
+        let (path, display_path) = self.0.resolve_path(&input.path, cx)?;
+        self.0
+            .claims
+            .borrow_mut()
+            .claim(path, display_path.clone(), input.owner, connection)?;
+        Ok(text_response(format!("Claimed {display_path}."), ()))
+    }
+}
+
+/// Releases a file that you claimed, so that other agents can edit it.
+#[derive(Deserialize, JsonSchema)]
+struct ReleaseFileInput {
+    /// The path of the file, relative to the project root.
+    path: String,
+}
+
+#[derive(Clone)]
+struct ReleaseFile(SessionTools);
+
+impl McpServerTool for ReleaseFile {
+    type Input = ReleaseFileInput;
+    type Output = ();
+
+    const NAME: &'static str = "release_file";
+
+    async fn run(
+        &self,
+        input: Self::Input,
+        connection: ConnectionId,
+        cx: &mut AsyncApp,
+    ) -> Result<ToolResponse<()>> {
+        let (path, display_path) = self.0.resolve_path(&input.path, cx)?;
+        if self.0.claims.borrow_mut().release(&path, connection)? {
+            Ok(text_response(format!("Released {display_path}."), ()))
+        } else {
+            Ok(text_response(format!("{display_path} wasn't claimed."), ()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use project::WorktreeId;
+    use util::rel_path::rel_path;
+
+    use super::*;
+
+    fn project_path(path: &str) -> ProjectPath {
+        ProjectPath {
+            worktree_id: WorktreeId::from_usize(1),
+            path: rel_path(path).into_arc(),
+        }
+    }
+
+    fn claims(claims: &FileClaims) -> Vec<(&str, &str)> {
+        claims
+            .iter()
+            .map(|claim| (claim.display_path.as_str(), claim.owner.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_file_claims() {
+        let lead = ConnectionId(0);
+        let worker = ConnectionId(1);
+        let mut file_claims = FileClaims::default();
+
+        file_claims
+            .claim(
+                project_path("src/a.rs"),
+                "src/a.rs".into(),
+                "lead".into(),
+                lead,
+            )
+            .unwrap();
+        file_claims
+            .claim(
+                project_path("src/b.rs"),
+                "src/b.rs".into(),
+                "worker".into(),
+                worker,
+            )
+            .unwrap();
+
+        // Another session can't claim the file, even with the same name, but the owner can
+        // claim it again.
+        let error = file_claims
+            .claim(
+                project_path("src/a.rs"),
+                "src/a.rs".into(),
+                "lead".into(),
+                worker,
+            )
+            .unwrap_err();
+        assert_eq!(error.to_string(), "src/a.rs is already claimed by lead");
+        file_claims
+            .claim(
+                project_path("src/a.rs"),
+                "src/a.rs".into(),
+                "lead".into(),
+                lead,
+            )
+            .unwrap();
+        assert_eq!(
+            claims(&file_claims),
+            [("src/a.rs", "lead"), ("src/b.rs", "worker")]
+        );
+
+        // Only the owner can release a claim.
+        let error = file_claims
+            .release(&project_path("src/a.rs"), worker)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "src/a.rs is claimed by lead, and only they can release it"
+        );
+        assert!(
+            file_claims
+                .release(&project_path("src/a.rs"), lead)
+                .unwrap()
+        );
+        assert!(
+            !file_claims
+                .release(&project_path("src/a.rs"), lead)
+                .unwrap()
+        );
+        file_claims
+            .claim(
+                project_path("src/a.rs"),
+                "src/a.rs".into(),
+                "worker".into(),
+                worker,
+            )
+            .unwrap();
+
+        // When a session ends, its claims are released.
+        file_claims
+            .claim(
+                project_path("src/c.rs"),
+                "src/c.rs".into(),
+                "lead".into(),
+                lead,
+            )
+            .unwrap();
+        file_claims.release_all(worker);
+        assert_eq!(claims(&file_claims), [("src/c.rs", "lead")]);
+    }
+}
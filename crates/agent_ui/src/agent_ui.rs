@@ -1,10 +1,18 @@
 pub mod acp;
+mod agent_cli;
+mod agent_comparison;
 mod agent_configuration;
+mod agent_dashboard;
 mod agent_diff;
+mod agent_ext_methods;
+mod agent_jobs;
 mod agent_model_selector;
 mod agent_panel;
 mod agent_registry_ui;
+mod agent_sessions_server;
+mod audit_log_view;
 mod buffer_codegen;
+mod checkpoint_timeline;
 mod completion_provider;
 mod context;
 mod context_server_configuration;
@@ -26,6 +34,7 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 // Another comment
+use agent_servers::{AcpPermissionRules, AutoPermission};
 use agent_settings::{AgentProfileId, AgentSettings};
 use assistant_slash_command::SlashCommandRegistry;
 use client::Client;
@@ -48,9 +57,11 @@ use settings::{LanguageModelSelection, Settings as _, SettingsStore};
 use std::any::TypeId;
 use workspace::Workspace;
 
+pub use crate::agent_cli::{AgentTabSummary, wait_for_agent_tab};
 use crate::agent_configuration::{ConfigureContextServerModal, ManageProfilesModal};
 pub use crate::agent_panel::{AgentPanel, ConcreteAssistantPanelDelegate};
 use crate::agent_registry_ui::AgentRegistryPage;
+use crate::audit_log_view::AuditLogView;
 pub use crate::inline_assistant::InlineAssistant;
 pub use agent_diff::{AgentDiffPane, AgentDiffToolbar};
 pub use text_thread_editor::{AgentPanelDelegate, TextThreadEditor};
@@ -79,6 +90,13 @@ actions!(
         RemoveHistory,
         /// Opens the conversation history view.
         OpenHistory,
+        /// Opens an overview of every open agent session and its status.
+        OpenAgentDashboard,
+        /// Opens a side-by-side comparison of the agents from the last fanned-out prompt.
+        OpenFanOutComparison,
+        /// Opens the log of every permission decision, tool call, file write, and terminal
+        /// command made by agents.
+        OpenAuditLog,
         /// Edits the current thread title.
         EditTitle,
         /// Adds a context server to the configuration.
@@ -103,6 +121,8 @@ actions!(
         OpenActiveThreadAsMarkdown,
         /// Opens the agent diff view to review changes.
         OpenAgentDiff,
+        /// Opens the timeline of the current thread's checkpoints, to compare and restore them.
+        OpenCheckpointTimeline,
         /// Copies the current thread to the clipboard as JSON for debugging.
         CopyThreadToClipboard,
         /// Loads a thread from the clipboard JSON for debugging.
@@ -193,6 +213,40 @@ pub struct NewExternalAgentThread {
     agent: Option<ExternalAgent>,
 }
 
+/// Creates a new agent thread in a fresh git worktree on its own branch, so that
+/// it can't interfere with other threads working on the same repository.
+#[derive(Default, Clone, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = agent)]
+#[serde(deny_unknown_fields)]
+pub struct NewIsolatedAgentThread {
+    /// Which agent to use for the conversation.
+    agent: Option<ExternalAgent>,
+}
+
+/// Sends the message editor's prompt to several agents at once, each in a new tab,
+/// and opens a view comparing their results.
+#[derive(Default, Clone, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = agent)]
+#[serde(deny_unknown_fields)]
+pub struct FanOutPrompt {
+    /// Which agents to send the prompt to.
+    #[serde(default)]
+    pub agents: Vec<ExternalAgent>,
+}
+
+/// Continues the current thread in a new tab with another agent, handing over the
+/// conversation, the unreviewed changes, and the mentioned context.
+#[derive(Clone, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = agent)]
+#[serde(deny_unknown_fields)]
+pub struct ContinueInAgent {
+    /// Which agent to continue the thread with.
+    pub agent: ExternalAgent,
+    /// Whether to hand over a summary of the conversation instead of all of it.
+    #[serde(default)]
+    pub summarize: bool,
+}
+
 #[derive(Clone, PartialEq, Deserialize, JsonSchema, Action)]
 #[action(namespace = agent)]
 #[serde(deny_unknown_fields)]
@@ -231,6 +285,10 @@ impl ExternalAgent {
 pub enum ExternalAgentInitialContent {
     ThreadSummary(acp_thread::AgentSessionInfo),
     Text(String),
+    /// A prompt, including its mentions, that is sent as soon as the thread is ready.
+    Prompt(Vec<agent_client_protocol::ContentBlock>),
+    /// A message, including its mentions, that is left in the editor to be changed and sent.
+    Draft(Vec<agent_client_protocol::ContentBlock>),
 }
 
 /// Opens the profile management interface for configuring agent tools and settings.
@@ -265,6 +323,23 @@ impl ModelUsageContext {
     }
 }
 
+/// Answers external agents' permission requests from the `tool_permissions` settings.
+fn init_acp_permission_rules(cx: &mut App) {
+    AcpPermissionRules::set(
+        |agent_name, tool_call, cx| {
+            let settings = AgentSettings::get_global(cx);
+            match agent::decide_permission_for_acp_tool_call(agent_name, tool_call, settings) {
+                decision @ agent::ToolPermissionDecision::Allow => Some(AutoPermission::Allow(
+                    agent::acp_tool_call_decision_rule(agent_name, tool_call, &decision, settings),
+                )),
+                agent::ToolPermissionDecision::Deny(reason) => Some(AutoPermission::Deny(reason)),
+                agent::ToolPermissionDecision::Confirm => None,
+            }
+        },
+        cx,
+    );
+}
+
 /// Initializes the `agent` crate.
 pub fn init(
     fs: Arc<dyn Fs>,
@@ -275,6 +350,7 @@ pub fn init(
     cx: &mut App,
 ) {
     agent::ThreadStore::init_global(cx);
+    agent::init_audit_log(cx);
     assistant_text_thread::init(client, cx);
     rules_library::init(cx);
     if !is_eval {
@@ -284,6 +360,8 @@ pub fn init(
     }
     assistant_slash_command::init(cx);
     agent_panel::init(cx);
+    agent_ext_methods::init(cx);
+    init_acp_permission_rules(cx);
     context_server_configuration::init(language_registry.clone(), fs.clone(), cx);
     TextThreadEditor::init(cx);
 
@@ -320,6 +398,20 @@ pub fn init(
                 }
             },
         );
+        workspace.register_action(|workspace, _: &OpenAuditLog, window, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items()
+                .find_map(|item| item.downcast::<AuditLogView>());
+
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let audit_log = AuditLogView::new(workspace, window, cx);
+                workspace.add_item_to_active_pane(Box::new(audit_log), None, true, window, cx);
+            }
+        });
     })
     .detach();
     cx.observe_new(ManageProfilesModal::register).detach();
@@ -566,7 +658,11 @@ mod tests {
             use_modifier_to_send: true,
             message_editor_min_lines: 1,
             tool_permissions: Default::default(),
+            agent_tool_permissions: Default::default(),
             show_turn_stats: false,
+            enable_agent_sessions_server: false,
+            session_budgets: Default::default(),
+            jobs: Vec::new(),
         };
 
         cx.update(|cx| {
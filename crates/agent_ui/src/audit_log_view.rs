@@ -0,0 +1,396 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+use acp_thread::{AuditAction, AuditEntry};
+use agent::AuditFilter;
+use chrono::{Duration, Local, Utc};
+use editor::{Editor, EditorEvent};
+use gpui::{
+    App, Entity, EventEmitter, FocusHandle, Focusable, Task, UniformListScrollHandle, Window,
+    uniform_list,
+};
+use project::Project;
+use ui::{
+    Chip, ToggleButtonGroup, ToggleButtonGroupSize, ToggleButtonGroupStyle, ToggleButtonSimple,
+    Tooltip, WithScrollbar, prelude::*,
+};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AuditTimeRange {
+    LastHour,
+    LastDay,
+    LastWeek,
+    All,
+}
+
+impl AuditTimeRange {
+    fn duration(self) -> Option<Duration> {
+        match self {
+            Self::LastHour => Some(Duration::hours(1)),
+            Self::LastDay => Some(Duration::days(1)),
+            Self::LastWeek => Some(Duration::weeks(1)),
+            Self::All => None,
+        }
+    }
+}
+
+/// Shows the audit log of every agent's actions, which can be filtered and exported as JSON.
+pub struct AuditLogView {
+    project: Entity<Project>,
+    agent_editor: Entity<Editor>,
+    path_editor: Entity<Editor>,
+    time_range: AuditTimeRange,
+    entries: Vec<AuditEntry>,
+    list: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    load_task: Task<()>,
+}
+
+impl AuditLogView {
+    pub fn new(
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        cx.new(|cx| {
+            let agent_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Filter by agent, e.g. zed", window, cx);
+                editor
+            });
+            let path_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Filter by path", window, cx);
+                editor
+            });
+            cx.subscribe(&agent_editor, Self::on_filter_edited).detach();
+            cx.subscribe(&path_editor, Self::on_filter_edited).detach();
+
+            let mut this = Self {
+                project,
+                agent_editor,
+                path_editor,
+                time_range: AuditTimeRange::LastDay,
+                entries: Vec::new(),
+                list: UniformListScrollHandle::new(),
+                focus_handle: cx.focus_handle(),
+                load_task: Task::ready(()),
+            };
+            this.reload(cx);
+            this
+        })
+    }
+
+    fn on_filter_edited(&mut self, _: Entity<Editor>, event: &EditorEvent, cx: &mut Context<Self>) {
+        if let EditorEvent::Edited { .. } = event {
+            self.reload(cx);
+        }
+    }
+
+    fn filter(&self, cx: &App) -> AuditFilter {
+        let text = |editor: &Entity<Editor>| {
+            let text = editor.read(cx).text(cx).trim().to_string();
+            (!text.is_empty()).then_some(text)
+        };
+        AuditFilter {
+            agent: text(&self.agent_editor),
+            path: text(&self.path_editor),
+            since: self
+                .time_range
+                .duration()
+                .map(|duration| Utc::now() - duration),
+            ..Default::default()
+        }
+    }
+
+    fn set_time_range(&mut self, time_range: AuditTimeRange, cx: &mut Context<Self>) {
+        self.time_range = time_range;
+        self.reload(cx);
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let entries = agent::query_audit_log(self.filter(cx), cx);
+        self.load_task = cx.spawn(async move |this, cx| {
+            let Some(entries) = entries.await.log_err() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.entries = entries;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Exports every entry that matches the filters, rather than only the latest ones shown.
+    fn export(&self, cx: &mut Context<Self>) {
+        let entries = agent::query_audit_log(
+            AuditFilter {
+                limit: None,
+                ..self.filter(cx)
+            },
+            cx,
+        );
+        let directory = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from(util::paths::home_dir()));
+        let fs = self.project.read(cx).fs().clone();
+        let path = cx.prompt_for_new_path(&directory, Some("agent-audit-log.json"));
+
+        cx.background_spawn(async move {
+            let Some(path) = path
+                .await
+                .log_err()
+                .and_then(|path| path.log_err())
+                .flatten()
+            else {
+                return;
+            };
+            let Some(json) = entries
+                .await
+                .log_err()
+                .and_then(|entries| serde_json::to_string_pretty(&entries).log_err())
+            else {
+                return;
+            };
+            fs.atomic_write(path, json).await.log_err();
+        })
+        .detach();
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range
+            .filter_map(|ix| {
+                let entry = self.entries.get(ix)?;
+                Some(self.render_entry(ix, entry, cx))
+            })
+            .collect()
+    }
+
+    fn render_entry(&self, ix: usize, entry: &AuditEntry, cx: &App) -> AnyElement {
+        let timestamp = entry
+            .timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let outcome_color = match entry.outcome.as_deref() {
+            Some("denied" | "failed" | "rejected once" | "always rejected") => Color::Error,
+            _ => Color::Muted,
+        };
+
+        h_flex()
+            .id(("audit-entry", ix))
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_3()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .tooltip(Tooltip::text(format!("Session {}", entry.session_id)))
+            .child(
+                Label::new(timestamp)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                Chip::new(entry.action.label()).label_color(match entry.action {
+                    AuditAction::PermissionDecision => Color::Accent,
+                    AuditAction::ToolCall => Color::Default,
+                    AuditAction::FileWrite => Color::Modified,
+                    AuditAction::TerminalCommand => Color::Info,
+                }),
+            )
+            .child(
+                Label::new(entry.agent.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                v_flex()
+                    .min_w_0()
+                    .flex_1()
+                    .child(Label::new(entry.description.clone()).truncate())
+                    .when_some(entry.path.as_ref(), |this, path| {
+                        this.child(
+                            Label::new(path.to_string_lossy().into_owned())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    })
+                    .when_some(entry.rule.clone(), |this, rule| {
+                        this.child(
+                            Label::new(format!("Rule: {rule}"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .when_some(entry.outcome.clone(), |this, outcome| {
+                this.child(
+                    Label::new(outcome)
+                        .size(LabelSize::Small)
+                        .color(outcome_color),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_filter(&self, editor: &Entity<Editor>, cx: &App) -> impl IntoElement {
+        h_flex()
+            .h_8()
+            .min_w(rems_from_px(200.))
+            .flex_1()
+            .px_2()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .rounded_md()
+            .child(editor.clone())
+    }
+}
+
+impl Render for AuditLogView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let time_range_button = |label: &'static str, time_range: AuditTimeRange| {
+            ToggleButtonSimple::new(
+                label,
+                cx.listener(move |this, _, _, cx| this.set_time_range(time_range, cx)),
+            )
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .p_4()
+                    .gap_4()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .justify_between()
+                            .child(Headline::new("Agent Audit Log").size(HeadlineSize::Large))
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .child(
+                                        IconButton::new("refresh-audit-log", IconName::RotateCw)
+                                            .tooltip(Tooltip::text("Refresh"))
+                                            .on_click(
+                                                cx.listener(|this, _, _, cx| this.reload(cx)),
+                                            ),
+                                    )
+                                    .child(
+                                        Button::new("export-audit-log", "Export JSON")
+                                            .style(ButtonStyle::Outlined)
+                                            .icon(IconName::Download)
+                                            .icon_size(IconSize::Small)
+                                            .icon_color(Color::Muted)
+                                            .disabled(self.entries.is_empty())
+                                            .on_click(
+                                                cx.listener(|this, _, _, cx| this.export(cx)),
+                                            ),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .flex_wrap()
+                            .gap_2()
+                            .child(self.render_filter(&self.agent_editor, cx))
+                            .child(self.render_filter(&self.path_editor, cx))
+                            .child(
+                                ToggleButtonGroup::single_row(
+                                    "audit-time-range",
+                                    [
+                                        time_range_button("Last Hour", AuditTimeRange::LastHour),
+                                        time_range_button("Last Day", AuditTimeRange::LastDay),
+                                        time_range_button("Last Week", AuditTimeRange::LastWeek),
+                                        time_range_button("All", AuditTimeRange::All),
+                                    ],
+                                )
+                                .style(ToggleButtonGroupStyle::Outlined)
+                                .size(ToggleButtonGroupSize::Custom(rems_from_px(30.)))
+                                .auto_width()
+                                .selected_index(
+                                    match self.time_range {
+                                        AuditTimeRange::LastHour => 0,
+                                        AuditTimeRange::LastDay => 1,
+                                        AuditTimeRange::LastWeek => 2,
+                                        AuditTimeRange::All => 3,
+                                    },
+                                ),
+                            ),
+                    ),
+            )
+            .child(v_flex().px_4().size_full().overflow_y_hidden().map(|this| {
+                if self.entries.is_empty() {
+                    this.child(h_flex().p_4().justify_center().child(
+                        Label::new("No agent actions match these filters.").color(Color::Muted),
+                    ))
+                    .into_any_element()
+                } else {
+                    this.child(
+                        uniform_list(
+                            "audit-log-entries",
+                            self.entries.len(),
+                            cx.processor(Self::render_entries),
+                        )
+                        .flex_grow()
+                        .track_scroll(&self.list),
+                    )
+                    .vertical_scrollbar_for(&self.list, window, cx)
+                    .into_any_element()
+                }
+            }))
+    }
+}
+
+impl EventEmitter<ItemEvent> for AuditLogView {}
+
+impl Focusable for AuditLogView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for AuditLogView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Agent Audit Log".into()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ShieldCheck))
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
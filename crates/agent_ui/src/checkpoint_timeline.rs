@@ -0,0 +1,919 @@
+use std::{path::PathBuf, sync::Arc};
+
+use acp_thread::{AcpThread, AgentThreadEntry};
+use anyhow::{Context as _, Result};
+use buffer_diff::BufferDiff;
+use collections::HashSet;
+use editor::{Editor, EditorEvent, MultiBuffer, multibuffer_context_lines};
+use git::repository::{CommitFile, RepoPath, is_binary_content};
+use gpui::{
+    AnyElement, App, AsyncApp, Entity, EntityId, EventEmitter, FocusHandle, Focusable, PromptLevel,
+    Subscription, Task, WeakEntity, Window,
+};
+use language::{
+    Buffer, Capability, DiskState, LanguageRegistry, LineEnding, OffsetRangeExt as _, Point,
+    ReplicaId, Rope, TextBuffer,
+};
+use multi_buffer::PathKey;
+use project::{
+    Project, ProjectPath, WorktreeId,
+    git_store::{GitStoreCheckpoint, Repository},
+};
+use ui::{Checkbox, Divider, Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathStyle, rel_path::RelPath, truncate_and_trailoff};
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+use crate::agent_panel::AgentPanel;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CheckpointId {
+    /// The checkpoint taken right before the user message at `entry_ix` was sent.
+    Message { thread: EntityId, entry_ix: usize },
+    /// The project as it is now.
+    Current,
+}
+
+struct TimelineEntry {
+    id: CheckpointId,
+    thread_title: SharedString,
+    message: SharedString,
+    /// `None` for the current state, which is checkpointed when it's diffed.
+    checkpoint: Option<GitStoreCheckpoint>,
+}
+
+/// A file that differs between the two checkpoints being compared.
+struct ChangedFile {
+    repository: Entity<Repository>,
+    path: RepoPath,
+    /// The contents at the "from" checkpoint, or `None` if the file didn't exist.
+    old_text: Option<String>,
+    is_binary: bool,
+    selected: bool,
+}
+
+/// Lists the checkpoints of an agent thread, and of other agent tabs working on the same
+/// repositories, and shows how the project changed between any two of them.
+///
+/// Unlike restoring a checkpoint from the thread, restoring from the timeline keeps the
+/// conversation as it is.
+pub(crate) struct CheckpointTimeline {
+    thread: Entity<AcpThread>,
+    panel: WeakEntity<AgentPanel>,
+    project: Entity<Project>,
+    entries: Vec<TimelineEntry>,
+    from: Option<CheckpointId>,
+    to: Option<CheckpointId>,
+    files: Vec<ChangedFile>,
+    multibuffer: Entity<MultiBuffer>,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    diff_task: Option<Task<()>>,
+    restore_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CheckpointTimeline {
+    pub fn deploy_in_workspace(
+        thread: Entity<AcpThread>,
+        panel: WeakEntity<AgentPanel>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let existing_timeline = workspace
+            .items_of_type::<CheckpointTimeline>(cx)
+            .find(|timeline| timeline.read(cx).thread == thread);
+
+        if let Some(existing_timeline) = existing_timeline {
+            existing_timeline.update(cx, |timeline, cx| timeline.refresh(window, cx));
+            workspace.activate_item(&existing_timeline, true, true, window, cx);
+            existing_timeline
+        } else {
+            let timeline = cx.new(|cx| CheckpointTimeline::new(thread, panel, window, cx));
+            workspace.add_item_to_center(Box::new(timeline.clone()), window, cx);
+            timeline
+        }
+    }
+
+    pub fn new(
+        thread: Entity<AcpThread>,
+        panel: WeakEntity<AgentPanel>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let project = thread.read(cx).project().clone();
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.disable_inline_diagnostics();
+            editor.set_show_breakpoints(false, cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor
+        });
+        let subscriptions = vec![cx.observe_in(&thread, window, |this, _, window, cx| {
+            if this.update_entries(cx) {
+                this.update_diff(window, cx);
+            }
+        })];
+
+        let mut this = Self {
+            thread,
+            panel,
+            project,
+            entries: Vec::new(),
+            from: None,
+            to: None,
+            files: Vec::new(),
+            multibuffer,
+            editor,
+            error: None,
+            focus_handle: cx.focus_handle(),
+            diff_task: None,
+            restore_task: None,
+            _subscriptions: subscriptions,
+        };
+        this.refresh(window, cx);
+        this
+    }
+
+    /// Reloads the checkpoints of every tab and diffs the selected ones again.
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_entries(cx);
+        self.update_diff(window, cx);
+    }
+
+    /// Collects the checkpoints of every tab, returning whether the selected ones changed.
+    fn update_entries(&mut self, cx: &mut Context<Self>) -> bool {
+        let own_thread_id = self.thread.entity_id();
+        let mut threads = vec![self.thread.clone()];
+        if let Some(panel) = self.panel.upgrade() {
+            threads.extend(
+                panel
+                    .read(cx)
+                    .agent_threads(cx)
+                    .into_iter()
+                    .filter(|thread| thread.entity_id() != own_thread_id),
+            );
+        }
+
+        let mut entries = Vec::new();
+        let mut own_work_dirs = HashSet::default();
+        for thread in threads {
+            let is_own_thread = thread.entity_id() == own_thread_id;
+            let thread_title = thread.read(cx).title();
+            for (entry_ix, entry) in thread.read(cx).entries().iter().enumerate() {
+                let AgentThreadEntry::UserMessage(message) = entry else {
+                    continue;
+                };
+                let Some(checkpoint) = message.checkpoint.as_ref() else {
+                    continue;
+                };
+                let checkpoint = checkpoint.git_checkpoint();
+                if is_own_thread {
+                    own_work_dirs.extend(checkpoint.work_directory_abs_paths().cloned());
+                } else if !checkpoint
+                    .work_directory_abs_paths()
+                    .any(|path| own_work_dirs.contains(path))
+                {
+                    continue;
+                }
+
+                let text = message.content.to_markdown(cx);
+                let first_line = text.lines().next().unwrap_or_default();
+                entries.push(TimelineEntry {
+                    id: CheckpointId::Message {
+                        thread: thread.entity_id(),
+                        entry_ix,
+                    },
+                    thread_title: thread_title.clone(),
+                    message: truncate_and_trailoff(first_line, 80).into(),
+                    checkpoint: Some(checkpoint.clone()),
+                });
+            }
+        }
+        entries.push(TimelineEntry {
+            id: CheckpointId::Current,
+            thread_title: "Now".into(),
+            message: "Current state of the project".into(),
+            checkpoint: None,
+        });
+        self.entries = entries;
+
+        let is_listed = |id: Option<CheckpointId>, entries: &[TimelineEntry]| {
+            id.is_some_and(|id| entries.iter().any(|entry| entry.id == id))
+        };
+        if is_listed(self.from, &self.entries) && is_listed(self.to, &self.entries) {
+            cx.notify();
+            return false;
+        }
+
+        let (from, to) = (self.from, self.to);
+        self.from = self
+            .entries
+            .iter()
+            .rev()
+            .find(|entry| {
+                matches!(entry.id, CheckpointId::Message { thread, .. } if thread == own_thread_id)
+            })
+            .map(|entry| entry.id);
+        self.to = Some(CheckpointId::Current);
+        let selection_changed = (from, to) != (self.from, self.to);
+        cx.notify();
+        selection_changed
+    }
+
+    fn select_from(&mut self, id: CheckpointId, window: &mut Window, cx: &mut Context<Self>) {
+        self.from = Some(id);
+        self.update_diff(window, cx);
+    }
+
+    fn select_to(&mut self, id: CheckpointId, window: &mut Window, cx: &mut Context<Self>) {
+        self.to = Some(id);
+        self.update_diff(window, cx);
+    }
+
+    fn resolve_checkpoint(
+        &self,
+        id: CheckpointId,
+        cx: &mut App,
+    ) -> Option<Task<Result<GitStoreCheckpoint>>> {
+        match id {
+            CheckpointId::Current => {
+                let git_store = self.project.read(cx).git_store().clone();
+                Some(git_store.update(cx, |git_store, cx| git_store.checkpoint(cx)))
+            }
+            CheckpointId::Message { .. } => {
+                let entry = self.entries.iter().find(|entry| entry.id == id)?;
+                Some(Task::ready(Ok(entry.checkpoint.clone()?)))
+            }
+        }
+    }
+
+    fn update_diff(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.files.clear();
+        self.error = None;
+        self.multibuffer
+            .update(cx, |multibuffer, cx| multibuffer.clear(cx));
+        cx.notify();
+
+        let (Some(from), Some(to)) = (self.from, self.to) else {
+            self.diff_task = None;
+            return;
+        };
+        let (Some(from), Some(to)) = (
+            self.resolve_checkpoint(from, cx),
+            self.resolve_checkpoint(to, cx),
+        ) else {
+            self.diff_task = None;
+            return;
+        };
+
+        let git_store = self.project.read(cx).git_store().clone();
+        let language_registry = self.project.read(cx).languages().clone();
+        let first_worktree_id = self
+            .project
+            .read(cx)
+            .worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).id());
+        self.diff_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let files = async {
+                let (from, to) = futures::try_join!(from, to)?;
+                git_store
+                    .update(cx, |git_store, cx| {
+                        git_store.diff_checkpoint_files(from, to, cx)
+                    })
+                    .await
+            }
+            .await;
+            let files = match files {
+                Ok(files) => files,
+                Err(error) => {
+                    this.update(cx, |this, cx| {
+                        this.error = Some(format!("Failed to diff checkpoints: {error}").into());
+                        cx.notify();
+                    })
+                    .ok();
+                    return;
+                }
+            };
+
+            for (repository, file) in files {
+                let changed_file = this
+                    .update(cx, |this, cx| {
+                        this.files.push(ChangedFile {
+                            repository: repository.clone(),
+                            path: file.path.clone(),
+                            old_text: file.old_text.clone(),
+                            is_binary: file.is_binary,
+                            selected: !file.is_binary,
+                        });
+                        cx.notify();
+                    })
+                    .is_ok();
+                if !changed_file {
+                    return;
+                }
+
+                let worktree_id = repository.update(cx, |repository, cx| {
+                    repository
+                        .repo_path_to_project_path(&file.path, cx)
+                        .map(|path| path.worktree_id)
+                        .or(first_worktree_id)
+                });
+                let Some(worktree_id) = worktree_id else {
+                    continue;
+                };
+                let Some((buffer, diff)) =
+                    build_file_diff(file, worktree_id, &language_registry, cx)
+                        .await
+                        .log_err()
+                else {
+                    continue;
+                };
+                this.update(cx, |this, cx| {
+                    this.multibuffer.update(cx, |multibuffer, cx| {
+                        let snapshot = buffer.read(cx).snapshot();
+                        let mut excerpt_ranges = diff
+                            .as_ref()
+                            .map(|diff| {
+                                diff.read(cx)
+                                    .snapshot(cx)
+                                    .hunks(&snapshot)
+                                    .map(|hunk| hunk.buffer_range.to_point(&snapshot))
+                                    .collect::<Vec<_>>()
+                            })
+                            .unwrap_or_default();
+                        if excerpt_ranges.is_empty() {
+                            excerpt_ranges.push(Point::zero()..snapshot.max_point());
+                        }
+                        multibuffer.set_excerpts_for_path(
+                            PathKey::for_buffer(&buffer, cx),
+                            buffer,
+                            excerpt_ranges,
+                            multibuffer_context_lines(cx),
+                            cx,
+                        );
+                        if let Some(diff) = diff {
+                            multibuffer.add_diff(diff, cx);
+                        }
+                    });
+                })
+                .ok();
+            }
+
+            this.update(cx, |this, cx| {
+                this.diff_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn restore_project(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(from) = self.from else {
+            return;
+        };
+        let Some(checkpoint) = self.resolve_checkpoint(from, cx) else {
+            return;
+        };
+        let git_store = self.project.read(cx).git_store().clone();
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            "Restore the project to this checkpoint?",
+            Some("Changes made since then will be lost. The conversation will be kept."),
+            &["Restore", "Cancel"],
+            cx,
+        );
+        self.restore_task = Some(cx.spawn_in(window, async move |this, cx| {
+            if answer.await != Ok(0) {
+                this.update(cx, |this, _| this.restore_task = None).ok();
+                return;
+            }
+            let result = async {
+                let checkpoint = checkpoint.await?;
+                git_store
+                    .update(cx, |git_store, cx| {
+                        git_store.restore_checkpoint(checkpoint, cx)
+                    })
+                    .await
+            }
+            .await;
+            this.update_in(cx, |this, window, cx| {
+                this.restore_task = None;
+                this.update_diff(window, cx);
+                if let Err(error) = result {
+                    this.error = Some(format!("Failed to restore checkpoint: {error}").into());
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// Puts the selected files back the way they were at the "from" checkpoint.
+    fn restore_selected_files(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let files = self
+            .files
+            .iter()
+            .filter(|file| file.selected && !file.is_binary)
+            .filter_map(|file| {
+                let project_path = file
+                    .repository
+                    .read(cx)
+                    .repo_path_to_project_path(&file.path, cx)?;
+                Some((project_path, file.old_text.clone()))
+            })
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return;
+        }
+
+        let message = if files.len() == 1 {
+            "Restore the selected file to this checkpoint?".to_string()
+        } else {
+            format!(
+                "Restore the {} selected files to this checkpoint?",
+                files.len()
+            )
+        };
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &message,
+            Some(
+                "Changes made to them since then will be lost. \
+                Files that didn't exist then will be moved to the trash.",
+            ),
+            &["Restore", "Cancel"],
+            cx,
+        );
+        let project = self.project.clone();
+        self.restore_task = Some(cx.spawn_in(window, async move |this, cx| {
+            if answer.await != Ok(0) {
+                this.update(cx, |this, _| this.restore_task = None).ok();
+                return;
+            }
+            let result = restore_files(project, files, cx).await;
+            this.update_in(cx, |this, window, cx| {
+                this.restore_task = None;
+                this.update_diff(window, cx);
+                if let Err(error) = result {
+                    this.error = Some(format!("Failed to restore files: {error}").into());
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_entry(&self, ix: usize, entry: &TimelineEntry, cx: &mut Context<Self>) -> AnyElement {
+        let id = entry.id;
+        let is_from = self.from == Some(id);
+        let is_to = self.to == Some(id);
+        let previous_title = ix
+            .checked_sub(1)
+            .map(|previous_ix| &self.entries[previous_ix].thread_title);
+
+        v_flex()
+            .when(previous_title != Some(&entry.thread_title), |this| {
+                this.child(
+                    Label::new(entry.thread_title.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
+            })
+            .child(
+                h_flex()
+                    .id(("checkpoint", ix))
+                    .py_0p5()
+                    .px_1()
+                    .gap_1()
+                    .rounded_sm()
+                    .when(is_from || is_to, |this| {
+                        this.bg(cx.theme().colors().element_selected)
+                    })
+                    .child(
+                        Icon::new(match id {
+                            CheckpointId::Message { .. } => IconName::HistoryRerun,
+                            CheckpointId::Current => IconName::Circle,
+                        })
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .child(Label::new(entry.message.clone()).truncate()),
+                    )
+                    .child(
+                        Button::new(("from", ix), "From")
+                            .label_size(LabelSize::Small)
+                            .toggle_state(is_from)
+                            .tooltip(Tooltip::text("Compare from this checkpoint"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.select_from(id, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new(("to", ix), "To")
+                            .label_size(LabelSize::Small)
+                            .toggle_state(is_to)
+                            .tooltip(Tooltip::text("Compare to this checkpoint"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.select_to(id, window, cx)
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn render_file(&self, ix: usize, file: &ChangedFile, cx: &mut Context<Self>) -> AnyElement {
+        let path = file.path.display(PathStyle::local()).to_string();
+        h_flex()
+            .gap_1()
+            .child(
+                Checkbox::new(("file", ix), file.selected.into())
+                    .disabled(file.is_binary)
+                    .on_click(cx.listener(move |this, state, _, cx| {
+                        if let Some(file) = this.files.get_mut(ix) {
+                            file.selected = *state == ToggleState::Selected;
+                            cx.notify();
+                        }
+                    })),
+            )
+            .child(
+                Label::new(path)
+                    .size(LabelSize::Small)
+                    .buffer_font(cx)
+                    .truncate(),
+            )
+            .when(file.old_text.is_none() && !file.is_binary, |this| {
+                this.child(
+                    Label::new("new")
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+async fn build_file_diff(
+    file: CommitFile,
+    worktree_id: WorktreeId,
+    language_registry: &Arc<LanguageRegistry>,
+    cx: &mut AsyncApp,
+) -> Result<(Entity<Buffer>, Option<Entity<BufferDiff>>)> {
+    let is_binary = file.is_binary
+        || file
+            .new_text
+            .as_ref()
+            .is_some_and(|text| is_binary_content(text.as_bytes()))
+        || file
+            .old_text
+            .as_ref()
+            .is_some_and(|text| is_binary_content(text.as_bytes()));
+    let blob = Arc::new(CheckpointBlob {
+        path: file.path.clone(),
+        worktree_id,
+        is_deleted: file.new_text.is_none(),
+        is_binary,
+    }) as Arc<dyn language::File>;
+
+    let mut text = if is_binary {
+        "(binary file not shown)".to_string()
+    } else {
+        file.new_text.unwrap_or_default()
+    };
+    let line_ending = LineEnding::detect(&text);
+    LineEnding::normalize(&mut text);
+    let text = Rope::from(text);
+    let language = cx.update(|cx| language_registry.language_for_file(&blob, Some(&text), cx));
+    let language = match language {
+        Some(language) => language_registry
+            .load_language(&language)
+            .await
+            .ok()
+            .and_then(|language| language.log_err()),
+        None => None,
+    };
+    let buffer = cx.new(|cx| {
+        let buffer = TextBuffer::new_normalized(
+            ReplicaId::LOCAL,
+            cx.entity_id().as_non_zero_u64().into(),
+            line_ending,
+            text,
+        );
+        let mut buffer = Buffer::build(buffer, Some(blob), Capability::ReadOnly);
+        buffer.set_language_async(language.clone(), cx);
+        buffer
+    });
+    if is_binary {
+        return Ok((buffer, None));
+    }
+
+    let mut old_text = file.old_text;
+    if let Some(old_text) = &mut old_text {
+        LineEnding::normalize(old_text);
+    }
+    let snapshot = cx.update(|cx| buffer.read(cx).snapshot());
+    let diff = cx.new(|cx| BufferDiff::new(&snapshot.text, cx));
+    let update = diff
+        .update(cx, |diff, cx| {
+            diff.update_diff(
+                snapshot.text.clone(),
+                old_text.map(|old_text| Arc::from(old_text.as_str())),
+                Some(true),
+                language.clone(),
+                cx,
+            )
+        })
+        .await;
+    diff.update(cx, |diff, cx| {
+        diff.language_changed(language, Some(language_registry.clone()), cx);
+        diff.set_snapshot(update, &snapshot.text, cx)
+    })
+    .await;
+
+    Ok((buffer, Some(diff)))
+}
+
+/// Writes each file's text to disk, moving the files that have no text to the trash.
+async fn restore_files(
+    project: Entity<Project>,
+    files: Vec<(ProjectPath, Option<String>)>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    for (project_path, text) in files {
+        match text {
+            Some(text) => {
+                let buffer = project
+                    .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                    .await?;
+                buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+                project
+                    .update(cx, |project, cx| project.save_buffer(buffer, cx))
+                    .await?;
+            }
+            None => {
+                let delete = project.update(cx, |project, cx| {
+                    project.delete_file(project_path.clone(), true, cx)
+                });
+                if let Some(delete) = delete {
+                    delete
+                        .await
+                        .with_context(|| format!("deleting {}", project_path.path.as_unix_str()))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The contents of a file at a checkpoint.
+struct CheckpointBlob {
+    path: RepoPath,
+    worktree_id: WorktreeId,
+    is_deleted: bool,
+    is_binary: bool,
+}
+
+impl language::File for CheckpointBlob {
+    fn as_local(&self) -> Option<&dyn language::LocalFile> {
+        None
+    }
+
+    fn disk_state(&self) -> DiskState {
+        DiskState::Historic {
+            was_deleted: self.is_deleted,
+        }
+    }
+
+    fn path_style(&self, _: &App) -> PathStyle {
+        PathStyle::local()
+    }
+
+    fn path(&self) -> &Arc<RelPath> {
+        self.path.as_ref()
+    }
+
+    fn full_path(&self, _: &App) -> PathBuf {
+        self.path.as_std_path().to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a App) -> &'a str {
+        self.path.file_name().unwrap_or_default()
+    }
+
+    fn worktree_id(&self, _: &App) -> WorktreeId {
+        self.worktree_id
+    }
+
+    fn to_proto(&self, _cx: &App) -> language::proto::File {
+        unimplemented!()
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+
+    fn can_open(&self) -> bool {
+        !self.is_binary
+    }
+}
+
+impl EventEmitter<EditorEvent> for CheckpointTimeline {}
+
+impl Focusable for CheckpointTimeline {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        if self.multibuffer.read(cx).is_empty() {
+            self.focus_handle.clone()
+        } else {
+            self.editor.focus_handle(cx)
+        }
+    }
+}
+
+impl Item for CheckpointTimeline {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        let title = self.thread.read(cx).title();
+        Label::new(format!("Checkpoints: {}", title))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Checkpoint Timeline".into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some("Checkpoint Timeline".into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+}
+
+impl Render for CheckpointTimeline {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| self.render_entry(ix, entry, cx))
+            .collect::<Vec<_>>();
+        let files = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(ix, file)| self.render_file(ix, file, cx))
+            .collect::<Vec<_>>();
+
+        let is_restoring = self.restore_task.is_some();
+        let from_is_checkpoint = matches!(self.from, Some(CheckpointId::Message { .. }));
+        let selected_files = self
+            .files
+            .iter()
+            .filter(|file| file.selected && !file.is_binary)
+            .count();
+        let is_diffing = self.diff_task.is_some() && self.files.is_empty() && self.error.is_none();
+
+        h_flex()
+            .key_context("CheckpointTimeline")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .w(rems(24.))
+                    .h_full()
+                    .border_r_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        h_flex()
+                            .p_2()
+                            .justify_between()
+                            .child(Label::new("Checkpoints"))
+                            .child(
+                                IconButton::new("refresh-checkpoints", IconName::RotateCcw)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::text("Refresh"))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.refresh(window, cx)
+                                    })),
+                            ),
+                    )
+                    .child(
+                        v_flex()
+                            .id("checkpoint-timeline-entries")
+                            .flex_1()
+                            .min_h_0()
+                            .px_2()
+                            .gap_1()
+                            .overflow_y_scroll()
+                            .children(entries),
+                    )
+                    .child(Divider::horizontal())
+                    .child(
+                        v_flex()
+                            .id("checkpoint-timeline-files")
+                            .max_h(rems(16.))
+                            .p_2()
+                            .gap_1()
+                            .overflow_y_scroll()
+                            .child(
+                                Label::new(format!("Changed Files ({})", self.files.len()))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .children(files),
+                    )
+                    .child(
+                        h_flex()
+                            .p_2()
+                            .gap_1()
+                            .justify_end()
+                            .border_t_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(
+                                Button::new("restore-files", "Restore Selected Files")
+                                    .label_size(LabelSize::Small)
+                                    .disabled(
+                                        is_restoring || !from_is_checkpoint || selected_files == 0,
+                                    )
+                                    .tooltip(Tooltip::text(
+                                        "Put the selected files back the way they were at the \"From\" checkpoint",
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.restore_selected_files(window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("restore-project", "Restore Project")
+                                    .label_size(LabelSize::Small)
+                                    .style(ButtonStyle::Filled)
+                                    .disabled(is_restoring || !from_is_checkpoint)
+                                    .tooltip(Tooltip::text(
+                                        "Restore the whole project to the \"From\" checkpoint",
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.restore_project(window, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .h_full()
+                    .map(|this| {
+                        if let Some(error) = self.error.clone() {
+                            this.items_center()
+                                .justify_center()
+                                .child(Label::new(error).color(Color::Error))
+                        } else if self.multibuffer.read(cx).is_empty() {
+                            this.items_center().justify_center().child(
+                                Label::new(if is_diffing {
+                                    "Loading changes…"
+                                } else if self.from.is_none() {
+                                    "No checkpoints yet"
+                                } else {
+                                    "No changes between these checkpoints"
+                                })
+                                .color(Color::Muted),
+                            )
+                        } else {
+                            this.child(self.editor.clone())
+                        }
+                    }),
+            )
+    }
+}
@@ -1,22 +1,29 @@
 use crate::{RemoveHistory, RemoveSelectedThread};
-use assistant_text_thread::{SavedTextThreadMetadata, TextThreadStore};
+use agent::{ThreadSearchDocument, ThreadSearchEntry};
+use anyhow::Result;
+use assistant_text_thread::{SavedTextThread, SavedTextThreadMetadata, TextThreadStore};
 use chrono::{Datelike, Local, NaiveDate, TimeDelta, Utc};
 use editor::{Editor, EditorEvent};
 use fuzzy::StringMatchCandidate;
 use gpui::{
-    App, Entity, EventEmitter, FocusHandle, Focusable, Task, UniformListScrollHandle, Window,
-    uniform_list,
+    App, AsyncApp, Entity, EventEmitter, FocusHandle, Focusable, Task, UniformListScrollHandle,
+    Window, uniform_list,
 };
-use std::{fmt::Display, ops::Range};
+use language_model::Role;
+use std::{fmt::Display, ops::Range, sync::Arc};
 use text::Bias;
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
     HighlightedLabel, IconButtonShape, ListItem, ListItemSpacing, Tab, Tooltip, WithScrollbar,
     prelude::*,
 };
+use util::ResultExt as _;
 
 const DEFAULT_TITLE: &SharedString = &SharedString::new_static("New Thread");
 
+/// The source that text threads are indexed under in the thread search index.
+const TEXT_THREADS_SEARCH_SOURCE: &str = "Text Threads";
+
 fn thread_title(entry: &SavedTextThreadMetadata) -> &SharedString {
     if entry.title.is_empty() {
         DEFAULT_TITLE
@@ -36,6 +43,7 @@ pub struct TextThreadHistory {
     local_timezone: UtcOffset,
     confirming_delete_history: bool,
     _update_task: Task<()>,
+    _index_task: Task<()>,
     _subscriptions: Vec<gpui::Subscription>,
 }
 
@@ -49,6 +57,11 @@ enum ListItemType {
         entry: SavedTextThreadMetadata,
         positions: Vec<usize>,
     },
+    MessageSearchResult {
+        entry: SavedTextThreadMetadata,
+        snippet: SharedString,
+        positions: Vec<usize>,
+    },
 }
 
 impl ListItemType {
@@ -56,6 +69,7 @@ impl ListItemType {
         match self {
             ListItemType::Entry { entry, .. } => Some(entry),
             ListItemType::SearchResult { entry, .. } => Some(entry),
+            ListItemType::MessageSearchResult { entry, .. } => Some(entry),
             _ => None,
         }
     }
@@ -92,6 +106,7 @@ impl TextThreadHistory {
 
         let store_subscription = cx.observe(&text_thread_store, |this, _, cx| {
             this.update_visible_items(true, cx);
+            this.index_text_threads(cx);
         });
 
         let scroll_handle = UniformListScrollHandle::default();
@@ -111,11 +126,29 @@ impl TextThreadHistory {
             confirming_delete_history: false,
             _subscriptions: vec![search_editor_subscription, store_subscription],
             _update_task: Task::ready(()),
+            _index_task: Task::ready(()),
         };
         this.update_visible_items(false, cx);
+        this.index_text_threads(cx);
         this
     }
 
+    /// Adds the saved text threads that changed since they were last indexed to the thread
+    /// search index.
+    fn index_text_threads(&mut self, cx: &mut Context<Self>) {
+        let text_thread_store = self.text_thread_store.clone();
+        let text_threads = text_thread_store
+            .read(cx)
+            .ordered_text_threads()
+            .cloned()
+            .collect::<Vec<_>>();
+        self._index_task = cx.spawn(async move |_, cx| {
+            index_text_threads(text_thread_store, text_threads, cx)
+                .await
+                .log_err();
+        });
+    }
+
     fn update_visible_items(&mut self, preserve_selected_item: bool, cx: &mut Context<Self>) {
         let entries = self.text_thread_store.update(cx, |store, _| {
             store.ordered_text_threads().cloned().collect::<Vec<_>>()
@@ -187,9 +220,17 @@ impl TextThreadHistory {
     fn filter_search_results(
         &self,
         entries: Vec<SavedTextThreadMetadata>,
-        cx: &App,
+        cx: &mut App,
     ) -> Task<Vec<ListItemType>> {
+        const MAX_MESSAGE_MATCHES: usize = 50;
+
         let query = self.search_query.clone();
+        let message_matches = agent::search_threads(
+            &query,
+            Some(TEXT_THREADS_SEARCH_SOURCE.into()),
+            MAX_MESSAGE_MATCHES,
+            cx,
+        );
         cx.background_spawn({
             let executor = cx.background_executor().clone();
             async move {
@@ -212,13 +253,35 @@ impl TextThreadHistory {
                 )
                 .await;
 
-                matches
+                let mut items = matches
                     .into_iter()
                     .map(|search_match| ListItemType::SearchResult {
                         entry: entries[search_match.candidate_id].clone(),
                         positions: search_match.positions,
                     })
-                    .collect()
+                    .collect::<Vec<_>>();
+
+                let message_matches = message_matches.await.log_err().unwrap_or_default();
+                items.extend(message_matches.into_iter().filter_map(|message_match| {
+                    let entry = entries.iter().find(|entry| {
+                        entry.path.to_string_lossy().as_ref() == message_match.thread_id.as_ref()
+                    })?;
+                    let positions = message_match
+                        .highlights
+                        .iter()
+                        .flat_map(|range| {
+                            message_match.snippet[range.clone()]
+                                .char_indices()
+                                .map(move |(ix, _)| range.start + ix)
+                        })
+                        .collect();
+                    Some(ListItemType::MessageSearchResult {
+                        entry: entry.clone(),
+                        snippet: message_match.snippet,
+                        positions,
+                    })
+                }));
+                items
             }
         })
     }
@@ -324,16 +387,25 @@ impl TextThreadHistory {
             return;
         };
 
+        let path = entry.path.clone();
         let task = self
             .text_thread_store
-            .update(cx, |store, cx| store.delete_local(entry.path.clone(), cx));
+            .update(cx, |store, cx| store.delete_local(path.clone(), cx));
         task.detach_and_log_err(cx);
+        agent::remove_from_search_index(
+            TEXT_THREADS_SEARCH_SOURCE.into(),
+            Some(path.to_string_lossy().into()),
+            cx,
+        )
+        .detach_and_log_err(cx);
     }
 
     fn remove_history(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         self.text_thread_store.update(cx, |store, cx| {
             store.delete_all_local(cx).detach_and_log_err(cx)
         });
+        agent::remove_from_search_index(TEXT_THREADS_SEARCH_SOURCE.into(), None, cx)
+            .detach_and_log_err(cx);
         self.confirming_delete_history = false;
         cx.notify();
     }
@@ -375,6 +447,11 @@ impl TextThreadHistory {
                 positions.clone(),
                 cx,
             ),
+            ListItemType::MessageSearchResult {
+                entry,
+                snippet,
+                positions,
+            } => self.render_message_search_result(entry, snippet, positions.clone(), ix, cx),
             ListItemType::BucketSeparator(bucket) => div()
                 .px(DynamicSpacing::Base06.rems(cx))
                 .pt_2()
@@ -388,6 +465,69 @@ impl TextThreadHistory {
         }
     }
 
+    fn render_message_search_result(
+        &self,
+        entry: &SavedTextThreadMetadata,
+        snippet: &SharedString,
+        highlight_positions: Vec<usize>,
+        ix: usize,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let title = thread_title(entry).clone();
+        let tooltip_snippet = snippet.clone();
+
+        h_flex()
+            .w_full()
+            .pb_1()
+            .child(
+                ListItem::new(ix)
+                    .rounded()
+                    .toggle_state(ix == self.selected_index)
+                    .spacing(ListItemSpacing::Sparse)
+                    .start_slot(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .justify_between()
+                            .child(
+                                h_flex()
+                                    .min_w_0()
+                                    .gap_1p5()
+                                    .child(
+                                        Icon::new(IconName::Chat)
+                                            .color(Color::Muted)
+                                            .size(IconSize::XSmall),
+                                    )
+                                    .child(
+                                        HighlightedLabel::new(snippet.clone(), highlight_positions)
+                                            .size(LabelSize::Small)
+                                            .truncate(),
+                                    ),
+                            )
+                            .child(
+                                Label::new(title.clone())
+                                    .color(Color::Muted)
+                                    .size(LabelSize::XSmall)
+                                    .truncate(),
+                            ),
+                    )
+                    .tooltip(move |_, cx| {
+                        Tooltip::with_meta(title.clone(), None, tooltip_snippet.clone(), cx)
+                    })
+                    .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
+                        if *is_hovered {
+                            this.hovered_index = Some(ix);
+                        } else if this.hovered_index == Some(ix) {
+                            this.hovered_index = None;
+                        }
+
+                        cx.notify();
+                    }))
+                    .on_click(cx.listener(move |this, _, _, cx| this.confirm_entry(ix, cx))),
+            )
+            .into_any_element()
+    }
+
     fn render_history_entry(
         &self,
         entry: &SavedTextThreadMetadata,
@@ -700,10 +840,123 @@ impl Display for TimeBucket {
     }
 }
 
+/// Indexes the saved text threads that changed since they were last indexed.
+async fn index_text_threads(
+    text_thread_store: Entity<TextThreadStore>,
+    text_threads: Vec<SavedTextThreadMetadata>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let indexed_threads = cx
+        .update(|cx| agent::indexed_threads(TEXT_THREADS_SEARCH_SOURCE.into(), cx))
+        .await?;
+
+    for metadata in text_threads {
+        let thread_id: Arc<str> = metadata.path.to_string_lossy().into();
+        let updated_at = metadata.mtime.with_timezone(&Utc);
+        if indexed_threads
+            .get(&thread_id)
+            .is_some_and(|indexed_at| *indexed_at >= updated_at)
+        {
+            continue;
+        }
+
+        let load = text_thread_store.read_with(cx, |store, cx| {
+            store.load_saved_text_thread(metadata.path.clone(), cx)
+        });
+        let Some(text_thread) = load.await.log_err() else {
+            continue;
+        };
+        let entry = ThreadSearchEntry {
+            source: TEXT_THREADS_SEARCH_SOURCE.into(),
+            thread_id,
+            title: metadata.title.clone(),
+            updated_at,
+            documents: text_thread_search_documents(&text_thread),
+        };
+        cx.update(|cx| agent::index_thread(entry, cx))
+            .await
+            .log_err();
+    }
+
+    Ok(())
+}
+
+/// Splits a saved text thread into turns for the search index, each starting at a user message.
+fn text_thread_search_documents(text_thread: &SavedTextThread) -> Vec<ThreadSearchDocument> {
+    let mut documents: Vec<ThreadSearchDocument> = Vec::new();
+    let mut messages = text_thread.messages.iter().peekable();
+    while let Some(message) = messages.next() {
+        let end = messages
+            .peek()
+            .map_or(text_thread.text.len(), |next_message| next_message.start);
+        let Some(text) = text_thread.text.get(message.start..end) else {
+            continue;
+        };
+        if message.metadata.role == Role::User {
+            documents.push(ThreadSearchDocument::new(documents.len()));
+        }
+        if let Some(document) = documents.last_mut() {
+            document.push_text(text);
+        }
+    }
+    documents
+}
+
 #[cfg(test)]
 mod tests {
+    use assistant_text_thread::{MessageId, MessageMetadata, MessageStatus, SavedMessage};
+
     use super::*;
 
+    #[test]
+    fn test_text_thread_search_documents() {
+        let mut clock = clock::Lamport::new(clock::ReplicaId::LOCAL);
+        let text = "You are terse.\nWhy does the parser fail?\nIt chokes on trailing commas.\n\nAnd the lexer?\n";
+        let mut message = |start: usize, role: Role| SavedMessage {
+            id: MessageId(clock.tick()),
+            start,
+            metadata: MessageMetadata {
+                role,
+                status: MessageStatus::Done,
+                timestamp: clock.tick(),
+                cache: None,
+            },
+        };
+        let messages = vec![
+            message(0, Role::System),
+            message(text.find("Why").unwrap(), Role::User),
+            message(text.find("It chokes").unwrap(), Role::Assistant),
+            message(text.find("\nAnd").unwrap(), Role::User),
+        ];
+        let text_thread = SavedTextThread {
+            id: None,
+            zed: "context".into(),
+            version: SavedTextThread::VERSION.into(),
+            text: text.into(),
+            messages,
+            summary: "Parser".into(),
+            slash_command_output_sections: Vec::new(),
+            thought_process_output_sections: Vec::new(),
+        };
+
+        // The system prompt comes before the first turn, so it isn't indexed.
+        let documents = text_thread_search_documents(&text_thread);
+        let bodies = documents
+            .iter()
+            .map(|document| (document.turn, document.body.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bodies,
+            [
+                (
+                    0,
+                    "Why does the parser fail?\nIt chokes on trailing commas."
+                ),
+                (1, "And the lexer?"),
+            ]
+        );
+    }
+
     #[test]
     fn test_time_bucket_from_dates() {
         let today = NaiveDate::from_ymd_opt(2023, 1, 15).unwrap();
@@ -116,6 +116,7 @@ impl AgentThreadPane {
                 agent,
                 resume_thread,
                 None,
+                None,
                 workspace,
                 project,
                 Some(thread_store),
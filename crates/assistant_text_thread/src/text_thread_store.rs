@@ -454,15 +454,8 @@ impl TextThreadStore {
             return Task::ready(Ok(existing_context));
         }
 
-        let fs = self.fs.clone();
         let languages = self.languages.clone();
-        let load = cx.background_spawn({
-            let path = path.clone();
-            async move {
-                let saved_context = fs.load(&path).await?;
-                SavedTextThread::from_json(&saved_context)
-            }
-        });
+        let load = self.load_saved_text_thread(path.clone(), cx);
         let prompt_builder = self.prompt_builder.clone();
         let slash_commands = self.slash_commands.clone();
 
@@ -489,6 +482,19 @@ impl TextThreadStore {
         })
     }
 
+    /// Reads a saved text thread without opening it.
+    pub fn load_saved_text_thread(
+        &self,
+        path: Arc<Path>,
+        cx: &App,
+    ) -> Task<Result<SavedTextThread>> {
+        let fs = self.fs.clone();
+        cx.background_spawn(async move {
+            let saved_context = fs.load(&path).await?;
+            SavedTextThread::from_json(&saved_context)
+        })
+    }
+
     pub fn delete_local(&mut self, path: Arc<Path>, cx: &mut Context<Self>) -> Task<Result<()>> {
         let fs = self.fs.clone();
 
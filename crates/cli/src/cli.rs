@@ -22,6 +22,32 @@ pub enum CliRequest {
         env: Option<HashMap<String, String>>,
         user_data_dir: Option<String>,
     },
+    /// Controls the agent panel of the workspace that contains `cwd`.
+    Agent {
+        command: CliAgentCommand,
+        cwd: String,
+        user_data_dir: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum CliAgentCommand {
+    /// Opens a tab that sends the prompt to the agent, or to the selected agent.
+    New {
+        agent: Option<String>,
+        prompt: String,
+        wait: bool,
+    },
+    /// Lists the agent tabs.
+    List,
+    /// Sends a message to the agent in a tab.
+    Send {
+        tab: usize,
+        message: String,
+        wait: bool,
+    },
+    /// Waits for the agent in a tab to finish and prints its final message.
+    Wait { tab: usize },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
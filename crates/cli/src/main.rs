@@ -8,8 +8,8 @@
 )]
 
 use anyhow::{Context as _, Result};
-use clap::Parser;
-use cli::{CliRequest, CliResponse, IpcHandshake, ipc::IpcOneShotServer};
+use clap::{Parser, Subcommand};
+use cli::{CliAgentCommand, CliRequest, CliResponse, IpcHandshake, ipc::IpcOneShotServer};
 use parking_lot::Mutex;
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -58,7 +58,9 @@ Examples:
     `zed path-to-your-project`
           Open your project in Zed
     `zed -n path-to-file `
-          Open file/folder in a new window",
+          Open file/folder in a new window
+    `zed agent new --agent claude-code \"fix the failing tests\"`
+          Start an agent in the project that contains the current directory",
     after_help = "To read from stdin, append '-', e.g. 'ps axf | zed -'"
 )]
 struct Args {
@@ -134,6 +136,63 @@ struct Args {
     /// by having Zed act like netcat communicating over a Unix socket.
     #[arg(long, hide = true)]
     askpass: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Start and control agents in the agent panel of the workspace that contains the current
+    /// directory.
+    #[command(subcommand)]
+    Agent(AgentCommand),
+}
+
+#[derive(Subcommand, Debug)]
+enum AgentCommand {
+    /// Open an agent tab that starts working on the prompt, and print the tab's id.
+    New {
+        /// The agent to use, like `zed`, `claude-code`, `codex`, `gemini`, or the name of a
+        /// custom agent. Defaults to the agent selected in the panel.
+        #[arg(long)]
+        agent: Option<String>,
+        /// Wait for the agent to finish and print its final message instead.
+        #[arg(short, long)]
+        wait: bool,
+        prompt: String,
+    },
+    /// List the agent tabs with their ids, agents, and statuses.
+    List,
+    /// Send a message to the agent in a tab, queueing it if the agent is busy.
+    Send {
+        tab: usize,
+        message: String,
+        /// Wait for the agent to finish and print its final message.
+        #[arg(short, long)]
+        wait: bool,
+    },
+    /// Wait for the agent in a tab to finish and print its final message.
+    Wait { tab: usize },
+}
+
+impl From<AgentCommand> for CliAgentCommand {
+    fn from(command: AgentCommand) -> Self {
+        match command {
+            AgentCommand::New {
+                agent,
+                wait,
+                prompt,
+            } => Self::New {
+                agent,
+                prompt,
+                wait,
+            },
+            AgentCommand::List => Self::List,
+            AgentCommand::Send { tab, message, wait } => Self::Send { tab, message, wait },
+            AgentCommand::Wait { tab } => Self::Wait { tab },
+        }
+    }
 }
 
 /// Parses a path containing a position (e.g. `path:line:column`)
@@ -313,6 +372,37 @@ mod tests {
         result
     }
 
+    #[test]
+    fn test_parse_agent_command() {
+        let args =
+            Args::try_parse_from(["zed", "agent", "new", "--agent", "codex", "fix it"]).unwrap();
+        assert!(args.paths_with_position.is_empty());
+        assert!(matches!(
+            args.command,
+            Some(Command::Agent(AgentCommand::New { agent: Some(agent), wait: false, prompt }))
+                if agent == "codex" && prompt == "fix it"
+        ));
+
+        let args = Args::try_parse_from(["zed", "agent", "send", "-w", "2", "go on"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Agent(AgentCommand::Send {
+                tab: 2,
+                wait: true,
+                ..
+            }))
+        ));
+
+        let args = Args::try_parse_from(["zed", "file.txt"]).unwrap();
+        assert!(args.command.is_none());
+        assert_eq!(args.paths_with_position, ["file.txt"]);
+
+        // A path named like the subcommand has to be written so that it doesn't parse as one.
+        let args = Args::try_parse_from(["zed", "./agent"]).unwrap();
+        assert!(args.command.is_none());
+        assert_eq!(args.paths_with_position, ["./agent"]);
+    }
+
     #[test]
     fn test_parse_non_existing_path() {
         // Absolute path
@@ -638,6 +728,29 @@ fn main() -> Result<()> {
         "Dev servers were removed in v0.157.x please upgrade to SSH remoting: https://zed.dev/docs/remote-development"
     );
 
+    let request = match args.command {
+        Some(Command::Agent(command)) => CliRequest::Agent {
+            command: command.into(),
+            cwd: env::current_dir()
+                .context("retrieving current directory")?
+                .to_string_lossy()
+                .into_owned(),
+            user_data_dir: user_data_dir.clone(),
+        },
+        None => CliRequest::Open {
+            paths,
+            urls,
+            diff_paths,
+            diff_all: diff_all_mode,
+            wsl: wsl.map(ToString::to_string),
+            wait: args.wait,
+            open_new_workspace,
+            reuse: args.reuse,
+            env,
+            user_data_dir: user_data_dir.clone(),
+        },
+    };
+
     rayon::ThreadPoolBuilder::new()
         .num_threads(4)
         .stack_size(10 * 1024 * 1024)
@@ -649,28 +762,11 @@ fn main() -> Result<()> {
         .name("CliReceiver".to_string())
         .spawn({
             let exit_status = exit_status.clone();
-            let user_data_dir_for_thread = user_data_dir.clone();
             move || {
                 let (_, handshake) = server.accept().context("Handshake after Zed spawn")?;
                 let (tx, rx) = (handshake.requests, handshake.responses);
 
-                #[cfg(target_os = "windows")]
-                let wsl = args.wsl;
-                #[cfg(not(target_os = "windows"))]
-                let wsl = None;
-
-                tx.send(CliRequest::Open {
-                    paths,
-                    urls,
-                    diff_paths,
-                    diff_all: diff_all_mode,
-                    wsl,
-                    wait: args.wait,
-                    open_new_workspace,
-                    reuse: args.reuse,
-                    env,
-                    user_data_dir: user_data_dir_for_thread,
-                })?;
+                tx.send(request)?;
 
                 while let Ok(response) = rx.recv() {
                     match response {
@@ -19,7 +19,7 @@ use std::{
     path::{Path, PathBuf},
     rc::Rc,
 };
-use util::ResultExt;
+use util::{ResultExt, post_inc};
 
 use crate::{
     client::{CspResult, RequestId, Response},
@@ -34,9 +34,14 @@ pub struct McpServer {
     socket_path: PathBuf,
     tools: Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
     handlers: Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
+    connection_closed_handlers: Rc<RefCell<Vec<ConnectionClosedHandler>>>,
     _server_task: Task<()>,
 }
 
+/// Identifies one of the clients connected to an [`McpServer`], for as long as it stays connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConnectionId(pub usize);
+
 struct RegisteredTool {
     tool: Tool,
     handler: ToolHandler,
@@ -45,10 +50,12 @@ struct RegisteredTool {
 type ToolHandler = Box<
     dyn Fn(
         Option<serde_json::Value>,
+        ConnectionId,
         &mut AsyncApp,
     ) -> Task<Result<ToolResponse<serde_json::Value>>>,
 >;
 type RequestHandler = Box<dyn Fn(RequestId, Option<Box<RawValue>>, &App) -> Task<String>>;
+type ConnectionClosedHandler = Box<dyn Fn(ConnectionId, &mut App)>;
 
 impl McpServer {
     pub fn new(cx: &AsyncApp) -> Task<Result<Self>> {
@@ -64,12 +71,23 @@ impl McpServer {
             let (temp_dir, socket_path, listener) = task.await?;
             let tools = Rc::new(RefCell::new(HashMap::default()));
             let handlers = Rc::new(RefCell::new(HashMap::default()));
+            let connection_closed_handlers = Rc::new(RefCell::new(Vec::new()));
             let server_task = cx.spawn({
                 let tools = tools.clone();
                 let handlers = handlers.clone();
+                let connection_closed_handlers = connection_closed_handlers.clone();
                 async move |cx| {
+                    let mut next_connection_id = 0;
                     while let Ok((stream, _)) = listener.accept().await {
-                        Self::serve_connection(stream, tools.clone(), handlers.clone(), cx);
+                        let connection = ConnectionId(post_inc(&mut next_connection_id));
+                        Self::serve_connection(
+                            stream,
+                            connection,
+                            tools.clone(),
+                            handlers.clone(),
+                            connection_closed_handlers.clone(),
+                            cx,
+                        );
                     }
                     drop(temp_dir)
                 }
@@ -79,6 +97,7 @@ impl McpServer {
                 _server_task: server_task,
                 tools,
                 handlers,
+                connection_closed_handlers,
             })
         })
     }
@@ -112,7 +131,7 @@ impl McpServer {
                 annotations: Some(tool.annotations()),
             },
             handler: Box::new({
-                move |input_value, cx| {
+                move |input_value, connection, cx| {
                     let input = match input_value {
                         Some(input) => serde_json::from_value(input),
                         None => serde_json::from_value(serde_json::Value::Null),
@@ -121,7 +140,7 @@ impl McpServer {
                     let tool = tool.clone();
                     match input {
                         Ok(input) => cx.spawn(async move |cx| {
-                            let output = tool.run(input, cx).await?;
+                            let output = tool.run(input, connection, cx).await?;
 
                             Ok(ToolResponse {
                                 content: output.content,
@@ -191,14 +210,24 @@ impl McpServer {
         );
     }
 
+    /// Calls `f` whenever a client disconnects, so that state kept for its connection can be
+    /// dropped.
+    pub fn on_connection_closed(&mut self, f: impl Fn(ConnectionId, &mut App) + 'static) {
+        self.connection_closed_handlers
+            .borrow_mut()
+            .push(Box::new(f));
+    }
+
     pub fn socket_path(&self) -> &Path {
         &self.socket_path
     }
 
     fn serve_connection(
         stream: UnixStream,
+        connection: ConnectionId,
         tools: Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
         handlers: Rc<RefCell<HashMap<&'static str, RequestHandler>>>,
+        connection_closed_handlers: Rc<RefCell<Vec<ConnectionClosedHandler>>>,
         cx: &mut AsyncApp,
     ) {
         let (read, write) = smol::io::split(stream);
@@ -215,8 +244,15 @@ impl McpServer {
                 };
 
                 if request.method == CallTool::METHOD {
-                    Self::handle_call_tool(request_id, request.params, &tools, &outgoing_tx, cx)
-                        .await;
+                    Self::handle_call_tool(
+                        request_id,
+                        request.params,
+                        connection,
+                        &tools,
+                        &outgoing_tx,
+                        cx,
+                    )
+                    .await;
                 } else if request.method == ListTools::METHOD {
                     Self::handle_list_tools(request.id.unwrap(), &tools, &outgoing_tx);
                 } else if let Some(handler) = handlers.borrow().get(&request.method.as_ref()) {
@@ -236,6 +272,12 @@ impl McpServer {
                     );
                 }
             }
+
+            cx.update(|cx| {
+                for handler in connection_closed_handlers.borrow().iter() {
+                    handler(connection, cx);
+                }
+            });
         })
         .detach();
     }
@@ -266,6 +308,7 @@ impl McpServer {
     async fn handle_call_tool(
         request_id: RequestId,
         params: Option<Box<RawValue>>,
+        connection: ConnectionId,
         tools: &Rc<RefCell<HashMap<&'static str, RegisteredTool>>>,
         outgoing_tx: &UnboundedSender<String>,
         cx: &mut AsyncApp,
@@ -280,7 +323,7 @@ impl McpServer {
                 if let Some(tool) = tools.borrow().get(&params.name.as_ref()) {
                     let outgoing_tx = outgoing_tx.clone();
 
-                    let task = (tool.handler)(params.arguments, cx);
+                    let task = (tool.handler)(params.arguments, connection, cx);
                     cx.spawn(async move |_| {
                         let response = match task.await {
                             Ok(result) => CallToolResponse {
@@ -413,9 +456,11 @@ pub trait McpServerTool {
         }
     }
 
+    /// Runs the tool for the client on the given connection.
     fn run(
         &self,
         input: Self::Input,
+        connection: ConnectionId,
         cx: &mut AsyncApp,
     ) -> impl Future<Output = Result<ToolResponse<Self::Output>>>;
 }
@@ -53,6 +53,12 @@ impl KeyValueStore {
         }
     }
 
+    query! {
+        pub fn read_kvps_with_prefix(prefix: &str) -> Result<Vec<(String, String)>> {
+            SELECT key, value FROM kv_store WHERE substr(key, 1, length(?1)) = ?1
+        }
+    }
+
     pub async fn write_kvp(&self, key: String, value: String) -> anyhow::Result<()> {
         log::debug!("Writing key-value pair for key {key}");
         self.write_kvp_inner(key, value).await
@@ -99,6 +105,27 @@ mod tests {
         db.delete_kvp("key-1".to_string()).await.unwrap();
         assert_eq!(db.read_kvp("key-1").unwrap(), None);
     }
+
+    #[gpui::test]
+    async fn test_read_kvps_with_prefix() {
+        let db = KeyValueStore::open_test_db("test_read_kvps_with_prefix").await;
+
+        for key in ["panel", "panel-1", "panel-2", "other-panel-3"] {
+            db.write_kvp(key.to_string(), key.to_uppercase())
+                .await
+                .unwrap();
+        }
+
+        let mut kvps = db.read_kvps_with_prefix("panel-").unwrap();
+        kvps.sort();
+        assert_eq!(
+            kvps,
+            vec![
+                ("panel-1".to_string(), "PANEL-1".to_string()),
+                ("panel-2".to_string(), "PANEL-2".to_string()),
+            ]
+        );
+    }
 }
 
 pub struct GlobalKeyValueStore(ThreadSafeConnection);
@@ -17,6 +17,7 @@ use zed_actions::agent::AddSelectionToThread;
 use zed_actions::preview::{
     markdown::OpenPreview as OpenMarkdownPreview, svg::OpenPreview as OpenSvgPreview,
 };
+use zed_actions::symbol_hierarchy::{ShowCallHierarchy, ShowTypeHierarchy};
 
 #[derive(Debug)]
 pub enum MenuPosition {
@@ -260,6 +261,8 @@ pub fn deploy_context_menu(
                     "Find All References",
                     Box::new(FindAllReferences::default()),
                 )
+                .action("Show Call Hierarchy", Box::new(ShowCallHierarchy))
+                .action("Show Type Hierarchy", Box::new(ShowTypeHierarchy))
                 .separator()
                 .action("Rename Symbol", Box::new(Rename))
                 .action("Format Buffer", Box::new(Format))
@@ -662,15 +662,23 @@ impl agent::ThreadEnvironment for EvalThreadEnvironment {
             let language_registry =
                 project.read_with(cx, |project, _cx| project.languages().clone());
             let id = acp::TerminalId::new(uuid::Uuid::new_v4().to_string());
-            let terminal =
-                acp_thread::create_terminal_entity(command, &[], vec![], cwd.clone(), &project, cx)
-                    .await?;
+            let terminal = acp_thread::create_terminal_entity(
+                command,
+                &[],
+                vec![],
+                cwd.clone(),
+                None,
+                &project,
+                cx,
+            )
+            .await?;
             let terminal = cx.new(|cx| {
                 acp_thread::Terminal::new(
                     id,
                     "",
                     cwd,
                     output_byte_limit.map(|limit| limit as usize),
+                    None,
                     terminal,
                     language_registry,
                     cx,
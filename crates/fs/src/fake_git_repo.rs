@@ -1,14 +1,18 @@
-use crate::{FakeFs, FakeFsEntry, Fs};
+use crate::{FakeFs, FakeFsEntry, Fs, RemoveOptions};
 use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet};
-use futures::future::{self, BoxFuture, join_all};
+use futures::{
+    StreamExt as _,
+    future::{self, BoxFuture, join_all},
+};
 use git::{
     Oid, RunHook,
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDataReader, CommitDetails, CommitOptions, FetchOptions,
-        GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        AskPassDelegate, BisectMark, BisectStatus, Branch, CommitDataReader, CommitDetails,
+        CommitOptions, FetchOptions, GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, RebaseAction, RebaseTodoEntry,
+        Remote, RepoPath, ResetMode, Tag, Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
@@ -20,7 +24,11 @@ use ignore::gitignore::GitignoreBuilder;
 use parking_lot::Mutex;
 use rope::Rope;
 use smol::{channel::Sender, future::FutureExt as _};
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    hash::{Hash as _, Hasher as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use text::LineEnding;
 use util::{paths::PathStyle, rel_path::RelPath};
 
@@ -46,11 +54,74 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    /// Branches with commits that aren't merged into HEAD, so only a forced delete removes them.
+    pub unmerged_branches: HashSet<String>,
+    /// Tag names, mapped to the commits they point to
+    pub tags: HashMap<String, String>,
     /// List of remotes, keys are names and values are URLs
     pub remotes: HashMap<String, String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
     pub graph_commits: Vec<Arc<InitialGraphCommitData>>,
+    /// Commits on the current branch, oldest first, as cherry-picks, reverts, and rebases
+    /// rewrite them.
+    pub commits: Vec<FakeCommit>,
+    /// Commits that stop a cherry-pick, revert, or rebase on a conflict when they're applied.
+    pub conflicting_commits: HashSet<String>,
+    /// The cherry-pick, revert, or rebase that stopped on a conflict.
+    pub operation: Option<FakeOperation>,
+    /// The bisect in progress, over `commits`.
+    pub bisect: Option<FakeBisect>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeCommit {
+    pub sha: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FakeOperationKind {
+    CherryPick,
+    Revert,
+    Rebase,
+}
+
+impl FakeOperationKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+            Self::Rebase => "rebase",
+        }
+    }
+
+    /// The ref git points at the commit that stopped the operation.
+    fn head_ref(self) -> &'static str {
+        match self {
+            Self::CherryPick => "CHERRY_PICK_HEAD",
+            Self::Revert => "REVERT_HEAD",
+            Self::Rebase => "REBASE_HEAD",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeOperation {
+    pub kind: FakeOperationKind,
+    /// The branch's commits before the operation started, restored when it's aborted.
+    pub original_commits: Vec<FakeCommit>,
+    /// The step that stopped on a conflict, followed by the steps still to be applied.
+    pub remaining: Vec<RebaseTodoEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FakeBisect {
+    /// The commit that was checked out when the bisect started, checked out again on reset.
+    pub original_head: String,
+    pub good: HashSet<String>,
+    pub bad: Option<String>,
+    pub skipped: HashSet<String>,
 }
 
 impl FakeGitRepositoryState {
@@ -63,14 +134,197 @@ impl FakeGitRepositoryState {
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            unmerged_branches: Default::default(),
+            tags: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
             merge_base_contents: Default::default(),
             oids: Default::default(),
             remotes: HashMap::default(),
             graph_commits: Vec::new(),
+            commits: Vec::new(),
+            conflicting_commits: HashSet::default(),
+            operation: None,
+            bisect: None,
+        }
+    }
+
+    fn commit_index(&self, sha: &str) -> Result<usize> {
+        self.commits
+            .iter()
+            .position(|commit| commit.sha == sha)
+            .with_context(|| format!("bad revision '{sha}'"))
+    }
+
+    /// Narrows the bisect down the way git does on a linear history, checking out the commit
+    /// halfway between the last good commit and the first bad one.
+    fn bisect_status(&mut self) -> Result<BisectStatus> {
+        let bisect = self.bisect.as_ref().context("not bisecting")?;
+        let plural = |count: usize, noun: &str| {
+            if count == 1 {
+                format!("{count} {noun}")
+            } else {
+                format!("{count} {noun}s")
+            }
+        };
+        let Some(bad) = bisect.bad.clone() else {
+            let message = if bisect.good.is_empty() {
+                "waiting for both good and bad commits".to_string()
+            } else {
+                format!(
+                    "waiting for bad commit, {} known",
+                    plural(bisect.good.len(), "good commit")
+                )
+            };
+            return Ok(BisectStatus::Waiting {
+                message: message.into(),
+            });
+        };
+        if bisect.good.is_empty() {
+            return Ok(BisectStatus::Waiting {
+                message: "waiting for good commit(s), bad commit known".into(),
+            });
+        }
+
+        let bad_ix = self.commit_index(&bad)?;
+        let mut last_good_ix = 0;
+        for good in &bisect.good {
+            let good_ix = self.commit_index(good)?;
+            if good_ix >= bad_ix {
+                bail!("Some good revs are not ancestors of the bad rev.");
+            }
+            last_good_ix = last_good_ix.max(good_ix);
+        }
+        let untested = self.commits[last_good_ix + 1..bad_ix]
+            .iter()
+            .filter(|commit| !bisect.skipped.contains(&commit.sha))
+            .cloned()
+            .collect::<Vec<_>>();
+        if untested.is_empty() {
+            if bad_ix > last_good_ix + 1 {
+                return Ok(BisectStatus::Waiting {
+                    message: "There are only 'skip'ped commits left to test.".into(),
+                });
+            }
+            let found = &self.commits[bad_ix];
+            return Ok(BisectStatus::Found {
+                sha: found.sha.clone().into(),
+                subject: found.message.lines().next().unwrap_or_default().into(),
+            });
+        }
+
+        let next = &untested[untested.len() / 2];
+        let left = untested.len() / 2;
+        let steps = (left + 1).ilog2() as usize;
+        self.refs.insert("HEAD".into(), next.sha.clone());
+        Ok(BisectStatus::Testing {
+            sha: next.sha.clone().into(),
+            subject: next.message.lines().next().unwrap_or_default().into(),
+            remaining: format!(
+                "{} left to test after this (roughly {})",
+                plural(left, "revision"),
+                plural(steps, "step")
+            )
+            .into(),
+        })
+    }
+
+    fn commit(&self, sha: &str) -> Result<&FakeCommit> {
+        self.commits
+            .iter()
+            .find(|commit| commit.sha == sha)
+            .with_context(|| format!("bad revision '{sha}'"))
+    }
+
+    fn push_commit(&mut self, message: String) {
+        let mut hasher = std::hash::DefaultHasher::new();
+        self.refs.get("HEAD").hash(&mut hasher);
+        message.hash(&mut hasher);
+        let sha = format!("{:016x}", hasher.finish());
+        self.refs.insert("HEAD".into(), sha.clone());
+        self.commits.push(FakeCommit { sha, message });
+    }
+
+    fn start_operation(
+        &mut self,
+        kind: FakeOperationKind,
+        steps: Vec<RebaseTodoEntry>,
+    ) -> Result<()> {
+        if let Some(operation) = &self.operation {
+            bail!("a {} is already in progress", operation.kind.name());
+        }
+        let original_commits = self.commits.clone();
+        self.apply_steps(kind, original_commits, steps, false)
+    }
+
+    /// Applies `steps` on top of the branch. Stops at the first step whose commit conflicts,
+    /// unless it's the one a continued operation stopped on.
+    fn apply_steps(
+        &mut self,
+        kind: FakeOperationKind,
+        original_commits: Vec<FakeCommit>,
+        steps: Vec<RebaseTodoEntry>,
+        resolved_first: bool,
+    ) -> Result<()> {
+        for (ix, step) in steps.iter().enumerate() {
+            if (ix > 0 || !resolved_first) && self.conflicting_commits.contains(step.sha.as_ref()) {
+                let sha = step.sha.to_string();
+                self.refs.insert(kind.head_ref().into(), sha.clone());
+                self.operation = Some(FakeOperation {
+                    kind,
+                    original_commits,
+                    remaining: steps[ix..].to_vec(),
+                });
+                bail!("CONFLICT (content): Merge conflict\nerror: could not apply {sha}");
+            }
+            match step.action {
+                RebaseAction::Pick | RebaseAction::Reword => {
+                    self.push_commit(step.message.to_string())
+                }
+                RebaseAction::Squash | RebaseAction::Fixup => {
+                    let previous = self.commits.pop().context("no commit to meld into")?;
+                    self.refs.insert("HEAD".into(), previous.sha);
+                    let message = if step.action == RebaseAction::Squash {
+                        format!("{}\n\n{}", previous.message, step.message)
+                    } else {
+                        previous.message
+                    };
+                    self.push_commit(message);
+                }
+                RebaseAction::Drop => {}
+            }
+        }
+        self.refs.remove(kind.head_ref());
+        self.operation = None;
+        Ok(())
+    }
+}
+
+/// Collects the text files in a checkpoint of a working directory, without the ones in `.git`.
+fn checkpoint_files(entry: &FakeFsEntry) -> HashMap<RepoPath, String> {
+    let mut files = HashMap::default();
+    let mut stack = vec![(PathBuf::new(), entry)];
+    while let Some((path, entry)) = stack.pop() {
+        match entry {
+            FakeFsEntry::File { content, .. } => {
+                if let Ok(content) = String::from_utf8(content.clone())
+                    && let Ok(rel_path) = RelPath::new(&path, PathStyle::local())
+                {
+                    files.insert(RepoPath::from_rel_path(&rel_path), content);
+                }
+            }
+            FakeFsEntry::Dir { entries, .. } => {
+                for (name, entry) in entries {
+                    if path.as_os_str().is_empty() && name == ".git" {
+                        continue;
+                    }
+                    stack.push((path.join(name), entry));
+                }
+            }
+            FakeFsEntry::Symlink { .. } => {}
         }
     }
+    files
 }
 
 impl FakeGitRepository {
@@ -402,16 +656,128 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>> {
-        unimplemented!()
+        let fs = self.fs.clone();
+        let executor = self.executor.clone();
+        let common_dir_path = self.common_dir_path.clone();
+        async move {
+            executor.simulate_random_delay().await;
+            let worktree = |dot_git_path: &Path| {
+                fs.with_git_state(dot_git_path, false, |state| Worktree {
+                    path: dot_git_path.parent().unwrap_or(dot_git_path).to_path_buf(),
+                    ref_name: state
+                        .current_branch_name
+                        .as_ref()
+                        .map(|branch| format!("refs/heads/{branch}"))
+                        .unwrap_or_default()
+                        .into(),
+                    sha: state.refs.get("HEAD").cloned().unwrap_or_default().into(),
+                })
+            };
+
+            let mut worktrees = vec![worktree(&common_dir_path)?];
+            let worktrees_dir = common_dir_path.join("worktrees");
+            if fs.is_dir(&worktrees_dir).await {
+                let mut git_dirs = fs.read_dir(&worktrees_dir).await?;
+                while let Some(git_dir) = git_dirs.next().await {
+                    let dot_git_path = fs.load(&git_dir?.join("gitdir")).await?;
+                    worktrees.push(worktree(Path::new(dot_git_path.trim()))?);
+                }
+            }
+            Ok(worktrees)
+        }
+        .boxed()
     }
 
+    /// Checks out HEAD in a linked worktree at `directory/name`, on a new branch named `name`
+    /// when `from_commit` is set.
     fn create_worktree(
         &self,
-        _: String,
-        _: PathBuf,
-        _: Option<String>,
+        name: String,
+        directory: PathBuf,
+        from_commit: Option<String>,
     ) -> BoxFuture<'_, Result<()>> {
-        unimplemented!()
+        let fs = self.fs.clone();
+        let executor = self.executor.clone();
+        let common_dir_path = self.common_dir_path.clone();
+        async move {
+            executor.simulate_random_delay().await;
+            let path = directory.join(&name);
+            if fs.is_dir(&path).await {
+                bail!("'{}' already exists", path.display());
+            }
+            let (branch, head_contents) =
+                fs.with_git_state(&common_dir_path, true, |state| {
+                    let branch = from_commit.map(|_| name.clone());
+                    if let Some(branch) = &branch
+                        && !state.branches.insert(branch.clone())
+                    {
+                        bail!("a branch named '{branch}' already exists");
+                    }
+                    Ok((branch, state.head_contents.clone()))
+                })??;
+
+            let git_dir_path = common_dir_path.join("worktrees").join(&name);
+            let dot_git_path = path.join(".git");
+            fs.create_dir(&git_dir_path).await?;
+            fs.insert_file(
+                git_dir_path.join("commondir"),
+                common_dir_path.to_string_lossy().into_owned().into_bytes(),
+            )
+            .await;
+            fs.insert_file(
+                git_dir_path.join("gitdir"),
+                dot_git_path.to_string_lossy().into_owned().into_bytes(),
+            )
+            .await;
+            fs.create_dir(&path).await?;
+            fs.insert_file(
+                &dot_git_path,
+                format!("gitdir: {}", git_dir_path.display()).into_bytes(),
+            )
+            .await;
+            for (repo_path, content) in &head_contents {
+                let file_path = path.join(repo_path.as_std_path());
+                if let Some(parent) = file_path.parent() {
+                    fs.create_dir(parent).await?;
+                }
+                fs.insert_file(file_path, content.clone().into_bytes())
+                    .await;
+            }
+            fs.with_git_state(&dot_git_path, true, |state| {
+                state.current_branch_name = branch;
+                state.index_contents = head_contents.clone();
+                state.head_contents = head_contents;
+            })
+        }
+        .boxed()
+    }
+
+    fn remove_worktree(&self, path: PathBuf, _force: bool) -> BoxFuture<'_, Result<()>> {
+        let fs = self.fs.clone();
+        let executor = self.executor.clone();
+        let common_dir_path = self.common_dir_path.clone();
+        async move {
+            executor.simulate_random_delay().await;
+            let git_dir_path = fs
+                .load(&path.join(".git"))
+                .await
+                .ok()
+                .and_then(|content| {
+                    let git_dir_path = PathBuf::from(content.strip_prefix("gitdir:")?.trim());
+                    git_dir_path
+                        .starts_with(&common_dir_path)
+                        .then_some(git_dir_path)
+                })
+                .with_context(|| format!("'{}' is not a working tree", path.display()))?;
+            let options = RemoveOptions {
+                recursive: true,
+                ignore_if_not_exists: false,
+            };
+            fs.remove_dir(&path, options).await?;
+            fs.remove_dir(&git_dir_path, options).await?;
+            Ok(())
+        }
+        .boxed()
     }
 
     fn change_branch(&self, name: String) -> BoxFuture<'_, Result<()>> {
@@ -445,11 +811,265 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
-    fn delete_branch(&self, name: String) -> BoxFuture<'_, Result<()>> {
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>> {
         self.with_state_async(true, move |state| {
-            if !state.branches.remove(&name) {
+            if !state.branches.contains(&name) {
                 bail!("no such branch: {name}");
             }
+            if !force && state.unmerged_branches.contains(&name) {
+                bail!("the branch '{name}' is not fully merged");
+            }
+            state.branches.remove(&name);
+            state.unmerged_branches.remove(&name);
+            Ok(())
+        })
+    }
+
+    /// Applies the files of a checkpoint, such as one of a linked worktree, to the working
+    /// tree and the index.
+    fn squash_merge(&self, commit: String) -> BoxFuture<'_, Result<()>> {
+        let fs = self.fs.clone();
+        let executor = self.executor.clone();
+        let checkpoints = self.checkpoints.clone();
+        let dot_git_path = self.dot_git_path.clone();
+        let work_directory = self.dot_git_path.parent().unwrap().to_path_buf();
+        async move {
+            executor.simulate_random_delay().await;
+            let oid = commit.parse::<Oid>()?;
+            let files = checkpoints
+                .lock()
+                .get(&oid)
+                .map(checkpoint_files)
+                .with_context(|| format!("merge: {commit} - not something we can merge"))?;
+            let head_contents =
+                fs.with_git_state(&dot_git_path, false, |state| state.head_contents.clone())?;
+
+            for (repo_path, content) in &files {
+                if head_contents.get(repo_path) != Some(content) {
+                    let path = work_directory.join(repo_path.as_std_path());
+                    if let Some(parent) = path.parent() {
+                        fs.create_dir(parent).await?;
+                    }
+                    fs.insert_file(path, content.clone().into_bytes()).await;
+                }
+            }
+            for repo_path in head_contents.keys() {
+                if !files.contains_key(repo_path) {
+                    fs.remove_file(
+                        &work_directory.join(repo_path.as_std_path()),
+                        RemoveOptions {
+                            recursive: false,
+                            ignore_if_not_exists: true,
+                        },
+                    )
+                    .await?;
+                }
+            }
+            fs.with_git_state(&dot_git_path, true, |state| {
+                state.index_contents = files;
+            })
+        }
+        .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let steps = commits
+                .iter()
+                .map(|sha| {
+                    let commit = state.commit(sha)?;
+                    Ok(RebaseTodoEntry {
+                        sha: commit.sha.clone().into(),
+                        action: RebaseAction::Pick,
+                        message: commit.message.clone().into(),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            state.start_operation(FakeOperationKind::CherryPick, steps)
+        })
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let steps = commits
+                .iter()
+                .map(|sha| {
+                    let commit = state.commit(sha)?;
+                    let subject = commit.message.lines().next().unwrap_or_default();
+                    Ok(RebaseTodoEntry {
+                        sha: commit.sha.clone().into(),
+                        action: RebaseAction::Pick,
+                        message: format!(
+                            "Revert \"{subject}\"\n\nThis reverts commit {}.",
+                            commit.sha
+                        )
+                        .into(),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            state.start_operation(FakeOperationKind::Revert, steps)
+        })
+    }
+
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        self.with_state_async(false, move |state| {
+            let base_ix = state
+                .commits
+                .iter()
+                .position(|commit| commit.sha == base)
+                .with_context(|| format!("bad revision '{base}'"))?;
+            Ok(state.commits[base_ix + 1..]
+                .iter()
+                .map(|commit| RebaseTodoEntry {
+                    sha: commit.sha.clone().into(),
+                    action: RebaseAction::Pick,
+                    message: commit.message.clone().into(),
+                })
+                .collect())
+        })
+    }
+
+    fn rebase_interactive(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let base_ix = state
+                .commits
+                .iter()
+                .position(|commit| commit.sha == base)
+                .with_context(|| format!("bad revision '{base}'"))?;
+            if let Some(first) = todo.iter().find(|entry| entry.action != RebaseAction::Drop)
+                && matches!(first.action, RebaseAction::Squash | RebaseAction::Fixup)
+            {
+                bail!("cannot meld '{}' without a previous commit", first.sha);
+            }
+            if let Some(operation) = &state.operation {
+                bail!("a {} is already in progress", operation.kind.name());
+            }
+            let original_commits = state.commits.clone();
+            state.commits.truncate(base_ix + 1);
+            state.refs.insert("HEAD".into(), base);
+            state.apply_steps(FakeOperationKind::Rebase, original_commits, todo, false)
+        })
+    }
+
+    fn continue_operation(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            let operation = state
+                .operation
+                .take()
+                .context("no cherry-pick, revert, or rebase in progress")?;
+            state.refs.remove(operation.kind.head_ref());
+            state.apply_steps(
+                operation.kind,
+                operation.original_commits,
+                operation.remaining,
+                true,
+            )
+        })
+    }
+
+    fn abort_operation(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            let operation = state
+                .operation
+                .take()
+                .context("no cherry-pick, revert, or rebase in progress")?;
+            state.refs.remove(operation.kind.head_ref());
+            state.commits = operation.original_commits;
+            if let Some(head) = state.commits.last() {
+                state.refs.insert("HEAD".into(), head.sha.clone());
+            }
+            Ok(())
+        })
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .tags
+                .iter()
+                .map(|(name, sha)| Tag {
+                    name: name.clone().into(),
+                    sha: sha.clone().into(),
+                    message: None,
+                    timestamp: 0,
+                })
+                .collect())
+        })
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        _message: Option<String>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.contains_key(&name) {
+                bail!("tag '{name}' already exists");
+            }
+            let sha = match target {
+                Some(target) => target,
+                None => state.refs.get("HEAD").context("no HEAD")?.clone(),
+            };
+            state.tags.insert(name, sha);
+            Ok(())
+        })
+    }
+
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.remove(&name).is_none() {
+                bail!("tag '{name}' not found");
+            }
+            Ok(())
+        })
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<BisectStatus>> {
+        self.with_state_async(true, move |state| {
+            let head = state.refs.get("HEAD").cloned().unwrap_or_default();
+            let commit = commit.unwrap_or_else(|| head.clone());
+            state.commit_index(&commit)?;
+            let bisect = state.bisect.get_or_insert_with(|| FakeBisect {
+                original_head: head,
+                ..FakeBisect::default()
+            });
+            match mark {
+                BisectMark::Good => {
+                    bisect.good.insert(commit);
+                }
+                BisectMark::Bad => bisect.bad = Some(commit),
+                BisectMark::Skip => {
+                    bisect.skipped.insert(commit);
+                }
+            }
+            state.bisect_status()
+        })
+    }
+
+    fn bisect_reset(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            if let Some(bisect) = state.bisect.take() {
+                state.refs.insert("HEAD".into(), bisect.original_head);
+            }
             Ok(())
         })
     }
@@ -655,11 +1275,11 @@ impl GitRepository for FakeGitRepository {
         let executor = self.executor.clone();
         let fs = self.fs.clone();
         let checkpoints = self.checkpoints.clone();
-        let repository_dir_path = self.repository_dir_path.parent().unwrap().to_path_buf();
+        let work_directory = self.dot_git_path.parent().unwrap().to_path_buf();
         async move {
             executor.simulate_random_delay().await;
             let oid = git::Oid::random(&mut *executor.rng().lock());
-            let entry = fs.entry(&repository_dir_path)?;
+            let entry = fs.entry(&work_directory)?;
             checkpoints.lock().insert(oid, entry);
             Ok(GitRepositoryCheckpoint { commit_sha: oid })
         }
@@ -670,14 +1290,14 @@ impl GitRepository for FakeGitRepository {
         let executor = self.executor.clone();
         let fs = self.fs.clone();
         let checkpoints = self.checkpoints.clone();
-        let repository_dir_path = self.repository_dir_path.parent().unwrap().to_path_buf();
+        let work_directory = self.dot_git_path.parent().unwrap().to_path_buf();
         async move {
             executor.simulate_random_delay().await;
             let checkpoints = checkpoints.lock();
             let entry = checkpoints
                 .get(&checkpoint.commit_sha)
                 .context(format!("invalid checkpoint: {}", checkpoint.commit_sha))?;
-            fs.insert_entry(&repository_dir_path, entry.clone())?;
+            fs.insert_entry(&work_directory, entry.clone())?;
             Ok(())
         }
         .boxed()
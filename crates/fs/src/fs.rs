@@ -1198,6 +1198,9 @@ pub struct FakeFs {
     // Use an unfair lock to ensure tests are deterministic.
     state: Arc<Mutex<FakeFsState>>,
     executor: gpui::BackgroundExecutor,
+    /// Checkpoints of every repository, which linked worktrees share with their main
+    /// repository like git's object store.
+    git_checkpoints: Arc<Mutex<collections::HashMap<git::Oid, FakeFsEntry>>>,
 }
 
 #[cfg(feature = "test-support")]
@@ -1480,6 +1483,7 @@ impl FakeFs {
         let this = Arc::new_cyclic(|this| Self {
             this: this.clone(),
             executor: executor.clone(),
+            git_checkpoints: Arc::default(),
             state: Arc::new(Mutex::new(FakeFsState {
                 root: FakeFsEntry::Dir {
                     inode: 0,
@@ -1977,6 +1981,43 @@ impl FakeFs {
         .unwrap();
     }
 
+    /// Sets the commits on the current branch, oldest first, as `(sha, message)` pairs.
+    pub fn set_commits_for_repo(&self, dot_git: &Path, commits: &[(&str, &str)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commits = commits
+                .iter()
+                .map(|(sha, message)| fake_git_repo::FakeCommit {
+                    sha: sha.to_string(),
+                    message: message.to_string(),
+                })
+                .collect();
+            if let Some(head) = state.commits.last() {
+                state.refs.insert("HEAD".into(), head.sha.clone());
+            }
+        })
+        .unwrap();
+    }
+
+    /// Makes cherry-picks, reverts, and rebases stop on a conflict when they apply these commits.
+    pub fn set_conflicting_commits_for_repo(&self, dot_git: &Path, shas: &[&str]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.conflicting_commits = shas.iter().map(|sha| sha.to_string()).collect();
+        })
+        .unwrap();
+    }
+
+    /// Returns the messages of the commits on the current branch, oldest first.
+    pub fn commit_messages_for_repo(&self, dot_git: &Path) -> Vec<String> {
+        self.with_git_state(dot_git, false, |state| {
+            state
+                .commits
+                .iter()
+                .map(|commit| commit.message.clone())
+                .collect()
+        })
+        .unwrap()
+    }
+
     /// Put the given git repository into a state with the given status,
     /// by mutating the head, index, and unmerged state.
     pub fn set_status_for_repo(&self, dot_git: &Path, statuses: &[(&str, FileStatus)]) {
@@ -2766,7 +2807,7 @@ impl Fs for FakeFs {
                     dot_git_path: abs_dot_git.to_path_buf(),
                     repository_dir_path: repository_dir_path.to_owned(),
                     common_dir_path: common_dir_path.to_owned(),
-                    checkpoints: Arc::default(),
+                    checkpoints: self.git_checkpoints.clone(),
                 }) as _
             },
         )
@@ -101,6 +101,18 @@ actions!(
         Clone,
         /// Adds a file to .gitignore.
         AddToGitignore,
+        /// Continues the rebase, cherry-pick, or revert that stopped on conflicts.
+        ContinueOperation,
+        /// Aborts the rebase, cherry-pick, or revert in progress.
+        AbortOperation,
+        /// Marks the checked-out commit as good for bisecting, starting a bisect if needed.
+        BisectGood,
+        /// Marks the checked-out commit as bad for bisecting, starting a bisect if needed.
+        BisectBad,
+        /// Skips the checked-out commit while bisecting.
+        BisectSkip,
+        /// Ends the bisect and checks out the branch it started from.
+        BisectReset,
     ]
 );
 
@@ -382,6 +382,103 @@ pub enum ResetMode {
     Mixed,
 }
 
+/// What an interactive rebase does with a commit.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RebaseAction {
+    #[default]
+    Pick,
+    /// Keeps the commit, replacing its message.
+    Reword,
+    /// Melds the commit into the previous one, combining their messages.
+    Squash,
+    /// Melds the commit into the previous one, keeping only the previous message.
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [RebaseAction; 5] = [
+        RebaseAction::Pick,
+        RebaseAction::Reword,
+        RebaseAction::Squash,
+        RebaseAction::Fixup,
+        RebaseAction::Drop,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+}
+
+/// A line of an interactive rebase's todo list.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub sha: SharedString,
+    pub action: RebaseAction,
+    /// The commit's full message, or for a reworded commit, the message to replace it with.
+    pub message: SharedString,
+}
+
+impl RebaseTodoEntry {
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit that the tag points to.
+    pub sha: SharedString,
+    /// The subject of an annotated tag's message. Lightweight tags have none.
+    pub message: Option<SharedString>,
+    /// When an annotated tag was created, or for a lightweight tag, when its commit was made.
+    pub timestamp: i64,
+}
+
+/// How a commit behaves with respect to the change being bisected.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum BisectMark {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl BisectMark {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BisectMark::Good => "good",
+            BisectMark::Bad => "bad",
+            BisectMark::Skip => "skip",
+        }
+    }
+}
+
+/// Where a bisect session stands after a commit was marked.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum BisectStatus {
+    /// Git needs both a good and a bad commit before it can pick one to test.
+    Waiting { message: SharedString },
+    /// Git checked out `sha` to be tested next.
+    Testing {
+        sha: SharedString,
+        subject: SharedString,
+        /// How much is left to test, like "3 revisions left to test after this (roughly 2 steps)".
+        remaining: SharedString,
+    },
+    /// Git found the commit that introduced the change.
+    Found {
+        sha: SharedString,
+        subject: SharedString,
+    },
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum FetchOptions {
     All,
@@ -618,7 +715,73 @@ pub trait GitRepository: Send + Sync {
     -> BoxFuture<'_, Result<()>>;
     fn rename_branch(&self, branch: String, new_name: String) -> BoxFuture<'_, Result<()>>;
 
-    fn delete_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
+    /// Deletes the branch. Unless `force` is set, git refuses to delete a branch that isn't
+    /// merged into its upstream or HEAD.
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the changes from `commit` to the index and working tree without committing them.
+    fn squash_merge(&self, commit: String) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the changes introduced by `commits`, oldest first, as new commits on top of HEAD.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Creates new commits that undo the changes introduced by `commits`, in the given order.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the commits that an interactive rebase onto `base` would replay, oldest first,
+    /// each set to be picked.
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>>;
+
+    /// Rebases the current branch onto `base`, replaying commits as the todo list says.
+    ///
+    /// Stops with an error when a commit doesn't apply cleanly, leaving the conflicts in the
+    /// working tree to be resolved before [`GitRepository::continue_operation`].
+    fn rebase_interactive(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Continues the rebase, cherry-pick, revert, or merge that stopped on conflicts.
+    fn continue_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Aborts the rebase, cherry-pick, revert, or merge in progress, restoring the branch to
+    /// where it was before the operation started.
+    fn abort_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the repository's tags, newest first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>>;
+
+    /// Tags `target`, or HEAD when it's `None`. The tag is annotated when it has a message.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>>;
+
+    /// Marks `commit`, or HEAD when it's `None`, for the bisect session in progress, starting
+    /// one if there's none.
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<BisectStatus>>;
+
+    /// Ends the bisect session, checking out the branch that was checked out when it started.
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
 
     fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>>;
 
@@ -629,6 +792,10 @@ pub trait GitRepository: Send + Sync {
         from_commit: Option<String>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Removes the linked worktree at `path`. Unless `force` is set, git refuses to remove a
+    /// worktree with uncommitted or untracked changes.
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<'_, Result<()>>;
+
     fn reset(
         &self,
         commit: String,
@@ -1599,6 +1766,35 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn remove_worktree(&self, path: PathBuf, force: bool) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let mut args = vec![
+            OsString::from("--no-optional-locks"),
+            OsString::from("worktree"),
+            OsString::from("remove"),
+        ];
+        if force {
+            args.push(OsString::from("--force"));
+        }
+        args.push(OsString::from(path.as_os_str()));
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .args(args)
+                    .output()
+                    .await?;
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    anyhow::bail!("git worktree remove failed: {stderr}");
+                }
+            })
+            .boxed()
+    }
+
     fn change_branch(&self, name: String) -> BoxFuture<'_, Result<()>> {
         let repo = self.repository.clone();
         let working_directory = self.working_directory();
@@ -1687,7 +1883,293 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn delete_branch(&self, name: String) -> BoxFuture<'_, Result<()>> {
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let flag = if force { "-D" } else { "-d" };
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["branch", flag, &name])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn squash_merge(&self, commit: String) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["merge", "--squash", &commit])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let mut args = vec!["cherry-pick".to_string()];
+                args.extend(commits);
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(without_editor(&env))
+                    .run(&args)
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let mut args = vec!["revert".to_string(), "--no-edit".to_string()];
+                args.extend(commits);
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(without_editor(&env))
+                    .run(&args)
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn rebase_todo(&self, base: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                // Like `git rebase`, leave out merge commits, which it doesn't replay.
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&[
+                        "log",
+                        "--reverse",
+                        "--topo-order",
+                        "--no-merges",
+                        "--format=%x1e%H%x00%B",
+                        &format!("{base}..HEAD"),
+                        "--",
+                    ])
+                    .await?;
+                Ok(parse_rebase_todo(&output))
+            })
+            .boxed()
+    }
+
+    fn rebase_interactive(
+        &self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let state_dir = self.path().join(REBASE_STATE_DIR);
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                // The reword messages must outlive this call, since the rebase may stop on
+                // conflicts and pick the reworded commits only once it's continued.
+                smol::fs::remove_dir_all(&state_dir).await.ok();
+                smol::fs::create_dir_all(&state_dir).await?;
+
+                let mut todo_list = String::new();
+                for (ix, entry) in todo.iter().enumerate() {
+                    if entry.action == RebaseAction::Reword {
+                        // Git would ask for the new message in an editor, so amend the picked
+                        // commit with the message instead.
+                        let message_path = state_dir.join(format!("message-{ix}"));
+                        smol::fs::write(&message_path, entry.message.as_bytes()).await?;
+                        todo_list.push_str(&format!(
+                            "pick {}\nexec {} commit --amend --allow-empty --no-verify --file {}\n",
+                            entry.sha,
+                            shell_quote(&git_binary_path),
+                            shell_quote(&message_path),
+                        ));
+                    } else {
+                        todo_list.push_str(&format!("{} {}\n", entry.action.as_str(), entry.sha));
+                    }
+                }
+                let todo_path = state_dir.join("git-rebase-todo");
+                smol::fs::write(&todo_path, todo_list).await?;
+
+                let mut envs = without_editor(&env);
+                envs.insert(
+                    "GIT_SEQUENCE_EDITOR".into(),
+                    format!("cp {}", shell_quote(&todo_path)),
+                );
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(envs)
+                    .run(&["rebase", "--interactive", "--no-autosquash", &base])
+                    .await?;
+
+                smol::fs::remove_dir_all(&state_dir).await.ok();
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn continue_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let git_dir = self.path();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let operation = in_progress_operation(&git_dir)
+                    .context("no rebase, cherry-pick, revert, or merge is in progress")?;
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs(without_editor(&env))
+                    .run(&[operation, "--continue"])
+                    .await?;
+
+                smol::fs::remove_dir_all(git_dir.join(REBASE_STATE_DIR))
+                    .await
+                    .ok();
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn abort_operation(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let git_dir = self.path();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let operation = in_progress_operation(&git_dir)
+                    .context("no rebase, cherry-pick, revert, or merge is in progress")?;
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs((*env).clone())
+                    .run(&[operation, "--abort"])
+                    .await?;
+
+                smol::fs::remove_dir_all(git_dir.join(REBASE_STATE_DIR))
+                    .await
+                    .ok();
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&[
+                        "for-each-ref",
+                        "--sort=-creatordate",
+                        "--format=%(refname:short)%00%(objecttype)%00%(objectname)%00%(*objectname)%00%(contents:subject)%00%(creatordate:unix)",
+                        "refs/tags",
+                    ])
+                    .await?;
+                Ok(parse_tags(&output))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let mut args = vec!["tag".to_string()];
+                if let Some(message) = message {
+                    args.extend(["--annotate".to_string(), "--message".to_string(), message]);
+                }
+                args.push(name);
+                args.extend(target);
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&args)
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["tag", "--delete", &name])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<BisectStatus>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let git_dir = self.path();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs((*env).clone());
+                if !git_dir.join("BISECT_START").exists() {
+                    git.run(&["bisect", "start"]).await?;
+                }
+                let mut args = vec!["bisect".to_string(), mark.as_str().to_string()];
+                args.extend(commit);
+                let output = git.run(&args).await?;
+                parse_bisect_output(&output)
+            })
+            .boxed()
+    }
+
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
         let git_binary_path = self.any_git_binary_path.clone();
         let working_directory = self.working_directory();
         let executor = self.executor.clone();
@@ -1695,7 +2177,8 @@ impl GitRepository for RealGitRepository {
         self.executor
             .spawn(async move {
                 GitBinary::new(git_binary_path, working_directory?, executor)
-                    .run(&["branch", "-d", &name])
+                    .envs((*env).clone())
+                    .run(&["bisect", "reset"])
                     .await?;
                 anyhow::Ok(())
             })
@@ -2885,7 +3368,8 @@ impl GitBinary {
         &mut self,
         f: impl AsyncFnOnce(&Self) -> Result<R>,
     ) -> Result<R> {
-        let index_file_path = self.path_for_index_id(Uuid::new_v4());
+        let git_directory = self.git_directory().await;
+        let index_file_path = git_directory.join(format!("index-{}.tmp", Uuid::new_v4()));
 
         let delete_temp_index = util::defer({
             let index_file_path = index_file_path.clone();
@@ -2901,12 +3385,9 @@ impl GitBinary {
 
         // Copy the default index file so that Git doesn't have to rebuild the
         // whole index from scratch. This might fail if this is an empty repository.
-        smol::fs::copy(
-            self.working_directory.join(".git").join("index"),
-            &index_file_path,
-        )
-        .await
-        .ok();
+        smol::fs::copy(git_directory.join("index"), &index_file_path)
+            .await
+            .ok();
 
         self.index_file_path = Some(index_file_path.clone());
         let result = f(self).await;
@@ -2921,18 +3402,33 @@ impl GitBinary {
 
     pub async fn with_exclude_overrides(&self) -> Result<GitExcludeOverride> {
         let path = self
-            .working_directory
-            .join(".git")
+            .git_common_directory()
+            .await
             .join("info")
             .join("exclude");
 
         GitExcludeOverride::new(path).await
     }
 
-    fn path_for_index_id(&self, id: Uuid) -> PathBuf {
-        self.working_directory
-            .join(".git")
-            .join(format!("index-{}.tmp", id))
+    /// The directory holding this working tree's index, which for linked worktrees is the one
+    /// their `.git` file points to.
+    async fn git_directory(&self) -> PathBuf {
+        let dot_git = self.working_directory.join(".git");
+        if let Ok(contents) = smol::fs::read_to_string(&dot_git).await
+            && let Some(git_directory) = contents.trim().strip_prefix("gitdir: ")
+        {
+            return self.working_directory.join(git_directory);
+        }
+        dot_git
+    }
+
+    /// The directory shared by all of the repository's worktrees, which holds `info/exclude`.
+    async fn git_common_directory(&self) -> PathBuf {
+        let git_directory = self.git_directory().await;
+        match smol::fs::read_to_string(git_directory.join("commondir")).await {
+            Ok(common_directory) => git_directory.join(common_directory.trim()),
+            Err(_) => git_directory,
+        }
     }
 
     pub async fn run<S>(&self, args: impl IntoIterator<Item = S>) -> Result<String>
@@ -3217,6 +3713,123 @@ fn checkpoint_author_envs() -> HashMap<String, String> {
     ])
 }
 
+/// The directory in the git dir holding the todo list and reword messages of an interactive
+/// rebase started by Zed.
+const REBASE_STATE_DIR: &str = "zed-rebase";
+
+/// Returns the environment for a git command that may write commit messages, accepting the
+/// messages git prepares instead of opening an editor.
+fn without_editor(env: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut env = env.clone();
+    env.insert("GIT_EDITOR".into(), "true".into());
+    env
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+/// Returns the git command whose operation stopped in the repository, if any.
+fn in_progress_operation(git_dir: &Path) -> Option<&'static str> {
+    if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        Some("rebase")
+    } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        Some("cherry-pick")
+    } else if git_dir.join("REVERT_HEAD").exists() {
+        Some("revert")
+    } else if git_dir.join("MERGE_HEAD").exists() {
+        Some("merge")
+    } else {
+        // A cherry-pick or revert of several commits can be left in progress after the commit
+        // that conflicted was committed by hand.
+        let todo = std::fs::read_to_string(git_dir.join("sequencer").join("todo")).ok()?;
+        if todo.trim_start().starts_with("revert") {
+            Some("revert")
+        } else {
+            Some("cherry-pick")
+        }
+    }
+}
+
+fn parse_rebase_todo(log: &str) -> Vec<RebaseTodoEntry> {
+    log.split('\x1e')
+        .filter_map(|commit| {
+            let (sha, message) = commit.split_once('\0')?;
+            Some(RebaseTodoEntry {
+                sha: sha.trim().to_string().into(),
+                action: RebaseAction::Pick,
+                message: message.trim_end().to_string().into(),
+            })
+        })
+        .collect()
+}
+
+fn parse_tags(output: &str) -> Vec<Tag> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let name = fields.next()?;
+            let object_type = fields.next()?;
+            let object = fields.next()?;
+            let peeled_object = fields.next()?;
+            let subject = fields.next()?;
+            let timestamp = fields.next()?.parse().unwrap_or_default();
+            // Only annotated tags are tag objects, which point to the commit through
+            // `*objectname`. For lightweight tags, the subject is the commit's.
+            let (sha, message) = if object_type == "tag" {
+                (peeled_object, Some(subject.to_string().into()))
+            } else {
+                (object, None)
+            };
+            Some(Tag {
+                name: name.to_string().into(),
+                sha: sha.to_string().into(),
+                message,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+fn parse_bisect_output(output: &str) -> Result<BisectStatus> {
+    let mut lines = output.lines();
+    let first_line = lines.next().unwrap_or_default().trim();
+
+    if let Some(sha) = first_line.strip_suffix(" is the first bad commit") {
+        // The commit follows as `git show` prints it, with the subject after the headers.
+        let subject = lines
+            .skip_while(|line| !line.is_empty())
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or_default()
+            .trim();
+        return Ok(BisectStatus::Found {
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+        });
+    }
+
+    if let Some(remaining) = first_line.strip_prefix("Bisecting: ") {
+        let next_line = lines
+            .next()
+            .context("git didn't say which commit to test")?;
+        let (sha, subject) = next_line
+            .strip_prefix('[')
+            .and_then(|line| line.split_once("] "))
+            .with_context(|| format!("unexpected bisect output: {next_line}"))?;
+        return Ok(BisectStatus::Testing {
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+            remaining: remaining.to_string().into(),
+        });
+    }
+
+    let message = first_line.strip_prefix("status: ").unwrap_or(first_line);
+    Ok(BisectStatus::Waiting {
+        message: message.to_string().into(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3470,6 +4083,297 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_rebase_interactive(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (file, message) in [
+            ("a", "Add a"),
+            ("b", "Add b"),
+            ("c", "Add c"),
+            ("d", "Add d"),
+        ] {
+            smol::fs::write(repo_dir.path().join(file), file)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(file)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let base = repo.revparse_batch(vec!["HEAD~3".into()]).await.unwrap()[0]
+            .clone()
+            .unwrap();
+        let mut todo = repo.rebase_todo(base.clone()).await.unwrap();
+        assert_eq!(
+            todo.iter().map(|entry| entry.subject()).collect::<Vec<_>>(),
+            ["Add b", "Add c", "Add d"]
+        );
+
+        todo.swap(1, 2);
+        todo[0].action = RebaseAction::Reword;
+        todo[0].message = "Add b, reworded".into();
+        todo[1].action = RebaseAction::Fixup;
+        todo[2].action = RebaseAction::Drop;
+        repo.rebase_interactive(base.clone(), todo, env.clone())
+            .await
+            .unwrap();
+
+        let todo = repo.rebase_todo(base).await.unwrap();
+        assert_eq!(
+            todo.iter().map(|entry| entry.subject()).collect::<Vec<_>>(),
+            ["Add b, reworded"]
+        );
+        assert!(repo_dir.path().join("d").exists());
+        assert!(!repo_dir.path().join("c").exists());
+    }
+
+    #[gpui::test]
+    async fn test_tags_and_bisect(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for ix in 1..=8 {
+            smol::fs::write(repo_dir.path().join("file"), ix.to_string())
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Commit {ix}").into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+        let shas = repo
+            .revparse_batch(vec!["HEAD~7".into(), "HEAD~3".into(), "HEAD".into()])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(Option::unwrap)
+            .collect::<Vec<_>>();
+
+        repo.create_tag("v1".into(), Some(shas[0].clone()), None)
+            .await
+            .unwrap();
+        repo.create_tag("v2".into(), None, None).await.unwrap();
+        let tags = repo.tags().await.unwrap();
+        assert_eq!(tags.len(), 2);
+        assert!(
+            tags.iter()
+                .any(|tag| tag.name == "v1" && tag.sha == shas[0])
+        );
+        assert!(
+            tags.iter()
+                .any(|tag| tag.name == "v2" && tag.sha == shas[2])
+        );
+        repo.delete_tag("v2".into()).await.unwrap();
+        let tags = repo.tags().await.unwrap();
+        assert_eq!(
+            tags.iter().map(|tag| tag.name.as_ref()).collect::<Vec<_>>(),
+            ["v1"]
+        );
+
+        // Commit 5 is the first bad commit.
+        let status = repo
+            .bisect_mark(BisectMark::Bad, None, env.clone())
+            .await
+            .unwrap();
+        assert!(matches!(status, BisectStatus::Waiting { .. }));
+        let mut status = repo
+            .bisect_mark(BisectMark::Good, Some("v1".into()), env.clone())
+            .await
+            .unwrap();
+        let first_bad = loop {
+            match status {
+                BisectStatus::Testing { subject, .. } => {
+                    let number = subject
+                        .trim_start_matches("Commit ")
+                        .parse::<u32>()
+                        .unwrap();
+                    let mark = if number >= 5 {
+                        BisectMark::Bad
+                    } else {
+                        BisectMark::Good
+                    };
+                    status = repo.bisect_mark(mark, None, env.clone()).await.unwrap();
+                }
+                BisectStatus::Found { sha, subject } => {
+                    assert_eq!(subject, "Commit 5");
+                    break sha;
+                }
+                BisectStatus::Waiting { .. } => panic!("bisect should have started"),
+            }
+        };
+        assert_eq!(first_bad, shas[1]);
+
+        repo.bisect_reset(env).await.unwrap();
+        assert_eq!(repo.head_sha().await, Some(shas[2].clone()));
+        assert!(!repo_dir.path().join(".git/BISECT_START").exists());
+    }
+
+    #[gpui::test]
+    async fn test_isolated_worktree_merge_and_discard(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        smol::fs::write(repo_dir.path().join("a"), "a")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("a")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Add a".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let head = repo.head_sha().await.unwrap();
+
+        // Created like the agent panel's isolated worktrees, on a new branch at a given commit.
+        let worktrees_dir = tempfile::tempdir().unwrap();
+        repo.create_worktree(
+            "zed-agent-test".into(),
+            worktrees_dir.path().to_path_buf(),
+            Some(head.clone()),
+        )
+        .await
+        .unwrap();
+        let worktree_path = worktrees_dir.path().join("zed-agent-test");
+        let worktree = repo
+            .worktrees()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|worktree| worktree.branch() == "zed-agent-test")
+            .unwrap();
+        assert_eq!(worktree.sha.as_ref(), head);
+        assert_eq!(
+            smol::fs::read_to_string(worktree_path.join("a"))
+                .await
+                .unwrap(),
+            "a"
+        );
+
+        // Uncommitted changes in the worktree are merged through a checkpoint of it.
+        let worktree_repo = RealGitRepository::new(
+            &worktree_path.join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        smol::fs::write(worktree_path.join("b"), "b").await.unwrap();
+        let checkpoint = worktree_repo.checkpoint().await.unwrap();
+        repo.squash_merge(checkpoint.commit_sha.to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("b"))
+                .await
+                .unwrap(),
+            "b"
+        );
+        assert_eq!(
+            repo.load_index_text(repo_path("b")).await.as_deref(),
+            Some("b")
+        );
+        assert_eq!(repo.head_sha().await, Some(head));
+
+        repo.remove_worktree(worktree_path.clone(), true)
+            .await
+            .unwrap();
+        repo.delete_branch("zed-agent-test".into(), false)
+            .await
+            .unwrap();
+        assert!(!worktree_path.exists());
+        assert!(
+            !repo
+                .worktrees()
+                .await
+                .unwrap()
+                .iter()
+                .any(|worktree| worktree.branch() == "zed-agent-test")
+        );
+    }
+
+    #[test]
+    fn test_tags_parsing() {
+        let input = "v2\0tag\0aaaa\0bbbb\0Release two\01733187470\nv1\0commit\0cccc\0\0Some commit\01733187000\n";
+        assert_eq!(
+            parse_tags(input),
+            vec![
+                Tag {
+                    name: "v2".into(),
+                    sha: "bbbb".into(),
+                    message: Some("Release two".into()),
+                    timestamp: 1733187470,
+                },
+                Tag {
+                    name: "v1".into(),
+                    sha: "cccc".into(),
+                    message: None,
+                    timestamp: 1733187000,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
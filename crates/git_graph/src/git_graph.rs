@@ -3,14 +3,18 @@ use feature_flags::{FeatureFlag, FeatureFlagAppExt as _};
 use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, Oid, ParsedGitRemote,
     parse_git_remote_url,
-    repository::{CommitDiff, InitialGraphCommitData, LogOrder, LogSource},
+    repository::{BisectMark, CommitDiff, InitialGraphCommitData, LogOrder, LogSource},
+};
+use git_ui::{
+    bisect,
+    commit_tooltip::CommitAvatar,
+    rebase_view::{self, RebaseTodoView},
 };
-use git_ui::commit_tooltip::CommitAvatar;
 use gpui::{
-    AnyElement, App, Bounds, ClipboardItem, Context, Corner, DefiniteLength, ElementId, Entity,
-    EventEmitter, FocusHandle, Focusable, FontWeight, Hsla, InteractiveElement, ParentElement,
-    PathBuilder, Pixels, Point, Render, ScrollWheelEvent, SharedString, Styled, Subscription, Task,
-    WeakEntity, Window, actions, anchored, deferred, point, px,
+    Action, AnyElement, App, Bounds, ClipboardItem, Context, Corner, DefiniteLength, DismissEvent,
+    ElementId, Entity, EventEmitter, FocusHandle, Focusable, FontWeight, Hsla, InteractiveElement,
+    MouseButton, ParentElement, PathBuilder, Pixels, Point, Render, ScrollWheelEvent, SharedString,
+    Styled, Subscription, Task, WeakEntity, Window, actions, anchored, deferred, point, px,
 };
 use project::{
     Project,
@@ -28,6 +32,7 @@ use ui::{
 use workspace::{
     Workspace,
     item::{Item, ItemEvent, SerializableItem},
+    notifications::DetachAndPromptErr as _,
 };
 
 pub struct GitGraphFeatureFlag;
@@ -49,6 +54,18 @@ actions!(
         Open,
         /// Opens the commit view for the selected commit.
         OpenCommitView,
+        /// Applies the changes of the selected commit on top of the current branch.
+        CherryPickCommit,
+        /// Creates a commit that undoes the changes of the selected commit.
+        RevertCommit,
+        /// Opens an interactive rebase of the commits from the selected one up to HEAD.
+        RebaseFromCommit,
+        /// Creates a tag pointing at the selected commit.
+        CreateTagAtCommit,
+        /// Marks the selected commit as good in the bisect session.
+        BisectMarkGood,
+        /// Marks the selected commit as bad in the bisect session.
+        BisectMarkBad,
     ]
 );
 
@@ -512,7 +529,9 @@ pub fn init(cx: &mut App) {
                         workspace
                             .update(cx, |workspace, cx| {
                                 let project = workspace.project().clone();
-                                let git_graph = cx.new(|cx| GitGraph::new(project, window, cx));
+                                let workspace_handle = workspace.weak_handle();
+                                let git_graph = cx
+                                    .new(|cx| GitGraph::new(project, workspace_handle, window, cx));
                                 workspace.add_item_to_active_pane(
                                     Box::new(git_graph),
                                     None,
@@ -578,6 +597,7 @@ pub struct GitGraph {
     focus_handle: FocusHandle,
     graph_data: GraphData,
     project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
     context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     row_height: Pixels,
     table_interaction_state: Entity<TableInteractionState>,
@@ -603,7 +623,12 @@ impl GitGraph {
         (LANE_WIDTH * self.graph_data.max_lanes.min(8) as f32) + LEFT_PADDING * 2.0
     }
 
-    pub fn new(project: Entity<Project>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let focus_handle = cx.focus_handle();
         cx.on_focus(&focus_handle, window, |_, _, cx| cx.notify())
             .detach();
@@ -661,6 +686,7 @@ impl GitGraph {
         GitGraph {
             focus_handle,
             project,
+            workspace,
             graph_data: graph,
             _load_task: None,
             _commit_diff_task: None,
@@ -710,6 +736,8 @@ impl GitGraph {
     }
 
     fn render_badge(&self, name: &SharedString, accent_color: gpui::Hsla) -> impl IntoElement {
+        // `git log --decorate` lists tags as "tag: <name>".
+        let tag = name.strip_prefix("tag: ");
         div()
             .px_1p5()
             .py_0p5()
@@ -721,11 +749,21 @@ impl GitGraph {
             .bg(accent_color.opacity(0.18))
             .border_1()
             .border_color(accent_color.opacity(0.55))
+            .gap_0p5()
+            .when(tag.is_some(), |this| {
+                this.child(
+                    Icon::new(IconName::Hash)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
             .child(
-                Label::new(name.clone())
-                    .size(LabelSize::Small)
-                    .color(Color::Default)
-                    .single_line(),
+                Label::new(
+                    tag.map_or_else(|| name.clone(), |tag| SharedString::from(tag.to_string())),
+                )
+                .size(LabelSize::Small)
+                .color(Color::Default)
+                .single_line(),
             )
     }
 
@@ -880,6 +918,158 @@ impl GitGraph {
         cx.notify();
     }
 
+    fn deploy_context_menu(
+        &mut self,
+        idx: usize,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(commit) = self.graph_data.commits.get(idx) else {
+            return;
+        };
+        let has_parent = !commit.data.parents.is_empty();
+        let tags = commit
+            .data
+            .ref_names
+            .iter()
+            .filter_map(|name| name.strip_prefix("tag: ").map(ToString::to_string))
+            .collect::<Vec<_>>();
+        let repository = self.project.read(cx).active_repository(cx);
+        self.select_entry(idx, cx);
+
+        let context_menu = ContextMenu::build(window, cx, |mut context_menu, _, _| {
+            context_menu = context_menu
+                .context(self.focus_handle.clone())
+                .action("Cherry-Pick", CherryPickCommit.boxed_clone())
+                .action("Revert", RevertCommit.boxed_clone())
+                .separator()
+                .action_disabled_when(
+                    !has_parent,
+                    "Rebase Interactively from Here",
+                    RebaseFromCommit.boxed_clone(),
+                )
+                .separator()
+                .action("Create Tag…", CreateTagAtCommit.boxed_clone());
+            if let Some(repository) = repository {
+                for tag in tags {
+                    let repository = repository.clone();
+                    context_menu =
+                        context_menu.entry(format!("Delete Tag {tag}"), None, move |window, cx| {
+                            let result = repository
+                                .update(cx, |repository, _| repository.delete_tag(tag.clone()));
+                            cx.spawn(async move |_| result.await?)
+                                .detach_and_prompt_err(
+                                    "Failed to delete tag",
+                                    window,
+                                    cx,
+                                    |_, _, _| None,
+                                );
+                        });
+                }
+            }
+            context_menu
+                .separator()
+                .action("Bisect: Mark as Good", BisectMarkGood.boxed_clone())
+                .action("Bisect: Mark as Bad", BisectMarkBad.boxed_clone())
+        });
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this
+                    .context_menu
+                    .as_ref()
+                    .is_some_and(|(context_menu, ..)| {
+                        context_menu.focus_handle(cx).contains_focused(window, cx)
+                    })
+                {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn selected_commit(
+        &self,
+        cx: &App,
+    ) -> Option<(Arc<InitialGraphCommitData>, Entity<Repository>)> {
+        let commit = self.graph_data.commits.get(self.selected_entry_idx?)?;
+        let repository = self.project.read(cx).active_repository(cx)?;
+        Some((commit.data.clone(), repository))
+    }
+
+    fn cherry_pick(&mut self, _: &CherryPickCommit, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((commit, repository)) = self.selected_commit(cx) {
+            let commits = vec![commit.sha.to_string()];
+            rebase_view::cherry_pick(commits, repository, self.workspace.clone(), window, cx);
+        }
+    }
+
+    fn revert(&mut self, _: &RevertCommit, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((commit, repository)) = self.selected_commit(cx) {
+            let commits = vec![commit.sha.to_string()];
+            rebase_view::revert(commits, repository, self.workspace.clone(), window, cx);
+        }
+    }
+
+    fn rebase_from(&mut self, _: &RebaseFromCommit, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((commit, repository)) = self.selected_commit(cx) else {
+            return;
+        };
+        // Rebase onto the commit's parent, so that the commit itself can be edited too.
+        if let Some(parent) = commit.parents.first() {
+            RebaseTodoView::open(
+                parent.to_string(),
+                repository,
+                self.workspace.clone(),
+                window,
+                cx,
+            );
+        }
+    }
+
+    fn create_tag(&mut self, _: &CreateTagAtCommit, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((commit, repository)) = self.selected_commit(cx) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                git_ui::create_tag(
+                    Some(commit.sha.to_string()),
+                    repository,
+                    workspace,
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+    }
+
+    fn bisect_mark_good(
+        &mut self,
+        _: &BisectMarkGood,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.bisect_mark(BisectMark::Good, window, cx);
+    }
+
+    fn bisect_mark_bad(&mut self, _: &BisectMarkBad, window: &mut Window, cx: &mut Context<Self>) {
+        self.bisect_mark(BisectMark::Bad, window, cx);
+    }
+
+    fn bisect_mark(&mut self, mark: BisectMark, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some((commit, repository)) = self.selected_commit(cx) {
+            let commit = Some(commit.sha.to_string());
+            bisect::mark(mark, commit, repository, self.workspace.clone(), window, cx);
+        }
+    }
+
     fn get_remote(
         &self,
         repository: &Repository,
@@ -1575,6 +1765,7 @@ impl Render for GitGraph {
                             .map_row(move |(index, row), _window, cx| {
                                 let is_selected = selected_entry_idx == Some(index);
                                 let weak = weak_self.clone();
+                                let weak_for_menu = weak_self.clone();
                                 row.h(row_height)
                                     .when(is_selected, |row| {
                                         row.bg(cx.theme().colors().element_selected)
@@ -1585,6 +1776,18 @@ impl Render for GitGraph {
                                         })
                                         .ok();
                                     })
+                                    .on_mouse_down(MouseButton::Right, move |event, window, cx| {
+                                        weak_for_menu
+                                            .update(cx, |this, cx| {
+                                                this.deploy_context_menu(
+                                                    index,
+                                                    event.position,
+                                                    window,
+                                                    cx,
+                                                );
+                                            })
+                                            .ok();
+                                    })
                                     .into_any_element()
                             })
                             .uniform_list(
@@ -1604,6 +1807,12 @@ impl Render for GitGraph {
             .bg(cx.theme().colors().editor_background)
             .key_context("GitGraph")
             .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::cherry_pick))
+            .on_action(cx.listener(Self::revert))
+            .on_action(cx.listener(Self::rebase_from))
+            .on_action(cx.listener(Self::create_tag))
+            .on_action(cx.listener(Self::bisect_mark_good))
+            .on_action(cx.listener(Self::bisect_mark_bad))
             .child(content)
             .children(self.context_menu.as_ref().map(|(menu, position, _)| {
                 deferred(
@@ -1663,7 +1872,7 @@ impl SerializableItem for GitGraph {
 
     fn deserialize(
         project: Entity<Project>,
-        _: WeakEntity<Workspace>,
+        workspace: WeakEntity<Workspace>,
         workspace_id: workspace::WorkspaceId,
         item_id: workspace::ItemId,
         window: &mut Window,
@@ -1674,7 +1883,7 @@ impl SerializableItem for GitGraph {
             .ok()
             .is_some_and(|is_open| is_open)
         {
-            let git_graph = cx.new(|cx| GitGraph::new(project, window, cx));
+            let git_graph = cx.new(|cx| GitGraph::new(project, workspace, window, cx));
             Task::ready(Ok(git_graph))
         } else {
             Task::ready(Err(anyhow::anyhow!("No git graph to deserialize")))
@@ -0,0 +1,577 @@
+use anyhow::{Context as _, Result};
+use futures::channel::oneshot;
+use git::repository::{BisectMark, BisectStatus};
+use gpui::{App, Context, DismissEvent, Entity, EventEmitter, Task, WeakEntity, Window};
+use project::git_store::Repository;
+use task::ResolvedTask;
+use ui::{Tooltip, prelude::*};
+use util::capitalize;
+use workspace::Workspace;
+
+use crate::{
+    commit_view::CommitView,
+    git_panel::{GitPanel, show_error_toast},
+    picker_prompt, resolve_active_repository,
+};
+
+/// `git bisect run` treats this exit code as "this commit can't be tested".
+const SKIP_EXIT_CODE: i32 = 125;
+
+/// Marks `commit` (or the checked-out commit) in the bisect session of `repository`, starting
+/// a session in the git panel if there isn't one yet.
+pub fn mark(
+    mark: BisectMark,
+    commit: Option<String>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    workspace
+        .update(cx, |workspace, cx| {
+            let session = session(repository, workspace, window, cx)?;
+            session.update(cx, |session, cx| session.mark(mark, commit, window, cx));
+            Some(())
+        })
+        .ok();
+}
+
+pub(crate) fn mark_head(
+    mark: BisectMark,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = resolve_active_repository(workspace, cx) else {
+        return;
+    };
+    if let Some(session) = session(repository, workspace, window, cx) {
+        session.update(cx, |session, cx| session.mark(mark, None, window, cx));
+    }
+}
+
+pub(crate) fn reset(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(repository) = resolve_active_repository(workspace, cx) else {
+        return;
+    };
+    if let Some(session) = session(repository, workspace, window, cx) {
+        session.update(cx, |session, cx| session.reset(window, cx));
+    }
+}
+
+fn session(
+    repository: Entity<Repository>,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Option<Entity<BisectSession>> {
+    workspace.open_panel::<GitPanel>(window, cx);
+    let panel = workspace.panel::<GitPanel>(cx)?;
+    Some(panel.update(cx, |panel, cx| panel.bisect_session(repository, cx)))
+}
+
+/// A `git bisect` in progress, shown at the bottom of the git panel.
+///
+/// Commits are marked by hand, or by a task that runs at each step and whose exit code decides
+/// the mark, the same way `git bisect run` does.
+pub struct BisectSession {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    status: Option<BisectStatus>,
+    task: Option<ResolvedTask>,
+    pending: Option<Task<()>>,
+}
+
+impl EventEmitter<DismissEvent> for BisectSession {}
+
+impl BisectSession {
+    pub fn new(repository: Entity<Repository>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            repository,
+            workspace,
+            status: None,
+            task: None,
+            pending: None,
+        }
+    }
+
+    pub fn repository(&self) -> &Entity<Repository> {
+        &self.repository
+    }
+
+    pub fn mark(
+        &mut self,
+        mark: BisectMark,
+        commit: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let result = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_mark(mark, commit));
+        self.await_status(result, window, cx);
+    }
+
+    fn await_status(
+        &mut self,
+        result: oneshot::Receiver<Result<BisectStatus>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let workspace = self.workspace.clone();
+        self.pending = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = result
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            this.update_in(cx, |this, window, cx| {
+                this.pending = None;
+                match result {
+                    Ok(status) => {
+                        let is_testing = matches!(status, BisectStatus::Testing { .. });
+                        this.status = Some(status);
+                        if is_testing && this.task.is_some() {
+                            this.run_task(window, cx);
+                        }
+                    }
+                    Err(error) => {
+                        if let Some(workspace) = workspace.upgrade() {
+                            show_error_toast(workspace, "bisect", error, cx);
+                        }
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    /// Runs the chosen task against the checked-out commit, and marks it from the exit code.
+    fn run_task(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(task) = self.task.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let exit_status = workspace.update(cx, |workspace, cx| {
+            workspace.spawn_in_terminal(task.resolved, window, cx)
+        });
+        let workspace = self.workspace.clone();
+        self.pending = Some(cx.spawn_in(window, async move |this, cx| {
+            let exit_status = exit_status.await;
+            this.update_in(cx, |this, window, cx| {
+                this.pending = None;
+                let mark = match exit_status {
+                    Some(Ok(status)) => match status.code() {
+                        Some(0) => BisectMark::Good,
+                        Some(SKIP_EXIT_CODE) => BisectMark::Skip,
+                        Some(_) => BisectMark::Bad,
+                        // Killed by a signal, so leave it to the user to decide.
+                        None => {
+                            cx.notify();
+                            return;
+                        }
+                    },
+                    Some(Err(error)) => {
+                        if let Some(workspace) = workspace.upgrade() {
+                            show_error_toast(workspace, "run bisect task", error, cx);
+                        }
+                        cx.notify();
+                        return;
+                    }
+                    None => {
+                        cx.notify();
+                        return;
+                    }
+                };
+                this.mark(mark, None, window, cx);
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn choose_task(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let task_contexts = workspace.update(cx, |workspace, cx| {
+            tasks_ui::task_contexts(workspace, window, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let task_contexts = task_contexts.await;
+            let tasks = workspace
+                .update(cx, |workspace, cx| {
+                    let inventory = workspace
+                        .project()
+                        .read(cx)
+                        .task_store()
+                        .read(cx)
+                        .task_inventory()
+                        .cloned();
+                    match inventory {
+                        Some(inventory) => {
+                            inventory
+                                .read(cx)
+                                .list_tasks(None, None, task_contexts.worktree(), cx)
+                        }
+                        None => Task::ready(Vec::new()),
+                    }
+                })?
+                .await;
+            let labels = tasks
+                .iter()
+                .map(|(_, template)| SharedString::from(template.label.clone()))
+                .collect();
+            let selection = cx
+                .update(|window, cx| {
+                    picker_prompt::prompt(
+                        "Run a task at each bisect step",
+                        labels,
+                        workspace.clone(),
+                        window,
+                        cx,
+                    )
+                })?
+                .await;
+            let Some(ix) = selection else {
+                return Ok(());
+            };
+            let (source_kind, template) = &tasks[ix];
+            let task_context = task_contexts.active_context().cloned().unwrap_or_default();
+            let task = template
+                .resolve_task(&source_kind.to_id_base(), &task_context)
+                .with_context(|| format!("failed to resolve task {:?}", template.label))?;
+            this.update_in(cx, |this, window, cx| {
+                this.task = Some(task);
+                if this.pending.is_none()
+                    && matches!(this.status, Some(BisectStatus::Testing { .. }))
+                {
+                    this.run_task(window, cx);
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub fn reset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let result = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_reset());
+        let workspace = self.workspace.clone();
+        self.pending = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = result
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            this.update(cx, |this, cx| {
+                this.pending = None;
+                match result {
+                    Ok(()) => cx.emit(DismissEvent),
+                    Err(error) => {
+                        if let Some(workspace) = workspace.upgrade() {
+                            show_error_toast(workspace, "bisect reset", error, cx);
+                        }
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_status(&self) -> impl IntoElement {
+        let short_sha = |sha: &SharedString| sha.chars().take(7).collect::<String>();
+        let (headline, detail): (SharedString, Option<SharedString>) = match &self.status {
+            None => (
+                "Mark a good and a bad commit to start".into(),
+                Some(
+                    "Mark the checked-out commit, or right-click commits in the git graph.".into(),
+                ),
+            ),
+            Some(BisectStatus::Waiting { message }) => (capitalize(message).into(), None),
+            Some(BisectStatus::Testing {
+                sha,
+                subject,
+                remaining,
+            }) => (
+                format!("Testing {} {}", short_sha(sha), subject).into(),
+                Some(capitalize(remaining).into()),
+            ),
+            Some(BisectStatus::Found { sha, subject }) => (
+                format!("First bad commit: {} {}", short_sha(sha), subject).into(),
+                None,
+            ),
+        };
+
+        v_flex()
+            .child(Label::new(headline).size(LabelSize::Small).truncate())
+            .children(detail.map(|detail| {
+                Label::new(detail)
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                    .truncate()
+            }))
+    }
+
+    fn render_task(&self, cx: &mut Context<Self>) -> AnyElement {
+        match &self.task {
+            Some(task) => h_flex()
+                .id("bisect-task")
+                .gap_1()
+                .justify_between()
+                .tooltip(Tooltip::text(
+                    "Exit code 0 marks the commit good, 125 skips it, and any other code marks it bad.",
+                ))
+                .child(
+                    Label::new(format!("Runs \"{}\" at each step", task.resolved_label))
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
+                .child(
+                    IconButton::new("bisect-clear-task", IconName::Close)
+                        .icon_size(IconSize::XSmall)
+                        .tooltip(Tooltip::text("Stop Running the Task"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.task = None;
+                            cx.notify();
+                        })),
+                )
+                .into_any_element(),
+            None => Button::new("bisect-choose-task", "Run a Task at Each Step…")
+                .label_size(LabelSize::XSmall)
+                .on_click(cx.listener(|this, _, window, cx| this.choose_task(window, cx)))
+                .into_any_element(),
+        }
+    }
+}
+
+impl Render for BisectSession {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_pending = self.pending.is_some();
+        let found = match &self.status {
+            Some(BisectStatus::Found { sha, .. }) => Some(sha.clone()),
+            _ => None,
+        };
+        let mark_button = |id: &'static str, label: &'static str, mark: BisectMark| {
+            Button::new(id, label)
+                .label_size(LabelSize::Small)
+                .disabled(is_pending)
+                .on_click(cx.listener(move |this, _, window, cx| this.mark(mark, None, window, cx)))
+        };
+        let buttons = match found {
+            Some(sha) => h_flex().child(
+                Button::new("bisect-view-commit", "View Commit")
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        CommitView::open(
+                            sha.to_string(),
+                            this.repository.downgrade(),
+                            this.workspace.clone(),
+                            None,
+                            None,
+                            window,
+                            cx,
+                        );
+                    })),
+            ),
+            None => h_flex()
+                .gap_1()
+                .child(mark_button("bisect-good", "Good", BisectMark::Good))
+                .child(mark_button("bisect-bad", "Bad", BisectMark::Bad))
+                .child(mark_button("bisect-skip", "Skip", BisectMark::Skip)),
+        };
+
+        v_flex()
+            .id("bisect-session")
+            .p_2()
+            .gap_1p5()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .gap_1p5()
+                            .child(
+                                Icon::new(IconName::MagnifyingGlass)
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new("Bisect")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .child(
+                        Button::new("bisect-reset", "End")
+                            .label_size(LabelSize::Small)
+                            .disabled(is_pending)
+                            .tooltip(Tooltip::text(
+                                "Reset Bisect and Return to the Original Branch",
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| this.reset(window, cx))),
+                    ),
+            )
+            .child(self.render_status())
+            .child(buttons)
+            .child(self.render_task(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{path::Path, process::ExitStatus, sync::Arc};
+    use task::{SpawnInTerminal, TaskContext, TaskTemplate};
+    use util::path;
+    use workspace::TerminalProvider;
+
+    /// The commit that broke the build.
+    const FIRST_BAD: &str = "ccc3";
+    /// A commit that can't be built, so it's skipped.
+    const UNTESTABLE: &str = "ccc4";
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn exit_status(code: i32) -> ExitStatus {
+        #[cfg(unix)]
+        {
+            std::os::unix::process::ExitStatusExt::from_raw(code << 8)
+        }
+        #[cfg(windows)]
+        {
+            std::os::windows::process::ExitStatusExt::from_raw(code as u32)
+        }
+    }
+
+    /// Runs the bisect task against the checked-out commit, failing from `FIRST_BAD` on.
+    struct BisectTaskProvider {
+        fs: Arc<FakeFs>,
+        dot_git: &'static Path,
+    }
+
+    impl TerminalProvider for BisectTaskProvider {
+        fn spawn(
+            &self,
+            _task: SpawnInTerminal,
+            _window: &mut Window,
+            _cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            let head = head(&self.fs, self.dot_git);
+            let code = if head == UNTESTABLE {
+                SKIP_EXIT_CODE
+            } else if head.as_str() >= FIRST_BAD {
+                1
+            } else {
+                0
+            };
+            Task::ready(Some(Ok(exit_status(code))))
+        }
+    }
+
+    fn head(fs: &FakeFs, dot_git: &Path) -> String {
+        fs.with_git_state(dot_git, false, |state| state.refs["HEAD"].clone())
+            .unwrap()
+    }
+
+    fn status(session: &Entity<BisectSession>, cx: &mut VisualTestContext) -> BisectStatus {
+        session.read_with(cx, |session, _| session.status.clone().unwrap())
+    }
+
+    #[gpui::test]
+    async fn test_bisect(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a" }))
+            .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        let commits = (0..8)
+            .map(|ix| (format!("ccc{ix}"), format!("Change {ix}")))
+            .collect::<Vec<_>>();
+        fs.set_commits_for_repo(
+            dot_git,
+            &commits
+                .iter()
+                .map(|(sha, message)| (sha.as_str(), message.as_str()))
+                .collect::<Vec<_>>(),
+        );
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        workspace.update(cx, |workspace, _| {
+            workspace.set_terminal_provider(BisectTaskProvider {
+                fs: fs.clone(),
+                dot_git,
+            })
+        });
+        cx.run_until_parked();
+
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        let session = cx.new(|_| BisectSession::new(repository, workspace.downgrade()));
+
+        // Git waits for both a good and a bad commit before checking one out.
+        session.update_in(cx, |session, window, cx| {
+            session.mark(BisectMark::Bad, None, window, cx)
+        });
+        cx.run_until_parked();
+        assert!(matches!(status(&session, cx), BisectStatus::Waiting { .. }));
+
+        session.update_in(cx, |session, window, cx| {
+            session.mark(BisectMark::Good, Some("ccc0".into()), window, cx)
+        });
+        cx.run_until_parked();
+        let BisectStatus::Testing { sha, .. } = status(&session, cx) else {
+            panic!("expected a commit to test");
+        };
+        assert_eq!(sha.as_ref(), head(&fs, dot_git));
+
+        // A task takes over from there, marking each commit it checks out from its exit code.
+        // It can't build the commit being tested, so that one is skipped.
+        let task = TaskTemplate {
+            label: "build".into(),
+            command: "make".into(),
+            ..TaskTemplate::default()
+        }
+        .resolve_task("test", &TaskContext::default())
+        .unwrap();
+        session.update_in(cx, |session, window, cx| {
+            session.task = Some(task);
+            session.run_task(window, cx);
+        });
+        cx.run_until_parked();
+        let BisectStatus::Found { sha, subject } = status(&session, cx) else {
+            panic!("expected the first bad commit to be found");
+        };
+        assert_eq!(sha.as_ref(), FIRST_BAD);
+        assert_eq!(subject.as_ref(), "Change 3");
+
+        // Ending the bisect checks out the original commit again.
+        session.update_in(cx, |session, window, cx| session.reset(window, cx));
+        cx.run_until_parked();
+        assert_eq!(head(&fs, dot_git), "ccc7");
+    }
+}
@@ -3,7 +3,7 @@ use editor::Editor;
 use fuzzy::StringMatchCandidate;
 
 use collections::HashSet;
-use git::repository::Branch;
+use git::repository::{Branch, Tag};
 use gpui::http_client::Url;
 use gpui::{
     Action, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
@@ -25,7 +25,11 @@ use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
-use crate::{branch_picker, git_panel::show_error_toast, resolve_active_repository};
+use crate::{
+    branch_picker,
+    git_panel::{GitPanel, show_error_toast},
+    resolve_active_repository,
+};
 
 actions!(
     branch_picker,
@@ -33,7 +37,9 @@ actions!(
         /// Deletes the selected git branch or remote.
         DeleteBranch,
         /// Filter the list of remotes
-        FilterRemotes
+        FilterRemotes,
+        /// Switch between the list of branches and the list of tags
+        FilterTags
     ]
 );
 
@@ -153,6 +159,10 @@ impl BranchList {
             repository.update(cx, |repository, _| repository.default_branch(false))
         });
 
+        let all_tags_request = repository
+            .clone()
+            .map(|repository| repository.update(cx, |repository, _| repository.tags()));
+
         cx.spawn_in(window, async move |this, cx| {
             let mut all_branches = all_branches_request
                 .context("No active repository")?
@@ -164,6 +174,12 @@ impl BranchList {
                 .ok()
                 .flatten()
                 .flatten();
+            let all_tags = all_tags_request
+                .context("No active repository")?
+                .await
+                .map(Result::ok)
+                .ok()
+                .flatten();
 
             let all_branches = cx
                 .background_spawn(async move {
@@ -198,6 +214,7 @@ impl BranchList {
                 this.picker.update(cx, |picker, cx| {
                     picker.delegate.default_branch = default_branch;
                     picker.delegate.all_branches = Some(all_branches);
+                    picker.delegate.all_tags = all_tags;
                     picker.refresh(window, cx);
                 })
             });
@@ -285,6 +302,23 @@ impl BranchList {
             cx.notify();
         });
     }
+
+    pub fn handle_filter_tags(
+        &mut self,
+        _: &branch_picker::FilterTags,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.branch_filter = match picker.delegate.branch_filter {
+                BranchFilter::Tags => BranchFilter::All,
+                BranchFilter::All | BranchFilter::Remote => BranchFilter::Tags,
+            };
+            picker.update_matches(picker.query(cx), window, cx);
+            picker.refresh_placeholder(window, cx);
+            cx.notify();
+        });
+    }
 }
 impl ModalView for BranchList {}
 impl EventEmitter<DismissEvent> for BranchList {}
@@ -303,6 +337,7 @@ impl Render for BranchList {
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
             .on_action(cx.listener(Self::handle_delete))
             .on_action(cx.listener(Self::handle_filter))
+            .on_action(cx.listener(Self::handle_filter_tags))
             .child(self.picker.clone())
             .when(!self.embedded, |this| {
                 this.on_mouse_down_out({
@@ -332,6 +367,13 @@ enum Entry {
         name: String,
         url: SharedString,
     },
+    Tag {
+        tag: Tag,
+        positions: Vec<usize>,
+    },
+    NewTag {
+        name: String,
+    },
 }
 
 impl Entry {
@@ -348,9 +390,21 @@ impl Entry {
             Entry::NewUrl { url, .. } => url.as_str(),
             Entry::NewBranch { name, .. } => name.as_str(),
             Entry::NewRemoteName { name, .. } => name.as_str(),
+            Entry::Tag { tag, .. } => tag.name.as_ref(),
+            Entry::NewTag { name } => name.as_str(),
         }
     }
 
+    fn is_new_item(&self) -> bool {
+        matches!(
+            self,
+            Entry::NewUrl { .. }
+                | Entry::NewBranch { .. }
+                | Entry::NewRemoteName { .. }
+                | Entry::NewTag { .. }
+        )
+    }
+
     #[cfg(test)]
     fn is_new_url(&self) -> bool {
         matches!(self, Self::NewUrl { .. })
@@ -368,12 +422,14 @@ enum BranchFilter {
     All,
     /// Only show remote branches.
     Remote,
+    /// Show tags instead of branches.
+    Tags,
 }
 
 impl BranchFilter {
     fn invert(&self) -> Self {
         match self {
-            BranchFilter::All => BranchFilter::Remote,
+            BranchFilter::All | BranchFilter::Tags => BranchFilter::Remote,
             BranchFilter::Remote => BranchFilter::All,
         }
     }
@@ -383,6 +439,7 @@ pub struct BranchListDelegate {
     workspace: WeakEntity<Workspace>,
     matches: Vec<Entry>,
     all_branches: Option<Vec<Branch>>,
+    all_tags: Option<Vec<Tag>>,
     default_branch: Option<SharedString>,
     repo: Option<Entity<Repository>>,
     style: BranchListStyle,
@@ -419,6 +476,7 @@ impl BranchListDelegate {
             repo,
             style,
             all_branches: None,
+            all_tags: None,
             default_branch: None,
             selected_index: 0,
             last_query: Default::default(),
@@ -475,6 +533,101 @@ impl BranchListDelegate {
         cx.emit(DismissEvent);
     }
 
+    fn create_tag(&self, name: String, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = name.replace(' ', "-");
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.create_tag(name, None, None))
+                .await??;
+
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn push_tag(&self, tag: &Tag, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        let name = tag.name.clone();
+        panel.update(cx, |panel, cx| panel.push_tag(name, window, cx));
+    }
+
+    fn update_tag_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<Entry> = if query.is_empty() {
+                all_tags
+                    .into_iter()
+                    .map(|tag| Entry::Tag {
+                        tag,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| Entry::Tag {
+                    tag: all_tags[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    if !query.is_empty()
+                        && !matches.first().is_some_and(|entry| entry.name() == query)
+                    {
+                        matches.push(Entry::NewTag {
+                            name: query.replace(' ', "-"),
+                        });
+                    }
+                    let delegate = &mut picker.delegate;
+                    delegate.state = PickerState::List;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                    delegate.last_query = query;
+                })
+                .log_err();
+        })
+    }
+
     fn delete_at(&self, idx: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
         let Some(entry) = self.matches.get(idx).cloned() else {
             return;
@@ -486,19 +639,29 @@ impl BranchListDelegate {
         let workspace = self.workspace.clone();
 
         cx.spawn_in(window, async move |picker, cx| {
-            let mut is_remote = false;
-            let result = match &entry {
+            let (kind, command, result) = match &entry {
                 Entry::Branch { branch, .. } => match branch.remote_name() {
-                    Some(remote_name) => {
-                        is_remote = true;
+                    Some(remote_name) => (
+                        "remote",
+                        format!("remote remove {}", entry.name()),
                         repo.update(cx, |repo, _| repo.remove_remote(remote_name.to_string()))
-                            .await?
-                    }
-                    None => {
-                        repo.update(cx, |repo, _| repo.delete_branch(branch.name().to_string()))
-                            .await?
-                    }
+                            .await?,
+                    ),
+                    None => (
+                        "branch",
+                        format!("branch -d {}", entry.name()),
+                        repo.update(cx, |repo, _| {
+                            repo.delete_branch(branch.name().to_string(), false)
+                        })
+                        .await?,
+                    ),
                 },
+                Entry::Tag { tag, .. } => (
+                    "tag",
+                    format!("tag --delete {}", tag.name),
+                    repo.update(cx, |repo, _| repo.delete_tag(tag.name.to_string()))
+                        .await?,
+                ),
                 _ => {
                     log::error!("Failed to delete remote: wrong entry to delete");
                     return Ok(());
@@ -506,30 +669,10 @@ impl BranchListDelegate {
             };
 
             if let Err(e) = result {
-                if is_remote {
-                    log::error!("Failed to delete remote: {}", e);
-                } else {
-                    log::error!("Failed to delete branch: {}", e);
-                }
+                log::error!("Failed to delete {}: {}", kind, e);
 
                 if let Some(workspace) = workspace.upgrade() {
-                    cx.update(|_window, cx| {
-                        if is_remote {
-                            show_error_toast(
-                                workspace,
-                                format!("remote remove {}", entry.name()),
-                                e,
-                                cx,
-                            )
-                        } else {
-                            show_error_toast(
-                                workspace,
-                                format!("branch -d {}", entry.name()),
-                                e,
-                                cx,
-                            )
-                        }
-                    })?;
+                    cx.update(|_window, cx| show_error_toast(workspace, command, e, cx))?;
                 }
 
                 return Ok(());
@@ -543,6 +686,11 @@ impl BranchListDelegate {
                         all_branches.retain(|e| e.ref_name != branch.ref_name);
                     }
                 }
+                if let Entry::Tag { tag, .. } = &entry {
+                    if let Some(all_tags) = &mut picker.delegate.all_tags {
+                        all_tags.retain(|e| e.name != tag.name);
+                    }
+                }
 
                 if picker.delegate.matches.is_empty() {
                     picker.delegate.selected_index = 0;
@@ -568,6 +716,7 @@ impl PickerDelegate for BranchListDelegate {
                 match self.branch_filter {
                     BranchFilter::All => "Select branch or remote…",
                     BranchFilter::Remote => "Select remote…",
+                    BranchFilter::Tags => "Select or create tag…",
                 }
             }
             PickerState::CreateRemote(_) => "Enter a name for this remote…",
@@ -609,28 +758,53 @@ impl PickerDelegate for BranchListDelegate {
                         self.editor_position() == PickerEditorPosition::End,
                         |this| {
                             let tooltip_label = match self.branch_filter {
-                                BranchFilter::All => "Filter Remote Branches",
+                                BranchFilter::All | BranchFilter::Tags => "Filter Remote Branches",
                                 BranchFilter::Remote => "Show All Branches",
                             };
+                            let tags_tooltip_label = match self.branch_filter {
+                                BranchFilter::Tags => "Show Branches",
+                                BranchFilter::All | BranchFilter::Remote => "Show Tags",
+                            };
+                            let tags_focus_handle = focus_handle.clone();
 
-                            this.gap_1().justify_between().child({
-                                IconButton::new("filter-remotes", IconName::Filter)
-                                    .toggle_state(self.branch_filter == BranchFilter::Remote)
-                                    .tooltip(move |_, cx| {
-                                        Tooltip::for_action_in(
-                                            tooltip_label,
-                                            &branch_picker::FilterRemotes,
-                                            &focus_handle,
-                                            cx,
-                                        )
-                                    })
-                                    .on_click(|_click, window, cx| {
-                                        window.dispatch_action(
-                                            branch_picker::FilterRemotes.boxed_clone(),
-                                            cx,
-                                        );
-                                    })
-                            })
+                            this.gap_1()
+                                .justify_between()
+                                .child(
+                                    IconButton::new("filter-tags", IconName::Hash)
+                                        .toggle_state(self.branch_filter == BranchFilter::Tags)
+                                        .tooltip(move |_, cx| {
+                                            Tooltip::for_action_in(
+                                                tags_tooltip_label,
+                                                &branch_picker::FilterTags,
+                                                &tags_focus_handle,
+                                                cx,
+                                            )
+                                        })
+                                        .on_click(|_click, window, cx| {
+                                            window.dispatch_action(
+                                                branch_picker::FilterTags.boxed_clone(),
+                                                cx,
+                                            );
+                                        }),
+                                )
+                                .child({
+                                    IconButton::new("filter-remotes", IconName::Filter)
+                                        .toggle_state(self.branch_filter == BranchFilter::Remote)
+                                        .tooltip(move |_, cx| {
+                                            Tooltip::for_action_in(
+                                                tooltip_label,
+                                                &branch_picker::FilterRemotes,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                        })
+                                        .on_click(|_click, window, cx| {
+                                            window.dispatch_action(
+                                                branch_picker::FilterRemotes.boxed_clone(),
+                                                cx,
+                                            );
+                                        })
+                                })
                         },
                     ),
             )
@@ -670,6 +844,11 @@ impl PickerDelegate for BranchListDelegate {
         window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) -> Task<()> {
+        if self.branch_filter == BranchFilter::Tags
+            && !matches!(self.state, PickerState::CreateRemote(_))
+        {
+            return self.update_tag_matches(query, window, cx);
+        }
         let Some(all_branches) = self.all_branches.clone() else {
             return Task::ready(());
         };
@@ -677,7 +856,7 @@ impl PickerDelegate for BranchListDelegate {
         let branch_filter = self.branch_filter;
         cx.spawn_in(window, async move |picker, cx| {
             let branch_matches_filter = |branch: &Branch| match branch_filter {
-                BranchFilter::All => true,
+                BranchFilter::All | BranchFilter::Tags => true,
                 BranchFilter::Remote => branch.is_remote(),
             };
 
@@ -849,6 +1028,12 @@ impl PickerDelegate for BranchListDelegate {
                 };
                 self.create_branch(from_branch, name.into(), window, cx);
             }
+            Entry::Tag { tag, .. } => {
+                self.push_tag(tag, window, cx);
+            }
+            Entry::NewTag { name } => {
+                self.create_tag(name.clone(), window, cx);
+            }
         }
 
         cx.emit(DismissEvent);
@@ -868,31 +1053,48 @@ impl PickerDelegate for BranchListDelegate {
     ) -> Option<Self::ListItem> {
         let entry = &self.matches.get(ix)?;
 
-        let (commit_time, author_name, subject) = entry
-            .as_branch()
-            .and_then(|branch| {
-                branch.most_recent_commit.as_ref().map(|commit| {
-                    let subject = commit.subject.clone();
-                    let commit_time = OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
-                        .unwrap_or_else(|_| OffsetDateTime::now_utc());
-                    let local_offset =
-                        time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
-                    let formatted_time = time_format::format_localized_timestamp(
-                        commit_time,
-                        OffsetDateTime::now_utc(),
-                        local_offset,
-                        time_format::TimestampFormat::Relative,
-                    );
-                    let author = commit.author_name.clone();
-                    (Some(formatted_time), Some(author), Some(subject))
+        let format_time = |timestamp: i64| {
+            let time = OffsetDateTime::from_unix_timestamp(timestamp)
+                .unwrap_or_else(|_| OffsetDateTime::now_utc());
+            let local_offset =
+                time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
+            time_format::format_localized_timestamp(
+                time,
+                OffsetDateTime::now_utc(),
+                local_offset,
+                time_format::TimestampFormat::Relative,
+            )
+        };
+
+        let (commit_time, author_name, subject) = match entry {
+            Entry::Tag { tag, .. } => {
+                let subject = tag.message.clone().unwrap_or_else(|| {
+                    format!("Tags {}", tag.sha.get(..7).unwrap_or(&tag.sha)).into()
+                });
+                (
+                    (tag.timestamp != 0).then(|| format_time(tag.timestamp)),
+                    None,
+                    Some(subject),
+                )
+            }
+            _ => entry
+                .as_branch()
+                .and_then(|branch| {
+                    branch.most_recent_commit.as_ref().map(|commit| {
+                        let subject = commit.subject.clone();
+                        let formatted_time = format_time(commit.commit_timestamp);
+                        let author = commit.author_name.clone();
+                        (Some(formatted_time), Some(author), Some(subject))
+                    })
                 })
-            })
-            .unwrap_or_else(|| (None, None, None));
+                .unwrap_or_else(|| (None, None, None)),
+        };
 
         let entry_icon = match entry {
-            Entry::NewUrl { .. } | Entry::NewBranch { .. } | Entry::NewRemoteName { .. } => {
-                Icon::new(IconName::Plus).color(Color::Muted)
-            }
+            Entry::NewUrl { .. }
+            | Entry::NewBranch { .. }
+            | Entry::NewRemoteName { .. }
+            | Entry::NewTag { .. } => Icon::new(IconName::Plus).color(Color::Muted),
             Entry::Branch { branch, .. } => {
                 if branch.is_remote() {
                     Icon::new(IconName::Screen).color(Color::Muted)
@@ -900,6 +1102,7 @@ impl PickerDelegate for BranchListDelegate {
                     Icon::new(IconName::GitBranchAlt).color(Color::Muted)
                 }
             }
+            Entry::Tag { .. } => Icon::new(IconName::Hash).color(Color::Muted),
         };
 
         let entry_title = match entry {
@@ -921,19 +1124,31 @@ impl PickerDelegate for BranchListDelegate {
                     .truncate()
                     .into_any_element()
             }
+            Entry::NewTag { name } => Label::new(format!("Create Tag: \"{name}\""))
+                .single_line()
+                .truncate()
+                .into_any_element(),
+            Entry::Tag { tag, positions } => {
+                HighlightedLabel::new(tag.name.to_string(), positions.clone())
+                    .single_line()
+                    .truncate()
+                    .into_any_element()
+            }
         };
 
         let focus_handle = self.focus_handle.clone();
-        let is_new_items = matches!(
-            entry,
-            Entry::NewUrl { .. } | Entry::NewBranch { .. } | Entry::NewRemoteName { .. }
-        );
+        let is_new_items = entry.is_new_item();
+        let delete_tooltip_label = if matches!(entry, Entry::Tag { .. }) {
+            "Delete Tag"
+        } else {
+            "Delete Branch"
+        };
 
         let deleted_branch_icon = |entry_ix: usize, is_head_branch: bool| {
             IconButton::new(("delete", entry_ix), IconName::Trash)
                 .tooltip(move |_, cx| {
                     Tooltip::for_action_in(
-                        "Delete Branch",
+                        delete_tooltip_label,
                         &branch_picker::DeleteBranch,
                         &focus_handle,
                         cx,
@@ -945,24 +1160,29 @@ impl PickerDelegate for BranchListDelegate {
                 }))
         };
 
-        let create_from_default_button = self.default_branch.as_ref().map(|default_branch| {
-            let tooltip_label: SharedString = format!("Create New From: {default_branch}").into();
-            let focus_handle = self.focus_handle.clone();
-
-            IconButton::new("create_from_default", IconName::GitBranchPlus)
-                .tooltip(move |_, cx| {
-                    Tooltip::for_action_in(
-                        tooltip_label.clone(),
-                        &menu::SecondaryConfirm,
-                        &focus_handle,
-                        cx,
-                    )
-                })
-                .on_click(cx.listener(|this, _, window, cx| {
-                    this.delegate.confirm(true, window, cx);
-                }))
-                .into_any_element()
-        });
+        let create_from_default_button = self
+            .default_branch
+            .as_ref()
+            .filter(|_| !matches!(entry, Entry::NewTag { .. }))
+            .map(|default_branch| {
+                let tooltip_label: SharedString =
+                    format!("Create New From: {default_branch}").into();
+                let focus_handle = self.focus_handle.clone();
+
+                IconButton::new("create_from_default", IconName::GitBranchPlus)
+                    .tooltip(move |_, cx| {
+                        Tooltip::for_action_in(
+                            tooltip_label.clone(),
+                            &menu::SecondaryConfirm,
+                            &focus_handle,
+                            cx,
+                        )
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.delegate.confirm(true, window, cx);
+                    }))
+                    .into_any_element()
+            });
 
         Some(
             ListItem::new(format!("vcs-menu-{ix}"))
@@ -994,7 +1214,8 @@ impl PickerDelegate for BranchListDelegate {
                                                     Entry::NewRemoteName { url, .. } => {
                                                         format!("Based off {url}")
                                                     }
-                                                    Entry::NewBranch { .. } => {
+                                                    Entry::NewBranch { .. }
+                                                    | Entry::NewTag { .. } => {
                                                         if let Some(current_branch) =
                                                             self.repo.as_ref().and_then(|repo| {
                                                                 repo.read(cx)
@@ -1033,6 +1254,9 @@ impl PickerDelegate for BranchListDelegate {
                                                             },
                                                         )
                                                     }
+                                                    Entry::Tag { .. } => subject
+                                                        .map(|subject| subject.to_string())
+                                                        .unwrap_or_default(),
                                                 };
 
                                                 Label::new(message)
@@ -1097,6 +1321,7 @@ impl PickerDelegate for BranchListDelegate {
             let label = match self.branch_filter {
                 BranchFilter::All => "Branches",
                 BranchFilter::Remote => "Remotes",
+                BranchFilter::Tags => "Tags",
             };
 
             ListHeader::new(label).inset(true).into_any_element()
@@ -1118,6 +1343,77 @@ impl PickerDelegate for BranchListDelegate {
         };
 
         match self.state {
+            PickerState::List if self.branch_filter == BranchFilter::Tags => {
+                let is_new_tag = matches!(
+                    self.matches.get(self.selected_index),
+                    Some(Entry::NewTag { .. })
+                );
+
+                Some(
+                    footer_container()
+                        .justify_between()
+                        .child(
+                            Button::new("filter-tags", "Show Branches")
+                                .key_binding(
+                                    KeyBinding::for_action_in(
+                                        &branch_picker::FilterTags,
+                                        &focus_handle,
+                                        cx,
+                                    )
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                                )
+                                .on_click(|_click, window, cx| {
+                                    window.dispatch_action(
+                                        branch_picker::FilterTags.boxed_clone(),
+                                        cx,
+                                    );
+                                }),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .when(!is_new_tag, |this| {
+                                    this.child(
+                                        Button::new("delete-tag", "Delete")
+                                            .key_binding(
+                                                KeyBinding::for_action_in(
+                                                    &branch_picker::DeleteBranch,
+                                                    &focus_handle,
+                                                    cx,
+                                                )
+                                                .map(|kb| kb.size(rems_from_px(12.))),
+                                            )
+                                            .on_click(|_, window, cx| {
+                                                window.dispatch_action(
+                                                    branch_picker::DeleteBranch.boxed_clone(),
+                                                    cx,
+                                                );
+                                            }),
+                                    )
+                                })
+                                .child(
+                                    Button::new(
+                                        "confirm-tag",
+                                        if is_new_tag { "Create" } else { "Push" },
+                                    )
+                                    .key_binding(
+                                        KeyBinding::for_action_in(
+                                            &menu::Confirm,
+                                            &focus_handle,
+                                            cx,
+                                        )
+                                        .map(|kb| kb.size(rems_from_px(12.))),
+                                    )
+                                    .on_click(cx.listener(
+                                        |this, _, window, cx| {
+                                            this.delegate.confirm(false, window, cx);
+                                        },
+                                    )),
+                                ),
+                        )
+                        .into_any_element(),
+                )
+            }
             PickerState::List => {
                 let selected_entry = self.matches.get(self.selected_index);
 
@@ -1197,25 +1493,47 @@ impl PickerDelegate for BranchListDelegate {
                                 this.justify_between()
                                     .child({
                                         let focus_handle = focus_handle.clone();
-                                        Button::new("filter-remotes", "Filter Remotes")
-                                            .toggle_state(matches!(
-                                                self.branch_filter,
-                                                BranchFilter::Remote
-                                            ))
-                                            .key_binding(
-                                                KeyBinding::for_action_in(
-                                                    &branch_picker::FilterRemotes,
-                                                    &focus_handle,
-                                                    cx,
-                                                )
-                                                .map(|kb| kb.size(rems_from_px(12.))),
+                                        h_flex()
+                                            .gap_1()
+                                            .child(
+                                                Button::new("filter-remotes", "Filter Remotes")
+                                                    .toggle_state(matches!(
+                                                        self.branch_filter,
+                                                        BranchFilter::Remote
+                                                    ))
+                                                    .key_binding(
+                                                        KeyBinding::for_action_in(
+                                                            &branch_picker::FilterRemotes,
+                                                            &focus_handle,
+                                                            cx,
+                                                        )
+                                                        .map(|kb| kb.size(rems_from_px(12.))),
+                                                    )
+                                                    .on_click(|_click, window, cx| {
+                                                        window.dispatch_action(
+                                                            branch_picker::FilterRemotes
+                                                                .boxed_clone(),
+                                                            cx,
+                                                        );
+                                                    }),
+                                            )
+                                            .child(
+                                                Button::new("filter-tags", "Tags")
+                                                    .key_binding(
+                                                        KeyBinding::for_action_in(
+                                                            &branch_picker::FilterTags,
+                                                            &focus_handle,
+                                                            cx,
+                                                        )
+                                                        .map(|kb| kb.size(rems_from_px(12.))),
+                                                    )
+                                                    .on_click(|_click, window, cx| {
+                                                        window.dispatch_action(
+                                                            branch_picker::FilterTags.boxed_clone(),
+                                                            cx,
+                                                        );
+                                                    }),
                                             )
-                                            .on_click(|_click, window, cx| {
-                                                window.dispatch_action(
-                                                    branch_picker::FilterRemotes.boxed_clone(),
-                                                    cx,
-                                                );
-                                            })
                                     })
                                     .child(delete_and_select_btns)
                             }
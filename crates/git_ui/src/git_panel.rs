@@ -1,4 +1,5 @@
 use crate::askpass_modal::AskPassModal;
+use crate::bisect::BisectSession;
 use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
@@ -625,6 +626,7 @@ pub struct GitPanel {
     local_committer_task: Option<Task<()>>,
     bulk_staging: Option<BulkStaging>,
     stash_entries: GitStash,
+    bisect: Option<(Entity<BisectSession>, Subscription)>,
     _settings_subscription: Subscription,
 }
 
@@ -791,6 +793,7 @@ impl GitPanel {
                 entry_count: 0,
                 bulk_staging: None,
                 stash_entries: Default::default(),
+                bisect: None,
                 _settings_subscription,
             };
 
@@ -3073,6 +3076,66 @@ impl GitPanel {
         .detach_and_log_err(cx);
     }
 
+    pub(crate) fn push_tag(
+        &mut self,
+        tag: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let remote = self.get_remote(true, true, window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            let tag_ref: SharedString = format!("refs/tags/{tag}").into();
+            let push = repo.update(cx, |repo, cx| {
+                repo.push(
+                    tag_ref.clone(),
+                    tag_ref,
+                    remote.name.clone(),
+                    None,
+                    askpass_delegate,
+                    cx,
+                )
+            });
+
+            let remote_output = push.await?;
+
+            let action = RemoteAction::Push(format!("tag {tag}").into(), remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn create_pull_request(&self, window: &mut Window, cx: &mut Context<Self>) {
         let result = (|| -> anyhow::Result<()> {
             let repo = self
@@ -3135,6 +3198,29 @@ impl GitPanel {
         }
     }
 
+    /// Returns the bisect session for `repository`, replacing the session of any other
+    /// repository.
+    pub(crate) fn bisect_session(
+        &mut self,
+        repository: Entity<Repository>,
+        cx: &mut Context<Self>,
+    ) -> Entity<BisectSession> {
+        if let Some((session, _)) = &self.bisect
+            && session.read(cx).repository() == &repository
+        {
+            return session.clone();
+        }
+
+        let session = cx.new(|_| BisectSession::new(repository, self.workspace.clone()));
+        let subscription = cx.subscribe(&session, |this, _, _: &DismissEvent, cx| {
+            this.bisect = None;
+            cx.notify();
+        });
+        self.bisect = Some((session.clone(), subscription));
+        cx.notify();
+        session
+    }
+
     fn askpass_delegate(
         &self,
         operation: impl Into<SharedString>,
@@ -5509,6 +5595,7 @@ impl Render for GitPanel {
                             this.child(self.render_empty_state(cx).into_any_element())
                         }
                     })
+                    .children(self.bisect.as_ref().map(|(session, _)| session.clone()))
                     .children(self.render_footer(window, cx))
                     .when(self.amend_pending, |this| {
                         this.child(self.render_pending_amend(cx))
@@ -15,7 +15,7 @@ mod blame_ui;
 pub mod clone;
 
 use git::{
-    repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
+    repository::{BisectMark, Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
 };
 use gpui::{
@@ -33,6 +33,7 @@ use zed_actions;
 use crate::{git_panel::GitPanel, text_diff_view::TextDiffView};
 
 mod askpass_modal;
+pub mod bisect;
 pub mod branch_picker;
 mod commit_modal;
 pub mod commit_tooltip;
@@ -47,6 +48,7 @@ pub mod multi_diff_view;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+pub mod rebase_view;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod stash_picker;
@@ -197,6 +199,24 @@ pub fn init(cx: &mut App) {
                 panel.uncommit(window, cx);
             })
         });
+        workspace.register_action(|workspace, _: &git::ContinueOperation, window, cx| {
+            rebase_view::continue_operation(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::AbortOperation, window, cx| {
+            rebase_view::abort_operation(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectGood, window, cx| {
+            bisect::mark_head(BisectMark::Good, workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectBad, window, cx| {
+            bisect::mark_head(BisectMark::Bad, workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectSkip, window, cx| {
+            bisect::mark_head(BisectMark::Skip, workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::BisectReset, window, cx| {
+            bisect::reset(workspace, window, cx);
+        });
         CommandPaletteFilter::update_global(cx, |filter, _cx| {
             filter.hide_action_types(&[
                 zed_actions::OpenGitIntegrationOnboarding.type_id(),
@@ -455,6 +475,128 @@ fn rename_current_branch(
     });
 }
 
+struct CreateTagModal {
+    target: Option<String>,
+    name_editor: Entity<Editor>,
+    message_editor: Entity<Editor>,
+    repo: Entity<Repository>,
+}
+
+impl CreateTagModal {
+    fn new(
+        target: Option<String>,
+        repo: Entity<Repository>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Tag name", window, cx);
+            editor
+        });
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Message (creates an annotated tag)", window, cx);
+            editor
+        });
+        Self {
+            target,
+            name_editor,
+            message_editor,
+            repo,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx);
+        if name.trim().is_empty() {
+            cx.emit(DismissEvent);
+            return;
+        }
+        let message = self.message_editor.read(cx).text(cx);
+        let message = (!message.trim().is_empty()).then_some(message);
+
+        let repo = self.repo.clone();
+        let target = self.target.clone();
+        cx.spawn(async move |_, cx| {
+            match repo
+                .update(cx, |repo, _| {
+                    repo.create_tag(name.trim().to_string(), target, message)
+                })
+                .await
+            {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err(error)) => Err(error),
+                Err(_) => Err(anyhow!("Operation was canceled")),
+            }
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CreateTagModal {}
+impl ModalView for CreateTagModal {}
+impl Focusable for CreateTagModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateTagModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match &self.target {
+            Some(target) => format!(
+                "Create Tag at {}",
+                target.chars().take(7).collect::<String>()
+            ),
+            None => "Create Tag".to_string(),
+        };
+        v_flex()
+            .key_context("CreateTagModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::Hash).size(IconSize::XSmall))
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(
+                v_flex()
+                    .px_3()
+                    .pb_3()
+                    .w_full()
+                    .gap_2()
+                    .child(self.name_editor.clone())
+                    .child(self.message_editor.clone()),
+            )
+    }
+}
+
+/// Prompts for a tag name, and tags `target` (or the checked-out commit) with it.
+pub fn create_tag(
+    target: Option<String>,
+    repo: Entity<Repository>,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    workspace.toggle_modal(window, cx, |window, cx| {
+        CreateTagModal::new(target, repo, window, cx)
+    });
+}
+
 fn render_remote_button(
     id: impl Into<SharedString>,
     branch: &Branch,
@@ -0,0 +1,788 @@
+use std::ops::Range;
+
+use anyhow::{Result, bail};
+use editor::{Editor, EditorEvent};
+use futures::channel::oneshot;
+use git::repository::{RebaseAction, RebaseTodoEntry};
+use gpui::{
+    Action, AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
+    ScrollStrategy, Subscription, UniformListScrollHandle, WeakEntity, Window, actions,
+    uniform_list,
+};
+use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::git_store::Repository;
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{
+    ContextMenu, DropdownMenu, DropdownStyle, KeyBinding, ListItem, ListItemSpacing, WithScrollbar,
+    prelude::*,
+};
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent},
+    notifications::{
+        DetachAndPromptErr as _, NotificationId, NotificationSource,
+        simple_message_notification::MessageNotification,
+    },
+};
+
+use crate::{git_panel::show_error_toast, project_diff::ProjectDiff};
+
+actions!(
+    rebase_todo,
+    [
+        /// Starts the rebase with the commits as listed.
+        StartRebase,
+        /// Moves the selected commit up, so that it's replayed earlier.
+        MoveUp,
+        /// Moves the selected commit down, so that it's replayed later.
+        MoveDown,
+        /// Keeps the selected commit.
+        PickCommit,
+        /// Keeps the selected commit, replacing its message.
+        RewordCommit,
+        /// Melds the selected commit into the one above, combining their messages.
+        SquashCommit,
+        /// Melds the selected commit into the one above, keeping only that commit's message.
+        FixupCommit,
+        /// Leaves the selected commit out.
+        DropCommit,
+    ]
+);
+
+/// Cherry-picks `commits` onto HEAD, oldest first.
+pub fn cherry_pick(
+    commits: Vec<String>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let result = repository.update(cx, |repository, _| repository.cherry_pick(commits));
+    report_outcome("cherry-pick", result, workspace, window, cx);
+}
+
+/// Reverts `commits` with new commits on top of HEAD.
+pub fn revert(
+    commits: Vec<String>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let result = repository.update(cx, |repository, _| repository.revert(commits));
+    report_outcome("revert", result, workspace, window, cx);
+}
+
+pub(crate) fn continue_operation(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let result = repository.update(cx, |repository, _| repository.continue_operation());
+    report_outcome("continue", result, workspace.weak_handle(), window, cx);
+}
+
+pub(crate) fn abort_operation(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let result = repository.update(cx, |repository, _| repository.abort_operation());
+    report_outcome("abort", result, workspace.weak_handle(), window, cx);
+}
+
+/// Reports a failed cherry-pick, revert, or rebase. When it stopped on conflicts, opens them in
+/// the project diff to be resolved, and offers to continue or abort the operation.
+fn report_outcome(
+    operation: &'static str,
+    result: oneshot::Receiver<Result<()>>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    window
+        .spawn(cx, async move |cx| {
+            let Err(error) = result
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+            else {
+                return;
+            };
+            let Some(workspace) = workspace.upgrade() else {
+                return;
+            };
+            // Git reports each conflicting file on a line starting with "CONFLICT".
+            if error.to_string().contains("CONFLICT") {
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        ProjectDiff::deploy_at(workspace, None, window, cx);
+                        show_conflicts_notification(operation, workspace, cx);
+                    })
+                    .ok();
+            } else {
+                cx.update(|_, cx| show_error_toast(workspace, operation, error, cx))
+                    .ok();
+            }
+        })
+        .detach();
+}
+
+fn show_conflicts_notification(
+    operation: &'static str,
+    workspace: &mut Workspace,
+    cx: &mut Context<Workspace>,
+) {
+    struct OperationConflicts;
+
+    let message = match operation {
+        "continue" => "Git stopped on more conflicts.".to_string(),
+        operation => format!("The {operation} stopped on conflicts."),
+    };
+    workspace.show_notification(
+        NotificationId::unique::<OperationConflicts>(),
+        NotificationSource::Git,
+        cx,
+        |cx| {
+            cx.new(|cx| {
+                MessageNotification::new(
+                    format!("{message} Resolve and stage them, then continue."),
+                    cx,
+                )
+                .primary_message("Continue")
+                .primary_on_click(|window, cx| {
+                    window.dispatch_action(git::ContinueOperation.boxed_clone(), cx);
+                })
+                .secondary_message("Abort")
+                .secondary_on_click(|window, cx| {
+                    window.dispatch_action(git::AbortOperation.boxed_clone(), cx);
+                })
+            })
+        },
+    );
+}
+
+/// The todo list of an interactive rebase, where commits can be reordered, reworded, melded
+/// together, or dropped before the rebase starts.
+pub struct RebaseTodoView {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    base: SharedString,
+    entries: Vec<RebaseTodoEntry>,
+    selected_index: usize,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    /// Edits the message of the selected commit, while it's reworded.
+    message_editor: Entity<Editor>,
+    _message_editor_subscription: Subscription,
+}
+
+#[derive(Clone)]
+struct DraggedRebaseEntry {
+    ix: usize,
+    entry: RebaseTodoEntry,
+}
+
+impl RebaseTodoView {
+    /// Opens the todo list for rebasing the commits after `base` onto it.
+    pub fn open(
+        base: String,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let entries = repository.update(cx, |repository, _| repository.rebase_todo(base.clone()));
+        window
+            .spawn(cx, async move |cx| {
+                let entries = entries.await??;
+                if entries.is_empty() {
+                    bail!("There are no commits to rebase after {base}.");
+                }
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let view = cx.new(|cx| {
+                        Self::new(
+                            base.into(),
+                            entries,
+                            repository,
+                            workspace.weak_handle(),
+                            window,
+                            cx,
+                        )
+                    });
+                    workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+                })?;
+                anyhow::Ok(())
+            })
+            .detach_and_prompt_err(
+                "Failed to list the commits to rebase",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+    }
+
+    fn new(
+        base: SharedString,
+        entries: Vec<RebaseTodoEntry>,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(3, 12, window, cx);
+            editor.set_placeholder_text("Commit message", window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&message_editor, |this, editor, event, cx| {
+            if let EditorEvent::BufferEdited = event
+                && let Some(entry) = this.entries.get_mut(this.selected_index)
+                && entry.action == RebaseAction::Reword
+            {
+                entry.message = editor.read(cx).text(cx).into();
+                cx.notify();
+            }
+        });
+
+        Self {
+            repository,
+            workspace,
+            base,
+            entries,
+            selected_index: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            message_editor,
+            _message_editor_subscription: subscription,
+        }
+    }
+
+    fn select(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        if entry.action == RebaseAction::Reword {
+            let message = entry.message.clone();
+            self.message_editor.update(cx, |editor, cx| {
+                editor.set_text(message.as_ref(), window, cx);
+            });
+        }
+        self.selected_index = ix;
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(self.selected_index + 1, window, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(self.selected_index.saturating_sub(1), window, cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(0, window, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(self.entries.len().saturating_sub(1), window, cx);
+    }
+
+    fn move_entry(&mut self, from: usize, to: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if from == to || from >= self.entries.len() || to >= self.entries.len() {
+            return;
+        }
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        self.select(to, window, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(to) = self.selected_index.checked_sub(1) {
+            self.move_entry(self.selected_index, to, window, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_entry(self.selected_index, self.selected_index + 1, window, cx);
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.action = action;
+        self.select(ix, window, cx);
+        if action == RebaseAction::Reword {
+            window.focus(&self.message_editor.focus_handle(cx), cx);
+        }
+    }
+
+    fn set_selected_action(
+        &mut self,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_action(self.selected_index, action, window, cx);
+    }
+
+    /// Returns why the todo list can't be started as it is.
+    fn validation_error(&self) -> Option<&'static str> {
+        let first_kept = self
+            .entries
+            .iter()
+            .find(|entry| entry.action != RebaseAction::Drop)?;
+        if matches!(
+            first_kept.action,
+            RebaseAction::Squash | RebaseAction::Fixup
+        ) {
+            return Some("The first commit has no earlier commit to be melded into.");
+        }
+        if self
+            .entries
+            .iter()
+            .any(|entry| entry.action == RebaseAction::Reword && entry.message.trim().is_empty())
+        {
+            return Some("Reworded commits need a message.");
+        }
+        None
+    }
+
+    fn start_rebase(&mut self, _: &StartRebase, window: &mut Window, cx: &mut Context<Self>) {
+        if self.validation_error().is_some() {
+            return;
+        }
+        let base = self.base.to_string();
+        let todo = self.entries.clone();
+        let result = self.repository.update(cx, |repository, _| {
+            repository.rebase_interactive(base, todo)
+        });
+        report_outcome("rebase", result, self.workspace.clone(), window, cx);
+        cx.emit(ItemEvent::CloseItem);
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("RebaseTodo");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range.map(|ix| self.render_entry(ix, window, cx)).collect()
+    }
+
+    fn render_entry(&self, ix: usize, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let current_action = entry.action;
+        let this = cx.weak_entity();
+        let action_menu = ContextMenu::build(window, cx, |mut menu, _, _| {
+            menu = menu.context(self.focus_handle.clone());
+            for action in RebaseAction::ALL {
+                let this = this.clone();
+                menu = menu.toggleable_entry(
+                    action_label(action),
+                    action == current_action,
+                    IconPosition::Start,
+                    Some(action_to_key_action(action)),
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.set_action(ix, action, window, cx))
+                            .ok();
+                    },
+                );
+            }
+            menu
+        });
+        let is_dropped = current_action == RebaseAction::Drop;
+        let short_sha = entry.sha.get(..7).unwrap_or(&entry.sha).to_string();
+
+        div()
+            .id(("rebase-todo-entry", ix))
+            .on_drag(
+                DraggedRebaseEntry {
+                    ix,
+                    entry: entry.clone(),
+                },
+                |dragged, _, _, cx| cx.new(|_| dragged.clone()),
+            )
+            .drag_over::<DraggedRebaseEntry>(|style, _, _, cx| {
+                style.bg(cx.theme().colors().drop_target_background)
+            })
+            .on_drop(
+                cx.listener(move |this, dragged: &DraggedRebaseEntry, window, cx| {
+                    this.move_entry(dragged.ix, ix, window, cx);
+                }),
+            )
+            .child(
+                ListItem::new(("rebase-todo-item", ix))
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(self.selected_index == ix)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.select(ix, window, cx);
+                        window.focus(&this.focus_handle, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .min_w_0()
+                            .gap_2()
+                            .child(
+                                DropdownMenu::new(
+                                    ("rebase-todo-action", ix),
+                                    action_label(current_action),
+                                    action_menu,
+                                )
+                                .style(DropdownStyle::Outlined)
+                                .trigger_size(ButtonSize::Compact),
+                            )
+                            .child(
+                                Label::new(short_sha)
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new(entry.subject().to_string())
+                                    .truncate()
+                                    .when(is_dropped, |label| {
+                                        label.strikethrough().color(Color::Disabled)
+                                    }),
+                            ),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+fn action_label(action: RebaseAction) -> &'static str {
+    match action {
+        RebaseAction::Pick => "Pick",
+        RebaseAction::Reword => "Reword",
+        RebaseAction::Squash => "Squash",
+        RebaseAction::Fixup => "Fixup",
+        RebaseAction::Drop => "Drop",
+    }
+}
+
+fn action_to_key_action(action: RebaseAction) -> Box<dyn Action> {
+    match action {
+        RebaseAction::Pick => PickCommit.boxed_clone(),
+        RebaseAction::Reword => RewordCommit.boxed_clone(),
+        RebaseAction::Squash => SquashCommit.boxed_clone(),
+        RebaseAction::Fixup => FixupCommit.boxed_clone(),
+        RebaseAction::Drop => DropCommit.boxed_clone(),
+    }
+}
+
+impl Render for DraggedRebaseEntry {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
+        h_flex()
+            .font_family(ui_font)
+            .bg(cx.theme().colors().background)
+            .p_1()
+            .gap_2()
+            .child(
+                Label::new(action_label(self.entry.action))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(self.entry.subject().to_string()))
+    }
+}
+
+impl Render for RebaseTodoView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let validation_error = self.validation_error();
+        let rewording = self
+            .entries
+            .get(self.selected_index)
+            .is_some_and(|entry| entry.action == RebaseAction::Reword);
+        let short_base = self.base.get(..7).unwrap_or(&self.base).to_string();
+
+        v_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        v_flex()
+                            .child(Label::new(format!(
+                                "Rebase {} commits onto {short_base}",
+                                self.entries.len()
+                            )))
+                            .child(
+                                Label::new(
+                                    validation_error.unwrap_or(
+                                        "Commits are replayed from top to bottom. Drag them to reorder.",
+                                    ),
+                                )
+                                .size(LabelSize::Small)
+                                .color(if validation_error.is_some() {
+                                    Color::Error
+                                } else {
+                                    Color::Muted
+                                }),
+                            ),
+                    )
+                    .child(
+                        Button::new("start-rebase", "Start Rebase")
+                            .style(ButtonStyle::Filled)
+                            .disabled(validation_error.is_some())
+                            .key_binding(KeyBinding::for_action_in(
+                                &StartRebase,
+                                &self.focus_handle,
+                                cx,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.start_rebase(&StartRebase, window, cx)
+                            })),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .key_context(self.dispatch_context())
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::select_first))
+                    .on_action(cx.listener(Self::select_last))
+                    .on_action(cx.listener(Self::move_up))
+                    .on_action(cx.listener(Self::move_down))
+                    .on_action(cx.listener(Self::start_rebase))
+                    .on_action(cx.listener(|this, _: &PickCommit, window, cx| {
+                        this.set_selected_action(RebaseAction::Pick, window, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &RewordCommit, window, cx| {
+                        this.set_selected_action(RebaseAction::Reword, window, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &SquashCommit, window, cx| {
+                        this.set_selected_action(RebaseAction::Squash, window, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &FixupCommit, window, cx| {
+                        this.set_selected_action(RebaseAction::Fixup, window, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &DropCommit, window, cx| {
+                        this.set_selected_action(RebaseAction::Drop, window, cx)
+                    }))
+                    .flex_1()
+                    .overflow_y_hidden()
+                    .child(
+                        uniform_list(
+                            "rebase-todo-entries",
+                            self.entries.len(),
+                            cx.processor(Self::render_entries),
+                        )
+                        .flex_grow()
+                        .track_scroll(&self.scroll_handle),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+            .when(rewording, |this| {
+                this.child(
+                    v_flex()
+                        .p_2()
+                        .gap_1()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Label::new("New message")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(self.message_editor.clone()),
+                )
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for RebaseTodoView {}
+
+impl Focusable for RebaseTodoView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RebaseTodoView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Interactive Rebase".into()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn subjects(view: &Entity<RebaseTodoView>, cx: &mut VisualTestContext) -> Vec<String> {
+        view.read_with(cx, |view, _| {
+            view.entries
+                .iter()
+                .map(|entry| entry.subject().to_string())
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_rebase_todo(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a" }))
+            .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_commits_for_repo(
+            dot_git,
+            &[
+                ("base", "Initial commit"),
+                ("aaa", "Add a"),
+                ("bbb", "Add b"),
+                ("ccc", "Add c"),
+            ],
+        );
+        fs.set_conflicting_commits_for_repo(dot_git, &["bbb"]);
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        cx.run_until_parked();
+
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        cx.update(|window, cx| {
+            RebaseTodoView::open("base".into(), repository, workspace.downgrade(), window, cx)
+        });
+        cx.run_until_parked();
+        let view = workspace
+            .read_with(cx, |workspace, cx| {
+                workspace.active_item_as::<RebaseTodoView>(cx)
+            })
+            .unwrap();
+        assert_eq!(subjects(&view, cx), ["Add a", "Add b", "Add c"]);
+
+        view.update_in(cx, |view, window, cx| {
+            view.move_entry(2, 1, window, cx);
+            view.set_action(0, RebaseAction::Fixup, window, cx);
+        });
+        assert_eq!(subjects(&view, cx), ["Add a", "Add c", "Add b"]);
+        assert!(view.read_with(cx, |view, _| view.validation_error().is_some()));
+
+        view.update_in(cx, |view, window, cx| {
+            view.set_action(0, RebaseAction::Reword, window, cx);
+            view.message_editor.update(cx, |editor, cx| {
+                editor.set_text("Add a, reworded", window, cx)
+            });
+            view.set_action(2, RebaseAction::Squash, window, cx);
+        });
+        assert_eq!(subjects(&view, cx), ["Add a, reworded", "Add c", "Add b"]);
+        assert_eq!(view.read_with(cx, |view, _| view.validation_error()), None);
+
+        // The rebase stops on the conflicting commit, with the ones before it replayed.
+        view.update_in(cx, |view, window, cx| {
+            view.start_rebase(&StartRebase, window, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            fs.commit_messages_for_repo(dot_git),
+            ["Initial commit", "Add a, reworded", "Add c"]
+        );
+        assert!(
+            workspace
+                .read_with(cx, |workspace, cx| workspace
+                    .active_item_as::<RebaseTodoView>(cx))
+                .is_none()
+        );
+
+        cx.dispatch_action(git::ContinueOperation);
+        cx.run_until_parked();
+        assert_eq!(
+            fs.commit_messages_for_repo(dot_git),
+            ["Initial commit", "Add a, reworded", "Add c\n\nAdd b"]
+        );
+
+        // Aborting a stopped rebase restores the branch.
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        let todo = cx
+            .update(|_, cx| {
+                repository.update(cx, |repository, _| repository.rebase_todo("base".into()))
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        fs.set_conflicting_commits_for_repo(dot_git, &[todo[0].sha.as_ref()]);
+        cx.update(|_, cx| {
+            repository.update(cx, |repository, _| {
+                repository.rebase_interactive("base".into(), todo.into_iter().rev().collect())
+            })
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+        assert_eq!(
+            fs.commit_messages_for_repo(dot_git),
+            ["Initial commit", "Add c\n\nAdd b"]
+        );
+        cx.dispatch_action(git::AbortOperation);
+        cx.run_until_parked();
+        assert_eq!(
+            fs.commit_messages_for_repo(dot_git),
+            ["Initial commit", "Add a, reworded", "Add c\n\nAdd b"]
+        );
+    }
+}
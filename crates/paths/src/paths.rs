@@ -437,6 +437,12 @@ pub fn remote_servers_dir() -> &'static PathBuf {
     REMOTE_SERVERS_DIR.get_or_init(|| data_dir().join("remote_servers"))
 }
 
+/// Returns the path to the directory holding the sockets of persistent terminal sessions.
+pub fn terminal_sessions_dir() -> &'static PathBuf {
+    static TERMINAL_SESSIONS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    TERMINAL_SESSIONS_DIR.get_or_init(|| data_dir().join("terminal_sessions"))
+}
+
 /// Returns the path to the directory where the devcontainer CLI is installed.
 pub fn devcontainer_dir() -> &'static PathBuf {
     static DEVCONTAINER_DIR: OnceLock<PathBuf> = OnceLock::new();
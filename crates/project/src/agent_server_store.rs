@@ -2324,6 +2324,10 @@ pub enum CustomAgentServerSettings {
         ///
         /// Default: {}
         favorite_config_option_values: HashMap<String, Vec<String>>,
+        /// The ACP extension methods this agent may call.
+        ///
+        /// Default: []
+        allowed_extension_methods: Vec<String>,
     },
     Extension {
         /// Additional environment variables to pass to the agent.
@@ -2358,6 +2362,10 @@ pub enum CustomAgentServerSettings {
         ///
         /// Default: {}
         favorite_config_option_values: HashMap<String, Vec<String>>,
+        /// The ACP extension methods this agent may call.
+        ///
+        /// Default: []
+        allowed_extension_methods: Vec<String>,
     },
     Registry {
         /// Additional environment variables to pass to the agent.
@@ -2392,6 +2400,10 @@ pub enum CustomAgentServerSettings {
         ///
         /// Default: {}
         favorite_config_option_values: HashMap<String, Vec<String>>,
+        /// The ACP extension methods this agent may call.
+        ///
+        /// Default: []
+        allowed_extension_methods: Vec<String>,
     },
 }
 
@@ -2451,6 +2463,23 @@ impl CustomAgentServerSettings {
         }
     }
 
+    pub fn allowed_extension_methods(&self) -> &[String] {
+        match self {
+            CustomAgentServerSettings::Custom {
+                allowed_extension_methods,
+                ..
+            }
+            | CustomAgentServerSettings::Extension {
+                allowed_extension_methods,
+                ..
+            }
+            | CustomAgentServerSettings::Registry {
+                allowed_extension_methods,
+                ..
+            } => allowed_extension_methods,
+        }
+    }
+
     pub fn favorite_config_option_values(&self, config_id: &str) -> Option<&[String]> {
         match self {
             CustomAgentServerSettings::Custom {
@@ -2483,6 +2512,7 @@ impl From<settings::CustomAgentServerSettings> for CustomAgentServerSettings {
                 favorite_models,
                 default_config_options,
                 favorite_config_option_values,
+                allowed_extension_methods,
             } => CustomAgentServerSettings::Custom {
                 command: AgentServerCommand {
                     path: PathBuf::from(shellexpand::tilde(&path.to_string_lossy()).as_ref()),
@@ -2494,6 +2524,7 @@ impl From<settings::CustomAgentServerSettings> for CustomAgentServerSettings {
                 favorite_models,
                 default_config_options,
                 favorite_config_option_values,
+                allowed_extension_methods,
             },
             settings::CustomAgentServerSettings::Extension {
                 env,
@@ -2502,6 +2533,7 @@ impl From<settings::CustomAgentServerSettings> for CustomAgentServerSettings {
                 default_config_options,
                 favorite_models,
                 favorite_config_option_values,
+                allowed_extension_methods,
             } => CustomAgentServerSettings::Extension {
                 env,
                 default_mode,
@@ -2509,6 +2541,7 @@ impl From<settings::CustomAgentServerSettings> for CustomAgentServerSettings {
                 default_config_options,
                 favorite_models,
                 favorite_config_option_values,
+                allowed_extension_methods,
             },
             settings::CustomAgentServerSettings::Registry {
                 env,
@@ -2517,6 +2550,7 @@ impl From<settings::CustomAgentServerSettings> for CustomAgentServerSettings {
                 default_config_options,
                 favorite_models,
                 favorite_config_option_values,
+                allowed_extension_methods,
             } => CustomAgentServerSettings::Registry {
                 env,
                 default_mode,
@@ -2524,6 +2558,7 @@ impl From<settings::CustomAgentServerSettings> for CustomAgentServerSettings {
                 default_config_options,
                 favorite_models,
                 favorite_config_option_values,
+                allowed_extension_methods,
             },
         }
     }
@@ -29,10 +29,11 @@ use git::{
     blame::Blame,
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, GraphCommitData, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode,
-        UpstreamTrackingStatus, Worktree as GitWorktree,
+        BisectMark, BisectStatus, Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions,
+        DiffType, FetchOptions, GitRepository, GitRepositoryCheckpoint, GraphCommitData,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, RebaseAction, RebaseTodoEntry,
+        Remote, RemoteCommandOutput, RepoPath, ResetMode, Tag, UpstreamTrackingStatus,
+        Worktree as GitWorktree,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -53,7 +54,10 @@ use pending_op::{PendingOp, PendingOpId, PendingOps, PendingOpsSummary};
 use postage::stream::Stream as _;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, git_reset, split_repository_update},
+    proto::{
+        self, git_bisect_mark, git_bisect_status, git_reset, rebase_todo_entry,
+        split_repository_update,
+    },
 };
 use serde::Deserialize;
 use settings::WorktreeId;
@@ -188,6 +192,20 @@ pub struct GitStoreCheckpoint {
     checkpoints_by_work_dir_abs_path: HashMap<Arc<Path>, GitRepositoryCheckpoint>,
 }
 
+impl GitStoreCheckpoint {
+    pub fn repository_checkpoint(
+        &self,
+        work_directory_abs_path: &Path,
+    ) -> Option<&GitRepositoryCheckpoint> {
+        self.checkpoints_by_work_dir_abs_path
+            .get(work_directory_abs_path)
+    }
+
+    pub fn work_directory_abs_paths(&self) -> impl Iterator<Item = &Arc<Path>> {
+        self.checkpoints_by_work_dir_abs_path.keys()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StatusEntry {
     pub repo_path: RepoPath,
@@ -541,6 +559,19 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_git_clone);
         client.add_entity_request_handler(Self::handle_get_worktrees);
         client.add_entity_request_handler(Self::handle_create_worktree);
+        client.add_entity_request_handler(Self::handle_remove_worktree);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_rebase_todo);
+        client.add_entity_request_handler(Self::handle_rebase_interactive);
+        client.add_entity_request_handler(Self::handle_continue_operation);
+        client.add_entity_request_handler(Self::handle_abort_operation);
+        client.add_entity_request_handler(Self::handle_get_tags);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_bisect_mark);
+        client.add_entity_request_handler(Self::handle_bisect_reset);
+        client.add_entity_request_handler(Self::handle_squash_merge);
     }
 
     pub fn is_local(&self) -> bool {
@@ -1078,6 +1109,53 @@ impl GitStore {
         })
     }
 
+    /// Returns the files that differ between two checkpoints, in the repositories that both of
+    /// them cover.
+    pub fn diff_checkpoint_files(
+        &self,
+        base: GitStoreCheckpoint,
+        mut target: GitStoreCheckpoint,
+        cx: &mut App,
+    ) -> Task<Result<Vec<(Entity<Repository>, CommitFile)>>> {
+        let repositories_by_work_dir_abs_path = self
+            .repositories
+            .values()
+            .map(|repo| (repo.read(cx).snapshot.work_directory_abs_path.clone(), repo))
+            .collect::<HashMap<_, _>>();
+
+        let mut tasks = Vec::new();
+        for (work_dir_abs_path, base_checkpoint) in base.checkpoints_by_work_dir_abs_path {
+            let Some(target_checkpoint) = target
+                .checkpoints_by_work_dir_abs_path
+                .remove(&work_dir_abs_path)
+            else {
+                continue;
+            };
+            if let Some(repository) = repositories_by_work_dir_abs_path.get(&work_dir_abs_path) {
+                let repository = (*repository).clone();
+                let diff = repository.update(cx, |repository, _| {
+                    repository.checkpoint_file_diffs(base_checkpoint, target_checkpoint)
+                });
+                tasks.push(async move {
+                    let files = diff.await??;
+                    anyhow::Ok(
+                        files
+                            .into_iter()
+                            .map(|file| (repository.clone(), file))
+                            .collect::<Vec<_>>(),
+                    )
+                });
+            }
+        }
+        cx.background_spawn(async move {
+            Ok(future::try_join_all(tasks)
+                .await?
+                .into_iter()
+                .flatten()
+                .collect())
+        })
+    }
+
     /// Compares two checkpoints, returning true if they are equal.
     pub fn compare_checkpoints(
         &self,
@@ -2298,6 +2376,249 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_remove_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = PathBuf::from(envelope.payload.path);
+        let force = envelope.payload.force;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.remove_worktree(path, force)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(envelope.payload.commits)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(envelope.payload.commits)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_todo(envelope.payload.base)
+            })
+            .await??;
+
+        Ok(proto::GitRebaseTodoResponse {
+            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_rebase_interactive(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseInteractive>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .todo
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_interactive(envelope.payload.base, todo)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_continue_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitContinueOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.continue_operation()
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_abort_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitAbortOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.abort_operation()
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())
+            .await??;
+
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let proto::GitCreateTag {
+            name,
+            target,
+            message,
+            ..
+        } = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(name, target, message)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_tag(envelope.payload.name)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_mark(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectMark>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBisectStatus> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let mark = match envelope.payload.mark() {
+            git_bisect_mark::Mark::Good => BisectMark::Good,
+            git_bisect_mark::Mark::Bad => BisectMark::Bad,
+            git_bisect_mark::Mark::Skip => BisectMark::Skip,
+        };
+
+        let status = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_mark(mark, envelope.payload.commit)
+            })
+            .await??;
+
+        Ok(bisect_status_to_proto(&status))
+    }
+
+    async fn handle_bisect_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_reset()
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_squash_merge(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSquashMerge>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commit = envelope.payload.commit;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.squash_merge(commit)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
     async fn handle_get_branches(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitGetBranches>,
@@ -2416,10 +2737,11 @@ impl GitStore {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
         let branch_name = envelope.payload.branch_name;
+        let force = envelope.payload.force;
 
         repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.delete_branch(branch_name)
+                repository_handle.delete_branch(branch_name, force)
             })
             .await??;
 
@@ -5561,6 +5883,32 @@ impl Repository {
         )
     }
 
+    pub fn remove_worktree(&mut self, path: PathBuf, force: bool) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git worktree remove".into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        backend.remove_worktree(path, force).await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRemoveWorktree {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: path.to_string_lossy().to_string(),
+                                force,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn default_branch(
         &mut self,
         include_remote_name: bool,
@@ -5730,19 +6078,27 @@ impl Repository {
         )
     }
 
-    pub fn delete_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+    pub fn delete_branch(
+        &mut self,
+        branch_name: String,
+        force: bool,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
+        let flag = if force { "-D" } else { "-d" };
         self.send_job(
-            Some(format!("git branch -d {branch_name}").into()),
+            Some(format!("git branch {flag} {branch_name}").into()),
             move |repo, _cx| async move {
                 match repo {
-                    RepositoryState::Local(state) => state.backend.delete_branch(branch_name).await,
+                    RepositoryState::Local(state) => {
+                        state.backend.delete_branch(branch_name, force).await
+                    }
                     RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
                         client
                             .request(proto::GitDeleteBranch {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
                                 branch_name,
+                                force,
                             })
                             .await?;
 
@@ -5817,24 +6173,350 @@ impl Repository {
         })
     }
 
-    pub fn restore_checkpoint(
-        &mut self,
-        checkpoint: GitRepositoryCheckpoint,
-    ) -> oneshot::Receiver<Result<()>> {
-        self.send_job(None, move |repo, _cx| async move {
-            match repo {
-                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
-                    backend.restore_checkpoint(checkpoint).await
-                }
-                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
-            }
-        })
-    }
-
-    pub(crate) fn apply_remote_update(
-        &mut self,
-        update: proto::UpdateRepository,
-        cx: &mut Context<Self>,
+    pub fn squash_merge(&mut self, commit: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git merge --squash {commit}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        backend.squash_merge(commit).await
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitSquashMerge {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn cherry_pick(&mut self, commits: Vec<String>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git cherry-pick".into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.cherry_pick(commits, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCherryPick {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commits,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn revert(&mut self, commits: Vec<String>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git revert".into()), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.revert(commits, environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitRevert {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commits,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn rebase_todo(&mut self, base: String) -> oneshot::Receiver<Result<Vec<RebaseTodoEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.rebase_todo(base).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitRebaseTodo {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            base,
+                        })
+                        .await?;
+
+                    Ok(response
+                        .entries
+                        .iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect())
+                }
+            }
+        })
+    }
+
+    pub fn rebase_interactive(
+        &mut self,
+        base: String,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git rebase --interactive".into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.rebase_interactive(base, todo, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRebaseInteractive {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                base,
+                                todo: todo.iter().map(rebase_todo_entry_to_proto).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Continues the rebase, cherry-pick, revert, or merge that stopped on conflicts, once
+    /// they're resolved and staged.
+    pub fn continue_operation(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git --continue".into()), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.continue_operation(environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitContinueOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn abort_operation(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(Some("git --abort".into()), move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.abort_operation(environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitAbortOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    pub fn tags(&mut self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.tags().await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
+                }
+            }
+        })
+    }
+
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = self.this.clone();
+        self.send_job(
+            Some(format!("git tag {name}").into()),
+            move |repo, mut cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        backend.create_tag(name, target, message).await?;
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                target,
+                                message,
+                            })
+                            .await?;
+                    }
+                }
+                // Tags don't move HEAD, so refresh the refs shown in the git graph manually.
+                this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::BranchChanged))
+            },
+        )
+    }
+
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = self.this.clone();
+        self.send_job(
+            Some(format!("git tag --delete {name}").into()),
+            move |repo, mut cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                        backend.delete_tag(name).await?;
+                    }
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                            })
+                            .await?;
+                    }
+                }
+                this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::BranchChanged))
+            },
+        )
+    }
+
+    /// Marks `commit`, or HEAD when it's `None`, for the bisect session in progress, starting
+    /// one if there's none.
+    pub fn bisect_mark(
+        &mut self,
+        mark: BisectMark,
+        commit: Option<String>,
+    ) -> oneshot::Receiver<Result<BisectStatus>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git bisect {}", mark.as_str()).into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_mark(mark, commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        let mark = match mark {
+                            BisectMark::Good => git_bisect_mark::Mark::Good,
+                            BisectMark::Bad => git_bisect_mark::Mark::Bad,
+                            BisectMark::Skip => git_bisect_mark::Mark::Skip,
+                        };
+                        let response = client
+                            .request(proto::GitBisectMark {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                mark: mark.into(),
+                                commit,
+                            })
+                            .await?;
+
+                        Ok(proto_to_bisect_status(response))
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn bisect_reset(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git bisect reset".into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_reset(environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectReset {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn restore_checkpoint(
+        &mut self,
+        checkpoint: GitRepositoryCheckpoint,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.restore_checkpoint(checkpoint).await
+                }
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
+    pub(crate) fn apply_remote_update(
+        &mut self,
+        update: proto::UpdateRepository,
+        cx: &mut Context<Self>,
     ) -> Result<()> {
         let conflicted_paths = TreeSet::from_ordered_entries(
             update
@@ -5912,6 +6594,73 @@ impl Repository {
         })
     }
 
+    /// Returns the contents of every file that differs between two checkpoints.
+    ///
+    /// Files whose contents aren't valid UTF-8 are returned without text and marked as binary.
+    pub fn checkpoint_file_diffs(
+        &mut self,
+        base_checkpoint: GitRepositoryCheckpoint,
+        target_checkpoint: GitRepositoryCheckpoint,
+    ) -> oneshot::Receiver<Result<Vec<CommitFile>>> {
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    let base: SharedString = base_checkpoint.commit_sha.to_string().into();
+                    let target: SharedString = target_checkpoint.commit_sha.to_string().into();
+                    let forward = backend
+                        .diff_tree(DiffTreeType::Since {
+                            base: base.clone(),
+                            head: target.clone(),
+                        })
+                        .await?;
+                    // Diffing in reverse gives us the object ids on the target side.
+                    let mut reverse = backend
+                        .diff_tree(DiffTreeType::Since {
+                            base: target,
+                            head: base,
+                        })
+                        .await?;
+
+                    let mut files = Vec::with_capacity(forward.entries.len());
+                    for (path, status) in forward.entries {
+                        let old_oid = match status {
+                            TreeDiffStatus::Added => None,
+                            TreeDiffStatus::Modified { old } | TreeDiffStatus::Deleted { old } => {
+                                Some(old)
+                            }
+                        };
+                        let new_oid = match reverse.entries.remove(&path) {
+                            Some(
+                                TreeDiffStatus::Modified { old } | TreeDiffStatus::Deleted { old },
+                            ) => Some(old),
+                            Some(TreeDiffStatus::Added) | None => None,
+                        };
+
+                        let old_text = match old_oid {
+                            Some(oid) => backend.load_blob_content(oid).await.ok(),
+                            None => None,
+                        };
+                        let new_text = match new_oid {
+                            Some(oid) => backend.load_blob_content(oid).await.ok(),
+                            None => None,
+                        };
+                        let is_binary = (old_oid.is_some() && old_text.is_none())
+                            || (new_oid.is_some() && new_text.is_none());
+                        files.push(CommitFile {
+                            path,
+                            old_text,
+                            new_text,
+                            is_binary,
+                        });
+                    }
+                    files.sort_by(|a, b| a.path.cmp(&b.path));
+                    Ok(files)
+                }
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
     pub fn diff_checkpoints(
         &mut self,
         base_checkpoint: GitRepositoryCheckpoint,
@@ -6473,6 +7222,101 @@ fn deserialize_blame_buffer_response(
     Some(Blame { entries, messages })
 }
 
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::RebaseTodoEntry {
+    let action = match entry.action {
+        RebaseAction::Pick => rebase_todo_entry::Action::Pick,
+        RebaseAction::Reword => rebase_todo_entry::Action::Reword,
+        RebaseAction::Squash => rebase_todo_entry::Action::Squash,
+        RebaseAction::Fixup => rebase_todo_entry::Action::Fixup,
+        RebaseAction::Drop => rebase_todo_entry::Action::Drop,
+    };
+    proto::RebaseTodoEntry {
+        sha: entry.sha.to_string(),
+        action: action.into(),
+        message: entry.message.to_string(),
+    }
+}
+
+fn proto_to_rebase_todo_entry(entry: &proto::RebaseTodoEntry) -> RebaseTodoEntry {
+    let action = match entry.action() {
+        rebase_todo_entry::Action::Pick => RebaseAction::Pick,
+        rebase_todo_entry::Action::Reword => RebaseAction::Reword,
+        rebase_todo_entry::Action::Squash => RebaseAction::Squash,
+        rebase_todo_entry::Action::Fixup => RebaseAction::Fixup,
+        rebase_todo_entry::Action::Drop => RebaseAction::Drop,
+    };
+    RebaseTodoEntry {
+        sha: entry.sha.clone().into(),
+        action,
+        message: entry.message.clone().into(),
+    }
+}
+
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(ToString::to_string),
+        timestamp: tag.timestamp,
+    }
+}
+
+fn proto_to_tag(tag: &proto::GitTag) -> Tag {
+    Tag {
+        name: tag.name.clone().into(),
+        sha: tag.sha.clone().into(),
+        message: tag.message.clone().map(Into::into),
+        timestamp: tag.timestamp,
+    }
+}
+
+fn bisect_status_to_proto(status: &BisectStatus) -> proto::GitBisectStatus {
+    let (kind, sha, subject, message) = match status {
+        BisectStatus::Waiting { message } => {
+            (git_bisect_status::Kind::Waiting, "", "", message.as_ref())
+        }
+        BisectStatus::Testing {
+            sha,
+            subject,
+            remaining,
+        } => (
+            git_bisect_status::Kind::Testing,
+            sha.as_ref(),
+            subject.as_ref(),
+            remaining.as_ref(),
+        ),
+        BisectStatus::Found { sha, subject } => (
+            git_bisect_status::Kind::Found,
+            sha.as_ref(),
+            subject.as_ref(),
+            "",
+        ),
+    };
+    proto::GitBisectStatus {
+        kind: kind.into(),
+        sha: sha.to_string(),
+        subject: subject.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn proto_to_bisect_status(status: proto::GitBisectStatus) -> BisectStatus {
+    match status.kind() {
+        git_bisect_status::Kind::Waiting => BisectStatus::Waiting {
+            message: status.message.into(),
+        },
+        git_bisect_status::Kind::Testing => BisectStatus::Testing {
+            sha: status.sha.into(),
+            subject: status.subject.into(),
+            remaining: status.message.into(),
+        },
+        git_bisect_status::Kind::Found => BisectStatus::Found {
+            sha: status.sha.into(),
+            subject: status.subject.into(),
+        },
+    }
+}
+
 fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     proto::Branch {
         is_head: branch.is_head,
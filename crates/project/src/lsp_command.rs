@@ -2,11 +2,11 @@ pub mod signature_help;
 
 use crate::{
     CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
-    LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
-    ProjectTransaction, PulledDiagnostics, ResolveState,
-    lsp_store::{LocalLspStore, LspFoldingRange, LspStore},
+    DocumentHighlight, DocumentSymbol, HierarchyCall, HierarchyItem, Hover, HoverBlock,
+    HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip,
+    InlayHintTooltip, Location, LocationLink, LspAction, LspPullDiagnostics, MarkupContent,
+    PrepareRenameResponse, ProjectTransaction, PulledDiagnostics, ResolveState,
+    lsp_store::{LanguageServerToQuery, LocalLspStore, LspFoldingRange, LspStore},
 };
 use anyhow::{Context as _, Result};
 use async_trait::async_trait;
@@ -120,6 +120,11 @@ pub trait LspCommand: 'static + Sized + Send + std::fmt::Debug {
         }
     }
 
+    /// The language server to send the request to when a guest makes it, on the host.
+    fn language_server_to_query(&self) -> LanguageServerToQuery {
+        LanguageServerToQuery::FirstCapable
+    }
+
     /// When false, `to_lsp_params_or_response` default implementation will return the default response.
     fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool;
 
@@ -218,6 +223,40 @@ pub(crate) struct GetDocumentHighlights {
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentSymbols;
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetIncomingCalls {
+    pub lsp_item: Value,
+    pub language_server_id: LanguageServerId,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetOutgoingCalls {
+    pub lsp_item: Value,
+    pub language_server_id: LanguageServerId,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetSupertypes {
+    pub lsp_item: Value,
+    pub language_server_id: LanguageServerId,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetSubtypes {
+    pub lsp_item: Value,
+    pub language_server_id: LanguageServerId,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct GetSignatureHelp {
     pub position: PointUtf16,
@@ -1858,6 +1897,809 @@ impl LspCommand for GetDocumentSymbols {
     }
 }
 
+/// Reads a call or type hierarchy item that the language server sent, opening its buffer.
+async fn hierarchy_item_from_lsp(
+    lsp_item: Value,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    // Type hierarchy items have the same fields as call hierarchy items.
+    let item = serde_json::from_value::<lsp::CallHierarchyItem>(lsp_item.clone())
+        .context("invalid hierarchy item")?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(item.uri, server_id, cx)
+        })
+        .await?;
+    let (range, selection_range) = buffer.read_with(cx, |buffer, _| {
+        (
+            anchor_range_from_lsp(buffer, item.range),
+            anchor_range_from_lsp(buffer, item.selection_range),
+        )
+    });
+    Ok(HierarchyItem {
+        name: item.name,
+        kind: item.kind,
+        detail: item.detail,
+        location: Location { buffer, range },
+        selection_range,
+        lsp_item,
+        language_server_id: server_id,
+    })
+}
+
+async fn hierarchy_items_from_lsp<T: serde::Serialize>(
+    lsp_items: Option<Vec<T>>,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let mut items = Vec::new();
+    for lsp_item in lsp_items.unwrap_or_default() {
+        let lsp_item = serde_json::to_value(&lsp_item)?;
+        items.push(hierarchy_item_from_lsp(lsp_item, lsp_store, server_id, cx).await?);
+    }
+    Ok(items)
+}
+
+fn anchor_range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+fn hierarchy_item_to_proto(
+    item: HierarchyItem,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyItem {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&item.location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    proto::HierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+        detail: item.detail,
+        location: Some(proto::Location {
+            buffer_id: item.location.buffer.read(cx).remote_id().into(),
+            start: Some(serialize_anchor(&item.location.range.start)),
+            end: Some(serialize_anchor(&item.location.range.end)),
+        }),
+        selection_range: Some(serialize_anchor_range(item.selection_range)),
+        lsp_item: item.lsp_item.to_string(),
+        language_server_id: item.language_server_id.to_proto(),
+    }
+}
+
+async fn hierarchy_item_from_proto(
+    item: proto::HierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem> {
+    let location = item.location.context("missing location")?;
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing location start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing location end")?;
+    let selection_range =
+        deserialize_anchor_range(item.selection_range.context("missing selection range")?)?;
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_anchors([start, end, selection_range.start, selection_range.end])
+        })
+        .await?;
+    Ok(HierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+        detail: item.detail,
+        location: Location {
+            buffer,
+            range: start..end,
+        },
+        selection_range,
+        lsp_item: serde_json::from_str(&item.lsp_item).context("invalid hierarchy item")?,
+        language_server_id: LanguageServerId::from_proto(item.language_server_id),
+    })
+}
+
+async fn hierarchy_items_from_proto(
+    items: Vec<proto::HierarchyItem>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let mut result = Vec::with_capacity(items.len());
+    for item in items {
+        result.push(hierarchy_item_from_proto(item, lsp_store, cx).await?);
+    }
+    Ok(result)
+}
+
+fn hierarchy_calls_to_proto(
+    calls: Vec<HierarchyCall>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::HierarchyCall> {
+    calls
+        .into_iter()
+        .map(|call| proto::HierarchyCall {
+            item: Some(hierarchy_item_to_proto(call.item, lsp_store, peer_id, cx)),
+            call_ranges: call
+                .call_ranges
+                .into_iter()
+                .map(serialize_anchor_range)
+                .collect(),
+        })
+        .collect()
+}
+
+/// Reads calls sent by the host, whose ranges are in the buffer of the calling symbol.
+async fn hierarchy_calls_from_proto(
+    calls: Vec<proto::HierarchyCall>,
+    lsp_store: &Entity<LspStore>,
+    calling_buffer: Option<&Entity<Buffer>>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyCall>> {
+    let mut result = Vec::with_capacity(calls.len());
+    for call in calls {
+        let item =
+            hierarchy_item_from_proto(call.item.context("missing item")?, lsp_store, cx).await?;
+        let call_ranges = call
+            .call_ranges
+            .into_iter()
+            .map(deserialize_anchor_range)
+            .collect::<Result<Vec<_>>>()?;
+        let calling_buffer = calling_buffer.unwrap_or(&item.location.buffer);
+        calling_buffer
+            .update(cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    call_ranges
+                        .iter()
+                        .flat_map(|range| [range.start, range.end])
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .await?;
+        result.push(HierarchyCall { item, call_ranges });
+    }
+    Ok(result)
+}
+
+fn call_hierarchy_supported(capabilities: &AdapterServerCapabilities) -> bool {
+    match &capabilities.server_capabilities.call_hierarchy_provider {
+        Some(lsp::CallHierarchyServerCapability::Simple(supported)) => *supported,
+        Some(lsp::CallHierarchyServerCapability::Options(_)) => true,
+        None => false,
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        call_hierarchy_supported(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::CallHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer)),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn language_server_to_query(&self) -> LanguageServerToQuery {
+        LanguageServerToQuery::Other(self.language_server_id)
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        call_hierarchy_supported(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: serde_json::from_value(self.lsp_item.clone())
+                .context("invalid call hierarchy item")?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_calls: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let mut calls = Vec::new();
+        for lsp_call in lsp_calls.unwrap_or_default() {
+            let item = hierarchy_item_from_lsp(
+                serde_json::to_value(&lsp_call.from)?,
+                &lsp_store,
+                server_id,
+                &mut cx,
+            )
+            .await?;
+            // Incoming calls happen in the calling symbol, which is the item.
+            let call_ranges = item.location.buffer.read_with(&cx, |buffer, _| {
+                lsp_call
+                    .from_ranges
+                    .into_iter()
+                    .map(|range| anchor_range_from_lsp(buffer, range))
+                    .collect()
+            });
+            calls.push(HierarchyCall { item, call_ranges });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+            language_server_id: self.language_server_id.to_proto(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item)
+                .context("invalid call hierarchy item")?,
+            language_server_id: LanguageServerId::from_proto(message.language_server_id),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetIncomingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message.calls, &lsp_store, None, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn language_server_to_query(&self) -> LanguageServerToQuery {
+        LanguageServerToQuery::Other(self.language_server_id)
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        call_hierarchy_supported(&capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: serde_json::from_value(self.lsp_item.clone())
+                .context("invalid call hierarchy item")?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_calls: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let mut calls = Vec::new();
+        for lsp_call in lsp_calls.unwrap_or_default() {
+            let item = hierarchy_item_from_lsp(
+                serde_json::to_value(&lsp_call.to)?,
+                &lsp_store,
+                server_id,
+                &mut cx,
+            )
+            .await?;
+            // Outgoing calls happen in the symbol that was asked about, which is in the buffer
+            // the request was made for.
+            let call_ranges = buffer.read_with(&cx, |buffer, _| {
+                lsp_call
+                    .from_ranges
+                    .into_iter()
+                    .map(|range| anchor_range_from_lsp(buffer, range))
+                    .collect()
+            });
+            calls.push(HierarchyCall { item, call_ranges });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+            language_server_id: self.language_server_id.to_proto(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item)
+                .context("invalid call hierarchy item")?,
+            language_server_id: LanguageServerId::from_proto(message.language_server_id),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message.calls, &lsp_store, Some(&buffer), &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        // The server capabilities don't report type hierarchy support, so ask every server.
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer)),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareTypeHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get supertypes"
+    }
+
+    fn language_server_to_query(&self) -> LanguageServerToQuery {
+        LanguageServerToQuery::Other(self.language_server_id)
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: serde_json::from_value(self.lsp_item.clone())
+                .context("invalid type hierarchy item")?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+            language_server_id: self.language_server_id.to_proto(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item)
+                .context("invalid type hierarchy item")?,
+            language_server_id: LanguageServerId::from_proto(message.language_server_id),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSupertypesResponse {
+        proto::GetSupertypesResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSupertypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
+    }
+
+    fn language_server_to_query(&self) -> LanguageServerToQuery {
+        LanguageServerToQuery::Other(self.language_server_id)
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: serde_json::from_value(self.lsp_item.clone())
+                .context("invalid type hierarchy item")?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: self.lsp_item.to_string(),
+            language_server_id: self.language_server_id.to_proto(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_str(&message.lsp_item)
+                .context("invalid type hierarchy item")?,
+            language_server_id: LanguageServerId::from_proto(message.language_server_id),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSubtypesResponse {
+        proto::GetSubtypesResponse {
+            items: response
+                .into_iter()
+                .map(|item| hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSubtypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetSignatureHelp {
     type Response = Option<SignatureHelp>;
@@ -0,0 +1,115 @@
+use std::{borrow::Cow, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use gpui::Context;
+use language::DiagnosticSourceKind;
+use task::{Problem, ProblemSeverity};
+
+use crate::LspStore;
+
+use super::DocumentDiagnosticsUpdate;
+
+impl LspStore {
+    /// Publishes the problems found in the output of a task as diagnostics, replacing the ones
+    /// its previous run published. Each task publishes as its own pseudo language server, so
+    /// its diagnostics neither mix with the ones of real servers nor of other tasks.
+    pub fn publish_task_problems(
+        &mut self,
+        task_label: &str,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let languages = self.languages.clone();
+        let worktree_store = self.worktree_store.clone();
+        let local = self
+            .as_local_mut()
+            .context("task problems can only be published in local projects")?;
+        let (server_id, published_paths) = local
+            .task_problem_servers
+            .entry(task_label.to_string())
+            .or_insert_with(|| (languages.next_language_server_id(), HashSet::default()));
+        let server_id = *server_id;
+
+        let worktree_store = worktree_store.read(cx);
+        let root = worktree_store
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path());
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<lsp::Diagnostic>>::default();
+        for problem in problems {
+            let path = if problem.path.is_absolute() {
+                problem.path.clone()
+            } else if let Some(root) = &root {
+                root.join(&problem.path)
+            } else {
+                continue;
+            };
+            diagnostics_by_path
+                .entry(path)
+                .or_default()
+                .push(problem_to_lsp(problem));
+        }
+        // Only files inside the project can show diagnostics.
+        diagnostics_by_path.retain(|path, _| worktree_store.find_worktree(path, cx).is_some());
+
+        let fixed_paths = published_paths
+            .iter()
+            .filter(|path| !diagnostics_by_path.contains_key(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        *published_paths = diagnostics_by_path.keys().cloned().collect();
+        for path in fixed_paths {
+            diagnostics_by_path.insert(path, Vec::new());
+        }
+
+        let updates = diagnostics_by_path
+            .into_iter()
+            .filter_map(|(path, diagnostics)| {
+                Some(DocumentDiagnosticsUpdate {
+                    diagnostics: lsp::PublishDiagnosticsParams {
+                        uri: lsp::Uri::from_file_path(&path).ok()?,
+                        diagnostics,
+                        version: None,
+                    },
+                    result_id: None,
+                    registration_id: None,
+                    server_id,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                })
+            })
+            .collect();
+        self.merge_lsp_diagnostics(DiagnosticSourceKind::Other, updates, |_, _, _| false, cx)
+    }
+}
+
+fn problem_to_lsp(problem: Problem) -> lsp::Diagnostic {
+    // Problems count lines and columns from 1, LSP counts them from 0.
+    let start = lsp::Position::new(
+        problem.line.saturating_sub(1),
+        problem.column.map_or(0, |column| column.saturating_sub(1)),
+    );
+    // Without an end column, the problem spans the rest of its last line.
+    let end = lsp::Position::new(
+        problem
+            .end_line
+            .map_or(start.line, |line| line.saturating_sub(1)),
+        problem
+            .end_column
+            .map_or(u32::MAX, |column| column.saturating_sub(1)),
+    );
+    let severity = match problem.severity {
+        ProblemSeverity::Error => lsp::DiagnosticSeverity::ERROR,
+        ProblemSeverity::Warning => lsp::DiagnosticSeverity::WARNING,
+        ProblemSeverity::Info => lsp::DiagnosticSeverity::INFORMATION,
+        ProblemSeverity::Hint => lsp::DiagnosticSeverity::HINT,
+    };
+    lsp::Diagnostic {
+        range: lsp::Range::new(start, end),
+        severity: Some(severity),
+        code: problem.code.map(lsp::NumberOrString::String),
+        source: Some(problem.source),
+        message: problem.message,
+        ..lsp::Diagnostic::default()
+    }
+}
@@ -151,7 +151,7 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskDependency, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -845,6 +845,32 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A symbol in a call or type hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The whole symbol, including its body.
+    pub location: Location,
+    /// The part of the symbol to reveal when navigating to it, like its name.
+    pub selection_range: Range<language::Anchor>,
+    /// The item as the language server sent it, which it expects back when asked for the
+    /// item's calls or related types.
+    pub lsp_item: serde_json::Value,
+    /// The language server that sent the item, which is the one to ask about it.
+    pub language_server_id: LanguageServerId,
+}
+
+/// A call in a call hierarchy.
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    /// The calling symbol, for incoming calls, or the called one, for outgoing calls.
+    pub item: HierarchyItem,
+    /// Where the calls are made, in the buffer of the calling symbol.
+    pub call_ranges: Vec<Range<language::Anchor>>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -4067,6 +4093,102 @@ impl Project {
         )
     }
 
+    /// Returns the symbols at the position whose callers and callees can be listed.
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetIncomingCalls {
+                lsp_item: item.lsp_item.clone(),
+                language_server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetOutgoingCalls {
+                lsp_item: item.lsp_item.clone(),
+                language_server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
+    /// Returns the types at the position whose supertypes and subtypes can be listed.
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSupertypes {
+                lsp_item: item.lsp_item.clone(),
+                language_server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSubtypes {
+                lsp_item: item.lsp_item.clone(),
+                language_server_id: item.language_server_id,
+            },
+            cx,
+        )
+    }
+
     pub fn document_symbols(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -155,6 +155,14 @@ pub enum TaskSourceKind {
     },
 }
 
+/// A task that another task depends on, along with the tasks it depends on itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskDependency {
+    pub source_kind: TaskSourceKind,
+    pub template: TaskTemplate,
+    pub dependencies: Vec<TaskDependency>,
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -417,6 +425,64 @@ impl Inventory {
         })
     }
 
+    /// Looks up the tasks that `template` depends on, and the ones those depend on, among the
+    /// global tasks and the tasks of the worktree given.
+    pub fn task_dependencies(
+        &self,
+        template: &TaskTemplate,
+        worktree: Option<WorktreeId>,
+        cx: &App,
+    ) -> Task<Result<Vec<TaskDependency>>> {
+        let tasks = self.list_tasks(None, None, worktree, cx);
+        let template = template.clone();
+        cx.background_spawn(async move { Self::resolve_dependencies(&template, &tasks.await) })
+    }
+
+    /// Looks up the tasks that `template` depends on among the `available` ones by their labels,
+    /// recursively. Fails if a dependency is missing, or if tasks depend on each other in a cycle.
+    pub fn resolve_dependencies(
+        template: &TaskTemplate,
+        available: &[(TaskSourceKind, TaskTemplate)],
+    ) -> Result<Vec<TaskDependency>> {
+        fn resolve(
+            template: &TaskTemplate,
+            available: &[(TaskSourceKind, TaskTemplate)],
+            path: &mut Vec<String>,
+        ) -> Result<Vec<TaskDependency>> {
+            template
+                .depends_on
+                .iter()
+                .map(|label| {
+                    if let Some(start) = path.iter().position(|visited| visited == label) {
+                        bail!(
+                            "tasks depend on each other in a cycle: {} -> {label}",
+                            path[start..].join(" -> ")
+                        );
+                    }
+                    let (source_kind, dependency) = available
+                        .iter()
+                        .find(|(_, candidate)| &candidate.label == label)
+                        .with_context(|| {
+                            format!(
+                                "task `{}` depends on an unknown task `{label}`",
+                                template.label
+                            )
+                        })?;
+                    path.push(label.clone());
+                    let dependencies = resolve(dependency, available, path)?;
+                    path.pop();
+                    Ok(TaskDependency {
+                        source_kind: source_kind.clone(),
+                        template: dependency.clone(),
+                        dependencies,
+                    })
+                })
+                .collect()
+        }
+
+        resolve(template, available, &mut vec![template.label.clone()])
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given and resolves them with the [`TaskContexts`] given.
     /// Joins the new resolutions with the resolved tasks that were used (spawned) before,
     /// orders them so that the most recently used come first, all equally used ones are ordered so that the most specific tasks come first.
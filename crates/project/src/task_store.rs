@@ -49,6 +49,17 @@ enum StoreMode {
 
 impl EventEmitter<crate::Event> for TaskStore {}
 
+/// Emitted when a task that was spawned in a terminal exits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskFinished {
+    pub label: String,
+    pub success: bool,
+    /// The exit code, or `None` if the task was killed by a signal.
+    pub exit_code: Option<i32>,
+}
+
+impl EventEmitter<TaskFinished> for TaskStore {}
+
 #[derive(Debug)]
 pub enum TaskSettingsLocation<'a> {
     Global(&'a Path),
@@ -56,6 +67,11 @@ pub enum TaskSettingsLocation<'a> {
 }
 
 impl TaskStore {
+    /// Lets subscribers know that a task exited.
+    pub fn task_finished(&mut self, event: TaskFinished, cx: &mut Context<Self>) {
+        cx.emit(event);
+    }
+
     pub fn init(client: Option<&AnyProtoClient>) {
         if let Some(client) = client {
             client.add_entity_request_handler(Self::handle_task_context_for_location);
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use collections::HashMap;
 use gpui::{App, AppContext as _, Context, Entity, Task, WeakEntity};
 
@@ -14,9 +14,10 @@ use std::{
 };
 use task::{Shell, ShellBuilder, ShellKind, SpawnInTerminal};
 use terminal::{
-    TaskState, TaskStatus, Terminal, TerminalBuilder, insert_zed_terminal_env,
+    PersistentSession, TaskState, TaskStatus, Terminal, TerminalBuilder, insert_zed_terminal_env,
     terminal_settings::TerminalSettings,
 };
+use terminal_session::SessionKind;
 use util::{command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath};
 
 use crate::{Project, ProjectPath};
@@ -25,6 +26,14 @@ pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
 }
 
+/// The persistent session to run a task's terminal in.
+enum TaskSession {
+    /// A new session for an agent's command, if persistent sessions are enabled.
+    NewAgentSession,
+    /// The session an agent's command kept running in while Zed was closed.
+    Reattach(String),
+}
+
 impl Project {
     pub fn active_entry_directory(&self, cx: &App) -> Option<PathBuf> {
         let entry_id = self.active_entry()?;
@@ -62,8 +71,46 @@ impl Project {
         &mut self,
         spawn_task: SpawnInTerminal,
         cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        self.create_terminal_task_internal(spawn_task, None, cx)
+    }
+
+    /// Creates a terminal running a command for an agent, in a new persistent session if those
+    /// are enabled, so that the command keeps running when Zed quits.
+    pub fn create_agent_terminal_task(
+        &mut self,
+        spawn_task: SpawnInTerminal,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        self.create_terminal_task_internal(spawn_task, Some(TaskSession::NewAgentSession), cx)
+    }
+
+    /// Creates a terminal attached to the persistent session an agent's command kept running in
+    /// while Zed was closed. Fails if the command isn't running anymore, rather than running it
+    /// again; `spawn_task` only describes the command.
+    pub fn reattach_agent_terminal_task(
+        &mut self,
+        spawn_task: SpawnInTerminal,
+        session_id: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        self.create_terminal_task_internal(spawn_task, Some(TaskSession::Reattach(session_id)), cx)
+    }
+
+    fn create_terminal_task_internal(
+        &mut self,
+        spawn_task: SpawnInTerminal,
+        session: Option<TaskSession>,
+        cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
         let is_via_remote = self.remote_client.is_some();
+        if matches!(session, Some(TaskSession::Reattach(_)))
+            && (is_via_remote || !terminal_session::is_supported())
+        {
+            return Task::ready(Err(anyhow!(
+                "persistent terminal sessions aren't supported in this project"
+            )));
+        }
 
         let path: Option<Arc<Path>> = if let Some(cwd) = &spawn_task.cwd {
             if is_via_remote {
@@ -88,6 +135,16 @@ impl Project {
         }
         let settings = TerminalSettings::get(settings_location, cx).clone();
         let detect_venv = settings.detect_venv.as_option().is_some();
+        let session = session
+            .and_then(|session| match session {
+                TaskSession::NewAgentSession => settings.persistent_sessions.then(|| {
+                    PersistentSession::StartOrReattach(terminal_session::new_session_id(
+                        SessionKind::Agent,
+                    ))
+                }),
+                TaskSession::Reattach(session_id) => Some(PersistentSession::Reattach(session_id)),
+            })
+            .filter(|_| !is_via_remote && terminal_session::is_supported());
 
         let (completion_tx, completion_rx) = bounded(1);
 
@@ -256,6 +313,7 @@ impl Project {
                         cx,
                         activation_script,
                         path_style,
+                        session,
                     ))
                 })??
                 .await?;
@@ -290,7 +348,18 @@ impl Project {
         cwd: Option<PathBuf>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
-        self.create_terminal_shell_internal(cwd, false, cx)
+        self.create_terminal_shell_internal(cwd, false, None, cx)
+    }
+
+    /// Creates a terminal attached to the persistent session a terminal had before Zed restarted,
+    /// starting the session anew if its shell didn't keep running.
+    pub fn reattach_terminal_shell(
+        &mut self,
+        cwd: Option<PathBuf>,
+        session_id: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Terminal>>> {
+        self.create_terminal_shell_internal(cwd, false, Some(session_id), cx)
     }
 
     /// Creates a local terminal even if the project is remote.
@@ -307,16 +376,18 @@ impl Project {
             // Local project: use project directory like normal terminals
             self.active_project_directory(cx).map(|p| p.to_path_buf())
         };
-        self.create_terminal_shell_internal(working_directory, true, cx)
+        self.create_terminal_shell_internal(working_directory, true, None, cx)
     }
 
     /// Internal method for creating terminal shells.
     /// If force_local is true, creates a local terminal even if the project has a remote client.
     /// This allows "breaking out" to a local shell in remote projects.
+    /// Local shells run in the given persistent session, or in a new one if those are enabled.
     fn create_terminal_shell_internal(
         &mut self,
         cwd: Option<PathBuf>,
         force_local: bool,
+        session_id: Option<String>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<Terminal>>> {
         let path = cwd.map(|p| Arc::from(&*p));
@@ -334,6 +405,14 @@ impl Project {
         let settings = TerminalSettings::get(settings_location, cx).clone();
         let detect_venv = settings.detect_venv.as_option().is_some();
         let local_path = if is_via_remote { None } else { path.clone() };
+        let session = session_id
+            .or_else(|| {
+                settings
+                    .persistent_sessions
+                    .then(|| terminal_session::new_session_id(SessionKind::Shell))
+            })
+            .filter(|_| !is_via_remote && terminal_session::is_supported())
+            .map(PersistentSession::StartOrReattach);
 
         let project_path_contexts = self
             .active_entry()
@@ -421,6 +500,7 @@ impl Project {
                         cx,
                         activation_script,
                         path_style,
+                        session,
                     ))
                 })??
                 .await?;
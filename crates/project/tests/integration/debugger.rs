@@ -22,6 +22,9 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
         };
 
         let scenario = locator
@@ -49,6 +52,9 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
         };
 
         let scenario = locator
@@ -187,6 +193,9 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
         };
 
         let scenario = locator
@@ -221,6 +230,9 @@ mod python_locator {
             shell: task::Shell::System,
             show_summary: false,
             show_command: false,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
         };
 
         let expected_scenario = DebugScenario {
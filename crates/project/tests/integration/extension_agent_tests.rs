@@ -330,6 +330,7 @@ fn test_tilde_expansion_in_settings() {
         favorite_models: vec![],
         default_config_options: Default::default(),
         favorite_config_option_values: Default::default(),
+        allowed_extension_methods: vec![],
     };
 
     let converted: CustomAgentServerSettings = settings.into();
@@ -4158,6 +4158,119 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }\nfn b() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    let uri = lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap();
+    let range = |row, start, end| {
+        lsp::Range::new(lsp::Position::new(row, start), lsp::Position::new(row, end))
+    };
+    let item = |name: &str, row, end| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: Some(format!("fn {name}()")),
+        uri: uri.clone(),
+        range: range(row, 0, end),
+        selection_range: range(row, 3, 4),
+        data: Some(json!({ "id": name })),
+    };
+
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item = item("b", 1, 9);
+        move |params, _| {
+            let item = item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 3)
+                );
+                Ok(Some(vec![item]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let item = item("a", 0, 14);
+        move |params, _| {
+            let item = item.clone();
+            async move {
+                assert_eq!(params.item.data, Some(json!({ "id": "b" })));
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: item,
+                    from_ranges: vec![range(0, 9, 10)],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(1, 3), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
+    assert_eq!(items[0].detail.as_deref(), Some("fn b()"));
+    // The item's calls are asked of the server that sent it.
+    assert_eq!(items[0].language_server_id, fake_server.server.server_id());
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let call = &calls[0];
+        let buffer = call.item.location.buffer.read(cx);
+        assert_eq!(call.item.name, "a");
+        assert_eq!(call.item.language_server_id, fake_server.server.server_id());
+        assert_eq!(call.item.selection_range.to_offset(buffer), 3..4);
+        assert_eq!(
+            call.call_ranges
+                .iter()
+                .map(|range| range.to_offset(buffer))
+                .collect::<Vec<_>>(),
+            [9..10]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -9008,6 +9121,67 @@ async fn test_staging_hunk_preserve_executable_permission(cx: &mut gpui::TestApp
     );
 }
 
+#[gpui::test]
+async fn test_diff_checkpoint_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.executor().allow_parking();
+    let root = TempTree::new(json!({
+        "project": {
+            "changed.txt": "one\n",
+            "deleted.txt": "keep\n",
+        },
+    }));
+
+    let work_dir = root.path().join("project");
+    let repo = git_init(work_dir.as_path());
+    git_add("changed.txt", &repo);
+    git_add("deleted.txt", &repo);
+    git_commit("Initial commit", &repo);
+
+    let project = Project::test(
+        Arc::new(RealFs::new(None, cx.executor())),
+        [root.path()],
+        cx,
+    )
+    .await;
+    cx.run_until_parked();
+    let git_store = project.read_with(cx, |project, _| project.git_store().clone());
+
+    let before = git_store
+        .update(cx, |git_store, cx| git_store.checkpoint(cx))
+        .await
+        .unwrap();
+    std::fs::write(work_dir.join("changed.txt"), "two\n").unwrap();
+    std::fs::remove_file(work_dir.join("deleted.txt")).unwrap();
+    std::fs::write(work_dir.join("added.txt"), "new\n").unwrap();
+    let after = git_store
+        .update(cx, |git_store, cx| git_store.checkpoint(cx))
+        .await
+        .unwrap();
+
+    let files = git_store
+        .update(cx, |git_store, cx| {
+            git_store.diff_checkpoint_files(before, after, cx)
+        })
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(_, file)| (file.path, file.old_text, file.new_text))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        files,
+        [
+            (repo_path("added.txt"), None, Some("new\n".to_string())),
+            (
+                repo_path("changed.txt"),
+                Some("one\n".to_string()),
+                Some("two\n".to_string())
+            ),
+            (repo_path("deleted.txt"), Some("keep\n".to_string()), None),
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_repository_and_path_for_project_path(
     background_executor: BackgroundExecutor,
@@ -6,6 +6,7 @@ use serde_json::json;
 use settings::SettingsLocation;
 use std::path::Path;
 use std::sync::Arc;
+use task::TaskTemplate;
 use util::rel_path::rel_path;
 
 use project::task_store::{TaskSettingsLocation, TaskStore};
@@ -560,6 +561,58 @@ async fn test_inventory_static_task_filters(cx: &mut TestAppContext) {
     );
 }
 
+#[test]
+fn test_task_dependencies() {
+    fn task(label: &str, depends_on: &[&str]) -> (TaskSourceKind, TaskTemplate) {
+        let template = TaskTemplate {
+            label: label.to_string(),
+            command: "echo".to_string(),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        };
+        (TaskSourceKind::UserInput, template)
+    }
+    fn describe(dependencies: &[TaskDependency]) -> String {
+        dependencies
+            .iter()
+            .map(|dependency| {
+                if dependency.dependencies.is_empty() {
+                    dependency.template.label.clone()
+                } else {
+                    format!(
+                        "{}({})",
+                        dependency.template.label,
+                        describe(&dependency.dependencies)
+                    )
+                }
+            })
+            .join(", ")
+    }
+
+    let available = vec![
+        task("test", &["build"]),
+        task("build", &["fetch", "codegen"]),
+        task("fetch", &[]),
+        task("codegen", &["fetch"]),
+    ];
+    let dependencies = Inventory::resolve_dependencies(&available[0].1, &available).unwrap();
+    assert_eq!(describe(&dependencies), "build(fetch, codegen(fetch))");
+
+    let cyclic = vec![task("a", &["b"]), task("b", &["c"]), task("c", &["b"])];
+    let error = Inventory::resolve_dependencies(&cyclic[0].1, &cyclic).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "tasks depend on each other in a cycle: b -> c -> b"
+    );
+
+    let (_, lonely) = task("lonely", &["missing"]);
+    let error = Inventory::resolve_dependencies(&lonely, &available).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "task `lonely` depends on an unknown task `missing`"
+    );
+}
+
 fn init_test(_cx: &mut TestAppContext) {
     zlog::init_test();
     TaskStore::init(None);
@@ -231,6 +231,18 @@ messages!(
     (GetColorPresentationResponse, Background),
     (GetFoldingRanges, Background),
     (GetFoldingRangesResponse, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
     (RefreshCodeLens, Background),
     (GetCodeLens, Background),
     (GetCodeLensResponse, Background),
@@ -354,6 +366,22 @@ messages!(
     (GitGetWorktrees, Background),
     (GitWorktreesResponse, Background),
     (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitRebaseInteractive, Background),
+    (GitContinueOperation, Background),
+    (GitAbortOperation, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitBisectMark, Background),
+    (GitBisectStatus, Background),
+    (GitBisectReset, Background),
+    (GitSquashMerge, Background),
     (ShareAgentThread, Foreground),
     (GetSharedAgentThread, Foreground),
     (GetSharedAgentThreadResponse, Foreground),
@@ -453,6 +481,12 @@ request_messages!(
     (ResolveInlayHint, ResolveInlayHintResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetFoldingRanges, GetFoldingRangesResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
     (GetColorPresentation, GetColorPresentationResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
@@ -552,6 +586,19 @@ request_messages!(
     (RemoteStarted, Ack),
     (GitGetWorktrees, GitWorktreesResponse),
     (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitRebaseInteractive, Ack),
+    (GitContinueOperation, Ack),
+    (GitAbortOperation, Ack),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitBisectMark, GitBisectStatus),
+    (GitBisectReset, Ack),
+    (GitSquashMerge, Ack),
     (TrustWorktrees, Ack),
     (RestrictWorktrees, Ack),
     (FindSearchCandidatesChunk, Ack),
@@ -596,6 +643,12 @@ entity_messages!(
     CreateProjectEntry,
     GetDocumentColor,
     GetFoldingRanges,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
     DeleteProjectEntry,
     ExpandProjectEntry,
     ExpandAllForProjectEntry,
@@ -737,6 +790,19 @@ entity_messages!(
     NewExternalAgentVersionAvailable,
     GitGetWorktrees,
     GitCreateWorktree,
+    GitRemoveWorktree,
+    GitCherryPick,
+    GitRevert,
+    GitRebaseTodo,
+    GitRebaseInteractive,
+    GitContinueOperation,
+    GitAbortOperation,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitBisectMark,
+    GitBisectReset,
+    GitSquashMerge,
     TrustWorktrees,
     RestrictWorktrees,
     FindSearchCandidatesChunk,
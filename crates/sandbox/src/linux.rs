@@ -0,0 +1,345 @@
+use std::{
+    fs, io,
+    os::{
+        fd::{AsRawFd as _, FromRawFd as _, OwnedFd},
+        unix::fs::OpenOptionsExt as _,
+    },
+    path::Path,
+    ptr,
+};
+
+use anyhow::{Context as _, Result, bail};
+
+use crate::SandboxPolicy;
+
+// Landlock's ABI, from `linux/landlock.h`.
+const LANDLOCK_CREATE_RULESET_VERSION: libc::c_uint = 1 << 0;
+const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+const ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+const ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+const ACCESS_FS_MAKE_CHAR: u64 = 1 << 6;
+const ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+const ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+const ACCESS_FS_MAKE_SOCK: u64 = 1 << 9;
+const ACCESS_FS_MAKE_FIFO: u64 = 1 << 10;
+const ACCESS_FS_MAKE_BLOCK: u64 = 1 << 11;
+const ACCESS_FS_MAKE_SYM: u64 = 1 << 12;
+/// Available since version 2 of the ABI.
+const ACCESS_FS_REFER: u64 = 1 << 13;
+/// Available since version 3 of the ABI.
+const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+/// The only accesses a rule for a file, rather than a directory, can give.
+const FILE_WRITE_ACCESS: u64 = ACCESS_FS_WRITE_FILE | ACCESS_FS_TRUNCATE;
+
+#[repr(C)]
+struct LandlockRulesetAttr {
+    handled_access_fs: u64,
+}
+
+#[repr(C, packed)]
+struct LandlockPathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+// seccomp's ABI, from `linux/seccomp.h`, `linux/filter.h` and `linux/audit.h`.
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_JMP_JEQ_K: u16 = 0x15;
+#[cfg(target_arch = "x86_64")]
+const BPF_JMP_JGE_K: u16 = 0x35;
+const BPF_RET_K: u16 = 0x06;
+const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+const SECCOMP_DATA_FIRST_ARG_OFFSET: u32 = 16;
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+/// System calls that could be used to get out of the sandbox or to tamper with other processes.
+/// `io_uring` is blocked because its operations aren't seen by the seccomp filter.
+const BLOCKED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_add_key,
+    libc::SYS_bpf,
+    libc::SYS_delete_module,
+    libc::SYS_finit_module,
+    libc::SYS_init_module,
+    libc::SYS_io_uring_setup,
+    libc::SYS_kexec_load,
+    libc::SYS_keyctl,
+    libc::SYS_mount,
+    libc::SYS_perf_event_open,
+    libc::SYS_pivot_root,
+    libc::SYS_process_vm_writev,
+    libc::SYS_ptrace,
+    libc::SYS_request_key,
+    libc::SYS_setns,
+    libc::SYS_umount2,
+];
+
+pub(crate) fn apply(policy: &SandboxPolicy) -> Result<()> {
+    // User namespaces can be disabled, in which case the seccomp filter still blocks the network.
+    if !policy.allow_network {
+        enter_network_namespace().ok();
+    }
+    restrict_file_system(policy)?;
+    install_seccomp_filter(policy)
+}
+
+/// Moves the process into a network namespace without any interfaces.
+fn enter_network_namespace() -> io::Result<()> {
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Map the user to itself, so that files are owned by the same user in the namespace.
+    fs::write("/proc/self/setgroups", "deny")?;
+    fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    Ok(())
+}
+
+/// Makes everything read-only, except for the writable paths and the temporary directory.
+fn restrict_file_system(policy: &SandboxPolicy) -> Result<()> {
+    let abi = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            ptr::null::<LandlockRulesetAttr>(),
+            0usize,
+            LANDLOCK_CREATE_RULESET_VERSION,
+        )
+    };
+    if abi < 1 {
+        bail!(
+            "Landlock isn't available in this kernel: {}",
+            io::Error::last_os_error()
+        );
+    }
+
+    let mut write_access = ACCESS_FS_WRITE_FILE
+        | ACCESS_FS_REMOVE_DIR
+        | ACCESS_FS_REMOVE_FILE
+        | ACCESS_FS_MAKE_CHAR
+        | ACCESS_FS_MAKE_DIR
+        | ACCESS_FS_MAKE_REG
+        | ACCESS_FS_MAKE_SOCK
+        | ACCESS_FS_MAKE_FIFO
+        | ACCESS_FS_MAKE_BLOCK
+        | ACCESS_FS_MAKE_SYM;
+    if abi >= 2 {
+        write_access |= ACCESS_FS_REFER;
+    }
+    if abi >= 3 {
+        write_access |= ACCESS_FS_TRUNCATE;
+    }
+
+    let attr = LandlockRulesetAttr {
+        handled_access_fs: write_access,
+    };
+    let ruleset = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &attr as *const LandlockRulesetAttr,
+            size_of::<LandlockRulesetAttr>(),
+            0,
+        )
+    };
+    if ruleset < 0 {
+        return Err(io::Error::last_os_error()).context("failed to create a Landlock ruleset");
+    }
+    let ruleset = unsafe { OwnedFd::from_raw_fd(ruleset as i32) };
+
+    let temp_dir = std::env::temp_dir();
+    for path in policy
+        .writable_paths
+        .iter()
+        .map(|path| path.as_path())
+        .chain([temp_dir.as_path(), Path::new("/tmp")])
+    {
+        allow_writes(&ruleset, path, write_access)?;
+    }
+    // Commands write to the terminal and to `/dev/null`, but don't create devices.
+    allow_writes(
+        &ruleset,
+        Path::new("/dev"),
+        write_access & FILE_WRITE_ACCESS,
+    )?;
+
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return Err(io::Error::last_os_error()).context("failed to set no_new_privs");
+    }
+    if unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset.as_raw_fd(), 0) } != 0 {
+        return Err(io::Error::last_os_error()).context("failed to enforce the Landlock ruleset");
+    }
+    Ok(())
+}
+
+fn allow_writes(ruleset: &OwnedFd, path: &Path, mut access: u64) -> Result<()> {
+    // A path that doesn't exist can't be written to anyway.
+    let Ok(file) = fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_PATH | libc::O_CLOEXEC)
+        .open(path)
+    else {
+        return Ok(());
+    };
+    if !file.metadata()?.is_dir() {
+        access &= FILE_WRITE_ACCESS;
+    }
+
+    let attr = LandlockPathBeneathAttr {
+        allowed_access: access,
+        parent_fd: file.as_raw_fd(),
+    };
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_add_rule,
+            ruleset.as_raw_fd(),
+            LANDLOCK_RULE_PATH_BENEATH,
+            &attr as *const LandlockPathBeneathAttr,
+            0,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error())
+            .with_context(|| format!("failed to allow writes to {}", path.display()));
+    }
+    Ok(())
+}
+
+fn install_seccomp_filter(policy: &SandboxPolicy) -> Result<()> {
+    let mut filter = seccomp_filter(policy);
+    let program = libc::sock_fprog {
+        len: filter.len() as libc::c_ushort,
+        filter: filter.as_mut_ptr(),
+    };
+    // `no_new_privs` was already set for Landlock, which allows an unprivileged process to do this.
+    let result = unsafe {
+        libc::prctl(
+            libc::PR_SET_SECCOMP,
+            libc::SECCOMP_MODE_FILTER,
+            &program as *const libc::sock_fprog,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error()).context("failed to install the seccomp filter");
+    }
+    Ok(())
+}
+
+/// Builds a BPF program that fails the blocked system calls with `EPERM`.
+fn seccomp_filter(policy: &SandboxPolicy) -> Vec<libc::sock_filter> {
+    let deny = statement(BPF_RET_K, SECCOMP_RET_ERRNO | libc::EPERM as u32);
+    let mut filter = vec![
+        statement(BPF_LD_W_ABS, SECCOMP_DATA_ARCH_OFFSET),
+        jump(BPF_JMP_JEQ_K, AUDIT_ARCH, 1, 0),
+        deny,
+        statement(BPF_LD_W_ABS, SECCOMP_DATA_NR_OFFSET),
+    ];
+    #[cfg(target_arch = "x86_64")]
+    filter.extend([jump(BPF_JMP_JGE_K, X32_SYSCALL_BIT, 0, 1), deny]);
+
+    for syscall in BLOCKED_SYSCALLS {
+        filter.extend([jump(BPF_JMP_JEQ_K, *syscall as u32, 0, 1), deny]);
+    }
+    if !policy.allow_network {
+        // Local sockets are still allowed, since many tools use them to talk to other processes.
+        filter.extend([
+            jump(BPF_JMP_JEQ_K, libc::SYS_socket as u32, 0, 5),
+            statement(BPF_LD_W_ABS, SECCOMP_DATA_FIRST_ARG_OFFSET),
+            jump(BPF_JMP_JEQ_K, libc::AF_INET as u32, 2, 0),
+            jump(BPF_JMP_JEQ_K, libc::AF_INET6 as u32, 1, 0),
+            statement(BPF_RET_K, SECCOMP_RET_ALLOW),
+            deny,
+        ]);
+    }
+    filter.push(statement(BPF_RET_K, SECCOMP_RET_ALLOW));
+    filter
+}
+
+fn statement(code: u16, k: u32) -> libc::sock_filter {
+    libc::sock_filter {
+        code,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter { code, jt, jf, k }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the subset of BPF used by [`seccomp_filter`] against a system call.
+    fn run_filter(filter: &[libc::sock_filter], arch: u32, nr: libc::c_long, arg: u32) -> u32 {
+        let mut accumulator = 0;
+        let mut pc = 0;
+        loop {
+            let instruction = filter[pc];
+            pc += 1;
+            match instruction.code {
+                BPF_LD_W_ABS => {
+                    accumulator = match instruction.k {
+                        SECCOMP_DATA_NR_OFFSET => nr as u32,
+                        SECCOMP_DATA_ARCH_OFFSET => arch,
+                        SECCOMP_DATA_FIRST_ARG_OFFSET => arg,
+                        offset => panic!("unexpected offset {offset}"),
+                    }
+                }
+                BPF_JMP_JEQ_K | 0x35 => {
+                    let matches = if instruction.code == BPF_JMP_JEQ_K {
+                        accumulator == instruction.k
+                    } else {
+                        accumulator >= instruction.k
+                    };
+                    pc += if matches {
+                        instruction.jt
+                    } else {
+                        instruction.jf
+                    } as usize;
+                }
+                BPF_RET_K => return instruction.k,
+                code => panic!("unexpected instruction {code:#x}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_seccomp_filter() {
+        let denied = SECCOMP_RET_ERRNO | libc::EPERM as u32;
+        let filter = seccomp_filter(&SandboxPolicy::default());
+        assert_eq!(
+            run_filter(&filter, AUDIT_ARCH, libc::SYS_write, 0),
+            SECCOMP_RET_ALLOW
+        );
+        assert_eq!(run_filter(&filter, AUDIT_ARCH, libc::SYS_ptrace, 0), denied);
+        assert_eq!(run_filter(&filter, 0, libc::SYS_write, 0), denied);
+        assert_eq!(
+            run_filter(&filter, AUDIT_ARCH, libc::SYS_socket, libc::AF_INET6 as u32),
+            denied
+        );
+        assert_eq!(
+            run_filter(&filter, AUDIT_ARCH, libc::SYS_socket, libc::AF_UNIX as u32),
+            SECCOMP_RET_ALLOW
+        );
+
+        let filter = seccomp_filter(&SandboxPolicy {
+            allow_network: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            run_filter(&filter, AUDIT_ARCH, libc::SYS_socket, libc::AF_INET as u32),
+            SECCOMP_RET_ALLOW
+        );
+        assert_eq!(run_filter(&filter, AUDIT_ARCH, libc::SYS_mount, 0), denied);
+    }
+}
@@ -0,0 +1,204 @@
+//! Runs the terminal commands of agents in an OS-level sandbox.
+//!
+//! Terminals spawn their process without a hook to restrict it in between, so the sandbox is
+//! applied by a copy of Zed started with [`SANDBOX_ARG`], which restricts itself and then
+//! executes the command. Only Linux is supported, where Landlock makes everything outside of the
+//! writable paths read-only, and a network namespace and a seccomp filter block network access.
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod linux;
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+/// Makes Zed apply the [`SandboxPolicy`] given as its value, and then run the command after `--`.
+pub const SANDBOX_ARG: &str = "--sandbox";
+
+/// What a sandboxed command is allowed to do.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxPolicy {
+    /// The paths the command can write to, besides the temporary directory.
+    pub writable_paths: Vec<PathBuf>,
+    pub allow_network: bool,
+}
+
+impl SandboxPolicy {
+    pub fn is_supported() -> bool {
+        cfg!(all(
+            target_os = "linux",
+            any(target_arch = "x86_64", target_arch = "aarch64")
+        ))
+    }
+
+    /// Returns the program and arguments that run the command in this sandbox.
+    pub fn wrap_command(
+        &self,
+        command: String,
+        args: Vec<String>,
+    ) -> Result<(String, Vec<String>)> {
+        anyhow::ensure!(
+            Self::is_supported(),
+            "sandboxing commands isn't supported on this platform"
+        );
+        let program = std::env::current_exe().context("failed to find the Zed executable")?;
+        let mut wrapped_args = vec![
+            SANDBOX_ARG.to_string(),
+            serde_json::to_string(self)?,
+            "--".to_string(),
+            command,
+        ];
+        wrapped_args.extend(args);
+        Ok((program.to_string_lossy().into_owned(), wrapped_args))
+    }
+
+    /// Guesses from the output of a failed command whether the sandbox stopped it.
+    ///
+    /// The kernel doesn't report what it denied, so this looks for the errors that the
+    /// sandbox causes. Write errors only count when they name a path that the sandbox doesn't
+    /// allow writing to, since commands also fail to write to paths they have no permission for.
+    pub fn denial(&self, output: &str) -> Option<SandboxDenial> {
+        const NETWORK_ERRORS: &[&str] = &[
+            "Network is unreachable",
+            "Temporary failure in name resolution",
+            "Could not resolve host",
+            "Name or service not known",
+        ];
+        const WRITE_ERRORS: &[&str] = &[
+            "Permission denied",
+            "Operation not permitted",
+            "Read-only file system",
+        ];
+
+        if !self.allow_network && NETWORK_ERRORS.iter().any(|error| output.contains(error)) {
+            return Some(SandboxDenial::Network);
+        }
+
+        let temp_dir = std::env::temp_dir();
+        output
+            .lines()
+            .filter(|line| WRITE_ERRORS.iter().any(|error| line.contains(error)))
+            .flat_map(absolute_paths)
+            .any(|path| {
+                !path.starts_with(&temp_dir)
+                    && !self
+                        .writable_paths
+                        .iter()
+                        .any(|writable_path| path.starts_with(writable_path))
+            })
+            .then_some(SandboxDenial::Write)
+    }
+}
+
+/// Returns the absolute paths mentioned in a line of output, such as the quoted path in
+/// `touch: cannot touch '/home/me/a': Permission denied`.
+fn absolute_paths(line: &str) -> impl Iterator<Item = &Path> {
+    line.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| "'\"`:,;()[]{}<>".contains(c)))
+        .filter(|word| word.starts_with('/'))
+        .map(Path::new)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SandboxDenial {
+    Network,
+    Write,
+}
+
+impl SandboxDenial {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::Network => "The sandbox may have blocked this command's network access.",
+            Self::Write => {
+                "The sandbox may have blocked this command from writing outside of the project."
+            }
+        }
+    }
+}
+
+/// Applies the policy to the current process and replaces it with the command.
+///
+/// Only returns if that fails.
+pub fn main(policy: &str, command: &[String]) -> Result<()> {
+    let policy: SandboxPolicy = serde_json::from_str(policy).context("invalid sandbox policy")?;
+    let (program, args) = command.split_first().context("no command to run")?;
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    {
+        use std::os::unix::process::CommandExt as _;
+
+        linux::apply(&policy)?;
+        let error = std::process::Command::new(program).args(args).exec();
+        Err(error).with_context(|| format!("failed to run {program}"))
+    }
+
+    #[cfg(not(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    {
+        let _ = (policy, program, args);
+        anyhow::bail!("sandboxing commands isn't supported on this platform")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denial() {
+        let policy = SandboxPolicy::default();
+        assert_eq!(
+            policy.denial("curl: (6) Could not resolve host: zed.dev"),
+            Some(SandboxDenial::Network)
+        );
+        assert_eq!(
+            policy.denial("touch: cannot touch '/home/me/a': Permission denied"),
+            Some(SandboxDenial::Write)
+        );
+        assert_eq!(policy.denial("error: expected `;`"), None);
+        assert_eq!(
+            policy.denial("bash: ./build.sh: Permission denied"),
+            None,
+            "relative paths are in the project, which is writable"
+        );
+
+        let policy = SandboxPolicy {
+            writable_paths: vec!["/home/me/project".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            policy
+                .denial("mkdir: cannot create directory '/home/me/project/out': Permission denied"),
+            None
+        );
+        assert_eq!(
+            policy.denial(&format!(
+                "touch: cannot touch '{}': Read-only file system",
+                std::env::temp_dir().join("a").display()
+            )),
+            None
+        );
+        assert_eq!(
+            policy.denial("error: failed to open `/home/me/.cargo/registry/index`: Read-only file system (os error 30)"),
+            Some(SandboxDenial::Write)
+        );
+
+        let policy = SandboxPolicy {
+            allow_network: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.denial("curl: (6) Could not resolve host: zed.dev"),
+            None
+        );
+    }
+}
@@ -115,6 +115,11 @@ pub struct AgentSettingsContent {
     ///
     /// Default: false
     pub show_turn_stats: Option<bool>,
+    /// Whether to offer agents a server through which they can see the project's other agent
+    /// sessions, read their transcripts, send them messages, and claim the files they edit.
+    ///
+    /// Default: false
+    pub enable_agent_sessions_server: Option<bool>,
     /// Per-tool permission rules for granular control over which tool actions
     /// require confirmation.
     ///
@@ -124,8 +129,19 @@ pub struct AgentSettingsContent {
     /// external agent's permission system is only used when Zed would allow
     /// the action. Per-tool regex patterns (`always_allow`, `always_deny`,
     /// `always_confirm`) match against the tool's text input (command, path,
-    /// URL, etc.).
+    /// URL, etc.). Rules for a single external agent can be set in `agents`.
     pub tool_permissions: Option<ToolPermissionsContent>,
+    /// Limits after which a running agent is stopped automatically. Each agent
+    /// tab is tracked separately.
+    ///
+    /// The `default` limits apply to every agent, and entries in `agents`, keyed
+    /// by agent name (e.g. "Zed Agent", "Claude Code"), override them.
+    pub session_budgets: Option<SessionBudgetsContent>,
+    /// Agent runs that start on their own, on a schedule or when something
+    /// happens in the project. Each run opens a new agent tab in the background.
+    ///
+    /// Default: []
+    pub jobs: Option<Vec<AgentJobContent>>,
 }
 
 impl AgentSettingsContent {
@@ -212,6 +228,29 @@ pub struct AgentProfileContent {
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
     /// The default language model selected when using this profile.
     pub default_model: Option<LanguageModelSelection>,
+    /// Whether to run the agent's terminal commands in a sandbox. Only supported on
+    /// Linux, and ignored on other platforms.
+    ///
+    /// External agents use the sandbox of the default profile.
+    pub sandbox: Option<TerminalSandboxContent>,
+}
+
+#[with_fallible_options]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct TerminalSandboxContent {
+    /// Whether terminal commands run in the sandbox, where they can only write to
+    /// the project's folders and the temporary directory.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Whether sandboxed commands can access the network.
+    ///
+    /// Default: false
+    pub allow_network: Option<bool>,
+    /// Additional paths that sandboxed commands can write to.
+    ///
+    /// Default: []
+    pub writable_paths: Option<Vec<PathBuf>>,
 }
 
 #[with_fallible_options]
@@ -434,6 +473,11 @@ pub enum CustomAgentServerSettings {
         /// Default: {}
         #[serde(default, skip_serializing_if = "HashMap::is_empty")]
         favorite_config_option_values: HashMap<String, Vec<String>>,
+        /// The ACP extension methods this agent may call, such as `zed/openFile`.
+        ///
+        /// Default: []
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allowed_extension_methods: Vec<String>,
     },
     Extension {
         /// Additional environment variables to pass to the agent.
@@ -474,6 +518,11 @@ pub enum CustomAgentServerSettings {
         /// Default: {}
         #[serde(default, skip_serializing_if = "HashMap::is_empty")]
         favorite_config_option_values: HashMap<String, Vec<String>>,
+        /// The ACP extension methods this agent may call, such as `zed/openFile`.
+        ///
+        /// Default: []
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allowed_extension_methods: Vec<String>,
     },
     Registry {
         /// Additional environment variables to pass to the agent.
@@ -514,9 +563,90 @@ pub enum CustomAgentServerSettings {
         /// Default: {}
         #[serde(default, skip_serializing_if = "HashMap::is_empty")]
         favorite_config_option_values: HashMap<String, Vec<String>>,
+        /// The ACP extension methods this agent may call, such as `zed/openFile`.
+        ///
+        /// Default: []
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allowed_extension_methods: Vec<String>,
     },
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SessionBudgetsContent {
+    /// Limits that apply to every agent.
+    pub default: Option<SessionBudgetContent>,
+
+    /// Per-agent limits, keyed by the agent's name as the agent panel shows it
+    /// (e.g. `"Claude Code"`).
+    /// Limits left unset fall back to the ones in `default`, and a limit of 0
+    /// lifts the default one.
+    #[serde(default)]
+    pub agents: HashMap<Arc<str>, SessionBudgetContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SessionBudgetContent {
+    /// How long the agent may spend generating, in minutes.
+    /// Default: null (unlimited)
+    pub max_duration_minutes: Option<u64>,
+
+    /// How many tokens the thread may spend, counting the input and output tokens of every request.
+    /// Default: null (unlimited)
+    pub max_tokens: Option<u64>,
+
+    /// How many tool calls the agent may make.
+    /// Default: null (unlimited)
+    pub max_tool_calls: Option<u64>,
+
+    /// How many files the agent may modify.
+    /// Default: null (unlimited)
+    pub max_files_modified: Option<u64>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentJobContent {
+    /// The name shown on the tabs of the job's runs.
+    pub name: String,
+
+    /// The agent that runs the job, e.g. "Zed Agent", "Claude Code", or the
+    /// name of a custom agent.
+    /// Default: "Zed Agent"
+    pub agent: Option<String>,
+
+    /// The prompt sent to the agent. `{file}` is replaced with the path of the
+    /// file that triggered the run, `{task}` with the label of the task that
+    /// failed, and `{date}` with today's date.
+    pub prompt: String,
+
+    /// When the job runs.
+    pub trigger: AgentJobTriggerContent,
+
+    /// Limits for each run of the job. Limits left unset fall back to the
+    /// agent's `session_budgets`.
+    pub budget: Option<SessionBudgetContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(tag = "on", rename_all = "snake_case")]
+pub enum AgentJobTriggerContent {
+    /// Runs on a schedule, given as a cron expression in local time with five
+    /// fields: minute, hour, day of month, month, and day of week, e.g.
+    /// "0 18 * * 1-5" for 6pm on weekdays.
+    Schedule { cron: String },
+    /// Runs when a file matching the glob is saved.
+    FileSaved { glob: String },
+    /// Runs when a task fails. If a label is given, only tasks with that label
+    /// trigger the job.
+    TaskFailed { label: Option<String> },
+    /// Runs when a file gets a new diagnostic error. If a glob is given, only
+    /// files matching it trigger the job.
+    DiagnosticError { glob: Option<String> },
+}
+
 #[with_fallible_options]
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct ToolPermissionsContent {
@@ -532,6 +662,30 @@ pub struct ToolPermissionsContent {
     /// even tools without meaningful text input can have a `default` set.
     #[serde(default)]
     pub tools: HashMap<Arc<str>, ToolRulesContent>,
+
+    /// Per-agent permission rules for external agents, keyed by the agent's
+    /// name as the agent panel shows it (e.g. `"Claude Code"`).
+    /// Their permission requests are matched against the rules of the tool
+    /// doing the same kind of work: `terminal` for commands, `edit_file`,
+    /// `delete_path`, `move_path` and `read_file` for files, `fetch` for URLs
+    /// and `grep` for searches.
+    #[serde(default)]
+    pub agents: HashMap<Arc<str>, AgentToolPermissionsContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentToolPermissionsContent {
+    /// Default permission for this agent when no tool-specific rules match.
+    /// When unset, inherits from the global `tool_permissions.default`.
+    #[serde(alias = "default_mode")]
+    pub default: Option<ToolPermissionMode>,
+
+    /// Per-tool permission rules for this agent.
+    /// Patterns are added to the global ones for the same tool, and a tool's
+    /// `default` replaces the global one.
+    #[serde(default)]
+    pub tools: HashMap<Arc<str>, ToolRulesContent>,
 }
 
 #[with_fallible_options]
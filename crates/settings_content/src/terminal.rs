@@ -147,6 +147,12 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// Whether terminal shells keep running when Zed quits, to be reattached along with
+    /// their scrollback when Zed restores the terminals.
+    /// Only supported on macOS and Linux.
+    ///
+    /// Default: false
+    pub persistent_sessions: Option<bool>,
     /// The multiplier for scrolling with the mouse wheel.
     ///
     /// Default: 1.0
@@ -6211,9 +6211,31 @@ fn terminal_page() -> SettingsPage {
         ]
     }
 
-    fn advanced_settings_section() -> [SettingsPageItem; 3] {
+    fn advanced_settings_section() -> [SettingsPageItem; 4] {
         [
             SettingsPageItem::SectionHeader("Advanced Settings"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Persistent Sessions",
+                description: "Keep terminal shells running when Zed quits, and reattach to them on restart.",
+                field: Box::new(SettingField {
+                    json_path: Some("terminal.persistent_sessions"),
+                    pick: |settings_content| {
+                        settings_content
+                            .terminal
+                            .as_ref()?
+                            .persistent_sessions
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .terminal
+                            .get_or_insert_default()
+                            .persistent_sessions = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Max Scroll History Lines",
                 description: "Maximum number of lines to keep in scrollback history (max: 100,000; 0 disables scrolling).",
@@ -6883,7 +6905,7 @@ fn ai_page() -> SettingsPage {
         ]
     }
 
-    fn agent_configuration_section() -> [SettingsPageItem; 12] {
+    fn agent_configuration_section() -> [SettingsPageItem; 13] {
         [
             SettingsPageItem::SectionHeader("Agent Configuration"),
             SettingsPageItem::SubPageLink(SubPageLink {
@@ -7099,6 +7121,28 @@ fn ai_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Enable Agent Sessions Server",
+                description: "Whether agents can see the project's other agent sessions, read their transcripts, send them messages, and claim files.",
+                field: Box::new(SettingField {
+                    json_path: Some("agent.enable_agent_sessions_server"),
+                    pick: |settings_content| {
+                        settings_content
+                            .agent
+                            .as_ref()?
+                            .enable_agent_sessions_server
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .agent
+                            .get_or_insert_default()
+                            .enable_agent_sessions_server = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
         ]
     }
 
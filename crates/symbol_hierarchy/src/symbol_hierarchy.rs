@@ -0,0 +1,584 @@
+use std::ops::Range;
+
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    App, Entity, EventEmitter, FocusHandle, Focusable, KeyContext, ScrollStrategy, Task,
+    UniformListScrollHandle, WeakEntity, Window, uniform_list,
+};
+use language::{Anchor, Buffer, ToPoint as _};
+use menu::{Confirm, SelectChild, SelectNext, SelectParent, SelectPrevious};
+use project::{HierarchyItem, Project};
+use ui::{
+    ListItem, ListItemSpacing, ToggleButtonGroup, ToggleButtonGroupSize, ToggleButtonGroupStyle,
+    ToggleButtonSimple, WithScrollbar, prelude::*,
+};
+use util::ResultExt as _;
+use workspace::{
+    Toast, Workspace,
+    item::{Item, ItemEvent},
+    notifications::{NotificationId, NotificationSource},
+};
+use zed_actions::symbol_hierarchy::{ShowCallHierarchy, ShowTypeHierarchy};
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ShowCallHierarchy, window, cx| {
+            SymbolHierarchyView::deploy(workspace, HierarchyKind::Calls, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowTypeHierarchy, window, cx| {
+            SymbolHierarchyView::deploy(workspace, HierarchyKind::Types, window, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyKind {
+    Calls,
+    Types,
+}
+
+/// Which related symbols are listed under each symbol of the tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HierarchyDirection {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyDirection {
+    fn kind(self) -> HierarchyKind {
+        match self {
+            Self::IncomingCalls | Self::OutgoingCalls => HierarchyKind::Calls,
+            Self::Supertypes | Self::Subtypes => HierarchyKind::Types,
+        }
+    }
+}
+
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// Where the calls that this node stands for are made, in the buffer of the calling symbol.
+    call_ranges: Vec<Range<Anchor>>,
+    depth: usize,
+    /// The nodes of the related symbols, once they were loaded.
+    children: Option<Vec<usize>>,
+    expanded: bool,
+    error: Option<SharedString>,
+    load_task: Option<Task<()>>,
+}
+
+/// Shows the callers and callees of a symbol, or the supertypes and subtypes of a type, as a tree
+/// that loads each symbol's relations from the language server when it's expanded.
+pub struct SymbolHierarchyView {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    direction: HierarchyDirection,
+    roots: Vec<HierarchyItem>,
+    nodes: Vec<HierarchyNode>,
+    /// The nodes in the order they're shown, skipping the children of collapsed nodes.
+    visible_nodes: Vec<usize>,
+    selected_node: Option<usize>,
+    list: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+}
+
+impl SymbolHierarchyView {
+    /// Opens the hierarchy of the symbol under the cursor of the active editor.
+    fn deploy(
+        workspace: &mut Workspace,
+        kind: HierarchyKind,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        let Some((buffer, position)) = editor.buffer().read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+
+        let project = workspace.project().clone();
+        let roots = project.update(cx, |project, cx| match kind {
+            HierarchyKind::Calls => project.prepare_call_hierarchy(&buffer, position, cx),
+            HierarchyKind::Types => project.prepare_type_hierarchy(&buffer, position, cx),
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let roots = roots.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if roots.is_empty() {
+                    let message = match kind {
+                        HierarchyKind::Calls => "No call hierarchy for the symbol under the cursor",
+                        HierarchyKind::Types => "No type hierarchy for the symbol under the cursor",
+                    };
+                    workspace.show_toast(
+                        Toast::new(NotificationId::unique::<SymbolHierarchyView>(), message)
+                            .autohide(),
+                        NotificationSource::Editor,
+                        cx,
+                    );
+                    return;
+                }
+                let workspace_handle = cx.weak_entity();
+                let view = cx.new(|cx| Self::new(workspace_handle, project, kind, roots, cx));
+                workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        kind: HierarchyKind,
+        roots: Vec<HierarchyItem>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let direction = match kind {
+            HierarchyKind::Calls => HierarchyDirection::IncomingCalls,
+            HierarchyKind::Types => HierarchyDirection::Supertypes,
+        };
+        let mut this = Self {
+            workspace,
+            project,
+            direction,
+            roots,
+            nodes: Vec::new(),
+            visible_nodes: Vec::new(),
+            selected_node: None,
+            list: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+        };
+        this.reset_tree(cx);
+        this
+    }
+
+    fn set_direction(&mut self, direction: HierarchyDirection, cx: &mut Context<Self>) {
+        if self.direction != direction {
+            self.direction = direction;
+            self.reset_tree(cx);
+        }
+    }
+
+    /// Rebuilds the tree from the root symbols, dropping the relations loaded so far.
+    fn reset_tree(&mut self, cx: &mut Context<Self>) {
+        self.nodes = self
+            .roots
+            .iter()
+            .map(|item| HierarchyNode {
+                item: item.clone(),
+                call_ranges: Vec::new(),
+                depth: 0,
+                children: None,
+                expanded: false,
+                error: None,
+                load_task: None,
+            })
+            .collect();
+        self.selected_node = (!self.nodes.is_empty()).then_some(0);
+        for ix in 0..self.nodes.len() {
+            self.expand(ix, cx);
+        }
+        self.update_visible_nodes(cx);
+    }
+
+    fn update_visible_nodes(&mut self, cx: &mut Context<Self>) {
+        fn push_visible(nodes: &[HierarchyNode], ix: usize, visible_nodes: &mut Vec<usize>) {
+            visible_nodes.push(ix);
+            let node = &nodes[ix];
+            if node.expanded {
+                for &child in node.children.iter().flatten() {
+                    push_visible(nodes, child, visible_nodes);
+                }
+            }
+        }
+
+        self.visible_nodes.clear();
+        for ix in 0..self.roots.len().min(self.nodes.len()) {
+            push_visible(&self.nodes, ix, &mut self.visible_nodes);
+        }
+        cx.notify();
+    }
+
+    fn toggle(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if self.nodes[ix].expanded {
+            self.nodes[ix].expanded = false;
+            self.update_visible_nodes(cx);
+        } else {
+            self.expand(ix, cx);
+        }
+    }
+
+    fn expand(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let node = &mut self.nodes[ix];
+        node.expanded = true;
+        if node.children.is_some() || node.load_task.is_some() {
+            self.update_visible_nodes(cx);
+            return;
+        }
+
+        let item = node.item.clone();
+        let direction = self.direction;
+        let project = self.project.clone();
+        node.load_task = Some(cx.spawn(async move |this, cx| {
+            let children = match direction {
+                HierarchyDirection::IncomingCalls => project
+                    .update(cx, |project, cx| project.incoming_calls(&item, cx))
+                    .await
+                    .map(|calls| {
+                        calls
+                            .into_iter()
+                            .map(|call| (call.item, call.call_ranges))
+                            .collect::<Vec<_>>()
+                    }),
+                HierarchyDirection::OutgoingCalls => project
+                    .update(cx, |project, cx| project.outgoing_calls(&item, cx))
+                    .await
+                    .map(|calls| {
+                        calls
+                            .into_iter()
+                            .map(|call| (call.item, call.call_ranges))
+                            .collect()
+                    }),
+                HierarchyDirection::Supertypes => project
+                    .update(cx, |project, cx| project.supertypes(&item, cx))
+                    .await
+                    .map(|items| items.into_iter().map(|item| (item, Vec::new())).collect()),
+                HierarchyDirection::Subtypes => project
+                    .update(cx, |project, cx| project.subtypes(&item, cx))
+                    .await
+                    .map(|items| items.into_iter().map(|item| (item, Vec::new())).collect()),
+            };
+            this.update(cx, |this, cx| {
+                let depth = this.nodes[ix].depth + 1;
+                let children = match children {
+                    Ok(children) => children
+                        .into_iter()
+                        .map(|(item, call_ranges)| {
+                            this.nodes.push(HierarchyNode {
+                                item,
+                                call_ranges,
+                                depth,
+                                children: None,
+                                expanded: false,
+                                error: None,
+                                load_task: None,
+                            });
+                            this.nodes.len() - 1
+                        })
+                        .collect(),
+                    Err(error) => {
+                        this.nodes[ix].error = Some(error.to_string().into());
+                        Vec::new()
+                    }
+                };
+                let node = &mut this.nodes[ix];
+                node.children = Some(children);
+                node.load_task = None;
+                this.update_visible_nodes(cx);
+            })
+            .ok();
+        }));
+        self.update_visible_nodes(cx);
+    }
+
+    /// Opens the editor at the symbol, or at the call for incoming calls.
+    fn open(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let node = &self.nodes[ix];
+        let position = match (self.direction, node.call_ranges.first()) {
+            (HierarchyDirection::IncomingCalls, Some(call_range)) => call_range.start,
+            _ => node.item.selection_range.start,
+        };
+        let buffer = node.item.location.buffer.clone();
+        self.selected_node = Some(ix);
+        cx.notify();
+        open_buffer_at(&self.workspace, buffer, position, window, cx);
+    }
+
+    fn selected_visible_ix(&self) -> Option<usize> {
+        let selected = self.selected_node?;
+        self.visible_nodes.iter().position(|&ix| ix == selected)
+    }
+
+    fn select_visible(&mut self, visible_ix: usize, cx: &mut Context<Self>) {
+        if let Some(&ix) = self.visible_nodes.get(visible_ix) {
+            self.selected_node = Some(ix);
+            self.list.scroll_to_item(visible_ix, ScrollStrategy::Center);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let visible_ix = self
+            .selected_visible_ix()
+            .map_or(0, |visible_ix| visible_ix + 1);
+        self.select_visible(visible_ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let visible_ix = self
+            .selected_visible_ix()
+            .map_or(0, |visible_ix| visible_ix.saturating_sub(1));
+        self.select_visible(visible_ix, cx);
+    }
+
+    /// Expands the selected symbol, or selects its first related symbol if it's expanded.
+    fn select_child(&mut self, _: &SelectChild, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(ix) = self.selected_node else {
+            return;
+        };
+        if !self.nodes[ix].expanded {
+            self.expand(ix, cx);
+        } else if let Some(visible_ix) = self.selected_visible_ix()
+            && self.nodes[ix]
+                .children
+                .as_ref()
+                .is_some_and(|c| !c.is_empty())
+        {
+            self.select_visible(visible_ix + 1, cx);
+        }
+    }
+
+    /// Collapses the selected symbol, or selects its parent if it's collapsed.
+    fn select_parent(&mut self, _: &SelectParent, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(ix) = self.selected_node else {
+            return;
+        };
+        if self.nodes[ix].expanded {
+            self.toggle(ix, cx);
+        } else if let Some(parent) = self.nodes.iter().position(|node| {
+            node.children
+                .as_ref()
+                .is_some_and(|children| children.contains(&ix))
+        }) {
+            self.selected_node = Some(parent);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_node {
+            self.open(ix, window, cx);
+        }
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("SymbolHierarchy");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_nodes(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range
+            .filter_map(|visible_ix| {
+                let ix = *self.visible_nodes.get(visible_ix)?;
+                Some(self.render_node(ix, cx))
+            })
+            .collect()
+    }
+
+    fn render_node(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let node = &self.nodes[ix];
+        let has_children = node
+            .children
+            .as_ref()
+            .is_none_or(|children| !children.is_empty());
+        let buffer = node.item.location.buffer.read(cx);
+        let file_name = buffer
+            .file()
+            .map(|file| file.file_name(cx).to_string())
+            .unwrap_or_default();
+        let row = node.item.selection_range.start.to_point(buffer).row + 1;
+        let calls = match node.call_ranges.len() {
+            0 | 1 => None,
+            count => Some(format!("{count} calls")),
+        };
+
+        ListItem::new(("symbol-hierarchy-node", ix))
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(node.depth)
+            .indent_step_size(px(12.))
+            .toggle_state(self.selected_node == Some(ix))
+            .toggle(has_children.then_some(node.expanded))
+            .on_toggle(cx.listener(move |this, _, _, cx| this.toggle(ix, cx)))
+            .on_click(cx.listener(move |this, _, window, cx| this.open(ix, window, cx)))
+            .child(
+                h_flex()
+                    .min_w_0()
+                    .gap_2()
+                    .child(Label::new(node.item.name.clone()).buffer_font(cx))
+                    .when_some(node.item.detail.clone(), |this, detail| {
+                        this.child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    })
+                    .child(
+                        Label::new(format!("{file_name}:{row}"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when_some(calls, |this, calls| {
+                        this.child(Label::new(calls).size(LabelSize::Small).color(Color::Muted))
+                    })
+                    .when(node.load_task.is_some(), |this| {
+                        this.child(
+                            Label::new("Loading…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when_some(node.error.clone(), |this, error| {
+                        this.child(
+                            Label::new(error)
+                                .size(LabelSize::Small)
+                                .color(Color::Error)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .into_any_element()
+    }
+
+    fn title(&self) -> SharedString {
+        let root = self
+            .roots
+            .first()
+            .map(|root| root.name.as_str())
+            .unwrap_or_default();
+        match self.direction.kind() {
+            HierarchyKind::Calls => format!("Calls: {root}").into(),
+            HierarchyKind::Types => format!("Types: {root}").into(),
+        }
+    }
+}
+
+fn open_buffer_at(
+    workspace: &WeakEntity<Workspace>,
+    buffer: Entity<Buffer>,
+    position: Anchor,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let position = position.to_point(buffer.read(cx));
+    workspace
+        .update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let editor = workspace
+                .open_project_item::<Editor>(pane, buffer, true, true, true, true, window, cx);
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_ranges([position..position]),
+                );
+            });
+        })
+        .log_err();
+}
+
+impl Render for SymbolHierarchyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let direction_button = |label: &'static str, direction: HierarchyDirection| {
+            ToggleButtonSimple::new(
+                label,
+                cx.listener(move |this, _, _, cx| this.set_direction(direction, cx)),
+            )
+        };
+        let (buttons, selected_index) = match self.direction {
+            HierarchyDirection::IncomingCalls | HierarchyDirection::OutgoingCalls => (
+                [
+                    direction_button("Incoming Calls", HierarchyDirection::IncomingCalls),
+                    direction_button("Outgoing Calls", HierarchyDirection::OutgoingCalls),
+                ],
+                (self.direction == HierarchyDirection::OutgoingCalls) as usize,
+            ),
+            HierarchyDirection::Supertypes | HierarchyDirection::Subtypes => (
+                [
+                    direction_button("Supertypes", HierarchyDirection::Supertypes),
+                    direction_button("Subtypes", HierarchyDirection::Subtypes),
+                ],
+                (self.direction == HierarchyDirection::Subtypes) as usize,
+            ),
+        };
+
+        v_flex()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_child))
+            .on_action(cx.listener(Self::select_parent))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        ToggleButtonGroup::single_row("symbol-hierarchy-direction", buttons)
+                            .style(ToggleButtonGroupStyle::Outlined)
+                            .size(ToggleButtonGroupSize::Custom(rems_from_px(26.)))
+                            .auto_width()
+                            .selected_index(selected_index),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .size_full()
+                    .overflow_y_hidden()
+                    .child(
+                        uniform_list(
+                            "symbol-hierarchy-nodes",
+                            self.visible_nodes.len(),
+                            cx.processor(Self::render_nodes),
+                        )
+                        .flex_grow()
+                        .track_scroll(&self.list),
+                    )
+                    .vertical_scrollbar_for(&self.list, window, cx),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for SymbolHierarchyView {}
+
+impl Focusable for SymbolHierarchyView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for SymbolHierarchyView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.title()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
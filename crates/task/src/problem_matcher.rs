@@ -0,0 +1,516 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to turn a task's output into diagnostics, as listed in [`crate::TaskTemplate::problem_matchers`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherTemplate {
+    /// One of the built-in matchers, by name.
+    Builtin(BuiltinProblemMatcher),
+    /// A matcher defined in place.
+    Custom(CustomProblemMatcher),
+}
+
+/// Problem matchers that Zed knows about without any configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum BuiltinProblemMatcher {
+    /// Errors and warnings reported by `rustc`, and so by `cargo build`, `cargo check`, etc.
+    #[serde(rename = "$rustc")]
+    Rustc,
+    /// Errors and warnings reported by the TypeScript compiler.
+    #[serde(rename = "$tsc")]
+    Tsc,
+    /// Errors and warnings reported by `gcc`, and by `clang` which uses the same format.
+    #[serde(rename = "$gcc")]
+    Gcc,
+}
+
+impl BuiltinProblemMatcher {
+    /// Looks a built-in matcher up by its name, e.g. `$rustc`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "$rustc" => Some(Self::Rustc),
+            "$tsc" => Some(Self::Tsc),
+            "$gcc" => Some(Self::Gcc),
+            _ => None,
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Self::Rustc => "rustc",
+            Self::Tsc => "tsc",
+            Self::Gcc => "gcc",
+        }
+    }
+
+    fn patterns(&self) -> Vec<ProblemPattern> {
+        match self {
+            // error[E0308]: mismatched types
+            //   --> src/main.rs:4:18
+            Self::Rustc => vec![
+                ProblemPattern {
+                    regexp: r"^(error|warning)(?:\[(\w+)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*--> (.+):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+            // src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.
+            // src/index.ts:3:7 - error TS2322: Type 'string' is not assignable to type 'number'.
+            Self::Tsc => vec![ProblemPattern {
+                regexp: r"^([^\s].*)[(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+            // main.c:5:10: error: expected ';' before 'return'
+            Self::Gcc => vec![ProblemPattern {
+                regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(error|warning|note):\s+(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+        }
+    }
+}
+
+/// A problem matcher defined in the task itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// A built-in matcher whose patterns to use when `patterns` is empty.
+    #[serde(default)]
+    pub base: Option<BuiltinProblemMatcher>,
+    /// Patterns matching consecutive output lines, the last of which completes a problem.
+    /// Each pattern picks some parts of the problem out of its line.
+    #[serde(default)]
+    pub patterns: Vec<ProblemPattern>,
+    /// The source shown next to each diagnostic, defaults to the base matcher's or the task's label.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// The severity of problems whose patterns don't capture one, defaults to `error`.
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+    /// The directory that relative paths in the output are relative to, defaults to the task's `cwd`.
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+/// A regular expression matching a line of a task's output, along with the numbers of its
+/// capture groups that hold each part of the problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// The group holding the path of the file the problem is in.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The group holding the 1-based line the problem starts on.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The group holding the 1-based column the problem starts at.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The group holding the 1-based line the problem ends on.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// The group holding the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The group holding the severity, e.g. `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The group holding the problem's code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The group holding the problem's message.
+    #[serde(default)]
+    pub message: Option<usize>,
+}
+
+/// How severe a problem found in a task's output is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    fn parse(severity: &str) -> Option<Self> {
+        match severity.to_ascii_lowercase().as_str() {
+            "error" | "fatal" => Some(Self::Error),
+            "warning" | "warn" => Some(Self::Warning),
+            "info" | "note" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+impl ProblemMatcherTemplate {
+    /// Fills in the defaults of the matcher, using `label` for the source of custom matchers
+    /// and `cwd` for the directory relative paths are relative to.
+    pub fn resolve(&self, label: &str, cwd: Option<&Path>) -> ProblemMatcher {
+        match self {
+            Self::Builtin(builtin) => ProblemMatcher {
+                source: builtin.source().to_string(),
+                severity: ProblemSeverity::default(),
+                patterns: builtin.patterns(),
+                directory: cwd.map(Path::to_path_buf),
+            },
+            Self::Custom(custom) => {
+                let patterns = match custom.base {
+                    Some(base) if custom.patterns.is_empty() => base.patterns(),
+                    _ => custom.patterns.clone(),
+                };
+                let source = custom
+                    .source
+                    .clone()
+                    .or_else(|| custom.base.map(|base| base.source().to_string()))
+                    .unwrap_or_else(|| label.to_string());
+                let directory = match (&custom.directory, cwd) {
+                    (Some(directory), Some(cwd)) => Some(cwd.join(directory)),
+                    (Some(directory), None) => Some(PathBuf::from(directory)),
+                    (None, cwd) => cwd.map(Path::to_path_buf),
+                };
+                ProblemMatcher {
+                    source,
+                    severity: custom.severity.unwrap_or_default(),
+                    patterns,
+                    directory,
+                }
+            }
+        }
+    }
+}
+
+/// A problem matcher ready to be run against the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProblemMatcher {
+    /// The source shown next to each diagnostic.
+    pub source: String,
+    /// The severity of problems whose patterns don't capture one.
+    pub severity: ProblemSeverity,
+    /// Patterns matching consecutive output lines, the last of which completes a problem.
+    pub patterns: Vec<ProblemPattern>,
+    /// The directory that relative paths in the output are relative to.
+    pub directory: Option<PathBuf>,
+}
+
+/// A problem found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The file the problem is in, relative to the current directory if the matcher had none.
+    pub path: PathBuf,
+    /// The 1-based line the problem starts on.
+    pub line: u32,
+    /// The 1-based column the problem starts at, if the output has it.
+    pub column: Option<u32>,
+    /// The 1-based line the problem ends on, if the output has it.
+    pub end_line: Option<u32>,
+    /// The 1-based column the problem ends at, if the output has it.
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    pub source: String,
+}
+
+#[derive(Default)]
+struct PartialProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl PartialProblem {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &regex::Captures) {
+        let group = |ix: Option<usize>| {
+            ix.and_then(|ix| captures.get(ix))
+                .map(|group| group.as_str())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |ix: Option<usize>| group(ix).and_then(|text| text.parse().ok());
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file.to_string());
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity).and_then(ProblemSeverity::parse) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_string());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.to_string());
+        }
+    }
+}
+
+/// Finds the problems that `matchers` match in `output`, in the order they appear.
+///
+/// Every matcher runs over every line, so several matchers may report the same problem.
+pub fn find_problems(matchers: &[ProblemMatcher], output: &str) -> anyhow::Result<Vec<Problem>> {
+    let mut problems = Vec::new();
+    for matcher in matchers {
+        let regexes = matcher
+            .patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp)
+                    .with_context(|| format!("invalid problem pattern {:?}", pattern.regexp))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if regexes.is_empty() {
+            continue;
+        }
+
+        let mut next_pattern = 0;
+        let mut partial = PartialProblem::default();
+        for line in output.lines() {
+            let line = line.trim_end();
+            let captures = match regexes[next_pattern].captures(line) {
+                Some(captures) => captures,
+                // A problem's lines have to be consecutive, so start over from this line.
+                None if next_pattern > 0 => {
+                    next_pattern = 0;
+                    partial = PartialProblem::default();
+                    match regexes[0].captures(line) {
+                        Some(captures) => captures,
+                        None => continue,
+                    }
+                }
+                None => continue,
+            };
+            partial.capture(&matcher.patterns[next_pattern], &captures);
+            next_pattern += 1;
+            if next_pattern < regexes.len() {
+                continue;
+            }
+
+            let problem = std::mem::take(&mut partial);
+            next_pattern = 0;
+            let (Some(file), Some(line)) = (problem.file, problem.line) else {
+                continue;
+            };
+            let path = match &matcher.directory {
+                Some(directory) => directory.join(file),
+                None => PathBuf::from(file),
+            };
+            problems.push(Problem {
+                path,
+                line,
+                column: problem.column,
+                end_line: problem.end_line,
+                end_column: problem.end_column,
+                severity: problem.severity.unwrap_or(matcher.severity),
+                code: problem.code,
+                message: problem.message.unwrap_or_default(),
+                source: matcher.source.clone(),
+            });
+        }
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn builtin(builtin: BuiltinProblemMatcher) -> ProblemMatcher {
+        ProblemMatcherTemplate::Builtin(builtin).resolve("task", Some(Path::new("/project")))
+    }
+
+    #[test]
+    fn test_rustc_problems() {
+        let output = indoc! {"
+               Compiling demo v0.1.0 (/project)
+            warning: unused variable: `x`
+             --> src/main.rs:2:9
+              |
+            2 |     let x = 1;
+              |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+
+            error[E0308]: mismatched types
+              --> src/lib.rs:10:18
+               |
+            warning: `demo` (bin \"demo\") generated 1 warning
+            error: could not compile `demo` (bin \"demo\") due to 1 previous error
+        "};
+        let problems = find_problems(&[builtin(BuiltinProblemMatcher::Rustc)], output).unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    path: PathBuf::from("/project/src/main.rs"),
+                    line: 2,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `x`".to_string(),
+                    source: "rustc".to_string(),
+                },
+                Problem {
+                    path: PathBuf::from("/project/src/lib.rs"),
+                    line: 10,
+                    column: Some(18),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                    source: "rustc".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tsc_and_gcc_problems() {
+        let output = indoc! {"
+            src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.
+            src/app.ts:12:1 - warning TS6133: 'y' is declared but its value is never read.
+        "};
+        let problems = find_problems(&[builtin(BuiltinProblemMatcher::Tsc)], output).unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.clone(),
+                    problem.line,
+                    problem.column,
+                    problem.severity,
+                    problem.code.clone(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PathBuf::from("/project/src/index.ts"),
+                    3,
+                    Some(7),
+                    ProblemSeverity::Error,
+                    Some("2322".to_string()),
+                ),
+                (
+                    PathBuf::from("/project/src/app.ts"),
+                    12,
+                    Some(1),
+                    ProblemSeverity::Warning,
+                    Some("6133".to_string()),
+                ),
+            ]
+        );
+
+        let output = indoc! {"
+            main.c: In function 'main':
+            main.c:5:10: error: expected ';' before 'return'
+            /usr/include/stdio.h:12: fatal error: bad.h: No such file or directory
+        "};
+        let problems = find_problems(&[builtin(BuiltinProblemMatcher::Gcc)], output).unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.clone(),
+                    problem.line,
+                    problem.column,
+                    problem.message.as_str(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PathBuf::from("/project/main.c"),
+                    5,
+                    Some(10),
+                    "expected ';' before 'return'",
+                ),
+                (
+                    PathBuf::from("/usr/include/stdio.h"),
+                    12,
+                    None,
+                    "bad.h: No such file or directory",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let template: ProblemMatcherTemplate = serde_json::from_value(serde_json::json!({
+            "source": "lint",
+            "severity": "warning",
+            "directory": "web",
+            "patterns": [{ "regexp": "^(.+)@(\\d+): (.*)$", "file": 1, "line": 2, "message": 3 }],
+        }))
+        .unwrap();
+        let matcher = template.resolve("Lint", Some(Path::new("/project")));
+        let problems = find_problems(&[matcher], "app.js@4: missing semicolon\n").unwrap();
+        assert_eq!(
+            problems,
+            vec![Problem {
+                path: PathBuf::from("/project/web/app.js"),
+                line: 4,
+                column: None,
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "missing semicolon".to_string(),
+                source: "lint".to_string(),
+            }]
+        );
+
+        let template: ProblemMatcherTemplate = serde_json::from_str("\"$rustc\"").unwrap();
+        assert_eq!(
+            template,
+            ProblemMatcherTemplate::Builtin(BuiltinProblemMatcher::Rustc)
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
 mod task_template;
@@ -22,8 +23,12 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    BuiltinProblemMatcher, CustomProblemMatcher, Problem, ProblemMatcher, ProblemMatcherTemplate,
+    ProblemPattern, ProblemSeverity, find_problems,
+};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
     substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use util::shell::{Shell, ShellKind};
@@ -75,6 +80,8 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Matchers that turn the task's output into diagnostics once it finishes.
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -122,6 +129,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task got resolved with, used to resolve the tasks it depends on.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: SpawnInTerminal,
@@ -138,6 +147,11 @@ impl ResolvedTask {
         &self.substituted_variables
     }
 
+    /// The context the task got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// A human-readable label to display in the UI.
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
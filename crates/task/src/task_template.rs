@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcherTemplate, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -72,6 +73,20 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks to run before this one. This task doesn't run if any of them fails.
+    /// A task with dependencies may leave `command` empty, to only run its dependencies.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks in `depends_on`:
+    /// * `sequential` — one after another, in the order they're listed (default)
+    /// * `parallel` — all at once
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Matchers that turn the task's output into diagnostics once it finishes.
+    /// Either the name of a built-in matcher (`$rustc`, `$tsc`, or `$gcc`),
+    /// or a matcher with its own regular expressions.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcherTemplate>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -96,6 +111,17 @@ pub enum RevealStrategy {
     Never,
 }
 
+/// How to run the tasks that a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the tasks one after another, in the order they're listed.
+    #[default]
+    Sequential,
+    /// Run all the tasks at once.
+    Parallel,
+}
+
 /// What to do with the terminal pane and tab, after the command has finished.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -132,7 +158,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -220,6 +248,25 @@ impl TaskTemplate {
             .log_err()?;
         let id = TaskId(format!("{id_base}_{task_hash}_{variables_hash}"));
 
+        let problem_matchers = self
+            .problem_matchers
+            .iter()
+            .map(|matcher| {
+                let mut matcher = matcher.clone();
+                if let ProblemMatcherTemplate::Custom(custom) = &mut matcher
+                    && let Some(directory) = &custom.directory
+                {
+                    custom.directory = Some(substitute_all_template_variables_in_str(
+                        directory,
+                        &task_variables,
+                        &variable_names,
+                        &mut substituted_variables,
+                    )?);
+                }
+                Some(matcher.resolve(&full_label, cwd.as_deref()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
         let env = {
             // Start with the project environment as the base.
             let mut env = cx.project_env.clone();
@@ -243,6 +290,7 @@ impl TaskTemplate {
         Some(ResolvedTask {
             id: id.clone(),
             substituted_variables,
+            task_context: cx.clone(),
             original_task: self.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
@@ -270,6 +318,7 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
             },
         })
     }
@@ -521,6 +570,18 @@ mod tests {
                 "should not resolve task with blank label and/or command: {task_with_blank_property:?}"
             );
         }
+
+        let task_with_only_dependencies = TaskTemplate {
+            label: "test_label".to_string(),
+            depends_on: vec!["other_label".to_string()],
+            ..TaskTemplate::default()
+        };
+        assert!(
+            task_with_only_dependencies
+                .resolve_task(TEST_ID_BASE, &TaskContext::default())
+                .is_some(),
+            "should resolve a task that only runs its dependencies"
+        );
     }
 
     #[test]
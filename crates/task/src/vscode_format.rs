@@ -1,9 +1,13 @@
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BuiltinProblemMatcher, CustomProblemMatcher, DependsOrder, EnvVariableReplacer,
+    ProblemMatcherTemplate, ProblemPattern, ProblemSeverity, TaskTemplate, TaskTemplates,
+    VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -41,30 +45,153 @@ enum Command {
     },
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            Self::One(item) => vec![item],
+            Self::Many(items) => items,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcher {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    severity: Option<ProblemSeverity>,
+    /// Either `"absolute"` or `"relative"`, or a list of that and the directory paths are relative to.
+    file_location: Option<OneOrMany<String>>,
+    pattern: Option<OneOrMany<VsCodeProblemPattern>>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> Option<ProblemMatcherTemplate> {
+        let base = match self.base.as_deref() {
+            Some(name) => Some(BuiltinProblemMatcher::from_name(name)?),
+            None => None,
+        };
+        let patterns = self
+            .pattern
+            .map(OneOrMany::into_vec)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pattern| ProblemPattern {
+                regexp: pattern.regexp,
+                file: pattern.file,
+                line: pattern.line,
+                column: pattern.column,
+                end_line: pattern.end_line,
+                end_column: pattern.end_column,
+                severity: pattern.severity,
+                code: pattern.code,
+                message: pattern.message,
+            })
+            .collect::<Vec<_>>();
+        if base.is_none() && patterns.is_empty() {
+            return None;
+        }
+        let directory = self
+            .file_location
+            .map(OneOrMany::into_vec)
+            .and_then(|file_location| file_location.into_iter().nth(1))
+            .map(|directory| replacer.replace(&directory));
+        Some(ProblemMatcherTemplate::Custom(CustomProblemMatcher {
+            base,
+            patterns,
+            source: self.source.or(self.owner),
+            severity: self.severity,
+            directory,
+        }))
+    }
+}
+
+/// Converts the `problemMatcher` of a task, skipping the matchers Zed doesn't know.
+fn problem_matchers_from_vscode(
+    label: &str,
+    value: serde_json_lenient::Value,
+    replacer: &EnvVariableReplacer,
+) -> Vec<ProblemMatcherTemplate> {
+    let values = match value {
+        serde_json_lenient::Value::Array(values) => values,
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .filter_map(|value| {
+            let matcher = match &value {
+                serde_json_lenient::Value::String(name) => {
+                    BuiltinProblemMatcher::from_name(name).map(ProblemMatcherTemplate::Builtin)
+                }
+                _ => serde_json_lenient::from_value::<VsCodeProblemMatcher>(value.clone())
+                    .ok()
+                    .and_then(|matcher| matcher.into_zed_format(replacer)),
+            };
+            if matcher.is_none() {
+                log::warn!("Skipping unsupported problem matcher {value} of a task `{label}`");
+            }
+            matcher
+        })
+        .collect()
+}
+
 impl VsCodeTaskDefinition {
     fn into_zed_format(
-        self,
+        mut self,
         replacer: &EnvVariableReplacer,
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let depends_on = match self.other_attributes.remove("dependsOn") {
+            Some(depends_on) => serde_json_lenient::from_value::<OneOrMany<String>>(depends_on)
+                .map(OneOrMany::into_vec)
+                .map_err(|_| anyhow!("Only task labels are supported in `dependsOn` of a task"))?,
+            None => Vec::new(),
         };
+        // VS Code runs the dependencies in parallel, unless told otherwise.
+        let depends_order = match self
+            .other_attributes
+            .get("dependsOrder")
+            .and_then(|order| order.as_str())
+        {
+            _ if depends_on.is_empty() => DependsOrder::default(),
+            Some("sequence") => DependsOrder::Sequential,
+            _ => DependsOrder::Parallel,
+        };
+        let problem_matchers = self
+            .other_attributes
+            .remove("problemMatcher")
+            .map(|value| problem_matchers_from_vscode(&self.label, value, replacer))
+            .unwrap_or_default();
 
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` might not be set in tasks that only use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+        // as that way we can provide more specific description of why deserialization failed.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -73,6 +200,9 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
@@ -124,6 +254,7 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
+        BuiltinProblemMatcher, CustomProblemMatcher, DependsOrder, ProblemMatcherTemplate,
         TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
@@ -227,6 +358,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
@@ -244,12 +376,14 @@ mod tests {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::Builtin(BuiltinProblemMatcher::Tsc)],
                 ..Default::default()
             },
         ];
@@ -325,6 +459,12 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let tsc_in_extension_directory = ProblemMatcherTemplate::Custom(CustomProblemMatcher {
+            base: Some(BuiltinProblemMatcher::Tsc),
+            directory: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+            ..CustomProblemMatcher::default()
+        });
+        let rustc = ProblemMatcherTemplate::Builtin(BuiltinProblemMatcher::Rustc);
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
@@ -336,22 +476,43 @@ mod tests {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![tsc_in_extension_directory.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![rustc.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![rustc.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![tsc_in_extension_directory],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![rustc.clone()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: vec![rustc],
                 ..Default::default()
             },
         ];
@@ -35,6 +35,14 @@ impl ProcessIdGetter {
         }
     }
 
+    /// Follows the shell of a persistent session, which runs outside of the terminal's PTY.
+    fn for_session(shell_pid: u32) -> ProcessIdGetter {
+        ProcessIdGetter {
+            handle: -1,
+            fallback_pid: shell_pid,
+        }
+    }
+
     fn pid(&self) -> Option<Pid> {
         let pid = unsafe { libc::tcgetpgrp(self.handle) };
         if pid < 0 {
@@ -87,23 +95,34 @@ pub struct PtyProcessInfo {
     system: RwLock<System>,
     refresh_kind: ProcessRefreshKind,
     pid_getter: ProcessIdGetter,
+    /// The process running in the PTY, which is not the shell for persistent sessions.
+    child_pid: Pid,
     pub current: RwLock<Option<ProcessInfo>>,
     task: Mutex<Option<Task<()>>>,
 }
 
 impl PtyProcessInfo {
-    pub fn new(pty: &Pty) -> PtyProcessInfo {
+    pub fn new(pty: &Pty, session_shell_pid: Option<u32>) -> PtyProcessInfo {
         let process_refresh_kind = ProcessRefreshKind::nothing()
             .with_cmd(UpdateKind::Always)
             .with_cwd(UpdateKind::Always)
             .with_exe(UpdateKind::Always);
         let refresh_kind = RefreshKind::nothing().with_processes(process_refresh_kind);
         let system = System::new_with_specifics(refresh_kind);
+        let pty_pid_getter = ProcessIdGetter::new(pty);
+        #[cfg(unix)]
+        let pid_getter = session_shell_pid.map_or(pty_pid_getter, ProcessIdGetter::for_session);
+        #[cfg(not(unix))]
+        let pid_getter = {
+            let _ = session_shell_pid;
+            pty_pid_getter
+        };
 
         PtyProcessInfo {
             system: RwLock::new(system),
             refresh_kind: process_refresh_kind,
-            pid_getter: ProcessIdGetter::new(pty),
+            pid_getter,
+            child_pid: pty_pid_getter.fallback_pid(),
             current: RwLock::new(None),
             task: Mutex::new(None),
         }
@@ -128,8 +147,12 @@ impl PtyProcessInfo {
     }
 
     fn get_child(&self) -> Option<MappedRwLockReadGuard<'_, Process>> {
-        let pid = self.pid_getter.fallback_pid();
-        RwLockReadGuard::try_map(self.system.read(), |system| system.process(pid)).ok()
+        self.system.write().refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[self.child_pid]),
+            true,
+            self.refresh_kind,
+        );
+        RwLockReadGuard::try_map(self.system.read(), |system| system.process(self.child_pid)).ok()
     }
 
     #[cfg(unix)]
@@ -50,7 +50,7 @@ use terminal_hyperlinks::RegexSearches;
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use urlencoding;
-use util::{paths::PathStyle, truncate_and_trailoff};
+use util::{ResultExt as _, paths::PathStyle, truncate_and_trailoff};
 
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
@@ -69,7 +69,7 @@ use thiserror::Error;
 use gpui::{
     App, AppContext as _, BackgroundExecutor, Bounds, ClipboardItem, Context, EventEmitter, Hsla,
     Keystroke, Modifiers, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point,
-    Rgba, ScrollWheelEvent, Size, Task, TouchPhase, Window, actions, black, px,
+    Rgba, ScrollWheelEvent, Size, Subscription, Task, TouchPhase, Window, actions, black, px,
 };
 
 use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
@@ -415,6 +415,7 @@ impl TerminalBuilder {
             event_loop_task: Task::ready(Ok(())),
             background_executor: background_executor.clone(),
             path_style,
+            session: None,
         };
 
         Ok(TerminalBuilder {
@@ -439,6 +440,7 @@ impl TerminalBuilder {
         cx: &App,
         activation_script: Vec<String>,
         path_style: PathStyle,
+        session: Option<PersistentSession>,
     ) -> Task<Result<TerminalBuilder>> {
         let version = release_channel::AppVersion::global(cx);
         let background_executor = cx.background_executor().clone();
@@ -518,13 +520,57 @@ impl TerminalBuilder {
             // supported remoting into windows.
             let shell_kind = shell.shell_kind(cfg!(windows));
 
+            // A persistent session runs the shell in its daemon, and the PTY relays to it.
+            // When the daemon can't start, the shell runs in the PTY like in any other terminal.
+            let session = match session {
+                Some(PersistentSession::StartOrReattach(session_id)) => {
+                    let (program, args) = match &shell_params {
+                        Some(params) => (params.program.clone(), params.args.clone()),
+                        None => (util::shell::get_system_shell(), None),
+                    };
+                    let working_directory = working_directory.clone();
+                    let env = env.clone();
+                    smol::unblock(move || {
+                        let attach_command = terminal_session::attach_command(&session_id)?;
+                        let session = terminal_session::start_or_reattach(
+                            &session_id,
+                            &program,
+                            &args.unwrap_or_default(),
+                            working_directory.as_deref(),
+                            &env,
+                        )?;
+                        anyhow::Ok((session_id, session, attach_command))
+                    })
+                    .await
+                    .context("failed to start a persistent terminal session")
+                    .log_err()
+                }
+                // Running the shell anew would run it twice, so there's nothing to fall back to.
+                Some(PersistentSession::Reattach(session_id)) => Some(
+                    smol::unblock(move || {
+                        let attach_command = terminal_session::attach_command(&session_id)?;
+                        let session = terminal_session::reattach(&session_id)?;
+                        anyhow::Ok((session_id, session, attach_command))
+                    })
+                    .await
+                    .context("failed to reattach to a persistent terminal session")?,
+                ),
+                None => None,
+            };
+
             let pty_options = {
-                let alac_shell = shell_params.as_ref().map(|params| {
-                    alacritty_terminal::tty::Shell::new(
-                        params.program.clone(),
-                        params.args.clone().unwrap_or_default(),
-                    )
-                });
+                let alac_shell = match &session {
+                    Some((_, _, (program, args))) => Some(alacritty_terminal::tty::Shell::new(
+                        program.clone(),
+                        args.clone(),
+                    )),
+                    None => shell_params.as_ref().map(|params| {
+                        alacritty_terminal::tty::Shell::new(
+                            params.program.clone(),
+                            params.args.clone().unwrap_or_default(),
+                        )
+                    }),
+                };
 
                 alacritty_terminal::tty::Options {
                     shell: alac_shell,
@@ -584,7 +630,10 @@ impl TerminalBuilder {
 
             let term = Arc::new(FairMutex::new(term));
 
-            let pty_info = PtyProcessInfo::new(&pty);
+            let pty_info = PtyProcessInfo::new(
+                &pty,
+                session.as_ref().map(|(_, session, _)| session.shell_pid),
+            );
 
             //And connect them together
             let event_loop = EventLoop::new(
@@ -600,6 +649,9 @@ impl TerminalBuilder {
             let _io_thread = event_loop.spawn(); // DANGER
 
             let no_task = task.is_none();
+            let reattached = session
+                .as_ref()
+                .is_some_and(|(_, session, _)| session.reattached);
             let terminal = Terminal {
                 task,
                 terminal_type: TerminalType::Pty {
@@ -646,9 +698,15 @@ impl TerminalBuilder {
                 event_loop_task: Task::ready(Ok(())),
                 background_executor,
                 path_style,
+                session: session.map(|(id, _, _)| TerminalSession {
+                    id,
+                    detached: false,
+                    _app_quit_subscription: None,
+                }),
             };
 
-            if !activation_script.is_empty() && no_task {
+            // A reattached shell already ran the activation script.
+            if !activation_script.is_empty() && no_task && !reattached {
                 for activation_script in activation_script {
                     terminal.write_to_pty(activation_script.into_bytes());
                     // Simulate enter key press
@@ -683,6 +741,16 @@ impl TerminalBuilder {
     }
 
     pub fn subscribe(mut self, cx: &Context<Terminal>) -> Terminal {
+        if let Some(session) = &mut self.terminal.session {
+            // Leave the session running for the next launch of Zed to reattach to.
+            session._app_quit_subscription = Some(cx.on_app_quit(|terminal, _| {
+                if let Some(session) = &mut terminal.session {
+                    session.detached = true;
+                }
+                async {}
+            }));
+        }
+
         //Event loop
         self.terminal.event_loop_task = cx.spawn(async move |terminal, cx| {
             while let Some(event) = self.events_rx.next().await {
@@ -870,6 +938,24 @@ pub struct Terminal {
     event_loop_task: Task<Result<(), anyhow::Error>>,
     background_executor: BackgroundExecutor,
     path_style: PathStyle,
+    session: Option<TerminalSession>,
+}
+
+/// The persistent session to run a new terminal's shell in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PersistentSession {
+    /// Attaches to the session if it's running, and otherwise starts it with the terminal's shell.
+    StartOrReattach(String),
+    /// Attaches to the session, failing if it isn't running anymore.
+    Reattach(String),
+}
+
+/// The persistent session a terminal's shell runs in.
+struct TerminalSession {
+    id: String,
+    /// Whether to leave the session running once the terminal is dropped.
+    detached: bool,
+    _app_quit_subscription: Option<Subscription>,
 }
 
 struct CopyTemplate {
@@ -2171,6 +2257,14 @@ impl Terminal {
                 // and wait_for_completed_task can complete
                 info.kill_child_process();
             }
+            // Killing the attached process leaves the session's daemon running the task.
+            if let Some(session) = self.session.take() {
+                self.background_executor
+                    .spawn(async move {
+                        terminal_session::end(&session.id).log_err();
+                    })
+                    .detach();
+            }
         }
     }
 
@@ -2192,6 +2286,11 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// The id of the persistent session the terminal's shell runs in, if any.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session.as_ref().map(|session| session.id.as_str())
+    }
+
     pub fn wait_for_completed_task(&self, cx: &App) -> Task<Option<ExitStatus>> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
@@ -2295,6 +2394,11 @@ impl Terminal {
             cx,
             self.activation_script.clone(),
             self.path_style,
+            self.session.as_ref().map(|_| {
+                PersistentSession::StartOrReattach(terminal_session::new_session_id(
+                    terminal_session::SessionKind::Shell,
+                ))
+            }),
         )
     }
 }
@@ -2391,6 +2495,16 @@ impl Drop for Terminal {
         {
             pty_tx.0.send(Msg::Shutdown).ok();
 
+            if let Some(session) = self.session.take()
+                && !session.detached
+            {
+                self.background_executor
+                    .spawn(async move {
+                        terminal_session::end(&session.id).log_err();
+                    })
+                    .detach();
+            }
+
             let timer = self.background_executor.timer(Duration::from_millis(100));
             self.background_executor
                 .spawn(async move {
@@ -2582,6 +2696,7 @@ mod tests {
                     cx,
                     vec![],
                     PathStyle::local(),
+                    None,
                 )
             })
             .await
@@ -2728,6 +2843,7 @@ mod tests {
                     cx,
                     Vec::new(),
                     PathStyle::local(),
+                    None,
                 )
             })
             .await
@@ -2804,6 +2920,7 @@ mod tests {
                     cx,
                     Vec::new(),
                     PathStyle::local(),
+                    None,
                 )
             })
             .await
@@ -3292,6 +3409,7 @@ mod tests {
                         cx,
                         vec![],
                         PathStyle::local(),
+                        None,
                     )
                 })
                 .await
@@ -0,0 +1,207 @@
+//! Persistent terminal sessions, whose shells keep running when Zed quits.
+//!
+//! Like in tmux, the PTY and the shell of a session are hosted by a small daemon: a copy of Zed
+//! started with [`DAEMON_ARG`], which detaches itself and listens on a Unix socket. The terminal
+//! in Zed runs a copy of Zed started with [`ATTACH_ARG`] instead of the shell, which relays the
+//! terminal's input, output and size to the daemon. When Zed restarts, a new attached terminal
+//! gets the output the daemon kept, and continues where the old one left off.
+//!
+//! Only macOS and Linux are supported.
+
+#[cfg(unix)]
+mod unix;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+
+/// Makes Zed attach to the session whose socket is given as its value.
+pub const ATTACH_ARG: &str = "--attach-terminal-session";
+
+/// Makes Zed host a session on the socket given as its value, running the command after `--`.
+pub const DAEMON_ARG: &str = "--terminal-session-daemon";
+
+pub fn is_supported() -> bool {
+    cfg!(unix)
+}
+
+/// What a session's terminal belongs to, which decides what remembers it across restarts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionKind {
+    /// A shell in the terminal panel or in a pane, remembered by its workspace.
+    Shell,
+    /// A command run by an agent, remembered by the agent's thread.
+    Agent,
+}
+
+const AGENT_SESSION_PREFIX: &str = "agent-";
+
+impl SessionKind {
+    fn of(session_id: &str) -> Self {
+        if session_id.starts_with(AGENT_SESSION_PREFIX) {
+            Self::Agent
+        } else {
+            Self::Shell
+        }
+    }
+}
+
+/// Returns a fresh id for a session.
+pub fn new_session_id(kind: SessionKind) -> String {
+    let prefix = match kind {
+        SessionKind::Shell => "",
+        SessionKind::Agent => AGENT_SESSION_PREFIX,
+    };
+    format!("{prefix}{:016x}", rand::random::<u64>())
+}
+
+/// Returns the path of the socket the daemon of a session listens on.
+pub fn socket_path(session_id: &str) -> PathBuf {
+    paths::terminal_sessions_dir().join(format!("{session_id}.sock"))
+}
+
+/// A session that's ready to be attached to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunningSession {
+    /// The process id of the session's shell.
+    pub shell_pid: u32,
+    /// Whether the session was already running, rather than started just now.
+    pub reattached: bool,
+}
+
+/// Finds the running session with the given id, or starts its daemon with the given shell.
+///
+/// Blocks until the daemon accepts connections.
+pub fn start_or_reattach(
+    session_id: &str,
+    program: &str,
+    args: &[String],
+    working_directory: Option<&Path>,
+    env: &HashMap<String, String>,
+) -> Result<RunningSession> {
+    #[cfg(unix)]
+    {
+        unix::start_or_reattach(
+            &socket_path(session_id),
+            program,
+            args,
+            working_directory,
+            env,
+        )
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (session_id, program, args, working_directory, env);
+        anyhow::bail!("persistent terminal sessions aren't supported on this platform")
+    }
+}
+
+/// Finds the running session with the given id, failing if it isn't running anymore.
+pub fn reattach(session_id: &str) -> Result<RunningSession> {
+    #[cfg(unix)]
+    {
+        unix::reattach(&socket_path(session_id))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = session_id;
+        anyhow::bail!("persistent terminal sessions aren't supported on this platform")
+    }
+}
+
+/// Returns the program and arguments that attach a terminal to the session.
+pub fn attach_command(session_id: &str) -> Result<(String, Vec<String>)> {
+    let program = std::env::current_exe().context("failed to find the Zed executable")?;
+    Ok((
+        program.to_string_lossy().into_owned(),
+        vec![
+            ATTACH_ARG.to_string(),
+            socket_path(session_id).to_string_lossy().into_owned(),
+        ],
+    ))
+}
+
+/// Ends the session, hanging up on its shell. Does nothing if the session isn't running.
+pub fn end(session_id: &str) -> Result<()> {
+    #[cfg(unix)]
+    {
+        unix::end(&socket_path(session_id))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = session_id;
+        Ok(())
+    }
+}
+
+/// Sessions younger than this are left running by [`end_unreferenced`], as their terminals may
+/// not have been saved yet.
+const UNREFERENCED_SESSION_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Ends the running sessions of the given kind whose ids aren't in `referenced`, which no
+/// terminal can reattach to.
+pub fn end_unreferenced(kind: SessionKind, referenced: &HashSet<String>) -> Result<()> {
+    let entries = match fs::read_dir(paths::terminal_sessions_dir()) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error).context("failed to list terminal sessions"),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "sock") {
+            continue;
+        }
+        let Some(session_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let is_recent = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| {
+                modified
+                    .elapsed()
+                    .is_ok_and(|age| age < UNREFERENCED_SESSION_GRACE_PERIOD)
+            });
+        if SessionKind::of(session_id) == kind && !referenced.contains(session_id) && !is_recent {
+            end(session_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Relays the current terminal to the session listening on `socket`, until its shell exits.
+///
+/// Returns the exit code of the shell.
+pub fn attach_main(socket: &Path) -> Result<i32> {
+    #[cfg(unix)]
+    {
+        unix::attach(socket)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = socket;
+        anyhow::bail!("persistent terminal sessions aren't supported on this platform")
+    }
+}
+
+/// Detaches from the current process and hosts a session on `socket`, running `command` in it.
+pub fn daemon_main(socket: &Path, command: &[String]) -> Result<()> {
+    #[cfg(unix)]
+    {
+        unix::run_daemon(socket, command)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (socket, command);
+        anyhow::bail!("persistent terminal sessions aren't supported on this platform")
+    }
+}
@@ -0,0 +1,673 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, Read, Write},
+    mem,
+    net::Shutdown,
+    os::{
+        fd::{AsRawFd, FromRawFd as _, OwnedFd},
+        unix::{
+            fs::PermissionsExt as _,
+            net::{UnixListener, UnixStream},
+            process::{CommandExt as _, ExitStatusExt as _},
+        },
+    },
+    path::{Path, PathBuf},
+    process::{self, Child, Command, ExitStatus, Stdio},
+    ptr,
+    sync::Arc,
+    thread,
+};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::HashMap;
+use parking_lot::Mutex;
+
+use crate::RunningSession;
+
+/// How much of the shell's output the daemon keeps, to replay to terminals that attach later.
+const MAX_SCROLLBACK_BYTES: usize = 1024 * 1024;
+
+/// The largest message either side accepts, to not allocate whatever a broken peer asks for.
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// The messages sent over a session's socket.
+///
+/// Each message is its kind as a byte, the length of its payload as a big-endian `u32`, and the
+/// payload. The first message of every connection tells the daemon what the connection is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum MessageKind {
+    /// Starts relaying the session to a terminal, replacing the one attached before, if any.
+    Attach,
+    /// Asks the daemon for the pid of its shell, answered with [`MessageKind::ShellPid`].
+    Query,
+    /// Ends the session.
+    End,
+    /// Input for the shell.
+    Input,
+    /// The size of the attached terminal, as rows, columns, width and height in pixels,
+    /// each a big-endian `u16`.
+    Resize,
+    /// Output of the shell.
+    Output,
+    /// The exit code of the shell as a big-endian `i32`, sent right before the daemon exits.
+    Exit,
+    /// The pid of the shell as a big-endian `u32`.
+    ShellPid,
+}
+
+impl MessageKind {
+    const ALL: [Self; 8] = [
+        Self::Attach,
+        Self::Query,
+        Self::End,
+        Self::Input,
+        Self::Resize,
+        Self::Output,
+        Self::Exit,
+        Self::ShellPid,
+    ];
+}
+
+fn write_message(writer: &mut impl Write, kind: MessageKind, payload: &[u8]) -> io::Result<()> {
+    let mut message = Vec::with_capacity(5 + payload.len());
+    message.push(kind as u8);
+    message.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    message.extend_from_slice(payload);
+    writer.write_all(&message)
+}
+
+fn read_message(reader: &mut impl Read) -> io::Result<(MessageKind, Vec<u8>)> {
+    let mut header = [0; 5];
+    reader.read_exact(&mut header)?;
+    let kind = MessageKind::ALL
+        .get(header[0] as usize)
+        .copied()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unknown message kind"))?;
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if len > MAX_MESSAGE_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message is too large",
+        ));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    Ok((kind, payload))
+}
+
+pub(crate) fn start_or_reattach(
+    socket: &Path,
+    program: &str,
+    args: &[String],
+    working_directory: Option<&Path>,
+    env: &HashMap<String, String>,
+) -> Result<RunningSession> {
+    if let Ok(shell_pid) = query_shell_pid(socket) {
+        return Ok(RunningSession {
+            shell_pid,
+            reattached: true,
+        });
+    }
+
+    let max_socket_path_len = unsafe { mem::zeroed::<libc::sockaddr_un>() }.sun_path.len();
+    if socket.as_os_str().len() >= max_socket_path_len {
+        bail!("the session socket path {socket:?} is too long for a Unix socket");
+    }
+
+    let sessions_dir = socket.parent().context("invalid session socket path")?;
+    fs::create_dir_all(sessions_dir)?;
+    // Whoever can connect to a session can type into its shell.
+    fs::set_permissions(sessions_dir, fs::Permissions::from_mode(0o700))?;
+
+    let zed = std::env::current_exe().context("failed to find the Zed executable")?;
+    let mut command = Command::new(zed);
+    command
+        .arg(crate::DAEMON_ARG)
+        .arg(socket)
+        .arg("--")
+        .arg(program)
+        .args(args)
+        .envs(env)
+        .stdin(Stdio::null());
+    if let Some(working_directory) = working_directory {
+        command.current_dir(working_directory);
+    }
+    // The daemon detaches itself once it listens on the socket, which ends this command.
+    let output = command
+        .output()
+        .context("failed to start the terminal session daemon")?;
+    if !output.status.success() {
+        bail!(
+            "failed to start the terminal session daemon: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(RunningSession {
+        shell_pid: query_shell_pid(socket)?,
+        reattached: false,
+    })
+}
+
+pub(crate) fn reattach(socket: &Path) -> Result<RunningSession> {
+    let shell_pid = query_shell_pid(socket)
+        .with_context(|| format!("the terminal session at {socket:?} isn't running anymore"))?;
+    Ok(RunningSession {
+        shell_pid,
+        reattached: true,
+    })
+}
+
+fn query_shell_pid(socket: &Path) -> Result<u32> {
+    let mut stream = UnixStream::connect(socket)?;
+    write_message(&mut stream, MessageKind::Query, &[])?;
+    match read_message(&mut stream)? {
+        (MessageKind::ShellPid, pid) => {
+            Ok(u32::from_be_bytes(pid.try_into().map_err(|_| {
+                anyhow!("invalid shell pid from the terminal session daemon")
+            })?))
+        }
+        (kind, _) => bail!("unexpected {kind:?} message from the terminal session daemon"),
+    }
+}
+
+pub(crate) fn end(socket: &Path) -> Result<()> {
+    let Ok(mut stream) = UnixStream::connect(socket) else {
+        // Nothing listens on a socket left behind by a daemon that was killed.
+        fs::remove_file(socket).ok();
+        return Ok(());
+    };
+    write_message(&mut stream, MessageKind::End, &[])?;
+    Ok(())
+}
+
+pub(crate) fn attach(socket: &Path) -> Result<i32> {
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("failed to connect to the terminal session at {socket:?}"))?;
+    write_message(&mut stream, MessageKind::Attach, &[])?;
+    let writer = Arc::new(Mutex::new(stream.try_clone()?));
+
+    // The session's own PTY does the line editing, echoing and signals.
+    let _raw_mode = RawMode::enable()?;
+
+    // Block SIGWINCH before starting any threads, so that only `sigwait` receives it.
+    let mut resize_signals: libc::sigset_t = unsafe { mem::zeroed() };
+    unsafe {
+        libc::sigemptyset(&mut resize_signals);
+        libc::sigaddset(&mut resize_signals, libc::SIGWINCH);
+        libc::pthread_sigmask(libc::SIG_BLOCK, &resize_signals, ptr::null_mut());
+    }
+    send_window_size(&writer)?;
+    thread::spawn({
+        let writer = writer.clone();
+        move || {
+            loop {
+                let mut signal = 0;
+                if unsafe { libc::sigwait(&resize_signals, &mut signal) } != 0
+                    || send_window_size(&writer).is_err()
+                {
+                    break;
+                }
+            }
+        }
+    });
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut buffer = [0; 4096];
+        loop {
+            match stdin.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(len) => {
+                    if write_message(&mut *writer.lock(), MessageKind::Input, &buffer[..len])
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let mut stdout = io::stdout().lock();
+    loop {
+        match read_message(&mut stream) {
+            Ok((MessageKind::Output, output)) => {
+                stdout.write_all(&output)?;
+                stdout.flush()?;
+            }
+            Ok((MessageKind::Exit, code)) => {
+                return Ok(i32::from_be_bytes(code.try_into().unwrap_or_default()));
+            }
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+                bail!("the terminal session was ended or attached elsewhere")
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+fn send_window_size(writer: &Mutex<UnixStream>) -> io::Result<()> {
+    let mut size: libc::winsize = unsafe { mem::zeroed() };
+    if unsafe { libc::ioctl(libc::STDIN_FILENO, libc::TIOCGWINSZ, &mut size) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut payload = Vec::with_capacity(8);
+    for field in [size.ws_row, size.ws_col, size.ws_xpixel, size.ws_ypixel] {
+        payload.extend_from_slice(&field.to_be_bytes());
+    }
+    write_message(&mut *writer.lock(), MessageKind::Resize, &payload)
+}
+
+/// Keeps the terminal of the current process in raw mode until dropped.
+struct RawMode(libc::termios);
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        let mut original: libc::termios = unsafe { mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut raw = original;
+        unsafe { libc::cfmakeraw(&mut raw) };
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(original))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0) };
+    }
+}
+
+pub(crate) fn run_daemon(socket: &Path, command: &[String]) -> Result<()> {
+    let (program, args) = command.split_first().context("no shell to run")?;
+
+    // The daemon reports through this pipe whether it started, then the starting process exits.
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error()).context("failed to create a pipe");
+    }
+    let (ready_reader, ready_writer) =
+        unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    // The shell must not inherit the pipe, or reading it would wait for the shell to exit.
+    for fd in [&ready_reader, &ready_writer] {
+        set_cloexec(fd.as_raw_fd());
+    }
+
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()).context("failed to fork the terminal session daemon"),
+        0 => {
+            drop(ready_reader);
+            let mut ready_writer = ready_writer;
+            match Daemon::start(socket, program, args) {
+                Ok(daemon) => {
+                    drop(ready_writer);
+                    daemon.run()
+                }
+                Err(error) => {
+                    write!(ready_writer, "{error:#}").ok();
+                    process::exit(1)
+                }
+            }
+        }
+        _ => {
+            drop(ready_writer);
+            let mut error = String::new();
+            let mut ready_reader = ready_reader;
+            ready_reader.read_to_string(&mut error)?;
+            if error.is_empty() {
+                Ok(())
+            } else {
+                Err(anyhow!(error))
+            }
+        }
+    }
+}
+
+struct Daemon {
+    socket: PathBuf,
+    listener: UnixListener,
+    master: File,
+    shell: Child,
+}
+
+/// What the daemon shares between the threads relaying the shell's output and the terminal's input.
+#[derive(Default)]
+struct SessionState {
+    /// The latest output of the shell.
+    scrollback: VecDeque<u8>,
+    /// The attached terminal, along with the number telling it apart from the ones before it.
+    client: Option<(usize, UnixStream)>,
+    next_client_id: usize,
+}
+
+impl SessionState {
+    fn push_output(&mut self, output: &[u8]) {
+        self.scrollback.extend(output);
+        // Trim in batches, since finding a line to start at means scanning the scrollback.
+        if self.scrollback.len() > MAX_SCROLLBACK_BYTES + MAX_SCROLLBACK_BYTES / 4 {
+            let excess = self.scrollback.len() - MAX_SCROLLBACK_BYTES;
+            self.scrollback.drain(..excess);
+            // Start at a line, rather than in the middle of an escape sequence.
+            if let Some(newline) = self.scrollback.iter().position(|&byte| byte == b'\n') {
+                self.scrollback.drain(..=newline);
+            }
+        }
+
+        if let Some((_, client)) = &mut self.client
+            && write_message(client, MessageKind::Output, output).is_err()
+        {
+            self.client = None;
+        }
+    }
+}
+
+impl Daemon {
+    /// Detaches from the terminal and the process group of whoever started the daemon, and
+    /// starts the shell.
+    fn start(socket: &Path, program: &str, args: &[String]) -> Result<Self> {
+        if unsafe { libc::setsid() } == -1 {
+            return Err(io::Error::last_os_error()).context("failed to start a new session");
+        }
+        let null = File::options().read(true).write(true).open("/dev/null")?;
+        for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+            unsafe { libc::dup2(null.as_raw_fd(), fd) };
+        }
+
+        let listener = bind(socket)?;
+        let (master, slave) = open_pty()?;
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+        unsafe {
+            command.pre_exec(|| {
+                // Make the PTY the controlling terminal of the shell, for job control and hangups.
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let shell = command
+            .spawn()
+            .with_context(|| format!("failed to run {program}"))?;
+
+        Ok(Self {
+            socket: socket.to_path_buf(),
+            listener,
+            master: File::from(master),
+            shell,
+        })
+    }
+
+    fn run(mut self) -> ! {
+        let state = Arc::new(Mutex::new(SessionState::default()));
+        let shell_pid = self.shell.id();
+
+        if let Ok(master) = self.master.try_clone() {
+            let state = state.clone();
+            let socket = self.socket.clone();
+            thread::spawn(move || {
+                for stream in self.listener.incoming() {
+                    let (Ok(stream), Ok(master)) = (stream, master.try_clone()) else {
+                        continue;
+                    };
+                    let state = state.clone();
+                    let socket = socket.clone();
+                    thread::spawn(move || {
+                        handle_connection(stream, master, shell_pid, &socket, &state).ok();
+                    });
+                }
+            });
+        }
+
+        let mut buffer = [0; 4096];
+        loop {
+            match self.master.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => state.lock().push_output(&buffer[..len]),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                // Reading fails with EIO once the shell and everything it started closed the PTY.
+                Err(_) => break,
+            }
+        }
+
+        let code = self.shell.wait().map_or(1, exit_code);
+        if let Some((_, client)) = &mut state.lock().client {
+            write_message(client, MessageKind::Exit, &code.to_be_bytes()).ok();
+        }
+        fs::remove_file(&self.socket).ok();
+        process::exit(0)
+    }
+}
+
+fn handle_connection(
+    mut stream: UnixStream,
+    mut master: File,
+    shell_pid: u32,
+    socket: &Path,
+    state: &Mutex<SessionState>,
+) -> io::Result<()> {
+    let (kind, _) = read_message(&mut stream)?;
+    match kind {
+        MessageKind::Query => {
+            write_message(&mut stream, MessageKind::ShellPid, &shell_pid.to_be_bytes())
+        }
+        MessageKind::End => {
+            fs::remove_file(socket).ok();
+            // Hang up on the shell, like closing a terminal window does.
+            unsafe { libc::kill(-(shell_pid as i32), libc::SIGHUP) };
+            process::exit(0)
+        }
+        MessageKind::Attach => {
+            let client_id = {
+                let mut state = state.lock();
+                let (front, back) = state.scrollback.as_slices();
+                write_message(&mut stream, MessageKind::Output, &[front, back].concat())?;
+                let client_id = state.next_client_id;
+                state.next_client_id += 1;
+                // A session has a single terminal, so the one attached before is disconnected.
+                if let Some((_, previous)) = state.client.replace((client_id, stream.try_clone()?))
+                {
+                    previous.shutdown(Shutdown::Both).ok();
+                }
+                client_id
+            };
+
+            loop {
+                match read_message(&mut stream) {
+                    Ok((MessageKind::Input, input)) => master.write_all(&input)?,
+                    Ok((MessageKind::Resize, size)) => resize(&master, &size),
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+
+            let mut state = state.lock();
+            if state
+                .client
+                .as_ref()
+                .is_some_and(|(id, _)| *id == client_id)
+            {
+                state.client = None;
+            }
+            Ok(())
+        }
+        kind => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected {kind:?} message"),
+        )),
+    }
+}
+
+fn bind(socket: &Path) -> Result<UnixListener> {
+    match UnixListener::bind(socket) {
+        Ok(listener) => Ok(listener),
+        Err(error) if error.kind() == io::ErrorKind::AddrInUse => {
+            if UnixStream::connect(socket).is_ok() {
+                bail!("the terminal session at {socket:?} is already running");
+            }
+            // Nothing listens on a socket left behind by a daemon that was killed.
+            fs::remove_file(socket)?;
+            UnixListener::bind(socket).with_context(|| format!("failed to listen on {socket:?}"))
+        }
+        Err(error) => Err(error).with_context(|| format!("failed to listen on {socket:?}")),
+    }
+}
+
+fn open_pty() -> Result<(OwnedFd, OwnedFd)> {
+    let mut master = -1;
+    let mut slave = -1;
+    if unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            ptr::null_mut(),
+            ptr::null_mut::<libc::termios>(),
+            ptr::null_mut::<libc::winsize>(),
+        )
+    } == -1
+    {
+        return Err(io::Error::last_os_error()).context("failed to open a PTY");
+    }
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+    // The shell only gets the PTY as its standard streams.
+    for fd in [&master, &slave] {
+        set_cloexec(fd.as_raw_fd());
+    }
+    Ok((master, slave))
+}
+
+fn set_cloexec(fd: libc::c_int) {
+    unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+}
+
+fn resize(master: &File, size: &[u8]) {
+    let Ok(size) = <[u8; 8]>::try_from(size) else {
+        return;
+    };
+    let field = |ix: usize| u16::from_be_bytes([size[ix * 2], size[ix * 2 + 1]]);
+    let size = libc::winsize {
+        ws_row: field(0),
+        ws_col: field(1),
+        ws_xpixel: field(2),
+        ws_ypixel: field(3),
+    };
+    unsafe { libc::ioctl(master.as_raw_fd(), libc::TIOCSWINSZ, &size) };
+}
+
+fn exit_code(status: ExitStatus) -> i32 {
+    status
+        .code()
+        .or_else(|| status.signal().map(|signal| 128 + signal))
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, MessageKind::Input, b"ls\r").unwrap();
+        write_message(&mut buffer, MessageKind::Attach, &[]).unwrap();
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            (MessageKind::Input, b"ls\r".to_vec())
+        );
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            (MessageKind::Attach, Vec::new())
+        );
+        assert_eq!(
+            read_message(&mut reader).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        let mut reader: &[u8] = &[42, 0, 0, 0, 0];
+        assert_eq!(
+            read_message(&mut reader).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_scrollback_starts_at_a_line() {
+        let mut state = SessionState::default();
+        let line = [b'a'; 99].iter().chain(b"\n").copied().collect::<Vec<_>>();
+        for _ in 0..(MAX_SCROLLBACK_BYTES / line.len()) * 2 {
+            state.push_output(&line);
+        }
+
+        assert!(state.scrollback.len() <= MAX_SCROLLBACK_BYTES + MAX_SCROLLBACK_BYTES / 4);
+        assert_eq!(state.scrollback.len() % line.len(), 0);
+        assert_eq!(state.scrollback.front(), Some(&b'a'));
+    }
+
+    #[test]
+    fn test_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("test.sock");
+
+        // Returns once the daemon listens, even though its shell keeps running.
+        run_daemon(&socket, &["/bin/sh".to_string()]).unwrap();
+        let shell_pid = query_shell_pid(&socket).unwrap();
+        assert_ne!(shell_pid, 0);
+        assert_eq!(reattach(&socket).unwrap().shell_pid, shell_pid);
+
+        let mut terminal = attach_for_test(&socket);
+        write_message(&mut terminal, MessageKind::Input, b"echo $((6 * 7))\n").unwrap();
+        read_output_until(&mut terminal, "42");
+
+        // A terminal attaching later gets the output the first one saw, and replaces it.
+        let mut second_terminal = attach_for_test(&socket);
+        read_output_until(&mut second_terminal, "42");
+        assert!(read_output_until_eof(&mut terminal));
+
+        end(&socket).unwrap();
+        assert!(read_output_until_eof(&mut second_terminal));
+        assert!(query_shell_pid(&socket).is_err());
+        assert!(reattach(&socket).is_err());
+    }
+
+    fn attach_for_test(socket: &Path) -> UnixStream {
+        let mut stream = UnixStream::connect(socket).unwrap();
+        stream
+            .set_read_timeout(Some(std::time::Duration::from_secs(10)))
+            .unwrap();
+        write_message(&mut stream, MessageKind::Attach, &[]).unwrap();
+        stream
+    }
+
+    fn read_output_until(stream: &mut UnixStream, expected: &str) {
+        let mut output = Vec::new();
+        while !String::from_utf8_lossy(&output).contains(expected) {
+            match read_message(stream).unwrap() {
+                (MessageKind::Output, chunk) => output.extend(chunk),
+                (kind, _) => panic!("unexpected {kind:?} message"),
+            }
+        }
+    }
+
+    /// Returns whether the daemon disconnected the stream, rather than timing out.
+    fn read_output_until_eof(stream: &mut UnixStream) -> bool {
+        loop {
+            match read_message(stream) {
+                Ok(_) => {}
+                Err(error) => return error.kind() == io::ErrorKind::UnexpectedEof,
+            }
+        }
+    }
+}
@@ -37,7 +37,7 @@ use workspace::{
     move_active_item, move_item, pane,
 };
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use zed_actions::assistant::InlineAssist;
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
@@ -627,6 +627,26 @@ impl TerminalPanel {
         cx.spawn(async move |_, _| rx.await?)
     }
 
+    /// Publishes the problems the task's problem matchers find in its terminal output as diagnostics.
+    fn publish_task_problems(
+        &self,
+        task: &SpawnInTerminal,
+        terminal: &WeakEntity<Terminal>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let workspace = self
+            .workspace
+            .upgrade()
+            .context("failed to read workspace")?;
+        let terminal = terminal.upgrade().context("task terminal was closed")?;
+        let output = terminal.read(cx).get_content();
+        let problems = task::find_problems(&task.problem_matchers, &output)?;
+        let lsp_store = workspace.read(cx).project().read(cx).lsp_store();
+        lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.publish_task_problems(&task.full_label, problems, cx)
+        })
+    }
+
     fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
@@ -1812,6 +1832,13 @@ impl workspace::TerminalProvider for TerminalProvider {
                         .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
                         .ok()?
                         .await?;
+                    if !task.problem_matchers.is_empty()
+                        && let Ok(Err(e)) = terminal_panel.update(cx, |terminal_panel, cx| {
+                            terminal_panel.publish_task_problems(&task, &terminal, cx)
+                        })
+                    {
+                        log::error!("failed to publish problems of task {:?}: {e:#}", task.label);
+                    }
                     Some(Ok(exit_status))
                 }
                 Err(e) => Some(Err(e)),
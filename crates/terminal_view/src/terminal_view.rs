@@ -101,6 +101,16 @@ pub fn init(cx: &mut App) {
 
     register_serializable_item::<TerminalView>(cx);
 
+    // Sessions of terminals that no workspace restores anymore have nothing to reattach to them.
+    cx.background_spawn(async {
+        let session_ids = TERMINAL_DB.get_session_ids()?;
+        terminal_session::end_unreferenced(
+            terminal_session::SessionKind::Shell,
+            &session_ids.into_iter().collect(),
+        )
+    })
+    .detach_and_log_err(cx);
+
     cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
         workspace.register_action(TerminalView::deploy);
     })
@@ -229,6 +239,8 @@ impl TerminalView {
         cx: &mut Context<Self>,
     ) -> Self {
         let workspace_handle = workspace.clone();
+        // The session of a terminal must be saved for it to be reattached after a restart.
+        let needs_serialize = terminal.read(cx).session_id().is_some();
         let terminal_subscriptions =
             subscribe_for_terminal_events(&terminal, workspace, window, cx);
 
@@ -285,7 +297,7 @@ impl TerminalView {
             block_below_cursor: None,
             scroll_top: Pixels::ZERO,
             scroll_handle,
-            needs_serialize: false,
+            needs_serialize,
             custom_title: None,
             ime_state: None,
             self_handle: cx.entity().downgrade(),
@@ -1565,6 +1577,7 @@ impl SerializableItem for TerminalView {
         let workspace_id = self.workspace_id?;
         let cwd = terminal.working_directory();
         let custom_title = self.custom_title.clone();
+        let session_id = terminal.session_id().map(ToOwned::to_owned);
         self.needs_serialize = false;
 
         Some(cx.background_spawn(async move {
@@ -1576,6 +1589,9 @@ impl SerializableItem for TerminalView {
             TERMINAL_DB
                 .save_custom_title(item_id, workspace_id, custom_title)
                 .await?;
+            TERMINAL_DB
+                .save_session_id(item_id, workspace_id, session_id)
+                .await?;
             Ok(())
         }))
     }
@@ -1593,7 +1609,7 @@ impl SerializableItem for TerminalView {
         cx: &mut App,
     ) -> Task<anyhow::Result<Entity<Self>>> {
         window.spawn(cx, async move |cx| {
-            let (cwd, custom_title) = cx
+            let (cwd, custom_title, session_id) = cx
                 .update(|_window, cx| {
                     let from_db = TERMINAL_DB
                         .get_working_directory(item_id, workspace_id)
@@ -1614,13 +1630,20 @@ impl SerializableItem for TerminalView {
                         .log_err()
                         .flatten()
                         .filter(|title| !title.trim().is_empty());
-                    (cwd, custom_title)
+                    let session_id = TERMINAL_DB
+                        .get_session_id(item_id, workspace_id)
+                        .log_err()
+                        .flatten();
+                    (cwd, custom_title, session_id)
                 })
                 .ok()
-                .unwrap_or((None, None));
+                .unwrap_or((None, None, None));
 
             let terminal = project
-                .update(cx, |project, cx| project.create_terminal_shell(cwd, cx))
+                .update(cx, |project, cx| match session_id {
+                    Some(session_id) => project.reattach_terminal_shell(cwd, session_id, cx),
+                    None => project.create_terminal_shell(cwd, cx),
+                })
                 .await?;
             cx.update(|window, cx| {
                 cx.new(|cx| {
@@ -1,12 +1,18 @@
-use std::process::ExitStatus;
+use std::{cell::RefCell, process::ExitStatus, rc::Rc};
 
 use anyhow::Result;
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{Shared, join_all},
+};
 use gpui::{AppContext, Context, Entity, Task};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskDependency, TaskSourceKind, WorktreeId, task_store::TaskFinished};
 use remote::ConnectionState;
 use task::{
-    DebugScenario, ResolvedTask, SharedTaskContext, SpawnInTerminal, TaskContext, TaskTemplate,
+    DebugScenario, DependsOrder, ResolvedTask, SharedTaskContext, SpawnInTerminal, TaskContext,
+    TaskTemplate,
 };
 use ui::Window;
 
@@ -15,6 +21,10 @@ use crate::{
     notifications::{NotificationId, NotificationSource},
 };
 
+/// The dependencies started while running a task, by label. A task that several dependencies
+/// depend on runs once, and the others wait for that run.
+type StartedDependencies = Rc<RefCell<HashMap<String, Shared<Task<bool>>>>>;
+
 impl Workspace {
     pub fn schedule_task(
         self: &mut Workspace,
@@ -60,6 +70,8 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let template = resolved_task.original_task().clone();
+        let task_context = resolved_task.task_context().clone();
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -70,16 +82,206 @@ impl Workspace {
                     project.task_store().read(cx).task_inventory().cloned()
                 {
                     task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task);
                     })
                 }
             });
         }
 
+        if template.depends_on.is_empty() {
+            let task = self.run_in_terminal(spawn_in_terminal, window, cx);
+            self.scheduled_tasks.push(cx.background_spawn(async move {
+                task.await;
+            }));
+            return;
+        }
+
+        // Look the dependencies up when the task is run, so that they're always up to date.
+        let worktree_id = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => task_context
+                .cwd
+                .as_ref()
+                .and_then(|cwd| self.project.read(cx).find_worktree(cwd, cx))
+                .map(|(worktree, _)| worktree.read(cx).id()),
+        };
+        let dependencies = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .map(|inventory| {
+                inventory
+                    .read(cx)
+                    .task_dependencies(&template, worktree_id, cx)
+            });
+        let Some(dependencies) = dependencies else {
+            return;
+        };
+        let depends_order = template.depends_order;
+        let has_command = !template.command.trim().is_empty();
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let dependencies = match dependencies.await {
+                Ok(dependencies) => dependencies,
+                Err(e) => {
+                    log::error!("Task spawn failed: {e:#}");
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            let id = NotificationId::unique::<ResolvedTask>();
+                            workspace.show_toast(
+                                Toast::new(id, format!("Task spawn failed: {e}")),
+                                NotificationSource::Task,
+                                cx,
+                            );
+                        })
+                        .ok();
+                    return;
+                }
+            };
+            let Ok(dependencies) = workspace.update_in(cx, |workspace, window, cx| {
+                workspace.run_dependencies(
+                    dependencies,
+                    depends_order,
+                    task_context,
+                    StartedDependencies::default(),
+                    window,
+                    cx,
+                )
+            }) else {
+                return;
+            };
+            if !dependencies.await || !has_command {
+                return;
+            }
+            if let Ok(task) = workspace.update_in(cx, |workspace, window, cx| {
+                workspace.run_in_terminal(spawn_in_terminal, window, cx)
+            }) {
+                task.await;
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
+    /// Runs the tasks that a task depends on, in the order given, each after its own
+    /// dependencies. Resolves to whether all of them succeeded.
+    ///
+    /// Like VS Code's `dependsOn`, a task runs at most once however many tasks depend on it.
+    fn run_dependencies(
+        &mut self,
+        dependencies: Vec<TaskDependency>,
+        depends_order: DependsOrder,
+        task_context: TaskContext,
+        started: StartedDependencies,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<bool> {
+        match depends_order {
+            DependsOrder::Parallel => {
+                let tasks = dependencies
+                    .into_iter()
+                    .map(|dependency| {
+                        self.run_with_dependencies(
+                            dependency,
+                            task_context.clone(),
+                            started.clone(),
+                            window,
+                            cx,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                cx.background_spawn(async move { join_all(tasks).await.into_iter().all(|ok| ok) })
+            }
+            DependsOrder::Sequential => cx.spawn_in(window, async move |workspace, cx| {
+                for dependency in dependencies {
+                    let task_context = task_context.clone();
+                    let started = started.clone();
+                    let Ok(task) = workspace.update_in(cx, |workspace, window, cx| {
+                        workspace.run_with_dependencies(
+                            dependency,
+                            task_context,
+                            started,
+                            window,
+                            cx,
+                        )
+                    }) else {
+                        return false;
+                    };
+                    if !task.await {
+                        return false;
+                    }
+                }
+                true
+            }),
+        }
+    }
+
+    fn run_with_dependencies(
+        &mut self,
+        dependency: TaskDependency,
+        task_context: TaskContext,
+        started: StartedDependencies,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<bool> {
+        let TaskDependency {
+            source_kind,
+            template,
+            dependencies,
+        } = dependency;
+        let existing_run = started.borrow().get(&template.label).cloned();
+        if let Some(run) = existing_run {
+            return cx.spawn(async move |_, _| run.await);
+        }
+
+        let label = template.label.clone();
+        let dependencies = self.run_dependencies(
+            dependencies,
+            template.depends_order,
+            task_context.clone(),
+            started.clone(),
+            window,
+            cx,
+        );
+        let run = cx
+            .spawn_in(window, async move |workspace, cx| {
+                if !dependencies.await {
+                    return false;
+                }
+                if template.command.trim().is_empty() {
+                    return true;
+                }
+                let Some(resolved_task) =
+                    template.resolve_task(&source_kind.to_id_base(), &task_context)
+                else {
+                    log::error!("Failed to resolve task `{}`", template.label);
+                    return false;
+                };
+                let Ok(task) = workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.run_in_terminal(resolved_task.resolved, window, cx)
+                }) else {
+                    return false;
+                };
+                task.await
+            })
+            .shared();
+        started.borrow_mut().insert(label, run.clone());
+        cx.spawn(async move |_, _| run.await)
+    }
+
+    /// Spawns the task in a terminal, and reports when it finishes.
+    /// Resolves to whether the task succeeded.
+    fn run_in_terminal(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<bool> {
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+            let label = spawn_in_terminal.label.clone();
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
-            let task = cx.spawn(async |w, cx| {
+            cx.spawn(async |w, cx| {
                 let res = cx.background_spawn(task_status).await;
                 match res {
                     Some(Ok(status)) => {
@@ -88,6 +290,20 @@ impl Workspace {
                         } else {
                             log::debug!("Task spawn failed, code: {:?}", status.code());
                         }
+                        _ = w.update(cx, |w, cx| {
+                            let task_store = w.project().read(cx).task_store().clone();
+                            task_store.update(cx, |task_store, cx| {
+                                task_store.task_finished(
+                                    TaskFinished {
+                                        label,
+                                        success: status.success(),
+                                        exit_code: status.code(),
+                                    },
+                                    cx,
+                                )
+                            });
+                        });
+                        status.success()
                     }
                     Some(Err(e)) => {
                         log::error!("Task spawn failed: {e:#}");
@@ -98,12 +314,17 @@ impl Workspace {
                                 NotificationSource::Task,
                                 cx,
                             );
-                        })
+                        });
+                        false
                     }
-                    None => log::debug!("Task spawn got cancelled"),
-                };
-            });
-            self.scheduled_tasks.push(task);
+                    None => {
+                        log::debug!("Task spawn got cancelled");
+                        false
+                    }
+                }
+            })
+        } else {
+            Task::ready(false)
         }
     }
 
@@ -141,3 +362,84 @@ impl Workspace {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, process::ExitStatus, rc::Rc};
+
+    use anyhow::Result;
+    use fs::FakeFs;
+    use gpui::{App, Task, TestAppContext, Window};
+    use project::{Project, TaskDependency, TaskSourceKind};
+    use task::{DependsOrder, SpawnInTerminal, TaskContext, TaskTemplate};
+
+    use super::StartedDependencies;
+    use crate::{TerminalProvider, Workspace, tests::init_test};
+
+    /// Records the labels of the tasks it's asked to spawn, which all succeed right away.
+    struct RecordingTerminalProvider(Rc<RefCell<Vec<String>>>);
+
+    impl TerminalProvider for RecordingTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _window: &mut Window,
+            _cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            self.0.borrow_mut().push(task.label);
+            Task::ready(Some(Ok(ExitStatus::default())))
+        }
+    }
+
+    fn dependency(label: &str, dependencies: Vec<TaskDependency>) -> TaskDependency {
+        TaskDependency {
+            source_kind: TaskSourceKind::UserInput,
+            template: TaskTemplate {
+                label: label.into(),
+                command: format!("echo {label}"),
+                ..TaskTemplate::default()
+            },
+            dependencies,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_shared_dependency_runs_once(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let spawned = Rc::new(RefCell::new(Vec::new()));
+        workspace.update(cx, |workspace, _| {
+            workspace.set_terminal_provider(RecordingTerminalProvider(spawned.clone()))
+        });
+
+        for depends_order in [DependsOrder::Sequential, DependsOrder::Parallel] {
+            spawned.borrow_mut().clear();
+            // Both `build` and `lint` depend on `install`.
+            let dependencies = vec![
+                dependency("build", vec![dependency("install", Vec::new())]),
+                dependency("lint", vec![dependency("install", Vec::new())]),
+            ];
+            let succeeded = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.run_dependencies(
+                        dependencies,
+                        depends_order,
+                        TaskContext::default(),
+                        StartedDependencies::default(),
+                        window,
+                        cx,
+                    )
+                })
+                .await;
+            assert!(succeeded);
+
+            let mut spawned = spawned.borrow().clone();
+            assert_eq!(spawned[0], "install", "{depends_order:?}");
+            spawned.sort();
+            assert_eq!(spawned, ["build", "install", "lint"], "{depends_order:?}");
+        }
+    }
+}
@@ -208,6 +208,34 @@ fn main() {
         }
     }
 
+    // `zed --sandbox` Makes zed run an agent's terminal command in a sandbox
+    if let Some(policy) = &args.sandbox {
+        if let Err(err) = sandbox::main(policy, &args.paths_or_urls) {
+            eprintln!("Error: {:#}", err);
+        }
+        process::exit(126);
+    }
+
+    // `zed --terminal-session-daemon` Makes zed host a persistent terminal session
+    if let Some(socket) = &args.terminal_session_daemon {
+        if let Err(err) = terminal_session::daemon_main(socket, &args.paths_or_urls) {
+            eprintln!("Error: {:#}", err);
+            process::exit(1);
+        }
+        return;
+    }
+
+    // `zed --attach-terminal-session` Makes zed relay a terminal to a persistent terminal session
+    if let Some(socket) = &args.attach_terminal_session {
+        match terminal_session::attach_main(socket) {
+            Ok(code) => process::exit(code),
+            Err(err) => {
+                eprintln!("Error: {:#}", err);
+                process::exit(1);
+            }
+        }
+    }
+
     #[cfg(all(not(debug_assertions), target_os = "windows"))]
     unsafe {
         use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
@@ -656,6 +684,7 @@ fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        symbol_hierarchy::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -1542,6 +1571,21 @@ struct Args {
     #[arg(long, hide = true)]
     nc: Option<String>,
 
+    /// Used for sandboxing the terminal commands of agents, by having Zed restrict itself
+    /// and then run the command given after `--` in its place.
+    #[arg(long, hide = true, value_name = "POLICY")]
+    sandbox: Option<String>,
+
+    /// Used for persistent terminal sessions, by having Zed host the shell of a session
+    /// on a Unix socket, running the command given after `--`.
+    #[arg(long, hide = true, value_name = "SOCKET")]
+    terminal_session_daemon: Option<PathBuf>,
+
+    /// Used for persistent terminal sessions, by having Zed relay the terminal it runs in
+    /// to the session hosted on a Unix socket.
+    #[arg(long, hide = true, value_name = "SOCKET")]
+    attach_terminal_session: Option<PathBuf>,
+
     /// Used for recording minidumps on crashes by having Zed run a separate
     /// process communicating over a socket.
     #[arg(long, hide = true)]
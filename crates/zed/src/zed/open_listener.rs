@@ -1,7 +1,8 @@
 use crate::handle_open_request;
 use crate::restorable_workspace_locations;
+use agent_ui::AgentPanel;
 use anyhow::{Context as _, Result, anyhow};
-use cli::{CliRequest, CliResponse, ipc::IpcSender};
+use cli::{CliAgentCommand, CliRequest, CliResponse, ipc::IpcSender};
 use cli::{IpcHandshake, ipc};
 use client::{ZedLink, parse_zed_link};
 use db::kvp::KEY_VALUE_STORE;
@@ -448,6 +449,106 @@ pub async fn handle_cli_connection(
                 let status = if open_workspace_result.is_err() { 1 } else { 0 };
                 responses.send(CliResponse::Exit { status }).log_err();
             }
+            CliRequest::Agent {
+                command,
+                cwd,
+                user_data_dir: _,
+            } => {
+                let status =
+                    match handle_agent_command(command, PathBuf::from(cwd), &responses, cx).await {
+                        Ok(()) => 0,
+                        Err(error) => {
+                            responses
+                                .send(CliResponse::Stderr {
+                                    message: format!("{error:#}"),
+                                })
+                                .log_err();
+                            1
+                        }
+                    };
+                responses.send(CliResponse::Exit { status }).log_err();
+            }
+        }
+    }
+}
+
+/// Runs a `zed agent` command in the agent panel of the workspace that contains `cwd`.
+async fn handle_agent_command(
+    command: CliAgentCommand,
+    cwd: PathBuf,
+    responses: &IpcSender<CliResponse>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let (window, _) = workspace::find_existing_workspace(
+        &[cwd.clone()],
+        &OpenOptions::default(),
+        &SerializedWorkspaceLocation::Local,
+        cx,
+    )
+    .await;
+    let window = window.with_context(|| format!("no open workspace contains {cwd:?}"))?;
+    let panel = window
+        .update(cx, |workspace, _, cx| workspace.panel::<AgentPanel>(cx))?
+        .context("the workspace has no agent panel")?;
+
+    let tab_id = match command {
+        CliAgentCommand::List => {
+            let tabs = window.update(cx, |_, _, cx| panel.read(cx).agent_tab_summaries(cx))?;
+            for tab in tabs {
+                responses.send(CliResponse::Stdout {
+                    message: format!(
+                        "{}\t{}\t{}\t{}",
+                        tab.tab_id, tab.agent, tab.status, tab.title
+                    ),
+                })?;
+            }
+            return Ok(());
+        }
+        CliAgentCommand::New {
+            agent,
+            prompt,
+            wait,
+        } => {
+            let tab_id = window.update(cx, |_, window, cx| {
+                panel.update(cx, |panel, cx| {
+                    panel.new_agent_tab_with_prompt(agent.as_deref(), prompt, window, cx)
+                })
+            })??;
+            if !wait {
+                responses.send(CliResponse::Stdout {
+                    message: tab_id.to_string(),
+                })?;
+                return Ok(());
+            }
+            tab_id
+        }
+        CliAgentCommand::Send { tab, message, wait } => {
+            window.update(cx, |_, window, cx| {
+                panel.update(cx, |panel, cx| {
+                    panel.send_to_agent_tab(tab, message, window, cx)
+                })
+            })??;
+            if !wait {
+                return Ok(());
+            }
+            tab
+        }
+        CliAgentCommand::Wait { tab } => tab,
+    };
+
+    let thread_view = window.update(cx, |_, _, cx| panel.read(cx).agent_tab(tab_id))??;
+    let wait = agent_ui::wait_for_agent_tab(thread_view, cx).fuse();
+    futures::pin_mut!(wait);
+    let background = cx.background_executor().clone();
+    loop {
+        // Stop waiting if the CLI was closed.
+        let mut timer = background.timer(Duration::from_secs(1)).fuse();
+        futures::select_biased! {
+            final_message = wait => {
+                responses.send(CliResponse::Stdout { message: final_message? })?;
+                return Ok(());
+            }
+            _ = timer => responses.send(CliResponse::Ping)?,
         }
     }
 }
@@ -195,6 +195,20 @@ pub mod editor {
     );
 }
 
+pub mod symbol_hierarchy {
+    use gpui::actions;
+
+    actions!(
+        symbol_hierarchy,
+        [
+            /// Shows the callers and callees of the symbol under the cursor.
+            ShowCallHierarchy,
+            /// Shows the supertypes and subtypes of the type under the cursor.
+            ShowTypeHierarchy,
+        ]
+    );
+}
+
 pub mod dev {
     use gpui::actions;
 